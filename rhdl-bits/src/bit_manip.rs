@@ -0,0 +1,121 @@
+use rhdl_typenum::*;
+
+use crate::{bits, Bits};
+
+// Bit manipulation intrinsics for priority encoders, arbiters and the
+// like.  The counts are returned with the same width as the argument,
+// which always has room for them.  The one-hot conversions take the
+// width of the result explicitly.  All of these can be used in kernel
+// functions, where each is a single operation, rather than a loop over
+// the bits of the argument.
+
+impl<N: BitWidth> Bits<N> {
+    /// Count the number of bits that are set.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(b8(0b1011_0001).count_ones(), b8(4));
+    /// ```
+    pub fn count_ones(self) -> Self {
+        bits(self.val.count_ones() as u128)
+    }
+    /// Count the number of zero bits above the most significant bit
+    /// that is set.  This is `N` if no bits are set.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(b8(0b0001_0110).leading_zeros(), b8(3));
+    /// assert_eq!(b8(0).leading_zeros(), b8(8));
+    /// ```
+    pub fn leading_zeros(self) -> Self {
+        bits((self.val.leading_zeros() as usize - (128 - N::BITS)) as u128)
+    }
+    /// Count the number of zero bits below the least significant bit
+    /// that is set.  This is `N` if no bits are set.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(b8(0b0001_0100).trailing_zeros(), b8(2));
+    /// assert_eq!(b8(0).trailing_zeros(), b8(8));
+    /// ```
+    pub fn trailing_zeros(self) -> Self {
+        bits((self.val.trailing_zeros() as usize).min(N::BITS) as u128)
+    }
+    /// Reverse the order of the bits, so that the least significant
+    /// bit becomes the most significant bit.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(b8(0b0001_0110).reverse_bits(), b8(0b0110_1000));
+    /// ```
+    pub fn reverse_bits(self) -> Self {
+        bits(self.val.reverse_bits() >> (128 - N::BITS))
+    }
+    /// Convert a one-hot value into the index of the bit that is set.
+    /// If more than one bit is set, the result is the bitwise OR of
+    /// their indices, which is what the usual OR-based encoder gives.
+    /// The result is zero if no bits are set.  In a kernel, the width
+    /// of the result must be given with a turbofish.
+    /// ```
+    /// # use rhdl_bits::{W3, alias::*};
+    /// assert_eq!(b8(0b0010_0000).one_hot_to_binary::<W3>(), b3(5));
+    /// ```
+    pub fn one_hot_to_binary<M: BitWidth>(self) -> Bits<M> {
+        let index = (0..N::BITS)
+            .filter(|ndx| self.val & (1 << ndx) != 0)
+            .fold(0, |acc, ndx| acc | ndx as u128);
+        bits(index & Bits::<M>::mask().val)
+    }
+    /// Convert an index into a one-hot value with the bit at that
+    /// index set.  The result is zero if the index is out of range.
+    /// In a kernel, the width of the result must be given with a
+    /// turbofish.
+    /// ```
+    /// # use rhdl_bits::{W8, alias::*};
+    /// assert_eq!(b3(5).binary_to_one_hot::<W8>(), b8(0b0010_0000));
+    /// ```
+    pub fn binary_to_one_hot<M: BitWidth>(self) -> Bits<M> {
+        if self.val < M::BITS as u128 {
+            bits(1 << self.val)
+        } else {
+            Bits::<M>::ZERO
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_match_u8() {
+        for a in 0..=255_u8 {
+            let x = bits::<W8>(a as u128);
+            assert_eq!(x.count_ones().raw(), a.count_ones() as u128);
+            assert_eq!(x.leading_zeros().raw(), a.leading_zeros() as u128);
+            assert_eq!(x.trailing_zeros().raw(), a.trailing_zeros() as u128);
+            assert_eq!(x.reverse_bits().raw(), a.reverse_bits() as u128);
+        }
+    }
+
+    #[test]
+    fn test_counts_at_full_width() {
+        let x = Bits::<W128>::MAX;
+        assert_eq!(x.count_ones().raw(), 128);
+        assert_eq!(x.leading_zeros().raw(), 0);
+        assert_eq!(Bits::<W128>::ZERO.trailing_zeros().raw(), 128);
+        assert_eq!(bits::<W128>(1).reverse_bits().raw(), 1 << 127);
+        let x = bits::<W1>(1);
+        assert_eq!(x.count_ones(), x);
+        assert_eq!(Bits::<W1>::ZERO.leading_zeros(), x);
+    }
+
+    #[test]
+    fn test_one_hot_round_trip() {
+        for ndx in 0..16 {
+            let x = bits::<W4>(ndx);
+            let one_hot = x.binary_to_one_hot::<W16>();
+            assert_eq!(one_hot.raw(), 1 << ndx);
+            assert_eq!(one_hot.one_hot_to_binary::<W4>(), x);
+        }
+        assert_eq!(bits::<W4>(12).binary_to_one_hot::<W8>(), Bits::<W8>::ZERO);
+        assert_eq!(bits::<W8>(0b1010).one_hot_to_binary::<W3>(), bits(3));
+        assert_eq!(Bits::<W8>::ZERO.one_hot_to_binary::<W3>(), bits(0));
+    }
+}
@@ -223,6 +223,17 @@ impl<N: BitWidth> Bits<N> {
         }
         v
     }
+    /// Build a value from its bits (LSB first).  This is the
+    /// inverse of [Bits::to_bools].  Missing bits are taken to
+    /// be zero, and extra bits are ignored.
+    pub fn from_bools(v: &[bool]) -> Self {
+        bits_masked(
+            v.iter()
+                .take(N::BITS)
+                .rev()
+                .fold(0, |acc, b| (acc << 1) | (*b as u128)),
+        )
+    }
     pub fn any(self) -> bool {
         (self.val & Self::mask().val) != 0
     }
@@ -334,6 +345,8 @@ mod tests {
             result,
             vec![false, true, false, true, true, false, true, true]
         );
+        assert_eq!(Bits::<W8>::from_bools(&result), bits);
+        assert_eq!(SignedBits::<W8>::from_bools(&result), -38);
     }
 
     #[test]
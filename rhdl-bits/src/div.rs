@@ -0,0 +1,223 @@
+use std::ops::{Div, DivAssign, Rem, RemAssign};
+
+use rhdl_typenum::BitWidth;
+
+use crate::bits_impl::bits_masked;
+use crate::bits_impl::Bits;
+use crate::signed_bits_impl::signed_wrapped;
+use crate::signed_bits_impl::SignedBits;
+
+// Division in hardware cannot panic, so a divisor of zero needs a
+// defined result.  We follow the (RISC-V) convention:
+//   - x / 0 is all ones (i.e., MAX for unsigned, -1 for signed)
+//   - x % 0 is x
+//   - MIN / -1 wraps to MIN, and MIN % -1 is 0
+// Otherwise, division truncates towards zero, just as it does
+// for the built in integers.
+
+impl<N: BitWidth> Div for Bits<N> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        self.val.checked_div(rhs.val).map_or(Self::MAX, bits_masked)
+    }
+}
+
+impl<N: BitWidth> Div<u128> for Bits<N> {
+    type Output = Bits<N>;
+    fn div(self, rhs: u128) -> Self::Output {
+        assert!(rhs <= Self::MASK.val);
+        self / bits_masked::<N>(rhs)
+    }
+}
+
+impl<N: BitWidth> Div<Bits<N>> for u128 {
+    type Output = Bits<N>;
+    fn div(self, rhs: Bits<N>) -> Self::Output {
+        assert!(self <= Bits::<N>::MASK.val);
+        bits_masked::<N>(self) / rhs
+    }
+}
+
+impl<N: BitWidth> DivAssign for Bits<N> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<N: BitWidth> DivAssign<u128> for Bits<N> {
+    fn div_assign(&mut self, rhs: u128) {
+        *self = *self / rhs;
+    }
+}
+
+impl<N: BitWidth> Rem for Bits<N> {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.val.checked_rem(rhs.val).map_or(self, bits_masked)
+    }
+}
+
+impl<N: BitWidth> Rem<u128> for Bits<N> {
+    type Output = Bits<N>;
+    fn rem(self, rhs: u128) -> Self::Output {
+        assert!(rhs <= Self::MASK.val);
+        self % bits_masked::<N>(rhs)
+    }
+}
+
+impl<N: BitWidth> Rem<Bits<N>> for u128 {
+    type Output = Bits<N>;
+    fn rem(self, rhs: Bits<N>) -> Self::Output {
+        assert!(self <= Bits::<N>::MASK.val);
+        bits_masked::<N>(self) % rhs
+    }
+}
+
+impl<N: BitWidth> RemAssign for Bits<N> {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = *self % rhs;
+    }
+}
+
+impl<N: BitWidth> RemAssign<u128> for Bits<N> {
+    fn rem_assign(&mut self, rhs: u128) {
+        *self = *self % rhs;
+    }
+}
+
+impl<N: BitWidth> Div for SignedBits<N> {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self::Output {
+        if rhs.val == 0 {
+            signed_wrapped(-1)
+        } else {
+            signed_wrapped(self.val.wrapping_div(rhs.val))
+        }
+    }
+}
+
+impl<N: BitWidth> Div<i128> for SignedBits<N> {
+    type Output = SignedBits<N>;
+    fn div(self, rhs: i128) -> Self::Output {
+        self / SignedBits::<N>::from(rhs)
+    }
+}
+
+impl<N: BitWidth> Div<SignedBits<N>> for i128 {
+    type Output = SignedBits<N>;
+    fn div(self, rhs: SignedBits<N>) -> Self::Output {
+        SignedBits::<N>::from(self) / rhs
+    }
+}
+
+impl<N: BitWidth> DivAssign for SignedBits<N> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<N: BitWidth> DivAssign<i128> for SignedBits<N> {
+    fn div_assign(&mut self, rhs: i128) {
+        *self = *self / rhs;
+    }
+}
+
+impl<N: BitWidth> Rem for SignedBits<N> {
+    type Output = Self;
+    fn rem(self, rhs: Self) -> Self::Output {
+        if rhs.val == 0 {
+            self
+        } else {
+            signed_wrapped(self.val.wrapping_rem(rhs.val))
+        }
+    }
+}
+
+impl<N: BitWidth> Rem<i128> for SignedBits<N> {
+    type Output = SignedBits<N>;
+    fn rem(self, rhs: i128) -> Self::Output {
+        self % SignedBits::<N>::from(rhs)
+    }
+}
+
+impl<N: BitWidth> Rem<SignedBits<N>> for i128 {
+    type Output = SignedBits<N>;
+    fn rem(self, rhs: SignedBits<N>) -> Self::Output {
+        SignedBits::<N>::from(self) % rhs
+    }
+}
+
+impl<N: BitWidth> RemAssign for SignedBits<N> {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = *self % rhs;
+    }
+}
+
+impl<N: BitWidth> RemAssign<i128> for SignedBits<N> {
+    fn rem_assign(&mut self, rhs: i128) {
+        *self = *self % rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rhdl_typenum::*;
+
+    #[test]
+    fn test_unsigned_division_matches_u8() {
+        for i in 0..=255_u8 {
+            for j in 1..=255_u8 {
+                let x = Bits::<W8>::from(i as u128);
+                let y = Bits::<W8>::from(j as u128);
+                assert_eq!((x / y).val, (i / j) as u128);
+                assert_eq!((x % y).val, (i % j) as u128);
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_division_matches_i8() {
+        for i in i8::MIN..=i8::MAX {
+            for j in i8::MIN..=i8::MAX {
+                if j == 0 {
+                    continue;
+                }
+                let x = SignedBits::<W8>::from(i as i128);
+                let y = SignedBits::<W8>::from(j as i128);
+                assert_eq!((x / y).val, i.wrapping_div(j) as i128);
+                assert_eq!((x % y).val, i.wrapping_rem(j) as i128);
+            }
+        }
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        let x = Bits::<W8>::from(42);
+        assert_eq!(x / 0, Bits::<W8>::MAX);
+        assert_eq!(x % 0, x);
+        let x = SignedBits::<W8>::from(-42);
+        assert_eq!(x / 0, -1);
+        assert_eq!(x % 0, x);
+        let x = Bits::<W128>::MAX;
+        assert_eq!(x / 0, x);
+        let x = SignedBits::<W128>::MIN;
+        let minus_one = SignedBits::<W128>::from(-1);
+        assert_eq!(x / minus_one, x);
+        assert_eq!(x % minus_one, 0);
+    }
+
+    #[test]
+    fn test_div_assign() {
+        let mut x = Bits::<W12>::from(1000);
+        x /= 10;
+        assert_eq!(x, 100);
+        x %= 7;
+        assert_eq!(x, 2);
+        let mut x = SignedBits::<W12>::from(-1000);
+        x /= 10;
+        assert_eq!(x, -100);
+        x %= 7;
+        assert_eq!(x, -2);
+    }
+}
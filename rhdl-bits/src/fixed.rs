@@ -0,0 +1,627 @@
+use std::marker::PhantomData;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+use rhdl_typenum::*;
+
+use crate::xadd::XAdd;
+use crate::xmul::XMul;
+use crate::xneg::XNeg;
+use crate::xsgn::XSgn;
+use crate::xsub::XSub;
+use crate::{bits, signed, Bits, SignedBits};
+
+// A fixed point value with I integer bits and F fractional bits is
+// stored as a raw I+F bit integer, which is the value scaled by 2^F.
+// In hardware, it is nothing more than that raw integer, so every
+// operation on a fixed point value must map onto an operation on
+// the raw bits.  Addition and subtraction of values with the same
+// scale are just integer operations, and the width-growing multiply
+// of the raw values yields the product with F1+F2 fractional bits.
+// Changing the scale is a shift, which is why the number of
+// fractional bits to add or remove is given explicitly to
+// `xfrac`, `truncate` and `round`.
+
+const fn width<I: BitWidth, F: BitWidth>() -> usize {
+    let width = I::BITS + F::BITS;
+    assert!(width <= 128, "Fixed point values are limited to 128 bits");
+    width
+}
+
+const fn mask(width: usize) -> u128 {
+    u128::MAX >> (128 - width)
+}
+
+fn from_bools(v: &[bool], width: usize) -> u128 {
+    v.iter()
+        .take(width)
+        .rev()
+        .fold(0, |acc, b| (acc << 1) | (*b as u128))
+}
+
+const fn sign_extend(val: i128, width: usize) -> i128 {
+    (val << (128 - width)) >> (128 - width)
+}
+
+/// An unsigned fixed point value with `I` integer bits and `F`
+/// fractional bits.  The value is stored as an `I+F` bit unsigned
+/// integer, equal to the value times `2^F`.  So a `UFixed<W4, W4>`
+/// holds values from `0` to `15.9375` in steps of `1/16`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct UFixed<I, F> {
+    marker: PhantomData<(I, F)>,
+    val: u128,
+}
+
+/// A signed fixed point value with `I` integer bits (including the
+/// sign bit) and `F` fractional bits.  The value is stored as an `I+F`
+/// bit 2's complement integer, equal to the value times `2^F`.  So a
+/// `SFixed<W4, W4>` holds values from `-8` to `7.9375` in steps of `1/16`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SFixed<I, F> {
+    marker: PhantomData<(I, F)>,
+    val: i128,
+}
+
+impl<I: BitWidth, F: BitWidth> UFixed<I, F> {
+    pub const MAX: Self = Self::wrapped(u128::MAX);
+    pub const MIN: Self = Self::wrapped(0);
+    pub const ZERO: Self = Self::wrapped(0);
+    const fn wrapped(val: u128) -> Self {
+        Self {
+            marker: PhantomData,
+            val: val & mask(width::<I, F>()),
+        }
+    }
+    /// Build a fixed point value from its raw bits (i.e., the value
+    /// times `2^F`).  This can be used in a kernel.
+    /// ```
+    /// # use rhdl_bits::{W4, UFixed, alias::*};
+    /// let x = UFixed::<W4, W4>::from_raw(b8(0x18));
+    /// assert_eq!(x.to_f64(), 1.5);
+    /// ```
+    pub const fn from_raw(raw: Bits<Sum<I, F>>) -> Self
+    where
+        I: Add<F>,
+        Sum<I, F>: BitWidth,
+    {
+        Self::wrapped(raw.val)
+    }
+    /// Extract the raw bits of the value.  This can be used in a kernel.
+    pub const fn raw(self) -> Bits<Sum<I, F>>
+    where
+        I: Add<F>,
+        Sum<I, F>: BitWidth,
+    {
+        bits(self.val)
+    }
+    /// Convert a floating point value to the nearest fixed point value.
+    /// Panics if the value is out of range.
+    pub fn from_f64(value: f64) -> Self {
+        let scaled = (value * (F::BITS as f64).exp2()).round();
+        assert!(
+            (0.0..=Self::MAX.val as f64).contains(&scaled),
+            "{value} is out of range for UFixed<W{}, W{}>",
+            I::BITS,
+            F::BITS
+        );
+        Self::wrapped(scaled as u128)
+    }
+    /// Convert the value to floating point.  This is exact as long as
+    /// the value has no more than 53 bits.
+    pub fn to_f64(self) -> f64 {
+        self.val as f64 / (F::BITS as f64).exp2()
+    }
+    pub fn to_bools(self) -> Vec<bool> {
+        (0..width::<I, F>())
+            .map(|i| self.val & (1 << i) != 0)
+            .collect()
+    }
+    /// Build a value from its raw bits (LSB first).  This is the
+    /// inverse of [UFixed::to_bools].
+    pub fn from_bools(v: &[bool]) -> Self {
+        Self::wrapped(from_bools(v, width::<I, F>()))
+    }
+    /// Add `M` integer bits to the value.  This is always exact.
+    pub const fn xext<M>(self) -> UFixed<Sum<I, M>, F>
+    where
+        I: Add<M>,
+        M: BitWidth,
+        Sum<I, M>: BitWidth,
+    {
+        UFixed::wrapped(self.val)
+    }
+    /// Add `M` fractional bits to the value.  This is always exact.
+    pub const fn xfrac<M>(self) -> UFixed<I, Sum<F, M>>
+    where
+        F: Add<M>,
+        M: BitWidth,
+        Sum<F, M>: BitWidth,
+    {
+        UFixed::wrapped(self.val << M::BITS)
+    }
+    /// Remove `M` fractional bits from the value by discarding them,
+    /// which rounds towards zero.
+    pub const fn xtrunc<M>(self) -> UFixed<I, Diff<F, M>>
+    where
+        F: Sub<M>,
+        M: BitWidth,
+        Diff<F, M>: BitWidth,
+    {
+        UFixed::wrapped(self.val >> M::BITS)
+    }
+    /// Remove `M` fractional bits from the value by rounding to the
+    /// nearest value, with ties rounded up.  Like `+`, this wraps if
+    /// the result is too large, so use `xext` first if you need the
+    /// headroom.
+    pub const fn xround<M>(self) -> UFixed<I, Diff<F, M>>
+    where
+        F: Sub<M>,
+        M: BitWidth,
+        Diff<F, M>: BitWidth,
+    {
+        let len = width::<I, F>() - M::BITS + 1;
+        let half_up = (self.val >> (M::BITS - 1)).wrapping_add(1) & mask(len);
+        UFixed::wrapped(half_up >> 1)
+    }
+    /// Change the number of integer bits, wrapping if the value is
+    /// too large.  In a kernel, the new width must be inferred from
+    /// the context.
+    pub const fn resize_fixed<J: BitWidth>(self) -> UFixed<J, F> {
+        UFixed::wrapped(self.val)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> SFixed<I, F> {
+    pub const MAX: Self = Self {
+        marker: PhantomData,
+        val: i128::MAX >> (128 - width::<I, F>()),
+    };
+    pub const MIN: Self = Self {
+        marker: PhantomData,
+        val: i128::MIN >> (128 - width::<I, F>()),
+    };
+    pub const ZERO: Self = Self::wrapped(0);
+    const fn wrapped(val: i128) -> Self {
+        Self {
+            marker: PhantomData,
+            val: sign_extend(val, width::<I, F>()),
+        }
+    }
+    /// Build a fixed point value from its raw bits (i.e., the value
+    /// times `2^F`).  This can be used in a kernel.
+    /// ```
+    /// # use rhdl_bits::{W4, SFixed, alias::*};
+    /// let x = SFixed::<W4, W4>::from_raw(s8(-0x18));
+    /// assert_eq!(x.to_f64(), -1.5);
+    /// ```
+    pub const fn from_raw(raw: SignedBits<Sum<I, F>>) -> Self
+    where
+        I: Add<F>,
+        Sum<I, F>: BitWidth,
+    {
+        Self::wrapped(raw.val)
+    }
+    /// Extract the raw bits of the value.  This can be used in a kernel.
+    pub const fn raw(self) -> SignedBits<Sum<I, F>>
+    where
+        I: Add<F>,
+        Sum<I, F>: BitWidth,
+    {
+        signed(self.val)
+    }
+    /// Convert a floating point value to the nearest fixed point value.
+    /// Panics if the value is out of range.
+    pub fn from_f64(value: f64) -> Self {
+        let scaled = (value * (F::BITS as f64).exp2()).round();
+        assert!(
+            (Self::MIN.val as f64..=Self::MAX.val as f64).contains(&scaled),
+            "{value} is out of range for SFixed<W{}, W{}>",
+            I::BITS,
+            F::BITS
+        );
+        Self::wrapped(scaled as i128)
+    }
+    /// Convert the value to floating point.  This is exact as long as
+    /// the value has no more than 53 bits.
+    pub fn to_f64(self) -> f64 {
+        self.val as f64 / (F::BITS as f64).exp2()
+    }
+    pub fn to_bools(self) -> Vec<bool> {
+        (0..width::<I, F>())
+            .map(|i| self.val & (1 << i) != 0)
+            .collect()
+    }
+    /// Build a value from its raw 2's complement bits (LSB first).
+    /// This is the inverse of [SFixed::to_bools].
+    pub fn from_bools(v: &[bool]) -> Self {
+        Self::wrapped(from_bools(v, width::<I, F>()) as i128)
+    }
+    pub fn is_negative(self) -> bool {
+        self.val < 0
+    }
+    /// Add `M` integer bits to the value.  This is always exact.
+    pub const fn xext<M>(self) -> SFixed<Sum<I, M>, F>
+    where
+        I: Add<M>,
+        M: BitWidth,
+        Sum<I, M>: BitWidth,
+    {
+        SFixed::wrapped(self.val)
+    }
+    /// Add `M` fractional bits to the value.  This is always exact.
+    pub const fn xfrac<M>(self) -> SFixed<I, Sum<F, M>>
+    where
+        F: Add<M>,
+        M: BitWidth,
+        Sum<F, M>: BitWidth,
+    {
+        SFixed::wrapped(self.val << M::BITS)
+    }
+    /// Remove `M` fractional bits from the value by discarding them,
+    /// which rounds towards negative infinity.
+    pub const fn xtrunc<M>(self) -> SFixed<I, Diff<F, M>>
+    where
+        F: Sub<M>,
+        M: BitWidth,
+        Diff<F, M>: BitWidth,
+    {
+        SFixed::wrapped(self.val >> M::BITS)
+    }
+    /// Remove `M` fractional bits from the value by rounding to the
+    /// nearest value, with ties rounded up (towards positive infinity).
+    /// Like `+`, this wraps if the result is too large, so use `xext`
+    /// first if you need the headroom.
+    pub const fn xround<M>(self) -> SFixed<I, Diff<F, M>>
+    where
+        F: Sub<M>,
+        M: BitWidth,
+        Diff<F, M>: BitWidth,
+    {
+        let len = width::<I, F>() - M::BITS + 1;
+        let half_up = sign_extend((self.val >> (M::BITS - 1)).wrapping_add(1), len);
+        SFixed::wrapped(half_up >> 1)
+    }
+    /// Change the number of integer bits, wrapping if the value is
+    /// out of range.  In a kernel, the new width must be inferred from
+    /// the context.
+    pub const fn resize_fixed<J: BitWidth>(self) -> SFixed<J, F> {
+        SFixed::wrapped(self.val)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Default for UFixed<I, F> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Default for SFixed<I, F> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::fmt::Debug for UFixed<I, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}_uq{}.{}", self.to_f64(), I::BITS, F::BITS)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::fmt::Debug for SFixed<I, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}_sq{}.{}", self.to_f64(), I::BITS, F::BITS)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::fmt::Display for UFixed<I, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_f64())
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> std::fmt::Display for SFixed<I, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_f64())
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> PartialOrd for UFixed<I, F> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Ord for UFixed<I, F> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.val.cmp(&other.val)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> PartialOrd for SFixed<I, F> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Ord for SFixed<I, F> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.val.cmp(&other.val)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Add for UFixed<I, F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::wrapped(self.val.wrapping_add(rhs.val))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> AddAssign for UFixed<I, F> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Sub for UFixed<I, F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::wrapped(self.val.wrapping_sub(rhs.val))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> SubAssign for UFixed<I, F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Add for SFixed<I, F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Self::wrapped(self.val.wrapping_add(rhs.val))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> AddAssign for SFixed<I, F> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Sub for SFixed<I, F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Self::wrapped(self.val.wrapping_sub(rhs.val))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> SubAssign for SFixed<I, F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Neg for SFixed<I, F> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::wrapped(self.val.wrapping_neg())
+    }
+}
+
+impl<I, J, F> XAdd<UFixed<J, F>> for UFixed<I, F>
+where
+    I: BitWidth + Max<J>,
+    J: BitWidth,
+    F: BitWidth,
+    Maximum<I, J>: Add<W1>,
+    Sum<Maximum<I, J>, W1>: BitWidth,
+{
+    type Output = UFixed<Sum<Maximum<I, J>, W1>, F>;
+    fn xadd(self, rhs: UFixed<J, F>) -> Self::Output {
+        UFixed::wrapped(self.val.wrapping_add(rhs.val))
+    }
+}
+
+impl<I, J, F> XAdd<SFixed<J, F>> for SFixed<I, F>
+where
+    I: BitWidth + Max<J>,
+    J: BitWidth,
+    F: BitWidth,
+    Maximum<I, J>: Add<W1>,
+    Sum<Maximum<I, J>, W1>: BitWidth,
+{
+    type Output = SFixed<Sum<Maximum<I, J>, W1>, F>;
+    fn xadd(self, rhs: SFixed<J, F>) -> Self::Output {
+        SFixed::wrapped(self.val.wrapping_add(rhs.val))
+    }
+}
+
+impl<I, J, F> XSub<UFixed<J, F>> for UFixed<I, F>
+where
+    I: BitWidth + Max<J>,
+    J: BitWidth,
+    F: BitWidth,
+    Maximum<I, J>: Add<W1>,
+    Sum<Maximum<I, J>, W1>: BitWidth,
+{
+    type Output = SFixed<Sum<Maximum<I, J>, W1>, F>;
+    fn xsub(self, rhs: UFixed<J, F>) -> Self::Output {
+        SFixed::wrapped((self.val as i128).wrapping_sub(rhs.val as i128))
+    }
+}
+
+impl<I, J, F> XSub<SFixed<J, F>> for SFixed<I, F>
+where
+    I: BitWidth + Max<J>,
+    J: BitWidth,
+    F: BitWidth,
+    Maximum<I, J>: Add<W1>,
+    Sum<Maximum<I, J>, W1>: BitWidth,
+{
+    type Output = SFixed<Sum<Maximum<I, J>, W1>, F>;
+    fn xsub(self, rhs: SFixed<J, F>) -> Self::Output {
+        SFixed::wrapped(self.val.wrapping_sub(rhs.val))
+    }
+}
+
+impl<I, J, F, G> XMul<UFixed<J, G>> for UFixed<I, F>
+where
+    I: BitWidth + Add<J>,
+    J: BitWidth,
+    F: BitWidth + Add<G>,
+    G: BitWidth,
+    Sum<I, J>: BitWidth,
+    Sum<F, G>: BitWidth,
+{
+    type Output = UFixed<Sum<I, J>, Sum<F, G>>;
+    fn xmul(self, rhs: UFixed<J, G>) -> Self::Output {
+        UFixed::wrapped(self.val.wrapping_mul(rhs.val))
+    }
+}
+
+impl<I, J, F, G> XMul<SFixed<J, G>> for SFixed<I, F>
+where
+    I: BitWidth + Add<J>,
+    J: BitWidth,
+    F: BitWidth + Add<G>,
+    G: BitWidth,
+    Sum<I, J>: BitWidth,
+    Sum<F, G>: BitWidth,
+{
+    type Output = SFixed<Sum<I, J>, Sum<F, G>>;
+    fn xmul(self, rhs: SFixed<J, G>) -> Self::Output {
+        SFixed::wrapped(self.val.wrapping_mul(rhs.val))
+    }
+}
+
+impl<I, F> XNeg for UFixed<I, F>
+where
+    I: BitWidth + Add<W1>,
+    F: BitWidth,
+    Sum<I, W1>: BitWidth,
+{
+    type Output = SFixed<Sum<I, W1>, F>;
+    fn xneg(self) -> Self::Output {
+        SFixed::wrapped((self.val as i128).wrapping_neg())
+    }
+}
+
+impl<I, F> XNeg for SFixed<I, F>
+where
+    I: BitWidth + Add<W1>,
+    F: BitWidth,
+    Sum<I, W1>: BitWidth,
+{
+    type Output = SFixed<Sum<I, W1>, F>;
+    fn xneg(self) -> Self::Output {
+        SFixed::wrapped(self.val.wrapping_neg())
+    }
+}
+
+impl<I, F> XSgn for UFixed<I, F>
+where
+    I: BitWidth + Add<W1>,
+    F: BitWidth,
+    Sum<I, W1>: BitWidth,
+{
+    type Output = SFixed<Sum<I, W1>, F>;
+    fn xsgn(self) -> Self::Output {
+        SFixed::wrapped(self.val as i128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::*;
+
+    #[test]
+    fn test_f64_round_trip() {
+        for i in 0..=255 {
+            let x = UFixed::<W4, W4>::from_raw(b8(i));
+            assert_eq!(UFixed::<W4, W4>::from_f64(x.to_f64()), x);
+            assert_eq!(x.to_f64(), i as f64 / 16.0);
+        }
+        for i in -128..=127 {
+            let x = SFixed::<W4, W4>::from_raw(s8(i));
+            assert_eq!(SFixed::<W4, W4>::from_f64(x.to_f64()), x);
+            assert_eq!(x.to_f64(), i as f64 / 16.0);
+        }
+        assert_eq!(UFixed::<W4, W4>::MAX.to_f64(), 15.9375);
+        assert_eq!(SFixed::<W4, W4>::MIN.to_f64(), -8.0);
+        assert_eq!(SFixed::<W4, W4>::MAX.to_f64(), 7.9375);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_f64_out_of_range() {
+        let _ = SFixed::<W4, W4>::from_f64(8.0);
+    }
+
+    #[test]
+    fn test_xops_track_scale() {
+        let a = SFixed::<W4, W4>::from_f64(-2.25);
+        let b = SFixed::<W2, W4>::from_f64(1.5);
+        assert_eq!(a.xadd(b).to_f64(), -0.75);
+        assert_eq!(a.xsub(b).to_f64(), -3.75);
+        let c = SFixed::<W3, W2>::from_f64(-1.75);
+        let p: SFixed<W7, W6> = a.xmul(c);
+        assert_eq!(p.to_f64(), 3.9375);
+        let u = UFixed::<W4, W4>::from_f64(15.9375);
+        assert_eq!(u.xneg().to_f64(), -15.9375);
+        assert_eq!(u.xsgn().to_f64(), 15.9375);
+        assert_eq!(u.xsub(u.xfrac::<W2>().xtrunc::<W2>()).to_f64(), 0.0);
+    }
+
+    #[test]
+    fn test_wrapping_arithmetic() {
+        let a = UFixed::<W2, W2>::from_f64(3.75);
+        let b = UFixed::<W2, W2>::from_f64(0.5);
+        assert_eq!((a + b).to_f64(), 0.25);
+        assert_eq!((b - a).to_f64(), 0.75);
+        let c = SFixed::<W2, W2>::MIN;
+        assert_eq!(-c, c);
+        assert!(c < SFixed::<W2, W2>::ZERO);
+    }
+
+    #[test]
+    fn test_truncate_and_round_unsigned() {
+        for i in 0..=255 {
+            let x = UFixed::<W4, W4>::from_raw(b8(i));
+            let value = x.to_f64();
+            assert_eq!(x.xtrunc::<W3>().to_f64(), (value * 2.0).floor() / 2.0);
+            let rounded = (value * 2.0 + 0.5).floor() / 2.0;
+            assert_eq!(x.xround::<W3>().to_f64(), rounded % 16.0);
+            assert_eq!(
+                x.xround::<W1>().to_f64(),
+                ((value * 8.0 + 0.5).floor() / 8.0) % 16.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_truncate_and_round_signed() {
+        for i in -128..=127 {
+            let x = SFixed::<W4, W4>::from_raw(s8(i));
+            let value = x.to_f64();
+            assert_eq!(x.xtrunc::<W2>().to_f64(), (value * 4.0).floor() / 4.0);
+            let rounded = (value * 4.0 + 0.5).floor() / 4.0;
+            let wrapped = if rounded >= 8.0 {
+                rounded - 16.0
+            } else {
+                rounded
+            };
+            assert_eq!(x.xround::<W2>().to_f64(), wrapped);
+        }
+    }
+
+    #[test]
+    fn test_resize_fixed() {
+        let x = SFixed::<W4, W4>::from_f64(-1.5);
+        assert_eq!(x.resize_fixed::<W2>().to_f64(), -1.5);
+        assert_eq!(x.resize_fixed::<W8>().to_f64(), -1.5);
+        let x = UFixed::<W4, W4>::from_f64(5.25);
+        assert_eq!(x.resize_fixed::<W2>().to_f64(), 1.25);
+        assert_eq!(x.xext::<W4>().to_f64(), 5.25);
+    }
+}
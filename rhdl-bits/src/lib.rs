@@ -84,11 +84,17 @@
 //! let bits: b8 = 0b1101_1010_u128.into(); // Short form (not the same as u8)
 //! ```
 //!
-//! Note that the [Bits] type only supports up to 128 bit values.  Larger bit vectors
-//! can easily be constructed using data structures (arrays, structs, enums, tuples).
-//! But arithmetic on them is not supported by default.  You will need to provide your
-//! own arithmetic implementations for these types.  This is a limitation of the
-//! [Bits] type, but it is a limitation that is not likely to be a problem in practice.
+//! Note that the [Bits] type only supports up to 128 bit values.  For wider values
+//! (up to 1024 bits), use the [WideBits] and [WideSignedBits] types, which store the
+//! value in multiple 64 bit limbs, but otherwise behave the same way.  The aliases
+//! `b129` through `b1024` (and `s129` through `s1024`) refer to these types.  Use
+//! [Bits::widen] and [WideBits::narrow] to move between the two.
+//! ```
+//! # use rhdl_bits::alias::*;
+//! let x: b256 = b64(0xDEAD_BEEF).widen();
+//! let y = (x << 200) + x;
+//! assert_eq!((y >> 200).narrow::<rhdl_bits::W32>(), 0xDEAD_BEEF);
+//! ```
 //! Practical hardware limitations can mean that performing arithmetic on very long
 //! bit vectors is likely to be very slow.
 //!
@@ -256,6 +262,75 @@
 //! assert_eq!(x, 0);
 //! ```
 //!
+//! ## Division and Remainder
+//! The [Div](std::ops::Div) and [Rem](std::ops::Rem) traits (and their assigning
+//! counterparts) are implemented for [Bits] and [SignedBits].  Division truncates
+//! towards zero, as it does for the built in integers.  Unlike the built in integers,
+//! hardware cannot panic, so dividing by zero has a defined result: the quotient is
+//! all ones (which is `-1` for [SignedBits]), and the remainder is the dividend.
+//! Dividing the most negative value by `-1` wraps (to the most negative value).
+//! ```
+//! # use rhdl_bits::alias::*;
+//! assert_eq!(b8(200) / b8(7), b8(28));
+//! assert_eq!(s8(-7) % s8(2), s8(-1));
+//! assert_eq!(b8(200) / b8(0), b8(255));
+//! assert_eq!(s8(-128) / s8(-1), s8(-128));
+//! ```
+//!
+//! In a kernel, division by a constant is cheap (a shift for powers of two, and
+//! a multiply otherwise).  Division by a value that is not known at compile time
+//! requires a combinational divider, which is only supported for narrow values.
+//!
+//! ## Saturating and Checked Arithmetic
+//! When wrapping is not what you want (as is often the case in DSP code), both [Bits]
+//! and [SignedBits] provide `saturating_add`, `saturating_sub`, `checked_add`,
+//! `checked_sub`, `overflowing_add` and `overflowing_sub`, which behave like the
+//! methods of the same name on the built in integers.  The `saturate` method converts
+//! to a different width, clamping the value to the range of the new type instead
+//! of truncating it.  All of these can be used in kernel functions.
+//! ```
+//! # use rhdl_bits::{W4, alias::*};
+//! assert_eq!(b8(200).saturating_add(b8(100)), b8(255));
+//! assert_eq!(s8(-100).checked_sub(s8(100)), None);
+//! assert_eq!(s8(100).saturate::<W4>(), s4(7));
+//! ```
+//!
+//! ## Bit Manipulation
+//! [Bits] provides `count_ones`, `leading_zeros`, `trailing_zeros` and `reverse_bits`,
+//! which return a value of the same width, along with `one_hot_to_binary` and
+//! `binary_to_one_hot`, which convert between an index and a one-hot value of the
+//! given width.  These can be used in kernel functions, where each is a single
+//! operation (and not a loop over the bits).
+//! ```
+//! # use rhdl_bits::{W3, W8, alias::*};
+//! assert_eq!(b8(0b0110_1000).count_ones(), b8(3));
+//! assert_eq!(b8(0b0110_1000).trailing_zeros(), b8(3));
+//! assert_eq!(b8(0b0110_1000).one_hot_to_binary::<W3>(), b3(7));
+//! assert_eq!(b3(3).binary_to_one_hot::<W8>(), b8(0b0000_1000));
+//! ```
+//!
+//! ## Fixed Point
+//! The [UFixed] and [SFixed] types hold fixed point values with `I` integer bits
+//! and `F` fractional bits, stored as the raw `I+F` bit value scaled by `2^F`.
+//! Addition, subtraction and comparison work on values of the same type.  The
+//! width-growing `xadd`, `xsub`, `xmul`, `xneg` and `xsgn` track the scale in the
+//! type, so that, for example, multiplying a `UFixed<W4, W4>` by a `UFixed<W2, W6>`
+//! gives a `UFixed<W6, W10>`.  The number of fractional bits is changed with
+//! `xfrac` (exact), `xtrunc` and `xround`, and the number of integer bits with
+//! `xext` (exact) and `resize_fixed`.  All of these can be used in kernel functions,
+//! along with `from_raw` and `raw` to move between fixed point values and [Bits].
+//! ```
+//! # use rhdl_bits::{W1, W2, W4, W6, W8, W10, UFixed, SFixed, xmul::XMul};
+//! let x = UFixed::<W4, W4>::from_f64(2.25);
+//! let y = UFixed::<W2, W6>::from_f64(1.5);
+//! let z: UFixed<W6, W10> = x.xmul(y);
+//! assert_eq!(z.to_f64(), 3.375);
+//! assert_eq!(z.xround::<W8>().to_f64(), 3.5);
+//! assert_eq!(z.xtrunc::<W8>().to_f64(), 3.25);
+//! let w = SFixed::<W2, W2>::from_f64(-1.25);
+//! assert_eq!(w.xround::<W1>().to_f64(), -1.0);
+//! ```
+//!
 //! ## Bitwise Logical Operators
 //!
 //! All four of the standard Rust logical operators are supported for both [Bits] and [SignedBits].
@@ -387,8 +462,14 @@ pub mod add;
 #[doc(hidden)]
 pub mod and;
 #[doc(hidden)]
+pub mod bit_manip;
+#[doc(hidden)]
 pub mod bits_impl;
 #[doc(hidden)]
+pub mod div;
+#[doc(hidden)]
+pub mod fixed;
+#[doc(hidden)]
 pub mod mul;
 #[doc(hidden)]
 pub mod neg;
@@ -397,6 +478,8 @@ pub mod not;
 #[doc(hidden)]
 pub mod or;
 #[doc(hidden)]
+pub mod saturating;
+#[doc(hidden)]
 pub mod shl;
 #[doc(hidden)]
 pub mod shr;
@@ -405,6 +488,10 @@ pub mod signed_bits_impl;
 #[doc(hidden)]
 pub mod sub;
 #[doc(hidden)]
+pub mod wide_bits_impl;
+#[doc(hidden)]
+pub mod wide_signed_bits_impl;
+#[doc(hidden)]
 pub mod xadd;
 #[doc(hidden)]
 pub mod xmul;
@@ -438,6 +525,10 @@ pub mod alias {
     //! On the other hand, you _can_ use `bool` in your design instead of `b1`.  The two types _behave_
     //! the same way, even though they are different.
     //!
+    //! Widths from 129 to 1024 bits are also available as `b129` through `b1024`
+    //! and `s129` through `s1024`, which are aliases for the multi-limb
+    //! [WideBits](crate::WideBits) and [WideSignedBits](crate::WideSignedBits) types.
+    //!
     seq_macro::seq!(N in 1..=128 {
         #(
             pub use crate::bits_impl::b~N;
@@ -448,12 +539,24 @@ pub mod alias {
             pub use crate::signed_bits_impl::s~N;
         )*
     });
+    seq_macro::seq!(N in 129..=1024 {
+        #(
+            pub use crate::wide_bits_impl::b~N;
+            pub use crate::wide_signed_bits_impl::s~N;
+        )*
+    });
 }
 
 pub use bits_impl::bits;
 pub use bits_impl::Bits;
+pub use fixed::SFixed;
+pub use fixed::UFixed;
 pub use signed_bits_impl::signed;
 pub use signed_bits_impl::SignedBits;
+pub use wide_bits_impl::wide_bits;
+pub use wide_bits_impl::WideBits;
+pub use wide_signed_bits_impl::wide_signed;
+pub use wide_signed_bits_impl::WideSignedBits;
 
 pub use rhdl_typenum::*;
 
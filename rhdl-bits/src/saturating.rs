@@ -0,0 +1,231 @@
+use rhdl_typenum::*;
+
+use crate::{bits, signed, Bits, SignedBits};
+
+// The saturating, checked and overflowing forms of addition and
+// subtraction.  These mirror the methods of the same name on the
+// Rust integer types, but respect the width of the [Bits] or
+// [SignedBits] value.  They can also be used in kernel functions.
+
+impl<N: BitWidth> Bits<N> {
+    /// Add two values, returning the wrapped sum along with a flag
+    /// that indicates if the addition overflowed.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(b8(200).overflowing_add(b8(100)), (b8(44), true));
+    /// assert_eq!(b8(100).overflowing_add(b8(100)), (b8(200), false));
+    /// ```
+    pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let sum = self + rhs;
+        (sum, sum < self)
+    }
+    /// Subtract two values, returning the wrapped difference along with
+    /// a flag that indicates if the subtraction underflowed.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(b8(1).overflowing_sub(b8(2)), (b8(255), true));
+    /// ```
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        (self - rhs, self < rhs)
+    }
+    /// Add two values, clamping the result at the maximum value
+    /// instead of wrapping.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(b8(200).saturating_add(b8(100)), b8(255));
+    /// ```
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        match self.overflowing_add(rhs) {
+            (_, true) => Self::MAX,
+            (sum, false) => sum,
+        }
+    }
+    /// Subtract two values, clamping the result at zero instead
+    /// of wrapping.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(b8(1).saturating_sub(b8(2)), b8(0));
+    /// ```
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        match self.overflowing_sub(rhs) {
+            (_, true) => Self::ZERO,
+            (diff, false) => diff,
+        }
+    }
+    /// Add two values, returning `None` if the addition overflowed.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(b8(200).checked_add(b8(100)), None);
+    /// assert_eq!(b8(100).checked_add(b8(100)), Some(b8(200)));
+    /// ```
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (_, true) => None,
+            (sum, false) => Some(sum),
+        }
+    }
+    /// Subtract two values, returning `None` if the subtraction underflowed.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(b8(1).checked_sub(b8(2)), None);
+    /// ```
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_sub(rhs) {
+            (_, true) => None,
+            (diff, false) => Some(diff),
+        }
+    }
+    /// Convert to a value with `M` bits, clamping the value to the
+    /// largest value that fits in `M` bits, instead of truncating it.
+    /// ```
+    /// # use rhdl_bits::{alias::*, W4};
+    /// assert_eq!(b8(200).saturate::<W4>(), b4(15));
+    /// assert_eq!(b8(7).saturate::<W4>(), b4(7));
+    /// ```
+    pub fn saturate<M: BitWidth>(self) -> Bits<M> {
+        if self.val > Bits::<M>::MAX.val {
+            Bits::<M>::MAX
+        } else {
+            bits(self.val)
+        }
+    }
+}
+
+impl<N: BitWidth> SignedBits<N> {
+    /// Add two values, returning the wrapped sum along with a flag
+    /// that indicates if the addition overflowed.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(s8(100).overflowing_add(s8(100)), (s8(-56), true));
+    /// ```
+    pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let sum = self + rhs;
+        let overflow =
+            (self.is_negative() == rhs.is_negative()) && (sum.is_negative() != self.is_negative());
+        (sum, overflow)
+    }
+    /// Subtract two values, returning the wrapped difference along with
+    /// a flag that indicates if the subtraction overflowed.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(s8(-100).overflowing_sub(s8(100)), (s8(56), true));
+    /// ```
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        let diff = self - rhs;
+        let overflow =
+            (self.is_negative() != rhs.is_negative()) && (diff.is_negative() != self.is_negative());
+        (diff, overflow)
+    }
+    /// Add two values, clamping the result to the range of
+    /// the type instead of wrapping.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(s8(100).saturating_add(s8(100)), s8(127));
+    /// assert_eq!(s8(-100).saturating_add(s8(-100)), s8(-128));
+    /// ```
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        match self.overflowing_add(rhs) {
+            (_, true) if self.is_negative() => Self::MIN,
+            (_, true) => Self::MAX,
+            (sum, false) => sum,
+        }
+    }
+    /// Subtract two values, clamping the result to the range of
+    /// the type instead of wrapping.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(s8(-100).saturating_sub(s8(100)), s8(-128));
+    /// ```
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        match self.overflowing_sub(rhs) {
+            (_, true) if self.is_negative() => Self::MIN,
+            (_, true) => Self::MAX,
+            (diff, false) => diff,
+        }
+    }
+    /// Add two values, returning `None` if the addition overflowed.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(s8(100).checked_add(s8(100)), None);
+    /// assert_eq!(s8(100).checked_add(s8(-100)), Some(s8(0)));
+    /// ```
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (_, true) => None,
+            (sum, false) => Some(sum),
+        }
+    }
+    /// Subtract two values, returning `None` if the subtraction overflowed.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// assert_eq!(s8(-100).checked_sub(s8(100)), None);
+    /// ```
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_sub(rhs) {
+            (_, true) => None,
+            (diff, false) => Some(diff),
+        }
+    }
+    /// Convert to a value with `M` bits, clamping the value to the
+    /// range that can be represented in `M` bits, instead of truncating it.
+    /// ```
+    /// # use rhdl_bits::{alias::*, W4};
+    /// assert_eq!(s8(100).saturate::<W4>(), s4(7));
+    /// assert_eq!(s8(-100).saturate::<W4>(), s4(-8));
+    /// assert_eq!(s8(-3).saturate::<W4>(), s4(-3));
+    /// ```
+    pub fn saturate<M: BitWidth>(self) -> SignedBits<M> {
+        signed(
+            self.val
+                .clamp(SignedBits::<M>::min_value(), SignedBits::<M>::max_value()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsigned_saturating_ops_match_u8() {
+        for a in 0..=255_u8 {
+            for b in 0..=255_u8 {
+                let x = bits::<W8>(a as u128);
+                let y = bits::<W8>(b as u128);
+                assert_eq!(x.saturating_add(y).raw(), a.saturating_add(b) as u128);
+                assert_eq!(x.saturating_sub(y).raw(), a.saturating_sub(b) as u128);
+                assert_eq!(
+                    x.checked_add(y).map(|x| x.raw()),
+                    a.checked_add(b).map(|x| x as u128)
+                );
+                assert_eq!(x.overflowing_sub(y).1, a.overflowing_sub(b).1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_saturating_ops_match_i8() {
+        for a in i8::MIN..=i8::MAX {
+            for b in i8::MIN..=i8::MAX {
+                let x = signed::<W8>(a as i128);
+                let y = signed::<W8>(b as i128);
+                assert_eq!(x.saturating_add(y).raw(), a.saturating_add(b) as i128);
+                assert_eq!(x.saturating_sub(y).raw(), a.saturating_sub(b) as i128);
+                assert_eq!(
+                    x.checked_sub(y).map(|x| x.raw()),
+                    a.checked_sub(b).map(|x| x as i128)
+                );
+                assert_eq!(x.overflowing_add(y).1, a.overflowing_add(b).1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_saturating_ops_at_full_width() {
+        let a = Bits::<W128>::MAX;
+        assert_eq!(a.saturating_add(bits(1)), a);
+        let a = SignedBits::<W128>::MIN;
+        assert_eq!(a.saturating_sub(signed(1)), a);
+        assert_eq!(a.saturate::<W8>(), signed::<W8>(-128));
+    }
+}
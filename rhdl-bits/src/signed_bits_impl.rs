@@ -241,6 +241,11 @@ impl<N: BitWidth> SignedBits<N> {
     pub fn to_bools(self) -> Vec<bool> {
         self.as_unsigned().to_bools()
     }
+    /// Build a value from its 2's complement bits (LSB first).
+    /// This is the inverse of [SignedBits::to_bools].
+    pub fn from_bools(v: &[bool]) -> Self {
+        Bits::<N>::from_bools(v).as_signed()
+    }
     pub fn any(self) -> bool {
         self.val != 0
     }
@@ -0,0 +1,788 @@
+#![allow(non_camel_case_types)]
+use std::ops::{Add, Sub};
+
+use crate::{
+    bits_impl::bits_masked, signed_bits_impl::signed_wrapped,
+    wide_signed_bits_impl::WideSignedBits, Bits, SignedBits,
+};
+use rhdl_typenum::*;
+use seq_macro::seq;
+
+/// The number of 64 bit limbs used to store a wide value.  This
+/// sets the upper limit on the width of the [WideBits] and
+/// [WideSignedBits] types at 1024 bits.
+pub const LIMBS: usize = 16;
+
+/// The maximum number of bits that can be held in a wide value.
+pub const MAX_WIDE_BITS: usize = LIMBS * 64;
+
+/// The [WideBits] type is the multi-limb counterpart of [Bits].  It
+/// holds unsigned bit vectors of up to 1024 bits, so that things like
+/// 256 bit data buses, 512 bit cache lines or wide hash states can be
+/// modelled and manipulated with the same (wrapping) semantics as the
+/// narrower [Bits] type.  The value is stored as little endian 64 bit
+/// limbs, and bits above the width of the type are always zero.
+///
+/// The [Bits] type is still the right choice for anything up to 128
+/// bits.  The [WideBits] type is slower, since every operation has
+/// to loop over the limbs.  You can move between the two using
+/// [Bits::widen] and [WideBits::narrow].
+///
+/// The width arithmetic used by the extending operations (like `xadd`,
+/// `xmul` and `xext`) is table driven, and the table for the wide
+/// widths is not complete.  A [WideBits] can be extended by, or
+/// combined with, a value that is no wider than itself and is either
+/// at most 8 bits wide or a multiple of 32 bits wide.  Any two narrow
+/// widths can be added, even if the result is wider than 128 bits (so
+/// `Sum<W128, W1>` is `W129`), which bridges the narrow and wide types.
+///
+/// ```
+/// # use rhdl_bits::alias::*;
+/// let x: b256 = b256(u128::MAX);
+/// let y = x + x;
+/// assert_eq!(y.narrow::<rhdl_bits::W8>(), 0xFE);
+/// assert!(y > x);
+/// // 32 bits is a supported extension of a wide value
+/// let z: b288 = x.xext::<rhdl_bits::W32>();
+/// assert_eq!(z, x.resize());
+/// ```
+///
+/// Extending a wide value by any other width (here 40 bits, which is
+/// neither at most 8 bits nor a multiple of 32 bits) does not compile.
+///
+/// ```compile_fail
+/// # use rhdl_bits::alias::*;
+/// let x: b256 = b256(42);
+/// let y = x.xext::<rhdl_bits::W40>();
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct WideBits<Len> {
+    pub(crate) marker: std::marker::PhantomData<Len>,
+    pub(crate) limbs: [u64; LIMBS],
+}
+
+// Helper functions that operate on the raw limbs.  These are shared
+// with the [WideSignedBits] type, which uses the same storage.
+
+pub(crate) const fn limb_count(bits: usize) -> usize {
+    bits.div_ceil(64)
+}
+
+pub(crate) const fn mask_limbs(bits: usize) -> [u64; LIMBS] {
+    assert!(bits <= MAX_WIDE_BITS);
+    let mut limbs = [0; LIMBS];
+    let mut ndx = 0;
+    while ndx < LIMBS {
+        if (ndx + 1) * 64 <= bits {
+            limbs[ndx] = u64::MAX;
+        } else if ndx * 64 < bits {
+            limbs[ndx] = u64::MAX >> (64 - (bits - ndx * 64));
+        }
+        ndx += 1;
+    }
+    limbs
+}
+
+pub(crate) const fn masked(mut limbs: [u64; LIMBS], bits: usize) -> [u64; LIMBS] {
+    let mask = mask_limbs(bits);
+    let mut ndx = 0;
+    while ndx < LIMBS {
+        limbs[ndx] &= mask[ndx];
+        ndx += 1;
+    }
+    limbs
+}
+
+pub(crate) const fn from_u128(value: u128) -> [u64; LIMBS] {
+    let mut limbs = [0; LIMBS];
+    limbs[0] = value as u64;
+    limbs[1] = (value >> 64) as u64;
+    limbs
+}
+
+pub(crate) const fn single_bit(ndx: usize) -> [u64; LIMBS] {
+    let mut limbs = [0; LIMBS];
+    limbs[ndx / 64] = 1 << (ndx % 64);
+    limbs
+}
+
+pub(crate) const fn from_i128(value: i128) -> [u64; LIMBS] {
+    let fill = if value < 0 { u64::MAX } else { 0 };
+    let mut limbs = [fill; LIMBS];
+    limbs[0] = value as u64;
+    limbs[1] = (value >> 64) as u64;
+    limbs
+}
+
+pub(crate) fn to_u128(limbs: &[u64; LIMBS]) -> u128 {
+    (limbs[0] as u128) | ((limbs[1] as u128) << 64)
+}
+
+pub(crate) fn get_bit(limbs: &[u64; LIMBS], ndx: usize) -> bool {
+    limbs[ndx / 64] & (1 << (ndx % 64)) != 0
+}
+
+pub(crate) fn is_zero(limbs: &[u64; LIMBS]) -> bool {
+    limbs.iter().all(|x| *x == 0)
+}
+
+pub(crate) fn wrapping_add(a: &[u64; LIMBS], b: &[u64; LIMBS], bits: usize) -> [u64; LIMBS] {
+    let mut result = [0; LIMBS];
+    let mut carry = false;
+    for ndx in 0..limb_count(bits) {
+        let (sum, c1) = a[ndx].overflowing_add(b[ndx]);
+        let (sum, c2) = sum.overflowing_add(carry as u64);
+        result[ndx] = sum;
+        carry = c1 || c2;
+    }
+    masked(result, bits)
+}
+
+pub(crate) fn wrapping_sub(a: &[u64; LIMBS], b: &[u64; LIMBS], bits: usize) -> [u64; LIMBS] {
+    let mut result = [0; LIMBS];
+    let mut borrow = false;
+    for ndx in 0..limb_count(bits) {
+        let (diff, b1) = a[ndx].overflowing_sub(b[ndx]);
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        result[ndx] = diff;
+        borrow = b1 || b2;
+    }
+    masked(result, bits)
+}
+
+pub(crate) fn wrapping_mul(a: &[u64; LIMBS], b: &[u64; LIMBS], bits: usize) -> [u64; LIMBS] {
+    // Schoolbook multiplication, keeping only the limbs that
+    // survive the truncation to `bits`.
+    let count = limb_count(bits);
+    let mut result = [0; LIMBS];
+    for i in 0..count {
+        let mut carry = 0_u128;
+        for j in 0..count - i {
+            let acc = (a[i] as u128) * (b[j] as u128) + (result[i + j] as u128) + carry;
+            result[i + j] = acc as u64;
+            carry = acc >> 64;
+        }
+    }
+    masked(result, bits)
+}
+
+// Restoring (shift and subtract) division, one bit at a time.  A
+// divisor of zero gives a quotient of all ones and leaves the
+// dividend as the remainder, which is what the hardware does.
+pub(crate) fn div_rem(
+    a: &[u64; LIMBS],
+    b: &[u64; LIMBS],
+    bits: usize,
+) -> ([u64; LIMBS], [u64; LIMBS]) {
+    if is_zero(b) {
+        return (mask_limbs(bits), *a);
+    }
+    let mut quotient = [0; LIMBS];
+    let mut remainder = [0; LIMBS];
+    for ndx in (0..bits).rev() {
+        // The shifted remainder can need one more bit than we have,
+        // in which case it is certainly larger than the divisor, and
+        // the wrapping subtraction still gives the right answer.
+        let carry = get_bit(&remainder, bits - 1);
+        remainder = shl(&remainder, 1, bits);
+        if get_bit(a, ndx) {
+            remainder[0] |= 1;
+        }
+        if carry || cmp_unsigned(&remainder, b) != std::cmp::Ordering::Less {
+            remainder = wrapping_sub(&remainder, b, bits);
+            quotient[ndx / 64] |= 1 << (ndx % 64);
+        }
+    }
+    (quotient, remainder)
+}
+
+pub(crate) fn wrapping_div(a: &[u64; LIMBS], b: &[u64; LIMBS], bits: usize) -> [u64; LIMBS] {
+    div_rem(a, b, bits).0
+}
+
+pub(crate) fn wrapping_rem(a: &[u64; LIMBS], b: &[u64; LIMBS], bits: usize) -> [u64; LIMBS] {
+    div_rem(a, b, bits).1
+}
+
+pub(crate) fn not(a: &[u64; LIMBS], bits: usize) -> [u64; LIMBS] {
+    masked(a.map(|x| !x), bits)
+}
+
+pub(crate) fn zip(a: &[u64; LIMBS], b: &[u64; LIMBS], f: fn(u64, u64) -> u64) -> [u64; LIMBS] {
+    std::array::from_fn(|ndx| f(a[ndx], b[ndx]))
+}
+
+pub(crate) fn shl(a: &[u64; LIMBS], shift: u128, bits: usize) -> [u64; LIMBS] {
+    if shift >= bits as u128 {
+        return [0; LIMBS];
+    }
+    let shift = shift as usize;
+    let (limb_shift, bit_shift) = (shift / 64, shift % 64);
+    let mut result = [0; LIMBS];
+    for ndx in (limb_shift..limb_count(bits)).rev() {
+        let src = ndx - limb_shift;
+        result[ndx] = a[src] << bit_shift;
+        if bit_shift != 0 && src > 0 {
+            result[ndx] |= a[src - 1] >> (64 - bit_shift);
+        }
+    }
+    masked(result, bits)
+}
+
+// Shift right, filling the vacated bits with `fill`, which is
+// all ones for an arithmetic shift of a negative value.
+pub(crate) fn shr(a: &[u64; LIMBS], shift: u128, bits: usize, fill: bool) -> [u64; LIMBS] {
+    let fill_limbs = masked([if fill { u64::MAX } else { 0 }; LIMBS], bits);
+    if shift >= bits as u128 {
+        return fill_limbs;
+    }
+    let shift = shift as usize;
+    let mut result = [0; LIMBS];
+    for ndx in 0..bits {
+        let bit = if ndx + shift < bits {
+            get_bit(a, ndx + shift)
+        } else {
+            fill
+        };
+        if bit {
+            result[ndx / 64] |= 1 << (ndx % 64);
+        }
+    }
+    result
+}
+
+pub(crate) fn cmp_unsigned(a: &[u64; LIMBS], b: &[u64; LIMBS]) -> std::cmp::Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+pub(crate) fn to_bools(a: &[u64; LIMBS], bits: usize) -> Vec<bool> {
+    (0..bits).map(|ndx| get_bit(a, ndx)).collect()
+}
+
+pub(crate) fn count_ones(a: &[u64; LIMBS]) -> u32 {
+    a.iter().map(|x| x.count_ones()).sum()
+}
+
+pub(crate) fn to_decimal(a: &[u64; LIMBS]) -> String {
+    // Repeatedly divide by 10^19 (the largest power of 10 that
+    // fits in a u64), collecting the remainders as digit groups.
+    const CHUNK: u128 = 10_000_000_000_000_000_000;
+    let mut value = *a;
+    let mut groups = vec![];
+    loop {
+        let mut rem = 0_u128;
+        for limb in value.iter_mut().rev() {
+            let acc = (rem << 64) | (*limb as u128);
+            *limb = (acc / CHUNK) as u64;
+            rem = acc % CHUNK;
+        }
+        groups.push(rem as u64);
+        if is_zero(&value) {
+            break;
+        }
+    }
+    let mut text = groups.pop().unwrap_or_default().to_string();
+    for group in groups.iter().rev() {
+        text += &format!("{:019}", group);
+    }
+    text
+}
+
+pub(crate) fn to_radix(a: &[u64; LIMBS], f: fn(u64) -> String, g: fn(u64) -> String) -> String {
+    // `f` formats the most significant limb, and `g` the remaining
+    // (zero padded) limbs.
+    let top = a.iter().rposition(|x| *x != 0).unwrap_or(0);
+    let mut text = f(a[top]);
+    for ndx in (0..top).rev() {
+        text += &g(a[ndx]);
+    }
+    text
+}
+
+seq!(N in 129..=1024 {
+    #(
+        pub type b~N = WideBits<W~N>;
+        pub const fn b~N(value: u128) -> b~N {
+            wide_bits::<W~N>(value)
+        }
+    )*
+});
+
+/// Helper function for creating a wide bits value from a
+/// constant.  As with [Bits], the value must fit in the type.
+/// ```
+/// # use rhdl_bits::{W256, WideBits, wide_bits};
+/// const VALUE: WideBits<W256> = wide_bits(0xDEAD_BEEF);
+/// assert_eq!(VALUE, 0xDEAD_BEEF);
+/// ```
+pub const fn wide_bits<N: BitWidth>(value: u128) -> WideBits<N> {
+    assert!(N::BITS >= 128 || value >> N::BITS == 0);
+    WideBits {
+        marker: std::marker::PhantomData,
+        limbs: from_u128(value),
+    }
+}
+
+pub struct wide_bits<N: BitWidth> {
+    marker: std::marker::PhantomData<N>,
+}
+
+impl<N: BitWidth> WideBits<N> {
+    /// Defines a constant WideBits value with all bits set to 1.
+    pub const MASK: Self = Self::mask();
+    pub const MAX: Self = Self::mask();
+    pub const ZERO: Self = Self {
+        marker: std::marker::PhantomData,
+        limbs: [0; LIMBS],
+    };
+    /// Return a [WideBits] value with all bits set to 1.
+    pub const fn mask() -> Self {
+        Self {
+            marker: std::marker::PhantomData,
+            limbs: mask_limbs(N::BITS),
+        }
+    }
+    /// Build a value from little endian 64 bit limbs.  Limbs (or bits)
+    /// beyond the width of the type are discarded.
+    /// ```
+    /// # use rhdl_bits::{W192, WideBits};
+    /// let x = WideBits::<W192>::from_limbs(&[1, 2, 3, 4]);
+    /// assert_eq!(x.limbs(), &[1, 2, 3]);
+    /// ```
+    pub fn from_limbs(limbs: &[u64]) -> Self {
+        let mut val = [0; LIMBS];
+        for (dest, src) in val.iter_mut().zip(limbs) {
+            *dest = *src;
+        }
+        Self {
+            marker: std::marker::PhantomData,
+            limbs: masked(val, N::BITS),
+        }
+    }
+    /// The little endian 64 bit limbs that hold the value.
+    pub fn limbs(&self) -> &[u64] {
+        &self.limbs[0..limb_count(N::BITS)]
+    }
+    /// Extend a value by the number of bits specified.
+    pub fn xext<M>(self) -> WideBits<Sum<N, M>>
+    where
+        N: BitWidth + Add<M>,
+        M: BitWidth,
+        Sum<N, M>: BitWidth,
+    {
+        self.resize()
+    }
+    pub fn xshr<M>(self) -> WideBits<Diff<N, M>>
+    where
+        N: BitWidth + Sub<M>,
+        M: BitWidth,
+        Diff<N, M>: BitWidth,
+    {
+        (self >> M::BITS as u128).resize()
+    }
+    pub fn xshl<M>(self) -> WideBits<Sum<N, M>>
+    where
+        N: BitWidth + Add<M>,
+        M: BitWidth,
+        Sum<N, M>: BitWidth,
+    {
+        self.resize::<Sum<N, M>>() << M::BITS as u128
+    }
+    /// Zero extend or truncate to another wide width.
+    pub fn resize<M: BitWidth>(self) -> WideBits<M> {
+        WideBits {
+            marker: std::marker::PhantomData,
+            limbs: masked(self.limbs, M::BITS),
+        }
+    }
+    /// Truncate (or zero extend) the value to a regular [Bits] value.
+    pub fn narrow<M: BitWidth>(self) -> Bits<M> {
+        bits_masked(to_u128(&self.limbs))
+    }
+    /// Reinterpret the [WideBits] value as a [WideSignedBits] value.
+    pub fn as_signed(self) -> WideSignedBits<N> {
+        WideSignedBits {
+            marker: std::marker::PhantomData,
+            limbs: self.limbs,
+        }
+    }
+    /// Build a (dynamic, stack allocated) vector containing
+    /// the bits that make up this value.  This will be slow.
+    pub fn to_bools(self) -> Vec<bool> {
+        to_bools(&self.limbs, N::BITS)
+    }
+    /// Build a value from its bits (LSB first).  This is the
+    /// inverse of [WideBits::to_bools].  Missing bits are taken
+    /// to be zero, and extra bits are ignored.
+    pub fn from_bools(v: &[bool]) -> Self {
+        let mut limbs = [0; LIMBS];
+        for (ndx, _) in v.iter().take(N::BITS).enumerate().filter(|(_, b)| **b) {
+            limbs[ndx / 64] |= 1 << (ndx % 64);
+        }
+        WideBits {
+            marker: std::marker::PhantomData,
+            limbs,
+        }
+    }
+    pub fn any(self) -> bool {
+        !is_zero(&self.limbs)
+    }
+    pub fn all(self) -> bool {
+        self == Self::MASK
+    }
+    pub fn xor(self) -> bool {
+        count_ones(&self.limbs) % 2 == 1
+    }
+}
+
+impl<N: BitWidth> Bits<N> {
+    /// Zero extend (or truncate) the value to a [WideBits] value.
+    /// ```
+    /// # use rhdl_bits::alias::*;
+    /// let x: b256 = b8(0xFF).widen();
+    /// assert_eq!(x, 0xFF);
+    /// ```
+    pub fn widen<M: BitWidth>(self) -> WideBits<M> {
+        WideBits {
+            marker: std::marker::PhantomData,
+            limbs: masked(from_u128(self.val), M::BITS),
+        }
+    }
+}
+
+impl<N: BitWidth> SignedBits<N> {
+    /// Sign extend (or truncate) the value to a [WideSignedBits] value.
+    pub fn widen<M: BitWidth>(self) -> WideSignedBits<M> {
+        WideSignedBits {
+            marker: std::marker::PhantomData,
+            limbs: masked(from_i128(self.raw()), M::BITS),
+        }
+    }
+}
+
+impl<N: BitWidth> WideSignedBits<N> {
+    /// Truncate (or sign extend) the value to a regular [SignedBits] value.
+    pub fn narrow<M: BitWidth>(self) -> SignedBits<M> {
+        signed_wrapped(self.resize::<W128>().as_unsigned().narrow::<W128>().raw() as i128)
+    }
+}
+
+/// The default value for a [WideBits] value is 0.
+impl<N: BitWidth> Default for WideBits<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+/// Provide conversion from a `u128` to a [WideBits] value.
+/// This will panic if you try to convert a value that
+/// is larger than the [WideBits] value can hold.
+impl<N: BitWidth> From<u128> for WideBits<N> {
+    fn from(value: u128) -> Self {
+        wide_bits(value)
+    }
+}
+
+impl<N: BitWidth, M: BitWidth> From<Bits<M>> for WideBits<N> {
+    fn from(value: Bits<M>) -> Self {
+        value.widen()
+    }
+}
+
+impl<Len: BitWidth> std::cmp::PartialOrd for WideBits<Len> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Len: BitWidth> std::cmp::Ord for WideBits<Len> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        cmp_unsigned(&self.limbs, &other.limbs)
+    }
+}
+
+impl<N: BitWidth> PartialEq<u128> for WideBits<N> {
+    fn eq(&self, other: &u128) -> bool {
+        *self == wide_bits::<N>(*other)
+    }
+}
+
+impl<N: BitWidth> PartialEq<WideBits<N>> for u128 {
+    fn eq(&self, other: &WideBits<N>) -> bool {
+        wide_bits::<N>(*self) == *other
+    }
+}
+
+impl<N: BitWidth> PartialOrd<u128> for WideBits<N> {
+    fn partial_cmp(&self, other: &u128) -> Option<std::cmp::Ordering> {
+        self.partial_cmp(&wide_bits::<N>(*other))
+    }
+}
+
+impl<N: BitWidth> PartialOrd<WideBits<N>> for u128 {
+    fn partial_cmp(&self, other: &WideBits<N>) -> Option<std::cmp::Ordering> {
+        wide_bits::<N>(*self).partial_cmp(other)
+    }
+}
+
+impl<Len: BitWidth> std::fmt::Debug for WideBits<Len> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}_b{}", to_decimal(&self.limbs), Len::BITS)
+    }
+}
+
+impl<Len: BitWidth> std::fmt::Display for WideBits<Len> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}'d{}", Len::BITS, to_decimal(&self.limbs))
+    }
+}
+
+impl<Len: BitWidth> std::fmt::LowerHex for WideBits<Len> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let text = to_radix(&self.limbs, |x| format!("{x:x}"), |x| format!("{x:016x}"));
+        write!(f, "{}'h{}", Len::BITS, text)
+    }
+}
+
+impl<Len: BitWidth> std::fmt::UpperHex for WideBits<Len> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let text = to_radix(&self.limbs, |x| format!("{x:X}"), |x| format!("{x:016X}"));
+        write!(f, "{}'H{}", Len::BITS, text)
+    }
+}
+
+impl<Len: BitWidth> std::fmt::Binary for WideBits<Len> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let text = to_radix(&self.limbs, |x| format!("{x:b}"), |x| format!("{x:064b}"));
+        write!(f, "{}'b{}", Len::BITS, text)
+    }
+}
+
+// The arithmetic and logical operators all come in three flavors,
+// (wide op wide, wide op literal, literal op wide), plus the
+// corresponding assignment operator.
+macro_rules! impl_wide_binop {
+    ($trait: ident, $op: ident, $assign_trait: ident, $assign_op: ident, $func: expr) => {
+        impl<N: BitWidth> std::ops::$trait for WideBits<N> {
+            type Output = Self;
+            fn $op(self, rhs: Self) -> Self {
+                WideBits {
+                    marker: std::marker::PhantomData,
+                    limbs: $func(&self.limbs, &rhs.limbs, N::BITS),
+                }
+            }
+        }
+
+        impl<N: BitWidth> std::ops::$trait<u128> for WideBits<N> {
+            type Output = Self;
+            fn $op(self, rhs: u128) -> Self {
+                std::ops::$trait::$op(self, wide_bits::<N>(rhs))
+            }
+        }
+
+        impl<N: BitWidth> std::ops::$trait<WideBits<N>> for u128 {
+            type Output = WideBits<N>;
+            fn $op(self, rhs: WideBits<N>) -> WideBits<N> {
+                std::ops::$trait::$op(wide_bits::<N>(self), rhs)
+            }
+        }
+
+        impl<N: BitWidth> std::ops::$assign_trait for WideBits<N> {
+            fn $assign_op(&mut self, rhs: Self) {
+                *self = std::ops::$trait::$op(*self, rhs);
+            }
+        }
+
+        impl<N: BitWidth> std::ops::$assign_trait<u128> for WideBits<N> {
+            fn $assign_op(&mut self, rhs: u128) {
+                *self = std::ops::$trait::$op(*self, rhs);
+            }
+        }
+    };
+}
+
+impl_wide_binop!(Add, add, AddAssign, add_assign, wrapping_add);
+impl_wide_binop!(Sub, sub, SubAssign, sub_assign, wrapping_sub);
+impl_wide_binop!(Mul, mul, MulAssign, mul_assign, wrapping_mul);
+impl_wide_binop!(Div, div, DivAssign, div_assign, wrapping_div);
+impl_wide_binop!(Rem, rem, RemAssign, rem_assign, wrapping_rem);
+impl_wide_binop!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b, _| zip(
+    a,
+    b,
+    |x, y| x & y
+));
+impl_wide_binop!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b, _| zip(
+    a,
+    b,
+    |x, y| x | y
+));
+impl_wide_binop!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b, _| zip(
+    a,
+    b,
+    |x, y| x ^ y
+));
+
+impl<N: BitWidth> std::ops::Not for WideBits<N> {
+    type Output = Self;
+    fn not(self) -> Self {
+        WideBits {
+            marker: std::marker::PhantomData,
+            limbs: not(&self.limbs, N::BITS),
+        }
+    }
+}
+
+// Note that the shift amount can be either a literal or a (narrow)
+// [Bits] value.  Shifting by more than the width of the value
+// yields zero.
+macro_rules! impl_wide_shift {
+    ($trait: ident, $op: ident, $assign_trait: ident, $assign_op: ident, $func: expr) => {
+        impl<N: BitWidth> std::ops::$trait<u128> for WideBits<N> {
+            type Output = Self;
+            fn $op(self, rhs: u128) -> Self {
+                WideBits {
+                    marker: std::marker::PhantomData,
+                    limbs: $func(&self.limbs, rhs, N::BITS),
+                }
+            }
+        }
+
+        impl<N: BitWidth, M: BitWidth> std::ops::$trait<Bits<M>> for WideBits<N> {
+            type Output = Self;
+            fn $op(self, rhs: Bits<M>) -> Self {
+                std::ops::$trait::$op(self, rhs.raw())
+            }
+        }
+
+        impl<N: BitWidth> std::ops::$assign_trait<u128> for WideBits<N> {
+            fn $assign_op(&mut self, rhs: u128) {
+                *self = std::ops::$trait::$op(*self, rhs);
+            }
+        }
+
+        impl<N: BitWidth, M: BitWidth> std::ops::$assign_trait<Bits<M>> for WideBits<N> {
+            fn $assign_op(&mut self, rhs: Bits<M>) {
+                *self = std::ops::$trait::$op(*self, rhs);
+            }
+        }
+    };
+}
+
+impl_wide_shift!(Shl, shl, ShlAssign, shl_assign, shl);
+impl_wide_shift!(Shr, shr, ShrAssign, shr_assign, |a, n, bits| shr(
+    a, n, bits, false
+));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::*;
+
+    #[test]
+    fn test_wide_add_carries_across_limbs() {
+        let x = b256(u64::MAX as u128);
+        let y = x + 1;
+        assert_eq!(y.limbs(), &[0, 1, 0, 0]);
+        let z = b256(u128::MAX) + 1;
+        assert_eq!(z.limbs(), &[0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_wide_wrapping() {
+        let x = WideBits::<W200>::MAX;
+        assert_eq!(x + 1, 0);
+        assert_eq!(b200(0) - 1, x);
+        assert_eq!(x.limbs()[3], 0xFF);
+        assert!(x.all());
+        assert!(!(x - 1).all());
+    }
+
+    #[test]
+    fn test_wide_mul_matches_narrow() {
+        let a = 0xDEAD_BEEF_CAFE_BABE_u128;
+        let b = 0x1234_5678_9ABC_DEF0_u128;
+        let x = b256(a) * b256(b);
+        assert_eq!(x, a * b);
+        assert_eq!(x.narrow::<W128>(), b128(a) * b128(b));
+        // (2^128 - 1)^2 = 2^256 - 2^129 + 1
+        let x = b256(u128::MAX) * b256(u128::MAX);
+        assert_eq!(x.limbs(), &[1, 0, u64::MAX - 1, u64::MAX]);
+        // Truncated to 192 bits, the product wraps
+        let x = b192(u128::MAX) * b192(u128::MAX);
+        assert_eq!(x.limbs(), &[1, 0, u64::MAX - 1]);
+    }
+
+    #[test]
+    fn test_wide_div_rem() {
+        let a = 0xDEAD_BEEF_CAFE_BABE_1234_5678_u128;
+        let b = 0x1234_5678_9ABC_u128;
+        assert_eq!(b256(a) / b256(b), a / b);
+        assert_eq!(b256(a) % b256(b), a % b);
+        let x = (b256(a) << 100) + 17;
+        let y = b256(b) << 50;
+        assert_eq!((x / y) * y + x % y, x);
+        assert!(x % y < y);
+        assert_eq!(b256(5) / 0, WideBits::<W256>::MAX);
+        assert_eq!(b256(5) % 0, 5);
+        let max = WideBits::<W1024>::MAX;
+        assert_eq!(max / max, 1);
+        assert_eq!(max / 2, max >> 1);
+        assert_eq!(max % 2, 1);
+    }
+
+    #[test]
+    fn test_wide_shifts() {
+        let x = b256(1);
+        assert_eq!((x << 200) >> 200, 1);
+        assert_eq!(x << 256, 0);
+        assert_eq!((x << 255).limbs(), &[0, 0, 0, 1 << 63]);
+        let y = WideBits::<W256>::MAX >> 250;
+        assert_eq!(y, 0x3F);
+        assert_eq!(x << b8(65), b256(2) << 64);
+    }
+
+    #[test]
+    fn test_wide_cmp() {
+        let a = b512(5) << 300;
+        let b = b512(u128::MAX);
+        assert!(a > b);
+        assert!(b < a);
+        assert!(b512(3) < 4);
+    }
+
+    #[test]
+    fn test_wide_format() {
+        let x = b256(1) << 128;
+        assert_eq!(
+            format!("{}", x),
+            "256'd340282366920938463463374607431768211456"
+        );
+        assert_eq!(format!("{:x}", x), "256'h100000000000000000000000000000000");
+        assert_eq!(format!("{:?}", b256(42)), "42_b256");
+        assert_eq!(format!("{:b}", b160(5)), "160'b101");
+    }
+
+    #[test]
+    fn test_wide_to_bools() {
+        let x = (b192(1) << 130) | 1;
+        let bools = x.to_bools();
+        assert_eq!(bools.len(), 192);
+        assert!(bools[0] && bools[130]);
+        assert_eq!(bools.iter().filter(|x| **x).count(), 2);
+        assert!(!x.xor());
+        assert!(x.any());
+        assert_eq!(b192::from_bools(&bools), x);
+        assert_eq!(s192::from_bools(&bools).as_unsigned(), x);
+    }
+
+    #[test]
+    fn test_widen_and_narrow() {
+        let x: b256 = b128(u128::MAX).widen();
+        assert_eq!(x, u128::MAX);
+        assert_eq!(x.narrow::<W8>(), 0xFF);
+        let y: b256 = x.xshl::<W64>().resize();
+        assert_eq!(y.limbs(), &[0, u64::MAX, u64::MAX, 0]);
+        let z = y.xshr::<W64>();
+        assert_eq!(z.resize::<W256>(), x);
+    }
+}
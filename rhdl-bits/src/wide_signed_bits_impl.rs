@@ -0,0 +1,517 @@
+#![allow(non_camel_case_types)]
+use std::ops::{Add, Sub};
+
+use crate::wide_bits_impl::{
+    cmp_unsigned, count_ones, div_rem, from_i128, get_bit, is_zero, mask_limbs, masked, not, shl,
+    shr, to_bools, to_decimal, to_radix, wrapping_add, wrapping_mul, wrapping_sub, zip, WideBits,
+    LIMBS,
+};
+use rhdl_typenum::*;
+use seq_macro::seq;
+
+/// The [WideSignedBits] type is the multi-limb counterpart of
+/// [SignedBits](crate::SignedBits), and holds signed values of up
+/// to 1024 bits.  The value is stored as the 2's complement bit
+/// pattern (in the same limbs as [WideBits]), so that all of the
+/// arithmetic wraps exactly as it does in hardware.
+///
+/// As with [SignedBits](crate::SignedBits), comparisons are signed,
+/// and the right shift operator sign extends.
+/// ```
+/// # use rhdl_bits::alias::*;
+/// let x: s256 = s256(-1) << 200;
+/// assert!(x < 0);
+/// assert_eq!(x >> 200, -1);
+/// ```
+///
+/// The extending operations support the same widths as those of
+/// [WideBits]: a wide value can be combined with a value that is no
+/// wider than itself and is at most 8 bits wide or a multiple of 32
+/// bits wide.
+///
+/// ```compile_fail
+/// # use rhdl_bits::alias::*;
+/// let x: s256 = s256(-42);
+/// let y = x.xext::<rhdl_bits::W40>();
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct WideSignedBits<Len> {
+    pub(crate) marker: std::marker::PhantomData<Len>,
+    pub(crate) limbs: [u64; LIMBS],
+}
+
+seq!(N in 129..=1024 {
+    #(
+        pub type s~N = WideSignedBits<W~N>;
+        pub const fn s~N(value: i128) -> s~N {
+            wide_signed::<W~N>(value)
+        }
+    )*
+});
+
+/// Helper function for creating a wide signed value from
+/// a constant.  The value must fit in the type.
+/// ```
+/// # use rhdl_bits::{W256, WideSignedBits, wide_signed};
+/// const VALUE: WideSignedBits<W256> = wide_signed(-42);
+/// assert_eq!(VALUE, -42);
+/// ```
+pub const fn wide_signed<N: BitWidth>(value: i128) -> WideSignedBits<N> {
+    assert!(N::BITS >= 128 || (value >> (N::BITS - 1) == 0) || (value >> (N::BITS - 1) == -1));
+    WideSignedBits {
+        marker: std::marker::PhantomData,
+        limbs: masked(from_i128(value), N::BITS),
+    }
+}
+
+pub struct wide_signed<N: BitWidth> {
+    marker: std::marker::PhantomData<N>,
+}
+
+impl<N: BitWidth> WideSignedBits<N> {
+    pub const MAX: Self = Self {
+        marker: std::marker::PhantomData,
+        limbs: masked([u64::MAX; LIMBS], N::BITS - 1),
+    };
+    pub const MIN: Self = Self {
+        marker: std::marker::PhantomData,
+        limbs: crate::wide_bits_impl::single_bit(N::BITS - 1),
+    };
+    pub const ZERO: Self = Self {
+        marker: std::marker::PhantomData,
+        limbs: [0; LIMBS],
+    };
+    /// Test if the value is negative.
+    pub fn is_negative(&self) -> bool {
+        get_bit(&self.limbs, N::BITS - 1)
+    }
+    /// Test if the value is positive or zero.
+    pub fn is_non_negative(&self) -> bool {
+        !self.is_negative()
+    }
+    /// Reinterpret the [WideSignedBits] value as an unsigned
+    /// [WideBits] value.
+    pub fn as_unsigned(self) -> WideBits<N> {
+        WideBits {
+            marker: std::marker::PhantomData,
+            limbs: self.limbs,
+        }
+    }
+    /// Build a (dynamic, stack allocated) vector
+    /// containing the bits that make up this value.
+    /// This will be slow.
+    pub fn to_bools(self) -> Vec<bool> {
+        to_bools(&self.limbs, N::BITS)
+    }
+    /// Build a value from its 2's complement bits (LSB first).
+    /// This is the inverse of [WideSignedBits::to_bools].
+    pub fn from_bools(v: &[bool]) -> Self {
+        WideBits::<N>::from_bools(v).as_signed()
+    }
+    pub fn any(self) -> bool {
+        !is_zero(&self.limbs)
+    }
+    pub fn all(self) -> bool {
+        self.as_unsigned().all()
+    }
+    pub fn xor(self) -> bool {
+        count_ones(&self.limbs) % 2 == 1
+    }
+    /// Sign extend a value by the number of bits specified.
+    pub fn xext<M>(self) -> WideSignedBits<Sum<N, M>>
+    where
+        N: BitWidth + Add<M>,
+        M: BitWidth,
+        Sum<N, M>: BitWidth,
+    {
+        self.resize()
+    }
+    pub fn xshr<M>(self) -> WideSignedBits<Diff<N, M>>
+    where
+        N: BitWidth + Sub<M>,
+        M: BitWidth,
+        Diff<N, M>: BitWidth,
+    {
+        (self >> M::BITS as u128).resize()
+    }
+    pub fn xshl<M>(self) -> WideSignedBits<Sum<N, M>>
+    where
+        N: BitWidth + Add<M>,
+        M: BitWidth,
+        Sum<N, M>: BitWidth,
+    {
+        self.resize::<Sum<N, M>>() << M::BITS as u128
+    }
+    /// Sign extend or truncate to another wide width.
+    pub fn resize<M: BitWidth>(self) -> WideSignedBits<M> {
+        let limbs = if M::BITS > N::BITS && self.is_negative() {
+            zip(&self.limbs, &not(&[0; LIMBS], N::BITS), |x, y| x | !y)
+        } else {
+            self.limbs
+        };
+        WideSignedBits {
+            marker: std::marker::PhantomData,
+            limbs: masked(limbs, M::BITS),
+        }
+    }
+}
+
+/// The default value for a [WideSignedBits] value is 0.
+impl<N: BitWidth> Default for WideSignedBits<N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+/// Provide conversion from an `i128` to a [WideSignedBits] value.
+/// This will panic if the value does not fit.
+impl<N: BitWidth> From<i128> for WideSignedBits<N> {
+    fn from(value: i128) -> Self {
+        wide_signed(value)
+    }
+}
+
+impl<Len: BitWidth> std::cmp::PartialOrd for WideSignedBits<Len> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Len: BitWidth> std::cmp::Ord for WideSignedBits<Len> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (true, false) => std::cmp::Ordering::Less,
+            (false, true) => std::cmp::Ordering::Greater,
+            _ => cmp_unsigned(&self.limbs, &other.limbs),
+        }
+    }
+}
+
+impl<N: BitWidth> PartialEq<i128> for WideSignedBits<N> {
+    fn eq(&self, other: &i128) -> bool {
+        *self == wide_signed::<N>(*other)
+    }
+}
+
+impl<N: BitWidth> PartialEq<WideSignedBits<N>> for i128 {
+    fn eq(&self, other: &WideSignedBits<N>) -> bool {
+        wide_signed::<N>(*self) == *other
+    }
+}
+
+impl<N: BitWidth> PartialOrd<i128> for WideSignedBits<N> {
+    fn partial_cmp(&self, other: &i128) -> Option<std::cmp::Ordering> {
+        self.partial_cmp(&wide_signed::<N>(*other))
+    }
+}
+
+impl<N: BitWidth> PartialOrd<WideSignedBits<N>> for i128 {
+    fn partial_cmp(&self, other: &WideSignedBits<N>) -> Option<std::cmp::Ordering> {
+        wide_signed::<N>(*self).partial_cmp(other)
+    }
+}
+
+// Split the value into a sign and a magnitude for formatting.  The
+// magnitude of the most negative value still fits in the limbs.
+fn sign_magnitude<N: BitWidth>(x: &WideSignedBits<N>) -> (&'static str, [u64; LIMBS]) {
+    if x.is_negative() {
+        ("-", (-*x).limbs)
+    } else {
+        ("", x.limbs)
+    }
+}
+
+impl<Len: BitWidth> std::fmt::Debug for WideSignedBits<Len> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (sign, magnitude) = sign_magnitude(self);
+        write!(f, "{}{}_s{}", sign, to_decimal(&magnitude), Len::BITS)
+    }
+}
+
+impl<Len: BitWidth> std::fmt::Display for WideSignedBits<Len> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (sign, magnitude) = sign_magnitude(self);
+        write!(f, "{}{}'sd{}", sign, Len::BITS, to_decimal(&magnitude))
+    }
+}
+
+impl<Len: BitWidth> std::fmt::LowerHex for WideSignedBits<Len> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (sign, magnitude) = sign_magnitude(self);
+        let text = to_radix(&magnitude, |x| format!("{x:x}"), |x| format!("{x:016x}"));
+        write!(f, "{}{}'sh{}", sign, Len::BITS, text)
+    }
+}
+
+impl<Len: BitWidth> std::fmt::UpperHex for WideSignedBits<Len> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (sign, magnitude) = sign_magnitude(self);
+        let text = to_radix(&magnitude, |x| format!("{x:X}"), |x| format!("{x:016X}"));
+        write!(f, "{}{}'SH{}", sign, Len::BITS, text)
+    }
+}
+
+impl<Len: BitWidth> std::fmt::Binary for WideSignedBits<Len> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (sign, magnitude) = sign_magnitude(self);
+        let text = to_radix(&magnitude, |x| format!("{x:b}"), |x| format!("{x:064b}"));
+        write!(f, "{}{}'sb{}", sign, Len::BITS, text)
+    }
+}
+
+macro_rules! impl_wide_signed_binop {
+    ($trait: ident, $op: ident, $assign_trait: ident, $assign_op: ident, $func: expr) => {
+        impl<N: BitWidth> std::ops::$trait for WideSignedBits<N> {
+            type Output = Self;
+            fn $op(self, rhs: Self) -> Self {
+                WideSignedBits {
+                    marker: std::marker::PhantomData,
+                    limbs: $func(&self.limbs, &rhs.limbs, N::BITS),
+                }
+            }
+        }
+
+        impl<N: BitWidth> std::ops::$trait<i128> for WideSignedBits<N> {
+            type Output = Self;
+            fn $op(self, rhs: i128) -> Self {
+                std::ops::$trait::$op(self, wide_signed::<N>(rhs))
+            }
+        }
+
+        impl<N: BitWidth> std::ops::$trait<WideSignedBits<N>> for i128 {
+            type Output = WideSignedBits<N>;
+            fn $op(self, rhs: WideSignedBits<N>) -> WideSignedBits<N> {
+                std::ops::$trait::$op(wide_signed::<N>(self), rhs)
+            }
+        }
+
+        impl<N: BitWidth> std::ops::$assign_trait for WideSignedBits<N> {
+            fn $assign_op(&mut self, rhs: Self) {
+                *self = std::ops::$trait::$op(*self, rhs);
+            }
+        }
+
+        impl<N: BitWidth> std::ops::$assign_trait<i128> for WideSignedBits<N> {
+            fn $assign_op(&mut self, rhs: i128) {
+                *self = std::ops::$trait::$op(*self, rhs);
+            }
+        }
+    };
+}
+
+// Signed division works on the magnitudes, and then fixes up the
+// signs, so that the quotient truncates towards zero.  As with
+// [SignedBits](crate::SignedBits), dividing by zero gives -1, and
+// leaves the dividend as the remainder.
+fn signed_div_rem(a: &[u64; LIMBS], b: &[u64; LIMBS], bits: usize) -> ([u64; LIMBS], [u64; LIMBS]) {
+    if is_zero(b) {
+        return (mask_limbs(bits), *a);
+    }
+    let zero = [0; LIMBS];
+    let a_neg = get_bit(a, bits - 1);
+    let b_neg = get_bit(b, bits - 1);
+    let magnitude = |x: &[u64; LIMBS], neg: bool| {
+        if neg {
+            wrapping_sub(&zero, x, bits)
+        } else {
+            *x
+        }
+    };
+    let (quotient, remainder) = div_rem(&magnitude(a, a_neg), &magnitude(b, b_neg), bits);
+    (
+        magnitude(&quotient, a_neg ^ b_neg),
+        magnitude(&remainder, a_neg),
+    )
+}
+
+fn signed_div(a: &[u64; LIMBS], b: &[u64; LIMBS], bits: usize) -> [u64; LIMBS] {
+    signed_div_rem(a, b, bits).0
+}
+
+fn signed_rem(a: &[u64; LIMBS], b: &[u64; LIMBS], bits: usize) -> [u64; LIMBS] {
+    signed_div_rem(a, b, bits).1
+}
+
+// 2's complement addition, subtraction and (truncated) multiplication
+// are the same bit operations as for unsigned values.
+impl_wide_signed_binop!(Add, add, AddAssign, add_assign, wrapping_add);
+impl_wide_signed_binop!(Sub, sub, SubAssign, sub_assign, wrapping_sub);
+impl_wide_signed_binop!(Mul, mul, MulAssign, mul_assign, wrapping_mul);
+impl_wide_signed_binop!(Div, div, DivAssign, div_assign, signed_div);
+impl_wide_signed_binop!(Rem, rem, RemAssign, rem_assign, signed_rem);
+impl_wide_signed_binop!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b, _| zip(
+    a,
+    b,
+    |x, y| x & y
+));
+impl_wide_signed_binop!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b, _| zip(
+    a,
+    b,
+    |x, y| x | y
+));
+impl_wide_signed_binop!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b, _| zip(
+    a,
+    b,
+    |x, y| x ^ y
+));
+
+impl<N: BitWidth> std::ops::Not for WideSignedBits<N> {
+    type Output = Self;
+    fn not(self) -> Self {
+        WideSignedBits {
+            marker: std::marker::PhantomData,
+            limbs: not(&self.limbs, N::BITS),
+        }
+    }
+}
+
+impl<N: BitWidth> std::ops::Neg for WideSignedBits<N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl<N: BitWidth> std::ops::Shl<u128> for WideSignedBits<N> {
+    type Output = Self;
+    fn shl(self, rhs: u128) -> Self {
+        WideSignedBits {
+            marker: std::marker::PhantomData,
+            limbs: shl(&self.limbs, rhs, N::BITS),
+        }
+    }
+}
+
+impl<N: BitWidth> std::ops::Shr<u128> for WideSignedBits<N> {
+    type Output = Self;
+    fn shr(self, rhs: u128) -> Self {
+        WideSignedBits {
+            marker: std::marker::PhantomData,
+            limbs: shr(&self.limbs, rhs, N::BITS, self.is_negative()),
+        }
+    }
+}
+
+impl<N: BitWidth, M: BitWidth> std::ops::Shl<crate::Bits<M>> for WideSignedBits<N> {
+    type Output = Self;
+    fn shl(self, rhs: crate::Bits<M>) -> Self {
+        self << rhs.raw()
+    }
+}
+
+impl<N: BitWidth, M: BitWidth> std::ops::Shr<crate::Bits<M>> for WideSignedBits<N> {
+    type Output = Self;
+    fn shr(self, rhs: crate::Bits<M>) -> Self {
+        self >> rhs.raw()
+    }
+}
+
+impl<N: BitWidth> std::ops::ShlAssign<u128> for WideSignedBits<N> {
+    fn shl_assign(&mut self, rhs: u128) {
+        *self = *self << rhs;
+    }
+}
+
+impl<N: BitWidth> std::ops::ShrAssign<u128> for WideSignedBits<N> {
+    fn shr_assign(&mut self, rhs: u128) {
+        *self = *self >> rhs;
+    }
+}
+
+impl<N: BitWidth, M: BitWidth> std::ops::ShlAssign<crate::Bits<M>> for WideSignedBits<N> {
+    fn shl_assign(&mut self, rhs: crate::Bits<M>) {
+        *self = *self << rhs;
+    }
+}
+
+impl<N: BitWidth, M: BitWidth> std::ops::ShrAssign<crate::Bits<M>> for WideSignedBits<N> {
+    fn shr_assign(&mut self, rhs: crate::Bits<M>) {
+        *self = *self >> rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::*;
+
+    #[test]
+    fn test_wide_signed_arithmetic() {
+        let x = s256(-5);
+        let y = s256(3);
+        assert_eq!(x + y, -2);
+        assert_eq!(x - y, -8);
+        assert_eq!(x * y, -15);
+        assert_eq!(x * x, 25);
+        assert_eq!(-x, 5);
+        assert_eq!(!y, -4);
+        assert_eq!(
+            x.as_unsigned().limbs(),
+            &[u64::MAX - 4, u64::MAX, u64::MAX, u64::MAX]
+        );
+    }
+
+    #[test]
+    fn test_wide_signed_division() {
+        let x = s256(-1000) << 130;
+        assert_eq!(x / (s256(7) << 130), -142);
+        assert_eq!(x % (s256(7) << 130), s256(-6) << 130);
+        assert_eq!(s256(-7) / 2, -3);
+        assert_eq!(s256(-7) % 2, -1);
+        assert_eq!(s256(7) / -2, -3);
+        assert_eq!(s256(5) / 0, -1);
+        assert_eq!(s256(5) % 0, 5);
+        let min = WideSignedBits::<W1024>::MIN;
+        let minus_one = WideSignedBits::<W1024>::from(-1);
+        assert_eq!(min / minus_one, min);
+        assert_eq!(min % minus_one, 0);
+        assert_eq!(min / min, 1);
+    }
+
+    #[test]
+    fn test_wide_signed_limits() {
+        let max = WideSignedBits::<W200>::MAX;
+        let min = WideSignedBits::<W200>::MIN;
+        assert!(max > 0);
+        assert!(min < 0);
+        assert_eq!(max + 1, min);
+        assert_eq!(min - 1, max);
+        assert_eq!(-min, min);
+        assert!(min < max);
+    }
+
+    #[test]
+    fn test_wide_signed_shifts() {
+        let x = s256(-1) << 250;
+        assert!(x.is_negative());
+        assert_eq!(x >> 250, -1);
+        assert_eq!(x.as_unsigned() >> 250, 0x3F);
+        assert_eq!(s256(-8) >> b4(2), -2);
+        assert_eq!(s256(-8) >> 300, -1);
+        assert_eq!(s256(8) >> 300, 0);
+    }
+
+    #[test]
+    fn test_wide_signed_resize() {
+        let x = s256(-3);
+        assert_eq!(x.resize::<W512>(), -3);
+        assert_eq!(x.narrow::<W8>(), -3);
+        let y: s256 = s8(-7).widen();
+        assert_eq!(y, -7);
+        assert_eq!((s256(1) << 130).narrow::<W64>(), 0);
+        assert_eq!(x.xext::<W1>(), -3);
+        assert_eq!(x.xshl::<W64>().xshr::<W64>(), -3);
+    }
+
+    #[test]
+    fn test_wide_signed_format() {
+        assert_eq!(format!("{}", s256(-42)), "-256'sd42");
+        assert_eq!(format!("{:?}", s256(-42)), "-42_s256");
+        assert_eq!(format!("{:x}", s256(255)), "256'shff");
+        let min = WideSignedBits::<W192>::MIN;
+        assert_eq!(
+            format!("{}", min),
+            "-192'sd3138550867693340381917894711603833208051177722232017256448"
+        );
+    }
+}
@@ -2,7 +2,7 @@ use std::ops::Add;
 
 use rhdl_typenum::*;
 
-use crate::{bits, signed, Bits, SignedBits};
+use crate::{bits, signed, Bits, SignedBits, WideBits, WideSignedBits};
 
 pub trait XAdd<Rhs = Self> {
     type Output;
@@ -43,6 +43,32 @@ where
     }
 }
 
+impl<N, M> XAdd<WideBits<M>> for WideBits<N>
+where
+    N: BitWidth + Max<M>,
+    M: BitWidth,
+    Maximum<N, M>: Add<W1>,
+    Sum<Maximum<N, M>, W1>: BitWidth,
+{
+    type Output = WideBits<Sum<Maximum<N, M>, W1>>;
+    fn xadd(self, rhs: WideBits<M>) -> Self::Output {
+        self.resize::<Sum<Maximum<N, M>, W1>>() + rhs.resize()
+    }
+}
+
+impl<N, M> XAdd<WideSignedBits<M>> for WideSignedBits<N>
+where
+    N: BitWidth + Max<M>,
+    M: BitWidth,
+    Maximum<N, M>: Add<W1>,
+    Sum<Maximum<N, M>, W1>: BitWidth,
+{
+    type Output = WideSignedBits<Sum<Maximum<N, M>, W1>>;
+    fn xadd(self, rhs: WideSignedBits<M>) -> Self::Output {
+        self.resize::<Sum<Maximum<N, M>, W1>>() + rhs.resize()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,4 +88,14 @@ mod tests {
         let c = a.xadd(b);
         assert_eq!(c, bits::<W9>(255 + 255));
     }
+
+    #[test]
+    fn test_wide_xadd() {
+        let a = WideBits::<W256>::MAX;
+        let c = a.xadd(a);
+        assert_eq!(c, (WideBits::<W257>::MAX >> 1) << 1);
+        let a = WideSignedBits::<W256>::MIN;
+        let c = a.xadd(a);
+        assert_eq!(c, WideSignedBits::<W257>::MIN);
+    }
 }
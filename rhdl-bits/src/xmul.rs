@@ -2,7 +2,7 @@ use std::ops::Add;
 
 use rhdl_typenum::*;
 
-use crate::{bits, signed, Bits, SignedBits};
+use crate::{bits, signed, Bits, SignedBits, WideBits, WideSignedBits};
 
 pub trait XMul<Rhs = Self> {
     type Output;
@@ -33,6 +33,30 @@ where
     }
 }
 
+impl<N, M> XMul<WideBits<M>> for WideBits<N>
+where
+    N: BitWidth + Add<M>,
+    M: BitWidth,
+    Sum<N, M>: BitWidth,
+{
+    type Output = WideBits<Sum<N, M>>;
+    fn xmul(self, rhs: WideBits<M>) -> Self::Output {
+        self.resize::<Sum<N, M>>() * rhs.resize()
+    }
+}
+
+impl<N, M> XMul<WideSignedBits<M>> for WideSignedBits<N>
+where
+    N: BitWidth + Add<M>,
+    M: BitWidth,
+    Sum<N, M>: BitWidth,
+{
+    type Output = WideSignedBits<Sum<N, M>>;
+    fn xmul(self, rhs: WideSignedBits<M>) -> Self::Output {
+        self.resize::<Sum<N, M>>() * rhs.resize()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,4 +105,16 @@ mod tests {
         let c = a.xmul(b);
         assert_eq!(c, signed(a.val * b.val));
     }
+
+    #[test]
+    fn test_wide_xmul() {
+        let a = b256(u128::MAX) << 128 | u128::MAX;
+        let c = a.xmul(a);
+        // (2^256 - 1)^2 = 2^512 - 2^257 + 1
+        assert_eq!(c, b512(1) - (b512(1) << 257));
+        let a = s256(-3) << 200;
+        let b = s256(5);
+        let c: s512 = a.xmul(b);
+        assert_eq!(c, s512(-15) << 200);
+    }
 }
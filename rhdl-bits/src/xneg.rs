@@ -2,7 +2,7 @@ use std::ops::Add;
 
 use rhdl_typenum::*;
 
-use crate::{signed, Bits, SignedBits};
+use crate::{signed, Bits, SignedBits, WideBits, WideSignedBits};
 
 pub trait XNeg {
     type Output;
@@ -30,3 +30,25 @@ where
         signed(self.val.wrapping_neg())
     }
 }
+
+impl<N> XNeg for WideBits<N>
+where
+    N: BitWidth + Add<W1>,
+    Sum<N, W1>: BitWidth,
+{
+    type Output = WideSignedBits<Sum<N, W1>>;
+    fn xneg(self) -> Self::Output {
+        -self.resize::<Sum<N, W1>>().as_signed()
+    }
+}
+
+impl<N> XNeg for WideSignedBits<N>
+where
+    N: BitWidth + Add<W1>,
+    Sum<N, W1>: BitWidth,
+{
+    type Output = WideSignedBits<Sum<N, W1>>;
+    fn xneg(self) -> Self::Output {
+        -self.resize::<Sum<N, W1>>()
+    }
+}
@@ -2,7 +2,7 @@ use std::ops::Add;
 
 use rhdl_typenum::*;
 
-use crate::{signed, Bits, SignedBits};
+use crate::{signed, Bits, SignedBits, WideBits, WideSignedBits};
 
 pub trait XSgn {
     type Output;
@@ -21,3 +21,15 @@ where
         signed(self.raw() as i128)
     }
 }
+
+impl<N> XSgn for WideBits<N>
+where
+    N: BitWidth + Add<W1>,
+    Sum<N, W1>: BitWidth,
+{
+    type Output = WideSignedBits<Sum<N, W1>>;
+
+    fn xsgn(self) -> Self::Output {
+        self.resize::<Sum<N, W1>>().as_signed()
+    }
+}
@@ -2,7 +2,7 @@ use std::ops::Add;
 
 use rhdl_typenum::*;
 
-use crate::{signed, Bits, SignedBits};
+use crate::{signed, Bits, SignedBits, WideBits, WideSignedBits};
 
 pub trait XSub<Rhs = Self> {
     type Output;
@@ -75,6 +75,36 @@ where
     }
 }
 
+impl<N, M> XSub<WideBits<M>> for WideBits<N>
+where
+    N: BitWidth + Max<M>,
+    M: BitWidth,
+    Maximum<N, M>: Add<W1>,
+    Sum<Maximum<N, M>, W1>: BitWidth,
+{
+    type Output = WideSignedBits<Sum<Maximum<N, M>, W1>>;
+    fn xsub(self, rhs: WideBits<M>) -> Self::Output {
+        // Zero extend both sides by (at least) one bit, and
+        // then reinterpret the difference as signed.
+        let a = self.resize::<Sum<Maximum<N, M>, W1>>();
+        let b = rhs.resize::<Sum<Maximum<N, M>, W1>>();
+        (a - b).as_signed()
+    }
+}
+
+impl<N, M> XSub<WideSignedBits<M>> for WideSignedBits<N>
+where
+    N: BitWidth + Max<M>,
+    M: BitWidth,
+    Maximum<N, M>: Add<W1>,
+    Sum<Maximum<N, M>, W1>: BitWidth,
+{
+    type Output = WideSignedBits<Sum<Maximum<N, M>, W1>>;
+    fn xsub(self, rhs: WideSignedBits<M>) -> Self::Output {
+        self.resize::<Sum<Maximum<N, M>, W1>>() - rhs.resize()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::bits;
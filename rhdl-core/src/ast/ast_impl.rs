@@ -170,6 +170,22 @@ pub enum ExprKind {
     Bits(ExprBits),
     Try(ExprTry),
     Cast(ExprCast),
+    Assert(ExprAssert),
+}
+
+// An `assert!` must hold whenever it is reached, while a `cover!`
+// marks a condition that we expect to be reachable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssertKind {
+    Assert,
+    Cover,
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct ExprAssert {
+    pub kind: AssertKind,
+    pub cond: Box<Expr>,
+    pub message: &'static str,
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -349,6 +365,8 @@ pub enum BinOp {
     Add,
     Sub,
     Mul,
+    Div,
+    Rem,
     And,
     Or,
     BitXor,
@@ -365,6 +383,8 @@ pub enum BinOp {
     AddAssign,
     SubAssign,
     MulAssign,
+    DivAssign,
+    RemAssign,
     BitXorAssign,
     BitAndAssign,
     BitOrAssign,
@@ -379,6 +399,8 @@ impl BinOp {
             BinOp::AddAssign
                 | BinOp::SubAssign
                 | BinOp::MulAssign
+                | BinOp::DivAssign
+                | BinOp::RemAssign
                 | BinOp::BitXorAssign
                 | BinOp::BitAndAssign
                 | BinOp::BitOrAssign
@@ -398,6 +420,7 @@ pub enum UnOp {
 pub struct Arm {
     pub id: NodeId,
     pub kind: ArmKind,
+    pub guard: Option<Box<Expr>>,
     pub body: Box<Expr>,
 }
 
@@ -406,6 +429,27 @@ pub enum ArmKind {
     Wild,
     Constant(ArmConstant),
     Enum(ArmEnum),
+    Range(ArmRange),
+    Or(ArmOr),
+    Bind(ArmBind),
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct ArmRange {
+    pub start: Option<ExprLit>,
+    pub limits: RangeLimits,
+    pub end: Option<ExprLit>,
+}
+
+#[derive(Debug, Clone, Hash)]
+pub struct ArmOr {
+    pub cases: Vec<ArmKind>,
+}
+
+// An arm that binds the matched value to a name, e.g. `x if x > 3 => ...`
+#[derive(Debug, Clone, Hash)]
+pub struct ArmBind {
+    pub pat: Box<Pat>,
 }
 
 #[derive(Debug, Clone, Hash)]
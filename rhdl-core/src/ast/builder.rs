@@ -1,6 +1,7 @@
 use std::cell::Cell;
 use std::hash::{Hash, Hasher};
 
+pub use crate::ast::ast_impl::AssertKind;
 pub use crate::ast::ast_impl::BinOp;
 pub use crate::ast::ast_impl::UnOp;
 use crate::kernel::KernelFnKind;
@@ -155,9 +156,31 @@ impl ASTBuilder {
         })
     }
 
-    pub fn arm(&self, kind: ArmKind, body: Box<Expr>) -> Box<Arm> {
+    pub fn arm_kind_range(
+        &self,
+        start: Option<ExprLit>,
+        limits: RangeLimits,
+        end: Option<ExprLit>,
+    ) -> ArmKind {
+        ArmKind::Range(ArmRange { start, limits, end })
+    }
+
+    pub fn arm_kind_or(&self, cases: Vec<ArmKind>) -> ArmKind {
+        ArmKind::Or(ArmOr { cases })
+    }
+
+    pub fn arm_kind_bind(&self, pat: Box<Pat>) -> ArmKind {
+        ArmKind::Bind(ArmBind { pat })
+    }
+
+    pub fn arm(&self, kind: ArmKind, guard: Option<Box<Expr>>, body: Box<Expr>) -> Box<Arm> {
         let id = self.id();
-        Box::new(Arm { id, kind, body })
+        Box::new(Arm {
+            id,
+            kind,
+            guard,
+            body,
+        })
     }
 
     pub fn field_expr(&self, expr: Box<Expr>, member: Member) -> Box<Expr> {
@@ -459,6 +482,23 @@ impl ASTBuilder {
         })
     }
 
+    pub fn expr_assert(
+        &self,
+        kind: AssertKind,
+        cond: Box<Expr>,
+        message: &'static str,
+    ) -> Box<Expr> {
+        let id = self.id();
+        Box::new(Expr {
+            id,
+            kind: ExprKind::Assert(ExprAssert {
+                kind,
+                cond,
+                message,
+            }),
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn kernel_fn(
         &self,
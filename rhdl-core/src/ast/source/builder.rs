@@ -34,8 +34,17 @@ impl<'a> SpannedSourceBuilder<'a> {
             ));
         }
         for (syn_input, ast_input) in fn_item.sig.inputs.iter().zip(kernel.inputs.iter()) {
-            let syn::FnArg::Typed(syn_input) = syn_input else {
-                return Err(syn::Error::new(syn_input.span(), "Mismatched input kinds"));
+            // The receiver of a kernel method is an untyped `self`
+            let syn_input = match syn_input {
+                syn::FnArg::Typed(syn_input) => syn_input,
+                syn::FnArg::Receiver(receiver) => {
+                    let span = receiver.span().byte_range();
+                    self.span_map.insert(ast_input.id, span.clone());
+                    if let ast::PatKind::Type(ast_input) = &ast_input.kind {
+                        self.span_map.insert(ast_input.pat.id, span);
+                    }
+                    continue;
+                }
             };
             self.span_map
                 .insert(ast_input.id, syn_input.span().byte_range());
@@ -81,12 +90,37 @@ impl<'a> SpannedSourceBuilder<'a> {
                 syn::Stmt::Expr(syn_expr, _),
                 ast::StmtKind::Expr(ast_expr) | ast::StmtKind::Semi(ast_expr),
             ) => self.expr(syn_expr, ast_expr),
+            (
+                syn::Stmt::Macro(syn_macro),
+                ast::StmtKind::Expr(ast_expr) | ast::StmtKind::Semi(ast_expr),
+            ) => self.assertion(&syn_macro.mac, ast_expr),
             _ => Err(syn::Error::new(
                 syn_stmt.span(),
                 "Mismatched statement kinds",
             )),
         }
     }
+    // The arguments of an `assert!` or `cover!` are just tokens to syn,
+    // so the condition must be parsed out of them.
+    fn assertion(&mut self, syn_macro: &syn::Macro, ast: &ast::Expr) -> syn::Result<()> {
+        self.span_map.insert(ast.id, syn_macro.span().byte_range());
+        let ast::ExprKind::Assert(assert) = &ast.kind else {
+            return Err(syn::Error::new(
+                syn_macro.span(),
+                "Mismatched expression kinds",
+            ));
+        };
+        let args = syn_macro.parse_body_with(
+            syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated,
+        )?;
+        let Some(cond) = args.first() else {
+            return Err(syn::Error::new(
+                syn_macro.span(),
+                "Missing condition in assertion",
+            ));
+        };
+        self.expr(cond, &assert.cond)
+    }
     fn local(&mut self, syn_local: &syn::Local, ast_local: &ast::Local) -> syn::Result<()> {
         self.span_map
             .insert(ast_local.id, syn_local.span().byte_range());
@@ -184,8 +218,13 @@ impl<'a> SpannedSourceBuilder<'a> {
     }
     fn arm(&mut self, syn: &syn::Arm, ast: &ast::Arm) -> syn::Result<()> {
         self.span_map.insert(ast.id, syn.span().byte_range());
-        if let (syn, ast::ArmKind::Enum(ast)) = (&syn.pat, &ast.kind) {
-            self.pattern(syn, &ast.pat)?
+        match &ast.kind {
+            ast::ArmKind::Enum(arm) => self.pattern(&syn.pat, &arm.pat)?,
+            ast::ArmKind::Bind(arm) => self.pattern(&syn.pat, &arm.pat)?,
+            _ => {}
+        }
+        if let (Some((_, syn_guard)), Some(ast_guard)) = (&syn.guard, &ast.guard) {
+            self.expr(syn_guard, ast_guard)?;
         }
         self.expr(&syn.body, &ast.body)
     }
@@ -284,6 +323,11 @@ impl<'a> SpannedSourceBuilder<'a> {
                 self.expr(&syn.receiver, &ast.receiver)?;
                 self.expr_list(&syn.args, &ast.args, syn.span())
             }
+            // Kernel methods are called with the receiver as the first argument
+            (syn::Expr::MethodCall(syn), ast::ExprKind::Call(ast)) if !ast.args.is_empty() => {
+                self.expr(&syn.receiver, &ast.args[0])?;
+                self.expr_list(&syn.args, &ast.args[1..], syn.span())
+            }
             (syn::Expr::Paren(syn), ast::ExprKind::Paren(ast)) => self.expr(&syn.expr, &ast.expr),
             (syn::Expr::Path(_), ast::ExprKind::Path(_)) => Ok(()),
             (syn::Expr::Range(syn), ast::ExprKind::Range(ast)) => {
@@ -314,6 +358,7 @@ impl<'a> SpannedSourceBuilder<'a> {
             }
             (syn::Expr::Unary(syn), ast::ExprKind::Unary(ast)) => self.expr(&syn.expr, &ast.expr),
             (syn::Expr::Cast(syn), ast::ExprKind::Cast(ast)) => self.expr(&syn.expr, &ast.expr),
+            (syn::Expr::Macro(syn), ast::ExprKind::Assert(_)) => self.assertion(&syn.mac, ast),
             (_, ast::ExprKind::Type(_)) => Ok(()),
             (syn::Expr::Path(_), ast::ExprKind::Call(_)) => Ok(()),
             (syn::Expr::Block(syn), _kind) => {
@@ -114,6 +114,9 @@ pub fn visit_match_arm<V>(visitor: &mut V, arm: &Arm) -> Result<()>
 where
     V: Visitor + ?Sized,
 {
+    if let Some(guard) = &arm.guard {
+        visitor.visit_expr(guard)?;
+    }
     visitor.visit_expr(&arm.body)?;
     Ok(())
 }
@@ -218,6 +221,9 @@ where
         ExprKind::Bits(bits) => {
             visitor.visit_expr(&bits.arg)?;
         }
+        ExprKind::Assert(expr) => {
+            visitor.visit_expr(&expr.cond)?;
+        }
         _ => {}
     }
     Ok(())
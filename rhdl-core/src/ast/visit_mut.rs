@@ -114,8 +114,13 @@ pub fn visit_mut_match_arm<V>(visitor: &mut V, arm: &mut Arm) -> Result<()>
 where
     V: VisitorMut + ?Sized,
 {
-    if let ArmKind::Enum(enum_arm) = &mut arm.kind {
-        visitor.visit_mut_pat(&mut enum_arm.pat)?;
+    match &mut arm.kind {
+        ArmKind::Enum(enum_arm) => visitor.visit_mut_pat(&mut enum_arm.pat)?,
+        ArmKind::Bind(bind_arm) => visitor.visit_mut_pat(&mut bind_arm.pat)?,
+        _ => {}
+    }
+    if let Some(guard) = &mut arm.guard {
+        visitor.visit_mut_expr(guard)?;
     }
     visitor.visit_mut_expr(&mut arm.body)?;
     Ok(())
@@ -221,6 +226,9 @@ where
         ExprKind::Bits(bits) => {
             visitor.visit_mut_expr(&mut bits.arg)?;
         }
+        ExprKind::Assert(expr) => {
+            visitor.visit_mut_expr(&mut expr.cond)?;
+        }
         _ => {}
     }
     Ok(())
@@ -0,0 +1,460 @@
+// Gates and word level operators built from CNF clauses (via the
+// Tseitin encoding).  Words are slices of literals, LSB first.  Gates
+// with constant inputs are folded away, and the two input gates are
+// hashed, so that identical logic is only encoded once.
+use std::collections::HashMap;
+
+use super::sat::{Lit, Solver};
+
+pub struct Cnf {
+    pub solver: Solver,
+    one: Lit,
+    and_gates: HashMap<(Lit, Lit), Lit>,
+    xor_gates: HashMap<(Lit, Lit), Lit>,
+}
+
+impl Default for Cnf {
+    fn default() -> Self {
+        let mut solver = Solver::default();
+        let one = solver.new_var();
+        solver.add_clause(&[one]);
+        Self {
+            solver,
+            one,
+            and_gates: Default::default(),
+            xor_gates: Default::default(),
+        }
+    }
+}
+
+impl Cnf {
+    pub fn constant(&self, value: bool) -> Lit {
+        if value {
+            self.one
+        } else {
+            !self.one
+        }
+    }
+    fn as_constant(&self, lit: Lit) -> Option<bool> {
+        (lit.var() == self.one.var()).then(|| lit == self.one)
+    }
+    pub fn fresh(&mut self) -> Lit {
+        self.solver.new_var()
+    }
+    pub fn word(&self, value: u128, len: usize) -> Vec<Lit> {
+        (0..len)
+            .map(|bit| self.constant(bit < 128 && value & (1 << bit) != 0))
+            .collect()
+    }
+    pub fn and(&mut self, a: Lit, b: Lit) -> Lit {
+        match (self.as_constant(a), self.as_constant(b)) {
+            (Some(false), _) | (_, Some(false)) => return self.constant(false),
+            (Some(true), _) => return b,
+            (_, Some(true)) => return a,
+            _ => {}
+        }
+        if a == b {
+            return a;
+        }
+        if a == !b {
+            return self.constant(false);
+        }
+        let key = (a.min(b), a.max(b));
+        if let Some(out) = self.and_gates.get(&key) {
+            return *out;
+        }
+        let out = self.fresh();
+        self.solver.add_clause(&[!out, a]);
+        self.solver.add_clause(&[!out, b]);
+        self.solver.add_clause(&[out, !a, !b]);
+        self.and_gates.insert(key, out);
+        out
+    }
+    pub fn or(&mut self, a: Lit, b: Lit) -> Lit {
+        !self.and(!a, !b)
+    }
+    pub fn xor(&mut self, a: Lit, b: Lit) -> Lit {
+        match (self.as_constant(a), self.as_constant(b)) {
+            (Some(a), Some(b)) => return self.constant(a ^ b),
+            (Some(false), _) => return b,
+            (Some(true), _) => return !b,
+            (_, Some(false)) => return a,
+            (_, Some(true)) => return !a,
+            _ => {}
+        }
+        if a == b {
+            return self.constant(false);
+        }
+        if a == !b {
+            return self.constant(true);
+        }
+        // Normalize the inputs to positive literals, so that all of the
+        // variants of the same gate share an output.
+        let flip = a.is_negated() ^ b.is_negated();
+        let (a, b) = (Lit::min(a, !a), Lit::min(b, !b));
+        let key = (a.min(b), a.max(b));
+        let out = match self.xor_gates.get(&key) {
+            Some(out) => *out,
+            None => {
+                let out = self.fresh();
+                self.solver.add_clause(&[!out, a, b]);
+                self.solver.add_clause(&[!out, !a, !b]);
+                self.solver.add_clause(&[out, !a, b]);
+                self.solver.add_clause(&[out, a, !b]);
+                self.xor_gates.insert(key, out);
+                out
+            }
+        };
+        if flip {
+            !out
+        } else {
+            out
+        }
+    }
+    // The output is `on_true` if `select` is true, and `on_false` otherwise.
+    pub fn mux(&mut self, select: Lit, on_true: Lit, on_false: Lit) -> Lit {
+        match self.as_constant(select) {
+            Some(true) => return on_true,
+            Some(false) => return on_false,
+            None => {}
+        }
+        if on_true == on_false {
+            return on_true;
+        }
+        let a = self.and(select, on_true);
+        let b = self.and(!select, on_false);
+        self.or(a, b)
+    }
+    pub fn and_all(&mut self, lits: impl IntoIterator<Item = Lit>) -> Lit {
+        lits.into_iter()
+            .fold(self.constant(true), |acc, lit| self.and(acc, lit))
+    }
+    pub fn or_all(&mut self, lits: impl IntoIterator<Item = Lit>) -> Lit {
+        lits.into_iter()
+            .fold(self.constant(false), |acc, lit| self.or(acc, lit))
+    }
+    pub fn xor_all(&mut self, lits: impl IntoIterator<Item = Lit>) -> Lit {
+        lits.into_iter()
+            .fold(self.constant(false), |acc, lit| self.xor(acc, lit))
+    }
+    pub fn mux_word(&mut self, select: Lit, on_true: &[Lit], on_false: &[Lit]) -> Vec<Lit> {
+        on_true
+            .iter()
+            .zip(on_false)
+            .map(|(t, f)| self.mux(select, *t, *f))
+            .collect()
+    }
+    // Ripple carry addition.  Returns the sum (with the width of `a`)
+    // and the carry out.
+    pub fn add_with_carry(&mut self, a: &[Lit], b: &[Lit], carry: Lit) -> (Vec<Lit>, Lit) {
+        let mut carry = carry;
+        let sum = a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| {
+                let half = self.xor(*a, *b);
+                let sum = self.xor(half, carry);
+                let generate = self.and(*a, *b);
+                let propagate = self.and(half, carry);
+                carry = self.or(generate, propagate);
+                sum
+            })
+            .collect();
+        (sum, carry)
+    }
+    pub fn add(&mut self, a: &[Lit], b: &[Lit]) -> Vec<Lit> {
+        self.add_with_carry(a, b, self.constant(false)).0
+    }
+    pub fn sub(&mut self, a: &[Lit], b: &[Lit]) -> Vec<Lit> {
+        let not_b = b.iter().map(|x| !*x).collect::<Vec<_>>();
+        self.add_with_carry(a, &not_b, self.constant(true)).0
+    }
+    pub fn neg(&mut self, a: &[Lit]) -> Vec<Lit> {
+        let zero = self.word(0, a.len());
+        self.sub(&zero, a)
+    }
+    // Shift and add multiplication, truncated to the width of `a`.
+    pub fn mul(&mut self, a: &[Lit], b: &[Lit]) -> Vec<Lit> {
+        let len = a.len();
+        let mut product = self.word(0, len);
+        for (shift, b) in b.iter().enumerate().take(len) {
+            let partial = (0..len)
+                .map(|bit| match bit.checked_sub(shift) {
+                    Some(ndx) => self.and(a[ndx], *b),
+                    None => self.constant(false),
+                })
+                .collect::<Vec<_>>();
+            product = self.add(&product, &partial);
+        }
+        product
+    }
+    pub fn eq(&mut self, a: &[Lit], b: &[Lit]) -> Lit {
+        let same = a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| !self.xor(*a, *b))
+            .collect::<Vec<_>>();
+        self.and_all(same)
+    }
+    // Unsigned less than, from the borrow out of `a - b`.
+    pub fn ult(&mut self, a: &[Lit], b: &[Lit]) -> Lit {
+        let not_b = b.iter().map(|x| !*x).collect::<Vec<_>>();
+        let (_, carry) = self.add_with_carry(a, &not_b, self.constant(true));
+        !carry
+    }
+    // Signed less than.  Flipping the sign bits maps the signed order
+    // onto the unsigned one.
+    pub fn slt(&mut self, a: &[Lit], b: &[Lit]) -> Lit {
+        let flip = |x: &[Lit]| {
+            let mut x = x.to_vec();
+            if let Some(msb) = x.last_mut() {
+                *msb = !*msb;
+            }
+            x
+        };
+        self.ult(&flip(a), &flip(b))
+    }
+    // Restoring division.  Returns the quotient and remainder.  Division
+    // by zero is handled by the caller.
+    pub fn udiv_rem(&mut self, a: &[Lit], b: &[Lit]) -> (Vec<Lit>, Vec<Lit>) {
+        let len = a.len();
+        let mut divisor = b.to_vec();
+        divisor.push(self.constant(false));
+        let mut remainder = self.word(0, len + 1);
+        let mut quotient = vec![self.constant(false); len];
+        for bit in (0..len).rev() {
+            // remainder = (remainder << 1) | a[bit]
+            remainder.pop();
+            remainder.insert(0, a[bit]);
+            let less = self.ult(&remainder, &divisor);
+            let difference = self.sub(&remainder, &divisor);
+            remainder = self.mux_word(less, &remainder, &difference);
+            quotient[bit] = !less;
+        }
+        remainder.truncate(len);
+        (quotient, remainder)
+    }
+    // Division and remainder with the conventions of the runtime: signed
+    // division truncates, and division by zero gives all ones (with the
+    // dividend as the remainder).
+    pub fn div_rem(&mut self, a: &[Lit], b: &[Lit], signed: bool) -> (Vec<Lit>, Vec<Lit>) {
+        let len = a.len();
+        let (quotient, remainder) = if signed && len > 0 {
+            let (a_negative, b_negative) = (a[len - 1], b[len - 1]);
+            let abs = |cnf: &mut Self, x: &[Lit], negative: Lit| {
+                let negated = cnf.neg(x);
+                cnf.mux_word(negative, &negated, x)
+            };
+            let a_abs = abs(self, a, a_negative);
+            let b_abs = abs(self, b, b_negative);
+            let (quotient, remainder) = self.udiv_rem(&a_abs, &b_abs);
+            let flip_quotient = self.xor(a_negative, b_negative);
+            let quotient = abs(self, &quotient, flip_quotient);
+            let remainder = abs(self, &remainder, a_negative);
+            (quotient, remainder)
+        } else {
+            self.udiv_rem(a, b)
+        };
+        let zero = self.word(0, b.len());
+        let by_zero = self.eq(b, &zero);
+        let ones = vec![self.constant(true); len];
+        let quotient = self.mux_word(by_zero, &ones, &quotient);
+        let remainder = self.mux_word(by_zero, a, &remainder);
+        (quotient, remainder)
+    }
+    // A barrel shifter.  Shifting by the width of the value or more
+    // leaves only the fill bits.
+    fn shift(&mut self, a: &[Lit], amount: &[Lit], left: bool, fill: Lit) -> Vec<Lit> {
+        let len = a.len();
+        let mut value = a.to_vec();
+        for (stage, select) in amount.iter().enumerate() {
+            let distance = 1_usize.checked_shl(stage as u32).unwrap_or(usize::MAX);
+            let shifted = (0..len)
+                .map(|bit| {
+                    let source = if left {
+                        bit.checked_sub(distance)
+                    } else {
+                        bit.checked_add(distance).filter(|ndx| *ndx < len)
+                    };
+                    source.map_or(fill, |ndx| value[ndx])
+                })
+                .collect::<Vec<_>>();
+            value = self.mux_word(*select, &shifted, &value);
+        }
+        value
+    }
+    pub fn shl(&mut self, a: &[Lit], amount: &[Lit]) -> Vec<Lit> {
+        self.shift(a, amount, true, self.constant(false))
+    }
+    pub fn shr(&mut self, a: &[Lit], amount: &[Lit], signed: bool) -> Vec<Lit> {
+        let fill = match a.last() {
+            Some(msb) if signed => *msb,
+            _ => self.constant(false),
+        };
+        self.shift(a, amount, false, fill)
+    }
+    // Select the value for the first matching condition, or the default.
+    pub fn priority(&mut self, cases: &[(Lit, Vec<Lit>)], default: Vec<Lit>) -> Vec<Lit> {
+        cases.iter().rev().fold(default, |acc, (select, value)| {
+            self.mux_word(*select, value, &acc)
+        })
+    }
+    pub fn count_ones(&mut self, a: &[Lit]) -> Vec<Lit> {
+        let len = a.len();
+        let mut count = self.word(0, len);
+        for bit in a {
+            let mut one = self.word(0, len);
+            if let Some(lsb) = one.first_mut() {
+                *lsb = *bit;
+            }
+            count = self.add(&count, &one);
+        }
+        count
+    }
+    pub fn leading_zeros(&mut self, a: &[Lit]) -> Vec<Lit> {
+        let len = a.len();
+        let cases = (0..len)
+            .rev()
+            .map(|bit| (a[bit], self.word((len - 1 - bit) as u128, len)))
+            .collect::<Vec<_>>();
+        let default = self.word(len as u128, len);
+        self.priority(&cases, default)
+    }
+    pub fn trailing_zeros(&mut self, a: &[Lit]) -> Vec<Lit> {
+        let len = a.len();
+        let cases = (0..len)
+            .map(|bit| (a[bit], self.word(bit as u128, len)))
+            .collect::<Vec<_>>();
+        let default = self.word(len as u128, len);
+        self.priority(&cases, default)
+    }
+    // The bitwise or of the indices of the bits that are set.
+    pub fn one_hot_to_binary(&mut self, a: &[Lit], len: usize) -> Vec<Lit> {
+        (0..len)
+            .map(|out| {
+                let set = a
+                    .iter()
+                    .enumerate()
+                    .filter(|(ndx, _)| out < usize::BITS as usize && ndx & (1 << out) != 0)
+                    .map(|(_, bit)| *bit)
+                    .collect::<Vec<_>>();
+                self.or_all(set)
+            })
+            .collect()
+    }
+    pub fn binary_to_one_hot(&mut self, a: &[Lit], len: usize) -> Vec<Lit> {
+        (0..len)
+            .map(|ndx| {
+                if a.len() < u128::BITS as usize && ndx as u128 >= 1 << a.len() {
+                    self.constant(false)
+                } else {
+                    let value = self.word(ndx as u128, a.len());
+                    self.eq(a, &value)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Evaluate an operator by pinning its (symbolic) inputs with
+    // assumptions, so that the clauses of the gates are exercised.
+    fn eval(
+        len: usize,
+        f: impl Fn(&mut Cnf, &[Lit], &[Lit]) -> Vec<Lit>,
+        a: u128,
+        b: u128,
+    ) -> u128 {
+        let mut cnf = Cnf::default();
+        let x = (0..len).map(|_| cnf.fresh()).collect::<Vec<_>>();
+        let y = (0..len).map(|_| cnf.fresh()).collect::<Vec<_>>();
+        let result = f(&mut cnf, &x, &y);
+        let pin = |word: &[Lit], value: u128| {
+            word.iter()
+                .enumerate()
+                .map(|(ndx, lit)| if value & (1 << ndx) != 0 { *lit } else { !*lit })
+                .collect::<Vec<_>>()
+        };
+        let assumptions = [pin(&x, a), pin(&y, b)].concat();
+        assert!(cnf.solver.solve(&assumptions));
+        result.iter().enumerate().fold(0, |acc, (ndx, lit)| {
+            acc | ((cnf.solver.model_value(*lit) as u128) << ndx)
+        })
+    }
+
+    fn sext(x: u128, len: usize) -> i128 {
+        ((x << (128 - len)) as i128) >> (128 - len)
+    }
+
+    #[test]
+    fn test_word_operators() {
+        let len = 4;
+        let mask = (1 << len) - 1;
+        for a in 0..16_u128 {
+            for b in 0..16_u128 {
+                assert_eq!(eval(len, |c, x, y| c.add(x, y), a, b), (a + b) & mask);
+                assert_eq!(
+                    eval(len, |c, x, y| c.sub(x, y), a, b),
+                    a.wrapping_sub(b) & mask
+                );
+                assert_eq!(eval(len, |c, x, y| c.mul(x, y), a, b), (a * b) & mask);
+                assert_eq!(
+                    eval(len, |c, x, y| vec![c.ult(x, y)], a, b),
+                    (a < b) as u128
+                );
+                assert_eq!(
+                    eval(len, |c, x, y| vec![c.slt(x, y)], a, b),
+                    (sext(a, len) < sext(b, len)) as u128
+                );
+                let (q, r) = a.checked_div(b).zip(a.checked_rem(b)).unwrap_or((mask, a));
+                assert_eq!(eval(len, |c, x, y| c.div_rem(x, y, false).0, a, b), q);
+                assert_eq!(eval(len, |c, x, y| c.div_rem(x, y, false).1, a, b), r);
+                let (sa, sb) = (sext(a, len), sext(b, len));
+                let (q, r) = if sb == 0 {
+                    (mask, a)
+                } else {
+                    ((sa / sb) as u128 & mask, (sa % sb) as u128 & mask)
+                };
+                assert_eq!(eval(len, |c, x, y| c.div_rem(x, y, true).0, a, b), q);
+                assert_eq!(eval(len, |c, x, y| c.div_rem(x, y, true).1, a, b), r);
+                let expected = if b < len as u128 { (a << b) & mask } else { 0 };
+                assert_eq!(eval(len, |c, x, y| c.shl(x, y), a, b), expected);
+                let expected = if b < len as u128 {
+                    (sext(a, len) >> b) as u128 & mask
+                } else if sext(a, len) < 0 {
+                    mask
+                } else {
+                    0
+                };
+                assert_eq!(eval(len, |c, x, y| c.shr(x, y, true), a, b), expected);
+            }
+            assert_eq!(
+                eval(len, |c, x, _| c.count_ones(x), a, 0),
+                a.count_ones() as u128
+            );
+            assert_eq!(
+                eval(len, |c, x, _| c.leading_zeros(x), a, 0),
+                (a as u8).leading_zeros() as u128 - 4
+            );
+            assert_eq!(
+                eval(len, |c, x, _| c.trailing_zeros(x), a, 0),
+                (a as u8 | 0x10).trailing_zeros() as u128
+            );
+        }
+    }
+
+    #[test]
+    fn test_gates_are_shared() {
+        let mut cnf = Cnf::default();
+        let a = cnf.fresh();
+        let b = cnf.fresh();
+        let x = cnf.and(a, b);
+        assert_eq!(cnf.and(b, a), x);
+        let y = cnf.xor(a, b);
+        assert_eq!(cnf.xor(!a, b), !y);
+        assert_eq!(cnf.xor(!b, !a), y);
+    }
+}
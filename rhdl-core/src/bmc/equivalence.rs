@@ -0,0 +1,154 @@
+// Combinational equivalence checking.
+//
+// The two flow graphs are fed the same (symbolic) inputs, and a miter
+// that is true when any of their output bits differ is handed to the
+// SAT solver.  If the miter cannot be satisfied, the graphs compute the
+// same function.  Otherwise, the satisfying assignment is an input that
+// distinguishes them.
+//
+// As with bounded model checking, an `X` is an arbitrary value that is
+// chosen independently in each graph.  So graphs that produce an `X`
+// are only equivalent if the `X` cannot reach their outputs.
+use crate::{
+    compiler::driver::{compile_design_stage1, compile_design_stage2},
+    flow_graph::build_rtl_flow_graph,
+    rhif::spec::Slot,
+    BitX, CompilationMode, DigitalFn, FlowGraph, Kind, RHDLError, TypedBits,
+};
+
+use super::{cnf::Cnf, unroll::Unroller};
+
+fn mismatch(msg: String) -> RHDLError {
+    RHDLError::EquivalenceCheckError(msg)
+}
+
+// Check without a limit, and attach the kinds to the distinguishing input.
+fn check(
+    a: &FlowGraph,
+    b: &FlowGraph,
+    kinds: &[Kind],
+) -> Result<Option<Vec<TypedBits>>, RHDLError> {
+    match check_equivalence(a, b, usize::MAX)? {
+        Verdict::Equivalent => Ok(None),
+        Verdict::Distinguished(inputs) => Ok(Some(
+            inputs
+                .into_iter()
+                .zip(kinds)
+                .map(|(bits, kind)| TypedBits { bits, kind: *kind })
+                .collect(),
+        )),
+        Verdict::Unknown => unreachable!("an unlimited search always finishes"),
+    }
+}
+
+pub(crate) enum Verdict {
+    Equivalent,
+    // One value per flow graph argument, for which the outputs differ.
+    Distinguished(Vec<Vec<BitX>>),
+    // The solver ran out of conflicts before reaching a verdict.
+    Unknown,
+}
+
+// Check two flow graphs for equivalence, giving up after the solver has
+// run into `max_conflicts` conflicts.
+pub(crate) fn check_equivalence(
+    a: &FlowGraph,
+    b: &FlowGraph,
+    max_conflicts: usize,
+) -> Result<Verdict, RHDLError> {
+    if !a.black_boxes.is_empty() || !b.black_boxes.is_empty() {
+        return Err(mismatch(
+            "only flow graphs without black boxes can be checked for equivalence".into(),
+        ));
+    }
+    let widths = |fg: &FlowGraph| fg.inputs.iter().map(|x| x.len()).collect::<Vec<_>>();
+    if widths(a) != widths(b) {
+        return Err(mismatch(format!(
+            "the flow graphs have different inputs: {:?} and {:?}",
+            widths(a),
+            widths(b)
+        )));
+    }
+    if a.output.len() != b.output.len() {
+        return Err(mismatch(format!(
+            "the flow graphs have outputs of different widths: {} and {}",
+            a.output.len(),
+            b.output.len()
+        )));
+    }
+    let (a, b) = (Unroller::new(a)?, Unroller::new(b)?);
+    let mut cnf = Cnf::default();
+    let inputs = widths(a.flow_graph())
+        .into_iter()
+        .map(|len| (0..len).map(|_| cnf.fresh()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let frame = a.frame(&mut cnf, &inputs, &[])?;
+    let a_outputs = a.outputs(&mut cnf, &frame);
+    let frame = b.frame(&mut cnf, &inputs, &[])?;
+    let b_outputs = b.outputs(&mut cnf, &frame);
+    let differences = a_outputs
+        .into_iter()
+        .zip(b_outputs)
+        .map(|(a, b)| cnf.xor(a, b))
+        .collect::<Vec<_>>();
+    let miter = cnf.or_all(differences);
+    match cnf.solver.solve_limited(&[miter], max_conflicts) {
+        None => return Ok(Verdict::Unknown),
+        Some(false) => return Ok(Verdict::Equivalent),
+        Some(true) => {}
+    }
+    Ok(Verdict::Distinguished(
+        inputs
+            .iter()
+            .map(|input| {
+                input
+                    .iter()
+                    .map(|lit| BitX::from(cnf.solver.model_value(*lit)))
+                    .collect()
+            })
+            .collect(),
+    ))
+}
+
+// Check that two flow graphs with the same inputs and outputs compute
+// the same function.  Returns `None` if they do, and an input (one value
+// per flow graph argument) for which they differ otherwise.  The flow
+// graphs carry no types, so the values are returned as plain bits.
+pub fn flow_graph_equivalence(
+    a: &FlowGraph,
+    b: &FlowGraph,
+) -> Result<Option<Vec<TypedBits>>, RHDLError> {
+    let kinds = a
+        .inputs
+        .iter()
+        .map(|x| Kind::make_bits(x.len()))
+        .collect::<Vec<_>>();
+    check(a, b, &kinds)
+}
+
+// Check that two kernels with the same signature compute the same
+// function.  Returns `None` if they do, and the arguments for which they
+// differ otherwise.
+pub fn kernel_equivalence<A: DigitalFn, B: DigitalFn>() -> Result<Option<Vec<TypedBits>>, RHDLError>
+{
+    let a = compile_design_stage1::<A>(CompilationMode::Synchronous)?;
+    let b = compile_design_stage1::<B>(CompilationMode::Synchronous)?;
+    let signature = |obj: &crate::rhif::Object| {
+        let arguments = obj
+            .arguments
+            .iter()
+            .map(|reg| obj.kind(Slot::Register(*reg)))
+            .collect::<Vec<_>>();
+        (arguments, obj.kind(obj.return_slot))
+    };
+    let (arguments, ret) = signature(&a);
+    if (arguments.clone(), ret) != signature(&b) {
+        return Err(mismatch(format!(
+            "the kernels {} and {} have different signatures",
+            a.name, b.name
+        )));
+    }
+    let a = build_rtl_flow_graph(&compile_design_stage2(&a)?);
+    let b = build_rtl_flow_graph(&compile_design_stage2(&b)?);
+    check(&a, &b, &arguments)
+}
@@ -0,0 +1,127 @@
+// Bounded model checking of synchronous circuits.
+//
+// The optimized flow graph of the circuit is unrolled for a number of
+// clock cycles, starting from the reset state of its flip flops, and a
+// predicate kernel over the inputs and outputs is checked in every
+// cycle.  Both are bit blasted to CNF and handed to the built in SAT
+// solver.  The cycles are checked one at a time, so that the first
+// counterexample found is also the shortest.
+//
+// Any `X` in the design (e.g., a `dont_care` value) is treated as an
+// arbitrary value that the solver is free to choose in every cycle.
+pub mod cnf;
+pub mod equivalence;
+pub mod sat;
+pub mod unroll;
+
+use crate::{
+    bitx::BitX,
+    flow_graph::{build_rtl_flow_graph, optimization::optimize_flow_graph},
+    sim::{
+        clock_pos_edge::ClockPosEdgeExt, run::synchronous::run_synchronous, stream::TimedStreamExt,
+    },
+    ClockReset, CompilationMode, Digital, DigitalFn, DigitalFn2, RHDLError, Synchronous,
+    SynchronousIO, TimedSample,
+};
+
+use self::{cnf::Cnf, sat::Lit, unroll::Unroller};
+
+pub type Counterexample<T> =
+    Vec<TimedSample<(ClockReset, <T as SynchronousIO>::I, <T as SynchronousIO>::O)>>;
+
+// The clock period used to replay a counterexample.
+const REPLAY_PERIOD: u64 = 100;
+
+// Check that the predicate kernel `P` holds for the inputs and outputs
+// of the circuit in each of the first `depth` clock cycles after a
+// reset.  Returns `None` if it does, and a trace of the shortest
+// sequence of inputs that violates it otherwise.  The trace starts with
+// a reset cycle, and is generated by replaying the inputs through the
+// circuit, so it can be collected into a VCD like any other simulation.
+// The predicate fails in the last clock cycle of the trace.
+pub fn bounded_model_check<T, P>(
+    uut: &T,
+    depth: usize,
+) -> Result<Option<Counterexample<T>>, RHDLError>
+where
+    T: Synchronous,
+    P: DigitalFn + DigitalFn2<A0 = <T as SynchronousIO>::I, A1 = <T as SynchronousIO>::O, O = bool>,
+{
+    // A counterexample can only be replayed if the inputs can be decoded.
+    if <T::I as Digital>::from_bin(&<T::I as Digital>::dont_care().bin()).is_none() {
+        return Err(RHDLError::BoundedModelCheckError(format!(
+            "the circuit input type {} cannot be decoded from its bits",
+            std::any::type_name::<T::I>()
+        )));
+    }
+    let circuit = Unroller::new(&uut.flow_graph("top")?)?;
+    let predicate = crate::compile_design::<P>(CompilationMode::Synchronous)?;
+    let predicate = Unroller::new(&optimize_flow_graph(build_rtl_flow_graph(&predicate))?)?;
+    let mut cnf = Cnf::default();
+    let mut state = circuit.reset_state(&mut cnf);
+    let mut inputs: Vec<Vec<Lit>> = vec![];
+    for _ in 0..depth {
+        let clock_reset = vec![cnf.constant(false); 2];
+        let input = (0..<T::I as Digital>::BITS)
+            .map(|_| cnf.fresh())
+            .collect::<Vec<_>>();
+        let frame = circuit.frame(&mut cnf, &[clock_reset, input.clone()], &state)?;
+        let output = circuit.outputs(&mut cnf, &frame);
+        let check = predicate.frame(&mut cnf, &[input.clone(), output], &[])?;
+        let holds = predicate.outputs(&mut cnf, &check)[0];
+        inputs.push(input);
+        while cnf.solver.solve(&[!holds]) {
+            match decode::<T>(&cnf, &inputs) {
+                Ok(values) => return Ok(Some(replay(uut, values))),
+                // Inputs that are not valid values of the input type
+                // (e.g., an undefined enum discriminant) cannot be
+                // replayed, so rule them out and look for another run.
+                Err(blocked) => {
+                    cnf.solver.add_clause(&blocked);
+                }
+            }
+        }
+        // The predicate holds in this cycle for every run, which helps
+        // the solver with the later cycles.
+        cnf.solver.add_clause(&[holds]);
+        state = circuit.next_state(&mut cnf, &frame);
+    }
+    Ok(None)
+}
+
+// Decode the inputs of a satisfying assignment.  If one of them is not
+// a valid value, returns a clause that excludes it instead.
+fn decode<T: Synchronous>(cnf: &Cnf, inputs: &[Vec<Lit>]) -> Result<Vec<T::I>, Vec<Lit>> {
+    inputs
+        .iter()
+        .map(|input| {
+            let bits = input
+                .iter()
+                .map(|lit| BitX::from(cnf.solver.model_value(*lit)))
+                .collect::<Vec<_>>();
+            <T::I as Digital>::from_bin(&bits).ok_or_else(|| {
+                input
+                    .iter()
+                    .map(|lit| {
+                        if cnf.solver.model_value(*lit) {
+                            !*lit
+                        } else {
+                            *lit
+                        }
+                    })
+                    .collect()
+            })
+        })
+        .collect()
+}
+
+fn replay<T: Synchronous>(uut: &T, inputs: Vec<T::I>) -> Counterexample<T> {
+    run_synchronous(
+        uut,
+        inputs
+            .into_iter()
+            .stream_after_reset(1)
+            .clock_pos_edge(REPLAY_PERIOD),
+    )
+    .collect()
+}
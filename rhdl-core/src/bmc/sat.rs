@@ -0,0 +1,696 @@
+// A small CDCL SAT solver.
+//
+// The solver follows the design of MiniSat: two watched literals per
+// clause, first UIP conflict analysis with clause minimization, VSIDS
+// variable activities with phase saving, Luby restarts, and periodic
+// removal of inactive learnt clauses.  Clauses can be added between
+// calls to `solve`, and each call can be given a set of assumptions, so
+// that a problem can be grown and queried incrementally.
+use std::ops::Not;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Lit(u32);
+
+impl Lit {
+    fn new(var: usize, negated: bool) -> Self {
+        Lit(((var as u32) << 1) | negated as u32)
+    }
+    pub fn var(self) -> usize {
+        (self.0 >> 1) as usize
+    }
+    pub fn is_negated(self) -> bool {
+        self.0 & 1 == 1
+    }
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Not for Lit {
+    type Output = Lit;
+    fn not(self) -> Lit {
+        Lit(self.0 ^ 1)
+    }
+}
+
+fn lit_value(assigns: &[Option<bool>], lit: Lit) -> Option<bool> {
+    assigns[lit.var()].map(|value| value ^ lit.is_negated())
+}
+
+// The restart schedule: 1, 1, 2, 1, 1, 2, 4, 1, 1, 2, ...
+fn luby(mut ndx: usize) -> usize {
+    let mut size = 1;
+    let mut seq = 0;
+    while size < ndx + 1 {
+        seq += 1;
+        size = 2 * size + 1;
+    }
+    while size - 1 != ndx {
+        size = (size - 1) / 2;
+        seq -= 1;
+        ndx %= size;
+    }
+    1 << seq
+}
+
+const RESTART_UNIT: usize = 100;
+const VAR_DECAY: f64 = 0.95;
+const CLAUSE_DECAY: f64 = 0.999;
+const RESCALE_LIMIT: f64 = 1e100;
+
+#[derive(Clone, Debug)]
+struct Clause {
+    lits: Vec<Lit>,
+    learnt: bool,
+    deleted: bool,
+    activity: f64,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Watcher {
+    clause: usize,
+    blocker: Lit,
+}
+
+// A binary max-heap of the unassigned variables, ordered by activity.
+#[derive(Clone, Debug, Default)]
+struct VarOrder {
+    heap: Vec<usize>,
+    position: Vec<Option<usize>>,
+}
+
+impl VarOrder {
+    fn contains(&self, var: usize) -> bool {
+        self.position[var].is_some()
+    }
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        self.position[self.heap[a]] = Some(a);
+        self.position[self.heap[b]] = Some(b);
+    }
+    fn up(&mut self, mut ndx: usize, activity: &[f64]) {
+        while ndx > 0 {
+            let parent = (ndx - 1) / 2;
+            if activity[self.heap[ndx]] <= activity[self.heap[parent]] {
+                break;
+            }
+            self.swap(ndx, parent);
+            ndx = parent;
+        }
+    }
+    fn down(&mut self, mut ndx: usize, activity: &[f64]) {
+        loop {
+            let mut largest = ndx;
+            for child in [2 * ndx + 1, 2 * ndx + 2] {
+                if child < self.heap.len()
+                    && activity[self.heap[child]] > activity[self.heap[largest]]
+                {
+                    largest = child;
+                }
+            }
+            if largest == ndx {
+                break;
+            }
+            self.swap(ndx, largest);
+            ndx = largest;
+        }
+    }
+    fn insert(&mut self, var: usize, activity: &[f64]) {
+        if self.position.len() <= var {
+            self.position.resize(var + 1, None);
+        }
+        if self.contains(var) {
+            return;
+        }
+        self.heap.push(var);
+        self.position[var] = Some(self.heap.len() - 1);
+        self.up(self.heap.len() - 1, activity);
+    }
+    fn increased(&mut self, var: usize, activity: &[f64]) {
+        if let Some(ndx) = self.position[var] {
+            self.up(ndx, activity);
+        }
+    }
+    fn pop(&mut self, activity: &[f64]) -> Option<usize> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        let var = self.heap.pop()?;
+        self.position[var] = None;
+        if !self.heap.is_empty() {
+            self.down(0, activity);
+        }
+        Some(var)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Solver {
+    clauses: Vec<Clause>,
+    // The clauses in which a literal is watched, indexed by literal.
+    watches: Vec<Vec<Watcher>>,
+    assigns: Vec<Option<bool>>,
+    level: Vec<usize>,
+    reason: Vec<Option<usize>>,
+    trail: Vec<Lit>,
+    trail_lim: Vec<usize>,
+    qhead: usize,
+    activity: Vec<f64>,
+    var_inc: f64,
+    clause_inc: f64,
+    polarity: Vec<bool>,
+    order: VarOrder,
+    seen: Vec<bool>,
+    learnts: usize,
+    max_learnts: f64,
+    model: Vec<bool>,
+    ok: bool,
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Self {
+            clauses: vec![],
+            watches: vec![],
+            assigns: vec![],
+            level: vec![],
+            reason: vec![],
+            trail: vec![],
+            trail_lim: vec![],
+            qhead: 0,
+            activity: vec![],
+            var_inc: 1.0,
+            clause_inc: 1.0,
+            polarity: vec![],
+            order: VarOrder::default(),
+            seen: vec![],
+            learnts: 0,
+            max_learnts: 0.0,
+            model: vec![],
+            ok: true,
+        }
+    }
+}
+
+impl Solver {
+    pub fn num_vars(&self) -> usize {
+        self.assigns.len()
+    }
+    pub fn num_clauses(&self) -> usize {
+        self.clauses.len() - self.learnts
+    }
+    // Allocate a new variable, and return its positive literal.
+    pub fn new_var(&mut self) -> Lit {
+        let var = self.assigns.len();
+        self.assigns.push(None);
+        self.level.push(0);
+        self.reason.push(None);
+        self.activity.push(0.0);
+        self.polarity.push(false);
+        self.seen.push(false);
+        self.watches.push(vec![]);
+        self.watches.push(vec![]);
+        self.order.insert(var, &self.activity);
+        Lit::new(var, false)
+    }
+    fn value(&self, lit: Lit) -> Option<bool> {
+        lit_value(&self.assigns, lit)
+    }
+    fn decision_level(&self) -> usize {
+        self.trail_lim.len()
+    }
+    fn enqueue(&mut self, lit: Lit, reason: Option<usize>) {
+        let var = lit.var();
+        self.assigns[var] = Some(!lit.is_negated());
+        self.level[var] = self.decision_level();
+        self.reason[var] = reason;
+        self.trail.push(lit);
+    }
+    fn attach(&mut self, lits: Vec<Lit>, learnt: bool) -> usize {
+        let cref = self.clauses.len();
+        self.watches[lits[0].index()].push(Watcher {
+            clause: cref,
+            blocker: lits[1],
+        });
+        self.watches[lits[1].index()].push(Watcher {
+            clause: cref,
+            blocker: lits[0],
+        });
+        self.clauses.push(Clause {
+            lits,
+            learnt,
+            deleted: false,
+            activity: 0.0,
+        });
+        if learnt {
+            self.learnts += 1;
+        }
+        cref
+    }
+    // Add a clause to the problem.  Returns false if the problem is
+    // now known to be unsatisfiable.
+    pub fn add_clause(&mut self, lits: &[Lit]) -> bool {
+        assert_eq!(
+            self.decision_level(),
+            0,
+            "clauses can only be added between calls to solve"
+        );
+        if !self.ok {
+            return false;
+        }
+        let mut lits = lits.to_vec();
+        lits.sort();
+        lits.dedup();
+        // A literal and its negation are adjacent once sorted
+        if lits.windows(2).any(|pair| pair[0] == !pair[1])
+            || lits.iter().any(|lit| self.value(*lit) == Some(true))
+        {
+            return true;
+        }
+        lits.retain(|lit| self.value(*lit).is_none());
+        match lits.len() {
+            0 => self.ok = false,
+            1 => {
+                self.enqueue(lits[0], None);
+                self.ok = self.propagate().is_none();
+            }
+            _ => {
+                self.attach(lits, false);
+            }
+        }
+        self.ok
+    }
+    // Propagate the assignments on the trail.  Returns the conflicting
+    // clause, if any.
+    fn propagate(&mut self) -> Option<usize> {
+        let mut conflict = None;
+        while conflict.is_none() && self.qhead < self.trail.len() {
+            let false_lit = !self.trail[self.qhead];
+            self.qhead += 1;
+            let mut watchers = std::mem::take(&mut self.watches[false_lit.index()]);
+            let mut kept = 0;
+            let mut ndx = 0;
+            while ndx < watchers.len() {
+                let watcher = watchers[ndx];
+                ndx += 1;
+                if lit_value(&self.assigns, watcher.blocker) == Some(true) {
+                    watchers[kept] = watcher;
+                    kept += 1;
+                    continue;
+                }
+                let clause = &mut self.clauses[watcher.clause];
+                if clause.deleted {
+                    continue;
+                }
+                let lits = &mut clause.lits;
+                if lits[0] == false_lit {
+                    lits.swap(0, 1);
+                }
+                let first = lits[0];
+                let watcher = Watcher {
+                    clause: watcher.clause,
+                    blocker: first,
+                };
+                if lit_value(&self.assigns, first) == Some(true) {
+                    watchers[kept] = watcher;
+                    kept += 1;
+                    continue;
+                }
+                // Look for a new literal to watch
+                if let Some(pos) =
+                    (2..lits.len()).find(|&k| lit_value(&self.assigns, lits[k]) != Some(false))
+                {
+                    lits.swap(1, pos);
+                    let watch = lits[1];
+                    self.watches[watch.index()].push(watcher);
+                    continue;
+                }
+                // The clause is unit or conflicting
+                watchers[kept] = watcher;
+                kept += 1;
+                if lit_value(&self.assigns, first) == Some(false) {
+                    conflict = Some(watcher.clause);
+                    self.qhead = self.trail.len();
+                    while ndx < watchers.len() {
+                        watchers[kept] = watchers[ndx];
+                        kept += 1;
+                        ndx += 1;
+                    }
+                } else {
+                    self.enqueue(first, Some(watcher.clause));
+                }
+            }
+            watchers.truncate(kept);
+            self.watches[false_lit.index()] = watchers;
+        }
+        conflict
+    }
+    fn bump_var(&mut self, var: usize) {
+        self.activity[var] += self.var_inc;
+        if self.activity[var] > RESCALE_LIMIT {
+            self.activity.iter_mut().for_each(|x| *x /= RESCALE_LIMIT);
+            self.var_inc /= RESCALE_LIMIT;
+        }
+        self.order.increased(var, &self.activity);
+    }
+    fn bump_clause(&mut self, cref: usize) {
+        if !self.clauses[cref].learnt {
+            return;
+        }
+        self.clauses[cref].activity += self.clause_inc;
+        if self.clauses[cref].activity > RESCALE_LIMIT {
+            self.clauses
+                .iter_mut()
+                .filter(|clause| clause.learnt)
+                .for_each(|clause| clause.activity /= RESCALE_LIMIT);
+            self.clause_inc /= RESCALE_LIMIT;
+        }
+    }
+    // Derive the first UIP clause from a conflict.  Returns the learnt
+    // clause (with the asserting literal first) and the level to
+    // backtrack to.
+    fn analyze(&mut self, mut conflict: usize) -> (Vec<Lit>, usize) {
+        let mut learnt = vec![];
+        let mut pending = 0;
+        let mut pivot: Option<Lit> = None;
+        let mut ndx = self.trail.len();
+        loop {
+            self.bump_clause(conflict);
+            let skip = usize::from(pivot.is_some());
+            for k in skip..self.clauses[conflict].lits.len() {
+                let lit = self.clauses[conflict].lits[k];
+                let var = lit.var();
+                if !self.seen[var] && self.level[var] > 0 {
+                    self.seen[var] = true;
+                    self.bump_var(var);
+                    if self.level[var] >= self.decision_level() {
+                        pending += 1;
+                    } else {
+                        learnt.push(lit);
+                    }
+                }
+            }
+            // Walk back along the trail to the next literal in the conflict
+            loop {
+                ndx -= 1;
+                if self.seen[self.trail[ndx].var()] {
+                    break;
+                }
+            }
+            let lit = self.trail[ndx];
+            self.seen[lit.var()] = false;
+            pivot = Some(lit);
+            pending -= 1;
+            if pending == 0 {
+                break;
+            }
+            conflict = self.reason[lit.var()].expect("implied literal has a reason");
+        }
+        // Drop the literals that are implied by the rest of the clause
+        let minimized = learnt
+            .iter()
+            .copied()
+            .filter(|lit| match self.reason[lit.var()] {
+                None => true,
+                Some(reason) => self.clauses[reason].lits[1..]
+                    .iter()
+                    .any(|q| !self.seen[q.var()] && self.level[q.var()] > 0),
+            })
+            .collect::<Vec<_>>();
+        learnt.iter().for_each(|lit| self.seen[lit.var()] = false);
+        let mut learnt = minimized;
+        learnt.insert(0, !pivot.expect("conflict has a pivot"));
+        let backtrack = if learnt.len() == 1 {
+            0
+        } else {
+            let (pos, _) = learnt
+                .iter()
+                .enumerate()
+                .skip(1)
+                .max_by_key(|(_, lit)| self.level[lit.var()])
+                .expect("learnt clause has more than one literal");
+            learnt.swap(1, pos);
+            self.level[learnt[1].var()]
+        };
+        (learnt, backtrack)
+    }
+    fn cancel_until(&mut self, level: usize) {
+        if self.decision_level() <= level {
+            return;
+        }
+        let start = self.trail_lim[level];
+        for ndx in (start..self.trail.len()).rev() {
+            let lit = self.trail[ndx];
+            let var = lit.var();
+            self.polarity[var] = !lit.is_negated();
+            self.assigns[var] = None;
+            self.reason[var] = None;
+            self.order.insert(var, &self.activity);
+        }
+        self.trail.truncate(start);
+        self.trail_lim.truncate(level);
+        self.qhead = start;
+    }
+    fn pick_branch(&mut self) -> Option<Lit> {
+        while let Some(var) = self.order.pop(&self.activity) {
+            if self.assigns[var].is_none() {
+                return Some(Lit::new(var, !self.polarity[var]));
+            }
+        }
+        None
+    }
+    fn locked(&self, cref: usize) -> bool {
+        let lit = self.clauses[cref].lits[0];
+        self.reason[lit.var()] == Some(cref) && self.value(lit) == Some(true)
+    }
+    // Remove the less active half of the learnt clauses.
+    fn reduce_learnts(&mut self) {
+        let mut learnts = (0..self.clauses.len())
+            .filter(|&cref| {
+                let clause = &self.clauses[cref];
+                clause.learnt && !clause.deleted && clause.lits.len() > 2
+            })
+            .collect::<Vec<_>>();
+        learnts.sort_by(|a, b| {
+            self.clauses[*a]
+                .activity
+                .total_cmp(&self.clauses[*b].activity)
+        });
+        for cref in learnts.iter().take(learnts.len() / 2) {
+            if !self.locked(*cref) {
+                self.clauses[*cref].deleted = true;
+                self.clauses[*cref].lits = vec![];
+                self.learnts -= 1;
+            }
+        }
+    }
+    // Search for a model until the given number of conflicts is reached,
+    // or the budget runs out.  Returns `None` if the search should be
+    // restarted.
+    fn search(
+        &mut self,
+        max_conflicts: usize,
+        budget: &mut usize,
+        assumptions: &[Lit],
+    ) -> Option<bool> {
+        let mut conflicts = 0;
+        loop {
+            if let Some(conflict) = self.propagate() {
+                conflicts += 1;
+                *budget = budget.saturating_sub(1);
+                if self.decision_level() == 0 {
+                    self.ok = false;
+                    return Some(false);
+                }
+                let (learnt, backtrack) = self.analyze(conflict);
+                self.cancel_until(backtrack);
+                if learnt.len() == 1 {
+                    self.enqueue(learnt[0], None);
+                } else {
+                    let asserting = learnt[0];
+                    let cref = self.attach(learnt, true);
+                    self.bump_clause(cref);
+                    self.enqueue(asserting, Some(cref));
+                }
+                self.var_inc /= VAR_DECAY;
+                self.clause_inc /= CLAUSE_DECAY;
+                continue;
+            }
+            if conflicts >= max_conflicts || *budget == 0 {
+                self.cancel_until(0);
+                return None;
+            }
+            if self.learnts as f64 >= self.max_learnts + self.trail.len() as f64 {
+                self.reduce_learnts();
+                self.max_learnts *= 1.1;
+            }
+            let mut next = None;
+            while self.decision_level() < assumptions.len() {
+                let assumption = assumptions[self.decision_level()];
+                match self.value(assumption) {
+                    Some(true) => self.trail_lim.push(self.trail.len()),
+                    Some(false) => return Some(false),
+                    None => {
+                        next = Some(assumption);
+                        break;
+                    }
+                }
+            }
+            let next = match next.or_else(|| self.pick_branch()) {
+                Some(lit) => lit,
+                None => return Some(true),
+            };
+            self.trail_lim.push(self.trail.len());
+            self.enqueue(next, None);
+        }
+    }
+    // Solve the problem under the given assumptions.  If it is satisfiable,
+    // the model can be read back with `model_value`.
+    pub fn solve(&mut self, assumptions: &[Lit]) -> bool {
+        self.solve_limited(assumptions, usize::MAX)
+            .expect("an unlimited search always finishes")
+    }
+    // As `solve`, but gives up (and returns `None`) once the search has
+    // run into the given number of conflicts.
+    pub fn solve_limited(&mut self, assumptions: &[Lit], max_conflicts: usize) -> Option<bool> {
+        if !self.ok {
+            return Some(false);
+        }
+        self.max_learnts = self.max_learnts.max(self.num_clauses() as f64 / 3.0);
+        let mut budget = max_conflicts;
+        let mut restarts = 0;
+        let result = loop {
+            if let Some(result) =
+                self.search(luby(restarts) * RESTART_UNIT, &mut budget, assumptions)
+            {
+                break result;
+            }
+            if budget == 0 {
+                return None;
+            }
+            restarts += 1;
+        };
+        if result {
+            self.model = self.assigns.iter().map(|x| x.unwrap_or(false)).collect();
+        }
+        self.cancel_until(0);
+        Some(result)
+    }
+    pub fn model_value(&self, lit: Lit) -> bool {
+        self.model.get(lit.var()).copied().unwrap_or(false) ^ lit.is_negated()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(solver: &mut Solver, count: usize) -> Vec<Lit> {
+        (0..count).map(|_| solver.new_var()).collect()
+    }
+
+    // Place `holes + 1` pigeons into `holes` holes, which is unsatisfiable
+    // and hard for resolution based solvers.
+    fn pigeon_hole(holes: usize) -> Solver {
+        let mut solver = Solver::default();
+        let p = (0..=holes)
+            .map(|_| vars(&mut solver, holes))
+            .collect::<Vec<_>>();
+        for pigeon in &p {
+            solver.add_clause(pigeon);
+        }
+        for hole in 0..holes {
+            for (a, pa) in p.iter().enumerate() {
+                for pb in &p[a + 1..] {
+                    solver.add_clause(&[!pa[hole], !pb[hole]]);
+                }
+            }
+        }
+        solver
+    }
+
+    #[test]
+    fn test_limited_search_gives_up() {
+        assert_eq!(pigeon_hole(9).solve_limited(&[], 10), None);
+    }
+
+    #[test]
+    fn test_luby_sequence() {
+        let seq = (0..15).map(luby).collect::<Vec<_>>();
+        assert_eq!(seq, vec![1, 1, 2, 1, 1, 2, 4, 1, 1, 2, 1, 1, 2, 4, 8]);
+    }
+
+    #[test]
+    fn test_simple_model() {
+        let mut solver = Solver::default();
+        let x = vars(&mut solver, 3);
+        solver.add_clause(&[x[0], x[1]]);
+        solver.add_clause(&[!x[0], x[2]]);
+        solver.add_clause(&[!x[1], x[2]]);
+        solver.add_clause(&[!x[2], !x[0]]);
+        assert!(solver.solve(&[]));
+        assert!(!solver.model_value(x[0]));
+        assert!(solver.model_value(x[1]));
+        assert!(solver.model_value(x[2]));
+    }
+
+    #[test]
+    fn test_assumptions_are_not_permanent() {
+        let mut solver = Solver::default();
+        let x = vars(&mut solver, 2);
+        solver.add_clause(&[!x[0], x[1]]);
+        assert!(!solver.solve(&[x[0], !x[1]]));
+        assert!(solver.solve(&[x[0]]));
+        assert!(solver.model_value(x[1]));
+        assert!(solver.solve(&[!x[1]]));
+        assert!(!solver.model_value(x[0]));
+    }
+
+    // No pigeon hole principle for n+1 pigeons in n holes
+    #[test]
+    fn test_pigeon_hole_is_unsat() {
+        assert!(!pigeon_hole(6).solve(&[]));
+    }
+
+    // Random 3-SAT problems, checked against exhaustive search
+    #[test]
+    fn test_random_problems_against_brute_force() {
+        use rand::{Rng, SeedableRng};
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x5a7);
+        for _ in 0..200 {
+            let num_vars = 12;
+            let clauses = (0..rng.gen_range(30..70))
+                .map(|_| {
+                    (0..3)
+                        .map(|_| (rng.gen_range(0..num_vars), rng.gen_bool(0.5)))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            let satisfies = |assignment: &dyn Fn(usize) -> bool| {
+                clauses.iter().all(|clause| {
+                    clause
+                        .iter()
+                        .any(|(var, negated)| assignment(*var) ^ negated)
+                })
+            };
+            let expected =
+                (0..1_u32 << num_vars).any(|bits| satisfies(&|var| bits & (1 << var) != 0));
+            let mut solver = Solver::default();
+            let x = vars(&mut solver, num_vars);
+            for clause in &clauses {
+                let lits = clause
+                    .iter()
+                    .map(|(var, negated)| if *negated { !x[*var] } else { x[*var] })
+                    .collect::<Vec<_>>();
+                solver.add_clause(&lits);
+            }
+            let result = solver.solve(&[]);
+            assert_eq!(result, expected);
+            if result {
+                assert!(satisfies(&|var| solver.model_value(x[var])));
+            }
+        }
+    }
+}
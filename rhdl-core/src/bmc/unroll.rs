@@ -0,0 +1,396 @@
+// Encode copies of the combinational logic of a flow graph as CNF.
+//
+// Each copy (or frame) is given literals for the arguments of the flow
+// graph and for the outputs of the black boxes, and computes literals
+// for every node in the graph.  Unknown values (`X` constants, cases
+// with no matching entry and out of range dynamic indices) are encoded
+// as fresh variables, so that the solver is free to pick any value for
+// them.  The semantics otherwise follow the native flow graph simulator.
+use petgraph::visit::{EdgeRef, NodeIndexable};
+
+use crate::{
+    bitx::BitX,
+    error::rhdl_error,
+    flow_graph::{
+        component::{CaseEntry, ComponentKind},
+        error::{FlowGraphError, FlowGraphICE},
+        flow_graph_impl::{BlackBoxMode, BlackBoxModel, FlowGraph, FlowIx},
+        sim::Ports,
+    },
+    hdl::ast::SignedWidth,
+    rtl::spec::{AluBinary, AluUnary},
+    RHDLError,
+};
+
+use super::{cnf::Cnf, sat::Lit};
+
+type Result<T> = std::result::Result<T, RHDLError>;
+
+pub struct Unroller {
+    fg: FlowGraph,
+    order: Vec<FlowIx>,
+    ports: Vec<Ports>,
+    is_input: Vec<bool>,
+}
+
+// The literals for every node in one copy of the flow graph.
+pub struct Frame {
+    values: Vec<Vec<Lit>>,
+}
+
+impl Frame {
+    fn bit(&self, node: FlowIx) -> Option<Lit> {
+        self.values[node.index()].first().copied()
+    }
+}
+
+fn bits(cnf: &mut Cnf, bits: &[BitX]) -> Vec<Lit> {
+    bits.iter()
+        .map(|bit| match bit.to_bool() {
+            Some(value) => cnf.constant(value),
+            None => cnf.fresh(),
+        })
+        .collect()
+}
+
+// Resize a result to the width of the component that holds it.
+fn fit(cnf: &Cnf, mut bits: Vec<Lit>, width: usize) -> Vec<Lit> {
+    bits.resize(width, cnf.constant(false));
+    bits
+}
+
+impl Unroller {
+    pub fn new(fg: &FlowGraph) -> Result<Self> {
+        let graph = &fg.graph;
+        let raise = |cause, node: Option<FlowIx>| {
+            let elements = node
+                .and_then(|node| graph[node].location)
+                .map(|location| fg.code.span(location).into())
+                .into_iter()
+                .collect();
+            rhdl_error(FlowGraphError {
+                cause,
+                src: fg.code.source(),
+                elements,
+            })
+        };
+        let order = petgraph::algo::toposort(graph, None)
+            .map_err(|cycle| raise(FlowGraphICE::LogicLoop, Some(cycle.node_id())))?;
+        // Only flip flops driven by the circuit clock can be unrolled.
+        for bb in &fg.black_boxes {
+            if bb.mode != BlackBoxMode::Synchronous
+                || !matches!(bb.model, BlackBoxModel::Dff { .. })
+            {
+                return Err(raise(
+                    FlowGraphICE::BlackBoxNotSupported,
+                    bb.outputs.first().copied(),
+                ));
+            }
+        }
+        let bound = graph.node_bound();
+        let mut ports = vec![Ports::default(); bound];
+        for node in graph.node_indices() {
+            ports[node.index()] = Ports::new(
+                graph
+                    .edges_directed(node, petgraph::Direction::Incoming)
+                    .map(|edge| (edge.weight().clone(), edge.source())),
+            );
+        }
+        let mut is_input = vec![false; bound];
+        for node in fg.inputs.iter().flatten() {
+            is_input[node.index()] = true;
+        }
+        Ok(Self {
+            fg: fg.clone(),
+            order,
+            ports,
+            is_input,
+        })
+    }
+    pub fn flow_graph(&self) -> &FlowGraph {
+        &self.fg
+    }
+    // The literals for the state of the flip flops after a reset.
+    pub fn reset_state(&self, cnf: &mut Cnf) -> Vec<Vec<Lit>> {
+        self.fg
+            .black_boxes
+            .iter()
+            .map(|bb| match &bb.model {
+                BlackBoxModel::Dff { reset } => bits(cnf, reset.bits()),
+                BlackBoxModel::Opaque => vec![],
+            })
+            .collect()
+    }
+    // Encode one copy of the combinational logic, given one word per
+    // flow graph argument (LSB first) and the state of the flip flops.
+    pub fn frame(&self, cnf: &mut Cnf, inputs: &[Vec<Lit>], state: &[Vec<Lit>]) -> Result<Frame> {
+        assert_eq!(
+            inputs.len(),
+            self.fg.inputs.len(),
+            "unrolling a flow graph expects one input per argument"
+        );
+        let mut frame = Frame {
+            values: vec![vec![]; self.fg.graph.node_bound()],
+        };
+        for (nodes, word) in self.fg.inputs.iter().zip(inputs) {
+            for (node, lit) in nodes.iter().zip(word) {
+                frame.values[node.index()] = vec![*lit];
+            }
+        }
+        for (bb, word) in self.fg.black_boxes.iter().zip(state) {
+            for (node, lit) in bb.outputs.iter().zip(word) {
+                frame.values[node.index()] = vec![*lit];
+            }
+        }
+        for node in &self.order {
+            if let Some(value) = self.component(cnf, &frame, *node)? {
+                frame.values[node.index()] = value;
+            }
+        }
+        Ok(frame)
+    }
+    pub fn outputs(&self, cnf: &mut Cnf, frame: &Frame) -> Vec<Lit> {
+        self.fg
+            .output
+            .iter()
+            .map(|node| frame.bit(*node).unwrap_or_else(|| cnf.fresh()))
+            .collect()
+    }
+    // The state of the flip flops after the clock edge at the end of
+    // the frame.  A flip flop that is held in reset loads its reset value.
+    pub fn next_state(&self, cnf: &mut Cnf, frame: &Frame) -> Vec<Vec<Lit>> {
+        self.fg
+            .black_boxes
+            .iter()
+            .map(|bb| {
+                let reset_value = match &bb.model {
+                    BlackBoxModel::Dff { reset } => bits(cnf, reset.bits()),
+                    BlackBoxModel::Opaque => vec![],
+                };
+                let mut bit = |node: Option<&FlowIx>| {
+                    node.and_then(|node| frame.bit(*node))
+                        .unwrap_or_else(|| cnf.fresh())
+                };
+                let reset = bit(bb.inputs.first().and_then(|clock_reset| clock_reset.get(1)));
+                let data = (0..reset_value.len())
+                    .map(|ndx| bit(bb.inputs.get(1).and_then(|nodes| nodes.get(ndx))))
+                    .collect::<Vec<_>>();
+                cnf.mux_word(reset, &reset_value, &data)
+            })
+            .collect()
+    }
+    fn raise_ice(&self, cause: FlowGraphICE, node: FlowIx) -> RHDLError {
+        rhdl_error(FlowGraphError {
+            cause,
+            src: self.fg.code.source(),
+            elements: self.fg.graph[node]
+                .location
+                .map(|loc| self.fg.code.span(loc).into())
+                .into_iter()
+                .collect(),
+        })
+    }
+    // Note that unlike the HDL generator, arguments are collected LSB first.
+    fn argument(
+        &self,
+        cnf: &mut Cnf,
+        frame: &Frame,
+        node: FlowIx,
+        ports: &[Option<FlowIx>],
+        width: usize,
+    ) -> Result<Vec<Lit>> {
+        (0..width)
+            .map(|bit| match ports.get(bit).copied().flatten() {
+                Some(src) => Ok(frame.bit(src).unwrap_or_else(|| cnf.fresh())),
+                None => Err(self.raise_ice(FlowGraphICE::MissingArgument { bit }, node)),
+            })
+            .collect()
+    }
+    // Returns `None` if the value of the node is not computed from its
+    // inputs (i.e., it is an argument or the output of a black box).
+    fn component(&self, cnf: &mut Cnf, frame: &Frame, node: FlowIx) -> Result<Option<Vec<Lit>>> {
+        let component = &self.fg.graph[node];
+        let width = component.width;
+        let ports = &self.ports[node.index()];
+        let value = match &component.kind {
+            ComponentKind::Constant(value) => bits(cnf, &vec![*value; width.max(1)]),
+            ComponentKind::BitString(bs) => bits(cnf, bs.bits()),
+            ComponentKind::Buffer(_) if self.is_input[node.index()] => return Ok(None),
+            ComponentKind::BBOutput(_) => return Ok(None),
+            ComponentKind::Buffer(_) | ComponentKind::BBInput(_) => {
+                vec![ports
+                    .parent
+                    .and_then(|src| frame.bit(src))
+                    .unwrap_or_else(|| cnf.fresh())]
+            }
+            ComponentKind::BitSelect(select) => {
+                let parent = ports
+                    .parent
+                    .ok_or_else(|| self.raise_ice(FlowGraphICE::BufferParentNotFound, node))?;
+                vec![frame.values[parent.index()]
+                    .get(select.bit_index)
+                    .copied()
+                    .unwrap_or_else(|| cnf.fresh())]
+            }
+            ComponentKind::Select => {
+                let find =
+                    |port: Option<FlowIx>, cause| port.ok_or_else(|| self.raise_ice(cause, node));
+                let control = find(
+                    ports.selector.first().copied().flatten(),
+                    FlowGraphICE::SelectControlNodeNotFound,
+                )?;
+                let true_value = find(ports.true_value, FlowGraphICE::SelectTrueNodeNotFound)?;
+                let false_value = find(ports.false_value, FlowGraphICE::SelectFalseNodeNotFound)?;
+                let [control, true_value, false_value] = [control, true_value, false_value]
+                    .map(|src| frame.bit(src).unwrap_or_else(|| cnf.fresh()));
+                vec![cnf.mux(control, true_value, false_value)]
+            }
+            ComponentKind::Case(kase) => {
+                let discriminant = self.argument(
+                    cnf,
+                    frame,
+                    node,
+                    &ports.selector,
+                    kase.discriminant_width.len(),
+                )?;
+                let mut cases = vec![];
+                let mut default = None;
+                for (ndx, entry) in kase.entries.iter().enumerate() {
+                    let value = self.argument(cnf, frame, node, ports.arg(ndx), 1)?;
+                    match entry {
+                        CaseEntry::Literal(literal) => {
+                            let matched = match literal
+                                .bits()
+                                .iter()
+                                .map(|bit| bit.to_bool())
+                                .collect::<Option<Vec<_>>>()
+                            {
+                                Some(literal) if literal.len() == discriminant.len() => {
+                                    let literal = literal
+                                        .into_iter()
+                                        .map(|bit| cnf.constant(bit))
+                                        .collect::<Vec<_>>();
+                                    cnf.eq(&discriminant, &literal)
+                                }
+                                _ => cnf.constant(false),
+                            };
+                            cases.push((matched, value));
+                        }
+                        CaseEntry::WildCard => {
+                            default.get_or_insert(value);
+                        }
+                    }
+                }
+                let default = default.unwrap_or_else(|| vec![cnf.fresh()]);
+                cnf.priority(&cases, default)
+            }
+            ComponentKind::Binary(bin) => {
+                let left = self.argument(cnf, frame, node, ports.arg(0), bin.left_len.len())?;
+                let right = self.argument(cnf, frame, node, ports.arg(1), bin.right_len.len())?;
+                let signed = matches!(bin.left_len, SignedWidth::Signed(_));
+                let zip = |cnf: &mut Cnf, f: fn(&mut Cnf, Lit, Lit) -> Lit| {
+                    left.iter()
+                        .zip(&right)
+                        .map(|(a, b)| f(cnf, *a, *b))
+                        .collect::<Vec<_>>()
+                };
+                let less = |cnf: &mut Cnf, a: &[Lit], b: &[Lit]| {
+                    if signed {
+                        cnf.slt(a, b)
+                    } else {
+                        cnf.ult(a, b)
+                    }
+                };
+                let result = match bin.op {
+                    AluBinary::BitAnd => zip(cnf, Cnf::and),
+                    AluBinary::BitOr => zip(cnf, Cnf::or),
+                    AluBinary::BitXor => zip(cnf, Cnf::xor),
+                    AluBinary::Add => cnf.add(&left, &right),
+                    AluBinary::Sub => cnf.sub(&left, &right),
+                    AluBinary::Mul => cnf.mul(&left, &right),
+                    AluBinary::Div => cnf.div_rem(&left, &right, signed).0,
+                    AluBinary::Rem => cnf.div_rem(&left, &right, signed).1,
+                    AluBinary::Shl => cnf.shl(&left, &right),
+                    AluBinary::Shr => cnf.shr(&left, &right, signed),
+                    AluBinary::Eq => vec![cnf.eq(&left, &right)],
+                    AluBinary::Ne => vec![!cnf.eq(&left, &right)],
+                    AluBinary::Lt => vec![less(cnf, &left, &right)],
+                    AluBinary::Ge => vec![!less(cnf, &left, &right)],
+                    AluBinary::Gt => vec![less(cnf, &right, &left)],
+                    AluBinary::Le => vec![!less(cnf, &right, &left)],
+                };
+                fit(cnf, result, width)
+            }
+            ComponentKind::Unary(uny) => {
+                let arg = self.argument(cnf, frame, node, ports.arg(0), uny.arg_len.len())?;
+                let result = match uny.op {
+                    AluUnary::Not => arg.iter().map(|x| !*x).collect(),
+                    AluUnary::Neg => cnf.neg(&arg),
+                    AluUnary::Signed | AluUnary::Unsigned | AluUnary::Val => arg,
+                    AluUnary::All => vec![cnf.and_all(arg)],
+                    AluUnary::Any => vec![cnf.or_all(arg)],
+                    AluUnary::Xor => vec![cnf.xor_all(arg)],
+                    AluUnary::CountOnes => cnf.count_ones(&arg),
+                    AluUnary::LeadingZeros => cnf.leading_zeros(&arg),
+                    AluUnary::TrailingZeros => cnf.trailing_zeros(&arg),
+                    AluUnary::ReverseBits => arg.into_iter().rev().collect(),
+                    AluUnary::OneHotToBinary(len) => cnf.one_hot_to_binary(&arg, len),
+                    AluUnary::BinaryToOneHot(len) => cnf.binary_to_one_hot(&arg, len),
+                };
+                fit(cnf, result, width)
+            }
+            ComponentKind::DynamicIndex(dyn_ndx) => {
+                let base = self.argument(cnf, frame, node, ports.arg(0), dyn_ndx.arg_len)?;
+                let offset = self.argument(cnf, frame, node, &ports.offset, dyn_ndx.offset_len)?;
+                let selects = self.offsets(cnf, &offset, base.len());
+                (0..width)
+                    .map(|bit| {
+                        let cases = selects
+                            .iter()
+                            .zip(base.iter().skip(bit))
+                            .map(|(select, value)| (*select, vec![*value]))
+                            .collect::<Vec<_>>();
+                        let default = vec![cnf.fresh()];
+                        cnf.priority(&cases, default)[0]
+                    })
+                    .collect()
+            }
+            ComponentKind::DynamicSplice(splice) => {
+                let base = self.argument(cnf, frame, node, ports.arg(0), width)?;
+                let value = self.argument(cnf, frame, node, &ports.splice, splice.splice_len)?;
+                let offset = self.argument(cnf, frame, node, &ports.offset, splice.offset_len)?;
+                let selects = self.offsets(cnf, &offset, base.len());
+                base.iter()
+                    .enumerate()
+                    .map(|(target, original)| {
+                        let cases = value
+                            .iter()
+                            .enumerate()
+                            .filter_map(|(bit, value)| {
+                                let offset = target.checked_sub(bit)?;
+                                Some((selects[offset], vec![*value]))
+                            })
+                            .collect::<Vec<_>>();
+                        cnf.priority(&cases, vec![*original])[0]
+                    })
+                    .collect()
+            }
+        };
+        Ok(Some(value))
+    }
+    // One literal per offset in `0..len`, which is true when the offset
+    // argument takes that value.
+    fn offsets(&self, cnf: &mut Cnf, offset: &[Lit], len: usize) -> Vec<Lit> {
+        (0..len)
+            .map(|ndx| {
+                let fits = offset.len() >= usize::BITS as usize || ndx >> offset.len() == 0;
+                if fits {
+                    let value = cnf.word(ndx as u128, offset.len());
+                    cnf.eq(offset, &value)
+                } else {
+                    cnf.constant(false)
+                }
+            })
+            .collect()
+    }
+}
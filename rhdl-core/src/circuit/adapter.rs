@@ -88,6 +88,10 @@ impl<I: Digital, D: Domain> Digital for AdapterInput<I, D> {
             input: Signal::dont_care(),
         }
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        let (clock_reset, input) = <(Signal<ClockReset, D>, Signal<I, D>)>::from_bin(bits)?;
+        Some(Self { clock_reset, input })
+    }
 }
 
 impl<C: Synchronous, D: Domain> CircuitIO for Adapter<C, D> {
@@ -169,6 +173,16 @@ impl<C: Synchronous, D: Domain> Circuit for Adapter<C, D> {
         .into_iter()
         .flatten()
         .collect();
+        module.kinds = vec![
+            (
+                "i".into(),
+                <<Self as CircuitIO>::I as Digital>::static_kind(),
+            ),
+            (
+                "o".into(),
+                <<Self as CircuitIO>::O as Digital>::static_kind(),
+            ),
+        ];
         let child_name = &format!("{}_inner", name);
         let child = self.circuit.descriptor(child_name)?;
         let clock_reset = concatenate(vec![index_bit("i", 1), index_bit("i", 0)]);
@@ -116,6 +116,10 @@ impl<T: Synchronous, const N: usize> Synchronous for [T; N] {
         .into_iter()
         .flatten()
         .collect();
+        module.kinds = vec![
+            ("i".into(), Self::I::static_kind()),
+            ("o".into(), Self::O::static_kind()),
+        ];
         let i_kind = Self::I::static_kind();
         let o_kind = Self::O::static_kind();
         let child_decls = descriptor
@@ -10,7 +10,7 @@ use crate::{
     Kind, RHDLError, Timed,
 };
 
-use super::hdl_backend::maybe_port_wire;
+use super::hdl_backend::{bind_assertions, maybe_port_wire};
 
 #[derive(Clone)]
 pub struct AsyncFunc<I: Timed, O: Timed> {
@@ -78,13 +78,19 @@ impl<I: Timed, O: Timed> Circuit for AsyncFunc<I, O> {
         .into_iter()
         .flatten()
         .collect();
+        module.kinds = vec![
+            ("i".into(), <Self::I as Timed>::static_kind()),
+            ("o".into(), <Self::O as Timed>::static_kind()),
+        ];
         let verilog = generate_verilog(descriptor.rtl.as_ref().unwrap())?;
         // Call the verilog function with (clock_reset, i, q), if they exist.
         let i_bind = (Self::I::bits() != 0).then(|| id("i"));
-        let fn_call = function_call(&verilog.name, vec![i_bind].into_iter().flatten().collect());
+        let args: Vec<_> = vec![i_bind].into_iter().flatten().collect();
+        let fn_call = function_call(&verilog.name, args.clone());
         let fn_call = continuous_assignment("o", fn_call);
         module.statements.push(fn_call);
         module.functions.push(verilog);
+        bind_assertions(&mut module, descriptor.rtl.as_ref().unwrap(), args, false)?;
         Ok(HDLDescriptor {
             name: module_name.into(),
             body: module,
@@ -0,0 +1,404 @@
+use std::sync::Arc;
+
+use crate::{
+    digital_fn::{NoKernel2, NoKernel3},
+    hdl::{
+        ast::{
+            component_instance, connection, index, index_bit, unary, Connection, Direction, Module,
+        },
+        export::build_coverage_error,
+    },
+    rtl::spec::AluUnary,
+    trace, trace_pop_path, trace_push_path,
+    types::path::{bit_range, Path},
+    Circuit, CircuitDQ, CircuitDescriptor, CircuitIO, ClockReset, Digital, FlowGraph,
+    HDLDescriptor, Kind, RHDLError, Synchronous, SynchronousDQ, SynchronousIO, Timed,
+};
+
+use super::hdl_backend::maybe_port_wire;
+
+// A binding of a port on the foreign core to a slice of the
+// input or output of the wrapping circuit.  The slice is given
+// as a path into the kind of the input (or output).
+#[derive(Clone, Debug, Hash)]
+struct PortBinding {
+    port: String,
+    direction: Direction,
+    path: Path,
+}
+
+// The description of the foreign core that is common to both the
+// synchronous and asynchronous wrappers.
+#[derive(Clone, Debug, Hash)]
+struct ForeignCore {
+    module_name: String,
+    source: String,
+    clock: Option<String>,
+    reset: Option<(String, bool)>,
+    bindings: Vec<PortBinding>,
+}
+
+impl ForeignCore {
+    fn new(module_name: &str, source: &str) -> Self {
+        Self {
+            module_name: module_name.into(),
+            source: source.into(),
+            clock: None,
+            reset: None,
+            bindings: vec![],
+        }
+    }
+    fn bind(&mut self, port: &str, direction: Direction, path: Path) {
+        self.bindings.push(PortBinding {
+            port: port.into(),
+            direction,
+            path,
+        });
+    }
+    // Check that every bit of the input is consumed by the core,
+    // and that every bit of the output is driven by it.
+    fn check_coverage(&self, i_kind: Kind, o_kind: Kind) -> Result<(), RHDLError> {
+        let mut i_cover = vec![false; i_kind.bits()];
+        let mut o_cover = vec![false; o_kind.bits()];
+        for binding in &self.bindings {
+            let (kind, cover) = match binding.direction {
+                Direction::Input => (i_kind, &mut i_cover),
+                Direction::Output => (o_kind, &mut o_cover),
+                Direction::Inout => {
+                    return Err(RHDLError::BlackBoxError(format!(
+                        "Port {} of core {} is bidirectional, which is not supported",
+                        binding.port, self.module_name
+                    )))
+                }
+            };
+            let (range, _) = bit_range(kind, &binding.path)?;
+            for bit in range {
+                cover[bit] = true;
+            }
+        }
+        if i_cover.iter().any(|b| !b) {
+            return Err(RHDLError::InputsNotCovered(build_coverage_error(
+                i_kind, &i_cover,
+            )));
+        }
+        if o_cover.iter().any(|b| !b) {
+            return Err(RHDLError::OutputsNotCovered(build_coverage_error(
+                o_kind, &o_cover,
+            )));
+        }
+        Ok(())
+    }
+    // Build the wrapper module.  It has the standard RHDL interface
+    // (clock_reset, i, o) and instantiates the foreign core with the
+    // ports wired to the appropriate slices of the input and output.
+    fn wrapper(
+        &self,
+        name: &str,
+        description: String,
+        synchronous: bool,
+        i_kind: Kind,
+        o_kind: Kind,
+    ) -> Result<Module, RHDLError> {
+        self.check_coverage(i_kind, o_kind)?;
+        let mut connections: Vec<Connection> = vec![];
+        if let Some(clock) = &self.clock {
+            connections.push(connection(clock, index_bit("clock_reset", 0)));
+        }
+        if let Some((reset, active_low)) = &self.reset {
+            let reset_bit = index_bit("clock_reset", 1);
+            let reset_bit = if *active_low {
+                unary(AluUnary::Not, reset_bit)
+            } else {
+                reset_bit
+            };
+            connections.push(connection(reset, reset_bit));
+        }
+        for binding in &self.bindings {
+            let (target, kind) = match binding.direction {
+                Direction::Output => ("o", o_kind),
+                _ => ("i", i_kind),
+            };
+            let (range, _) = bit_range(kind, &binding.path)?;
+            if range.is_empty() {
+                continue;
+            }
+            connections.push(connection(&binding.port, index(target, range)));
+        }
+        let ports = [
+            synchronous
+                .then(|| maybe_port_wire(Direction::Input, 2, "clock_reset"))
+                .flatten(),
+            maybe_port_wire(Direction::Input, i_kind.bits(), "i"),
+            maybe_port_wire(Direction::Output, o_kind.bits(), "o"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        Ok(Module {
+            name: name.into(),
+            description,
+            ports,
+            statements: vec![component_instance(&self.module_name, "core", connections)],
+            verbatim: vec![self.source.clone()],
+            kinds: vec![("i".into(), i_kind), ("o".into(), o_kind)],
+            ..Default::default()
+        })
+    }
+}
+
+type SyncUpdate<I, O, S> = dyn Fn(ClockReset, I, &mut S) -> O + Send + Sync;
+
+/// A synchronous circuit that wraps a core written in Verilog.
+///
+/// The core is treated as a black box.  You provide the Verilog source,
+/// the mapping of the core's ports onto the input and output of the
+/// circuit (as [Path]s into their [Kind]s), and a Rust model of the
+/// core's behavior that is used for simulation.  The wrapper then
+/// takes care of generating the descriptor, the HDL, and the flow graph.
+///
+/// ```ignore
+/// let adder = BlackBox::new("adder", ADDER_V, (), |_cr, i: (b8, b8), _s| i.0 + i.1)
+///     .clock("clk")
+///     .input("a", Path::default().tuple_index(0))
+///     .input("b", Path::default().tuple_index(1))
+///     .output("sum", Path::default());
+/// ```
+#[derive(Clone)]
+pub struct BlackBox<I: Digital, O: Digital, S: Clone + PartialEq + 'static> {
+    core: ForeignCore,
+    init: S,
+    update: Arc<SyncUpdate<I, O, S>>,
+}
+
+impl<I: Digital, O: Digital, S: Clone + PartialEq + 'static> BlackBox<I, O, S> {
+    /// Create a black box for the Verilog module `module_name` defined in `source`.
+    /// The `update` function is called on every simulation step with the state
+    /// (which starts with the value `init`), and must model the behavior of the core.
+    pub fn new(
+        module_name: &str,
+        source: &str,
+        init: S,
+        update: impl Fn(ClockReset, I, &mut S) -> O + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            core: ForeignCore::new(module_name, source),
+            init,
+            update: Arc::new(update),
+        }
+    }
+    /// As [BlackBox::new], but read the Verilog source from a file.
+    pub fn from_file(
+        module_name: &str,
+        path: impl AsRef<std::path::Path>,
+        init: S,
+        update: impl Fn(ClockReset, I, &mut S) -> O + Send + Sync + 'static,
+    ) -> Result<Self, RHDLError> {
+        let source = std::fs::read_to_string(path)?;
+        Ok(Self::new(module_name, &source, init, update))
+    }
+    /// Connect the clock to the named port of the core.
+    pub fn clock(mut self, port: &str) -> Self {
+        self.core.clock = Some(port.into());
+        self
+    }
+    /// Connect the (active high) reset to the named port of the core.
+    pub fn reset(mut self, port: &str) -> Self {
+        self.core.reset = Some((port.into(), false));
+        self
+    }
+    /// Connect the inverted reset to the named (active low) port of the core.
+    pub fn reset_n(mut self, port: &str) -> Self {
+        self.core.reset = Some((port.into(), true));
+        self
+    }
+    /// Drive the named input port of the core from the part of the input at `path`.
+    pub fn input(mut self, port: &str, path: Path) -> Self {
+        self.core.bind(port, Direction::Input, path);
+        self
+    }
+    /// Drive the part of the output at `path` from the named output port of the core.
+    pub fn output(mut self, port: &str, path: Path) -> Self {
+        self.core.bind(port, Direction::Output, path);
+        self
+    }
+}
+
+impl<I: Digital, O: Digital, S: Clone + PartialEq + 'static> SynchronousIO for BlackBox<I, O, S> {
+    type I = I;
+    type O = O;
+    type Kernel = NoKernel3<ClockReset, I, (), (O, ())>;
+}
+
+impl<I: Digital, O: Digital, S: Clone + PartialEq + 'static> SynchronousDQ for BlackBox<I, O, S> {
+    type D = ();
+    type Q = ();
+}
+
+impl<I: Digital, O: Digital, S: Clone + PartialEq + 'static> Synchronous for BlackBox<I, O, S> {
+    type S = S;
+
+    fn init(&self) -> Self::S {
+        self.init.clone()
+    }
+
+    fn sim(&self, clock_reset: ClockReset, input: Self::I, state: &mut Self::S) -> Self::O {
+        trace_push_path("black_box");
+        trace("input", &input);
+        let output = (self.update)(clock_reset, input, state);
+        trace("output", &output);
+        trace_pop_path();
+        output
+    }
+
+    fn description(&self) -> String {
+        format!("Black box wrapping Verilog core {}", self.core.module_name)
+    }
+
+    fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError> {
+        let mut flow_graph = FlowGraph::default();
+        let hdl = self.hdl(name)?;
+        let (clock_reset, input, output) = flow_graph.synchronous_black_box::<Self>(hdl);
+        flow_graph.inputs = vec![clock_reset, input];
+        flow_graph.output = output;
+        Ok(CircuitDescriptor {
+            unique_name: name.to_string(),
+            input_kind: I::static_kind(),
+            output_kind: O::static_kind(),
+            d_kind: Kind::Empty,
+            q_kind: Kind::Empty,
+            children: Default::default(),
+            flow_graph,
+            rtl: None,
+        })
+    }
+
+    fn hdl(&self, name: &str) -> Result<HDLDescriptor, RHDLError> {
+        let body = self.core.wrapper(
+            name,
+            self.description(),
+            true,
+            I::static_kind(),
+            O::static_kind(),
+        )?;
+        Ok(HDLDescriptor {
+            name: name.into(),
+            body,
+            children: Default::default(),
+        })
+    }
+}
+
+type AsyncUpdate<I, O, S> = dyn Fn(I, &mut S) -> O + Send + Sync;
+
+/// An asynchronous circuit that wraps a core written in Verilog.
+///
+/// This is the [Circuit] counterpart of [BlackBox].  As there is no
+/// implied clock or reset, any clocks used by the core must be part
+/// of the input, and are bound to the core's ports like any other signal.
+#[derive(Clone)]
+pub struct AsyncBlackBox<I: Timed, O: Timed, S: Clone + PartialEq + 'static> {
+    core: ForeignCore,
+    init: S,
+    update: Arc<AsyncUpdate<I, O, S>>,
+}
+
+impl<I: Timed, O: Timed, S: Clone + PartialEq + 'static> AsyncBlackBox<I, O, S> {
+    /// Create a black box for the Verilog module `module_name` defined in `source`.
+    pub fn new(
+        module_name: &str,
+        source: &str,
+        init: S,
+        update: impl Fn(I, &mut S) -> O + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            core: ForeignCore::new(module_name, source),
+            init,
+            update: Arc::new(update),
+        }
+    }
+    /// As [AsyncBlackBox::new], but read the Verilog source from a file.
+    pub fn from_file(
+        module_name: &str,
+        path: impl AsRef<std::path::Path>,
+        init: S,
+        update: impl Fn(I, &mut S) -> O + Send + Sync + 'static,
+    ) -> Result<Self, RHDLError> {
+        let source = std::fs::read_to_string(path)?;
+        Ok(Self::new(module_name, &source, init, update))
+    }
+    /// Drive the named input port of the core from the part of the input at `path`.
+    pub fn input(mut self, port: &str, path: Path) -> Self {
+        self.core.bind(port, Direction::Input, path);
+        self
+    }
+    /// Drive the part of the output at `path` from the named output port of the core.
+    pub fn output(mut self, port: &str, path: Path) -> Self {
+        self.core.bind(port, Direction::Output, path);
+        self
+    }
+}
+
+impl<I: Timed, O: Timed, S: Clone + PartialEq + 'static> CircuitIO for AsyncBlackBox<I, O, S> {
+    type I = I;
+    type O = O;
+    type Kernel = NoKernel2<I, (), (O, ())>;
+}
+
+impl<I: Timed, O: Timed, S: Clone + PartialEq + 'static> CircuitDQ for AsyncBlackBox<I, O, S> {
+    type D = ();
+    type Q = ();
+}
+
+impl<I: Timed, O: Timed, S: Clone + PartialEq + 'static> Circuit for AsyncBlackBox<I, O, S> {
+    type S = S;
+
+    fn init(&self) -> Self::S {
+        self.init.clone()
+    }
+
+    fn sim(&self, input: Self::I, state: &mut Self::S) -> Self::O {
+        trace_push_path("black_box");
+        trace("input", &input);
+        let output = (self.update)(input, state);
+        trace("output", &output);
+        trace_pop_path();
+        output
+    }
+
+    fn description(&self) -> String {
+        format!("Black box wrapping Verilog core {}", self.core.module_name)
+    }
+
+    fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError> {
+        let mut flow_graph = FlowGraph::default();
+        let hdl = self.hdl(name)?;
+        let (input, output) = flow_graph.circuit_black_box::<Self>(hdl);
+        flow_graph.inputs = vec![input];
+        flow_graph.output = output;
+        Ok(CircuitDescriptor {
+            unique_name: name.to_string(),
+            input_kind: <I as Timed>::static_kind(),
+            output_kind: <O as Timed>::static_kind(),
+            d_kind: Kind::Empty,
+            q_kind: Kind::Empty,
+            children: Default::default(),
+            flow_graph,
+            rtl: None,
+        })
+    }
+
+    fn hdl(&self, name: &str) -> Result<HDLDescriptor, RHDLError> {
+        let body = self.core.wrapper(
+            name,
+            self.description(),
+            false,
+            <I as Timed>::static_kind(),
+            <O as Timed>::static_kind(),
+        )?;
+        Ok(HDLDescriptor {
+            name: name.into(),
+            body,
+            children: Default::default(),
+        })
+    }
+}
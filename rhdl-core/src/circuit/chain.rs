@@ -136,6 +136,11 @@ where
         .into_iter()
         .flatten()
         .collect();
+        module.kinds = vec![
+            ("i".into(), <A as SynchronousIO>::I::static_kind()),
+            ("o".into(), <B as SynchronousIO>::O::static_kind()),
+            ("pipe".into(), pipe_kind),
+        ];
         module.declarations.push(Declaration {
             kind: HDLKind::Wire,
             name: "pipe".into(),
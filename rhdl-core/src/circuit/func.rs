@@ -10,7 +10,7 @@ use crate::{
     RHDLError, Synchronous, SynchronousDQ, SynchronousIO,
 };
 
-use super::hdl_backend::maybe_port_wire;
+use super::hdl_backend::{bind_assertions, maybe_port_wire};
 
 #[derive(Clone)]
 pub struct Func<I: Digital, O: Digital> {
@@ -79,17 +79,20 @@ impl<I: Digital, O: Digital> Synchronous for Func<I, O> {
         .into_iter()
         .flatten()
         .collect();
+        module.kinds = vec![
+            ("i".into(), Self::I::static_kind()),
+            ("o".into(), Self::O::static_kind()),
+        ];
         let verilog = generate_verilog(descriptor.rtl.as_ref().unwrap())?;
         // Call the verilog function with (clock_reset, i, q), if they exist.
         let clock_reset = Some(id("clock_reset"));
         let i_bind = (Self::I::bits() != 0).then(|| id("i"));
-        let fn_call = function_call(
-            &verilog.name,
-            vec![clock_reset, i_bind].into_iter().flatten().collect(),
-        );
+        let args: Vec<_> = vec![clock_reset, i_bind].into_iter().flatten().collect();
+        let fn_call = function_call(&verilog.name, args.clone());
         let fn_call = continuous_assignment("o", fn_call);
         module.statements.push(fn_call);
         module.functions.push(verilog);
+        bind_assertions(&mut module, descriptor.rtl.as_ref().unwrap(), args, true)?;
         Ok(HDLDescriptor {
             name: module_name.into(),
             body: module,
@@ -2,15 +2,17 @@ use std::collections::BTreeMap;
 
 use crate::hdl::{
     ast::{
-        component_instance, connection, continuous_assignment, function_call, id, index,
-        unsigned_width, Declaration, Direction, HDLKind, Module, Port, Statement,
+        component_instance, connection, continuous_assignment, function_call, id, index, index_bit,
+        unsigned_width, Assertion, Declaration, Direction, Expression, HDLKind, Module, Port,
+        Statement,
     },
-    builder::generate_verilog,
+    builder::{generate_assertions, generate_verilog},
 };
+use crate::rtl::Object;
 use crate::types::path::bit_range;
 use crate::types::path::Path;
-use crate::Digital;
 use crate::{Circuit, HDLDescriptor, RHDLError, Synchronous};
+use crate::{Digital, Kind};
 
 pub(crate) fn maybe_port_wire(dir: Direction, num_bits: usize, name: &str) -> Option<Port> {
     (num_bits != 0).then(|| Port {
@@ -30,6 +32,43 @@ pub(crate) fn maybe_decl_wire(num_bits: usize, name: &str) -> Option<Declaration
     })
 }
 
+// If the kernel contains assertions, they are computed by a second
+// function, called with the same arguments as the kernel, and attached
+// to the module.  Assertions in a synchronous module are checked on the
+// clock edge, using the `clock_reset` port.
+pub(crate) fn bind_assertions(
+    module: &mut Module,
+    rtl: &Object,
+    args: Vec<Expression>,
+    synchronous: bool,
+) -> Result<(), RHDLError> {
+    let Some(func) = generate_assertions(rtl)? else {
+        return Ok(());
+    };
+    module
+        .declarations
+        .extend(maybe_decl_wire(rtl.assertions.len(), "assertions"));
+    module.statements.push(continuous_assignment(
+        "assertions",
+        function_call(&func.name, args),
+    ));
+    module.functions.push(func);
+    let clock_reset =
+        synchronous.then(|| (index_bit("clock_reset", 0), index_bit("clock_reset", 1)));
+    module.assertions = rtl
+        .assertions
+        .iter()
+        .enumerate()
+        .map(|(ndx, assertion)| Assertion {
+            kind: assertion.kind,
+            message: assertion.message.clone(),
+            signal: index_bit("assertions", ndx),
+            clock_reset: clock_reset.clone(),
+        })
+        .collect();
+    Ok(())
+}
+
 pub fn build_hdl<C: Circuit>(
     circuit: &C,
     name: &str,
@@ -51,6 +90,16 @@ pub fn build_hdl<C: Circuit>(
     .into_iter()
     .flatten()
     .collect();
+    module.kinds = vec![
+        ("i".into(), C::I::static_kind()),
+        ("o".into(), C::O::static_kind()),
+        ("d".into(), C::D::static_kind()),
+        ("q".into(), C::Q::static_kind()),
+        (
+            "od".into(),
+            Kind::make_tuple(vec![C::O::static_kind(), C::D::static_kind()]),
+        ),
+    ];
     module.declarations.extend(
         [
             maybe_decl_wire(C::O::bits() + C::D::bits(), "od"),
@@ -89,10 +138,8 @@ pub fn build_hdl<C: Circuit>(
     // Call the verilog function with (i, q), if they exist.
     let i_bind = (C::I::bits() != 0).then(|| id("i"));
     let q_bind = (C::Q::bits() != 0).then(|| id("q"));
-    let fn_call = function_call(
-        &verilog.name,
-        vec![i_bind, q_bind].into_iter().flatten().collect(),
-    );
+    let args: Vec<_> = vec![i_bind, q_bind].into_iter().flatten().collect();
+    let fn_call = function_call(&verilog.name, args.clone());
     let fn_call = continuous_assignment("od", fn_call);
     let o_bind = continuous_assignment("o", index("od", 0..outputs));
     let d_bind = (C::D::bits() != 0)
@@ -104,6 +151,7 @@ pub fn build_hdl<C: Circuit>(
         module.statements.push(d_bind);
     }
     module.functions.push(verilog);
+    bind_assertions(&mut module, descriptor.rtl.as_ref().unwrap(), args, false)?;
     Ok(HDLDescriptor {
         name: module_name.into(),
         body: module,
@@ -135,6 +183,16 @@ pub fn build_synchronous_hdl<C: Synchronous>(
     .into_iter()
     .flatten()
     .collect();
+    module.kinds = vec![
+        ("i".into(), C::I::static_kind()),
+        ("o".into(), C::O::static_kind()),
+        ("d".into(), C::D::static_kind()),
+        ("q".into(), C::Q::static_kind()),
+        (
+            "od".into(),
+            Kind::make_tuple(vec![C::O::static_kind(), C::D::static_kind()]),
+        ),
+    ];
     module.declarations.extend(
         [
             maybe_decl_wire(C::O::bits() + C::D::bits(), "od"),
@@ -181,13 +239,11 @@ pub fn build_synchronous_hdl<C: Synchronous>(
     let clock_reset = Some(id("clock_reset"));
     let i_bind = (C::I::bits() != 0).then(|| id("i"));
     let q_bind = (C::Q::bits() != 0).then(|| id("q"));
-    let fn_call = function_call(
-        &verilog.name,
-        vec![clock_reset, i_bind, q_bind]
-            .into_iter()
-            .flatten()
-            .collect(),
-    );
+    let args: Vec<_> = vec![clock_reset, i_bind, q_bind]
+        .into_iter()
+        .flatten()
+        .collect();
+    let fn_call = function_call(&verilog.name, args.clone());
     let fn_call = continuous_assignment("od", fn_call);
     let o_bind = continuous_assignment("o", index("od", 0..outputs));
     let d_bind = (C::D::bits() != 0)
@@ -199,6 +255,7 @@ pub fn build_synchronous_hdl<C: Synchronous>(
         module.statements.push(d_bind);
     }
     module.functions.push(verilog);
+    bind_assertions(&mut module, descriptor.rtl.as_ref().unwrap(), args, true)?;
     Ok(HDLDescriptor {
         name: module_name.into(),
         body: module,
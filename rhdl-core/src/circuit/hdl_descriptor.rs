@@ -16,4 +16,38 @@ impl HDLDescriptor {
             .extend(self.children.values().map(HDLDescriptor::as_module));
         module
     }
+    pub fn as_vhdl(&self) -> String {
+        self.as_module().as_vhdl()
+    }
+    pub fn as_system_verilog(&self) -> String {
+        self.as_module().as_system_verilog()
+    }
+}
+
+#[cfg(feature = "iverilog")]
+impl HDLDescriptor {
+    // Compile the SystemVerilog output with Icarus Verilog, so that the
+    // package and the packed types are checked by a real tool, and not
+    // just by comparing strings.
+    pub fn check_system_verilog(&self) -> Result<(), crate::RHDLError> {
+        let d = tempfile::tempdir()?;
+        let d_path = d.path();
+        std::fs::write(d_path.join("top.sv"), self.as_system_verilog())?;
+        let output = std::process::Command::new("iverilog")
+            .arg("-g2012")
+            .arg("-o")
+            .arg(d_path.join("top"))
+            .arg(d_path.join("top.sv"))
+            .output()
+            .expect("Icarus Verilog should be installed and in your PATH.");
+        if !output.status.success() {
+            return Err(anyhow::anyhow!(
+                "Failed to compile SystemVerilog with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            )
+            .into());
+        }
+        Ok(())
+    }
 }
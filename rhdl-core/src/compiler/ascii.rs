@@ -93,7 +93,18 @@ impl AsciiRenderer {
     fn render_arm(&mut self, arm: &Arm) -> Result<()> {
         self.push(&format!("arm {:?}", arm.id));
         self.indent += 1;
-        match &arm.kind {
+        self.render_arm_kind(&arm.kind)?;
+        if let Some(guard) = &arm.guard {
+            self.push(" if ");
+            self.render_expr(guard)?;
+        }
+        self.push(" => ");
+        self.render_expr(&arm.body)?;
+        self.indent -= 1;
+        Ok(())
+    }
+    fn render_arm_kind(&mut self, kind: &ArmKind) -> Result<()> {
+        match kind {
             ArmKind::Wild => {
                 self.push("_");
             }
@@ -104,10 +115,24 @@ impl AsciiRenderer {
                 self.render_pat(&enum_arm.pat)?;
                 self.push(&format!("#{:?}", &enum_arm.discriminant));
             }
+            ArmKind::Range(range) => {
+                self.push(&format!(
+                    "range {:?} {:?} {:?}",
+                    range.start, range.limits, range.end
+                ));
+            }
+            ArmKind::Or(or) => {
+                self.push("or");
+                self.indent += 1;
+                for case in &or.cases {
+                    self.render_arm_kind(case)?;
+                }
+                self.indent -= 1;
+            }
+            ArmKind::Bind(bind) => {
+                self.render_pat(&bind.pat)?;
+            }
         }
-        self.push(" => ");
-        self.render_expr(&arm.body)?;
-        self.indent -= 1;
         Ok(())
     }
     fn render_expr(&mut self, expr: &Expr) -> Result<()> {
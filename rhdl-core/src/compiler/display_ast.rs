@@ -42,6 +42,35 @@ impl PrettyPrinter {
     fn push(&mut self, s: &str) {
         self.buffer.write(s);
     }
+    fn print_arm_kind(&mut self, kind: &ArmKind) -> Result<()> {
+        match kind {
+            ArmKind::Wild => self.push("_"),
+            ArmKind::Constant(constant) => self.push(&format!("const {:?}", constant.value)),
+            ArmKind::Enum(enum_arm) => {
+                self.print_pattern(&enum_arm.pat)?;
+                self.push(&format!("#{:?}", enum_arm.discriminant));
+            }
+            ArmKind::Range(range) => {
+                if let Some(start) = &range.start {
+                    self.push(&format!("{:?}", start));
+                }
+                self.push(&range.limits.to_string());
+                if let Some(end) = &range.end {
+                    self.push(&format!("{:?}", end));
+                }
+            }
+            ArmKind::Or(or) => {
+                for (ndx, case) in or.cases.iter().enumerate() {
+                    if ndx > 0 {
+                        self.push(" | ");
+                    }
+                    self.print_arm_kind(case)?;
+                }
+            }
+            ArmKind::Bind(bind) => self.print_pattern(&bind.pat)?,
+        }
+        Ok(())
+    }
     fn print_pattern(&mut self, pat: &Pat) -> Result<()> {
         match &pat.kind {
             PatKind::Ident(ident) => {
@@ -235,16 +264,7 @@ impl PrettyPrinter {
             }
             ExprKind::IfLet(expr) => {
                 self.push("if let ");
-                match &expr.kind {
-                    ArmKind::Wild => self.push("_"),
-                    ArmKind::Constant(constant) => {
-                        self.push(&format!("const {:?}", constant.value))
-                    }
-                    ArmKind::Enum(enum_arm) => {
-                        self.print_pattern(&enum_arm.pat)?;
-                        self.push(&format!("#{:?}", enum_arm.discriminant));
-                    }
-                }
+                self.print_arm_kind(&expr.kind)?;
                 self.push(" = ");
                 self.print_expr(&expr.test)?;
                 self.print_block(&expr.then_block)?;
@@ -273,15 +293,10 @@ impl PrettyPrinter {
                 self.print_expr(&expr.expr)?;
                 self.push(" {\n");
                 for arm in &expr.arms {
-                    match &arm.kind {
-                        ArmKind::Wild => self.push("_"),
-                        ArmKind::Constant(constant) => {
-                            self.push(&format!("const {:?}", constant.value))
-                        }
-                        ArmKind::Enum(enum_arm) => {
-                            self.print_pattern(&enum_arm.pat)?;
-                            self.push(&format!("#{:?}", enum_arm.discriminant));
-                        }
+                    self.print_arm_kind(&arm.kind)?;
+                    if let Some(guard) = &arm.guard {
+                        self.push(" if ");
+                        self.print_expr(guard)?;
                     }
                     self.push(" => ");
                     self.print_expr(&arm.body)?;
@@ -356,6 +371,17 @@ impl PrettyPrinter {
                 self.print_expr(&tri.expr)?;
                 self.push("?");
             }
+            ExprKind::Assert(assert) => {
+                match assert.kind {
+                    AssertKind::Assert => self.push("assert!("),
+                    AssertKind::Cover => self.push("cover!("),
+                }
+                self.print_expr(&assert.cond)?;
+                if !assert.message.is_empty() {
+                    self.push(&format!(", {:?}", assert.message));
+                }
+                self.push(")");
+            }
             ExprKind::Unary(expr) => {
                 self.push(&format!("{}", expr.op));
                 self.print_expr(&expr.expr)?;
@@ -409,6 +435,8 @@ impl Display for BinOp {
             BinOp::Add => write!(f, "+"),
             BinOp::Sub => write!(f, "-"),
             BinOp::Mul => write!(f, "*"),
+            BinOp::Div => write!(f, "/"),
+            BinOp::Rem => write!(f, "%"),
             BinOp::And => write!(f, "&&"),
             BinOp::Or => write!(f, "||"),
             BinOp::BitXor => write!(f, "^"),
@@ -425,6 +453,8 @@ impl Display for BinOp {
             BinOp::AddAssign => write!(f, "+="),
             BinOp::SubAssign => write!(f, "-="),
             BinOp::MulAssign => write!(f, "*="),
+            BinOp::DivAssign => write!(f, "/="),
+            BinOp::RemAssign => write!(f, "%="),
             BinOp::BitXorAssign => write!(f, "^="),
             BinOp::BitAndAssign => write!(f, "&="),
             BinOp::BitOrAssign => write!(f, "|="),
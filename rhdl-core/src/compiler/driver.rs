@@ -20,6 +20,12 @@ pub fn compile_design_stage2(
     super::stage2::compile(object)
 }
 
+pub fn compile_design_stage2_unoptimized(
+    object: &crate::rhif::Object,
+) -> Result<crate::rtl::Object, RHDLError> {
+    super::stage2::lower(object)
+}
+
 pub fn compile_design<K: DigitalFn>(
     mode: CompilationMode,
 ) -> Result<crate::rtl::Object, RHDLError> {
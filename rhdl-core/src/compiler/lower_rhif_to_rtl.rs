@@ -1,9 +1,13 @@
 use std::collections::BTreeMap;
 
+use num_bigint::BigInt;
+
 use rhdl_bits::alias::b8;
 
 use crate::ast::ast_impl::{FunctionId, WrapOp};
 use crate::ast::source::source_location::SourceLocation;
+use crate::bitx::dyn_bit_manip::from_bigint;
+use crate::bitx::BitX;
 use crate::error::rhdl_error;
 use crate::rhif::spec::{AluBinary, Slot};
 use crate::rtl::object::{lop, RegisterKind};
@@ -557,6 +561,199 @@ impl<'a> RTLCompiler<'a> {
         );
         Ok(())
     }
+    // Saturating add and subtract are computed one bit wider than the
+    // result (so they cannot overflow), and then clamped to the range
+    // of the result.  Unsigned subtraction is carried out in signed
+    // arithmetic, so that an underflow shows up as a negative value.
+    fn make_saturating(
+        &mut self,
+        lhs: Operand,
+        arg1: Operand,
+        arg2: Operand,
+        loc: SourceLocation,
+        op: tl::AluBinary,
+    ) -> Result<()> {
+        let len = self.operand_bit_width(lhs) + 1;
+        if self.operand_is_signed(lhs) {
+            let wide = self.allocate_signed(len, loc);
+            self.make_xadd_or_xmul(wide, arg1, arg2, loc, op)?;
+            self.make_clamp(lhs, wide, loc)
+        } else if op == tl::AluBinary::Sub {
+            let wide = self.allocate_signed(len, loc);
+            self.make_xsub(wide, arg1, arg2, loc)?;
+            self.make_clamp(lhs, wide, loc)
+        } else {
+            let wide = self.allocate_unsigned(len, loc);
+            self.make_xadd_or_xmul(wide, arg1, arg2, loc, op)?;
+            self.make_clamp(lhs, wide, loc)
+        }
+    }
+    // Assign the argument to the lhs, clamping it to the range of
+    // values that the lhs can represent.  Only the bounds that the
+    // argument can actually exceed are tested.
+    fn make_clamp(&mut self, lhs: Operand, arg: Operand, loc: SourceLocation) -> Result<()> {
+        let range = |signed: bool, len: usize| {
+            if signed {
+                let half = BigInt::from(1) << (len - 1);
+                (-half.clone(), half - 1)
+            } else {
+                (BigInt::ZERO, (BigInt::from(1) << len) - 1)
+            }
+        };
+        let literal = |value: &BigInt, signed: bool, len: usize| {
+            let bits = from_bigint(value, len);
+            if signed {
+                BitString::Signed(bits)
+            } else {
+                BitString::Unsigned(bits)
+            }
+        };
+        let lhs_len = self.operand_bit_width(lhs);
+        let lhs_signed = self.operand_is_signed(lhs);
+        let arg_len = self.operand_bit_width(arg);
+        let arg_signed = self.operand_is_signed(arg);
+        let lhs_kind = if lhs_signed {
+            RegisterKind::Signed(lhs_len)
+        } else {
+            RegisterKind::Unsigned(lhs_len)
+        };
+        let (lhs_min, lhs_max) = range(lhs_signed, lhs_len);
+        let (arg_min, arg_max) = range(arg_signed, arg_len);
+        // The in-range value is just the truncated argument
+        let mut value = if arg_signed {
+            self.allocate_signed(lhs_len, loc)
+        } else {
+            self.allocate_unsigned(lhs_len, loc)
+        };
+        self.lop(
+            tl::OpCode::Cast(tl::Cast {
+                lhs: value,
+                arg,
+                len: lhs_len,
+                kind: CastKind::Resize,
+            }),
+            loc,
+        );
+        if arg_signed != lhs_signed {
+            let reinterpreted = self.allocate_register_with_register_kind(&lhs_kind, loc);
+            self.lop(
+                tl::OpCode::Cast(tl::Cast {
+                    lhs: reinterpreted,
+                    arg: value,
+                    len: lhs_len,
+                    kind: if lhs_signed {
+                        CastKind::Signed
+                    } else {
+                        CastKind::Unsigned
+                    },
+                }),
+                loc,
+            );
+            value = reinterpreted;
+        }
+        let bounds = [
+            (arg_max > lhs_max, lhs_max, tl::AluBinary::Gt),
+            (arg_min < lhs_min, lhs_min, tl::AluBinary::Lt),
+        ];
+        for (needed, bound, op) in bounds {
+            if !needed {
+                continue;
+            }
+            let limit =
+                self.allocate_literal_from_bit_string(&literal(&bound, arg_signed, arg_len), loc);
+            let cond = self.allocate_unsigned(1, loc);
+            self.lop(
+                tl::OpCode::Binary(tl::Binary {
+                    lhs: cond,
+                    op,
+                    arg1: arg,
+                    arg2: limit,
+                }),
+                loc,
+            );
+            let clamped =
+                self.allocate_literal_from_bit_string(&literal(&bound, lhs_signed, lhs_len), loc);
+            let next = self.allocate_register_with_register_kind(&lhs_kind, loc);
+            self.lop(
+                tl::OpCode::Select(tl::Select {
+                    lhs: next,
+                    cond,
+                    true_value: clamped,
+                    false_value: value,
+                }),
+                loc,
+            );
+            value = next;
+        }
+        self.lop(tl::OpCode::Assign(tl::Assign { lhs, rhs: value }), loc);
+        Ok(())
+    }
+    // The RTL divider is only asked to divide by non-zero values.  A
+    // zero divisor is handled here explicitly, by selecting all ones
+    // for the quotient, or the dividend for the remainder.  When the
+    // divisor is a constant, the guard is removed by constant propagation.
+    fn make_div_rem(
+        &mut self,
+        lhs: Operand,
+        arg1: Operand,
+        arg2: Operand,
+        loc: SourceLocation,
+        op: tl::AluBinary,
+    ) -> Result<()> {
+        let len = self.operand_bit_width(lhs);
+        let signed = self.operand_is_signed(lhs);
+        let kind = if signed {
+            RegisterKind::Signed(len)
+        } else {
+            RegisterKind::Unsigned(len)
+        };
+        let raw = self.allocate_register_with_register_kind(&kind, loc);
+        self.lop(
+            tl::OpCode::Binary(tl::Binary {
+                lhs: raw,
+                op,
+                arg1,
+                arg2,
+            }),
+            loc,
+        );
+        let zero = if signed {
+            BitString::Signed(vec![BitX::Zero; len])
+        } else {
+            BitString::Unsigned(vec![BitX::Zero; len])
+        };
+        let zero = self.allocate_literal_from_bit_string(&zero, loc);
+        let is_zero = self.allocate_unsigned(1, loc);
+        self.lop(
+            tl::OpCode::Binary(tl::Binary {
+                lhs: is_zero,
+                op: tl::AluBinary::Eq,
+                arg1: arg2,
+                arg2: zero,
+            }),
+            loc,
+        );
+        let fallback = if op == tl::AluBinary::Div {
+            let ones = if signed {
+                BitString::Signed(vec![BitX::One; len])
+            } else {
+                BitString::Unsigned(vec![BitX::One; len])
+            };
+            self.allocate_literal_from_bit_string(&ones, loc)
+        } else {
+            arg1
+        };
+        self.lop(
+            tl::OpCode::Select(tl::Select {
+                lhs,
+                cond: is_zero,
+                true_value: fallback,
+                false_value: raw,
+            }),
+            loc,
+        );
+        Ok(())
+    }
     fn make_binary(&mut self, binary: &hf::Binary, loc: SourceLocation) -> Result<()> {
         let hf::Binary {
             lhs,
@@ -586,6 +783,8 @@ impl<'a> RTLCompiler<'a> {
             AluBinary::Add => rtl_binop(tl::AluBinary::Add),
             AluBinary::Sub => rtl_binop(tl::AluBinary::Sub),
             AluBinary::Mul => rtl_binop(tl::AluBinary::Mul),
+            AluBinary::Div => self.make_div_rem(lhs, arg1, arg2, loc, tl::AluBinary::Div),
+            AluBinary::Rem => self.make_div_rem(lhs, arg1, arg2, loc, tl::AluBinary::Rem),
             AluBinary::BitAnd => rtl_binop(tl::AluBinary::BitAnd),
             AluBinary::BitOr => rtl_binop(tl::AluBinary::BitOr),
             AluBinary::BitXor => rtl_binop(tl::AluBinary::BitXor),
@@ -600,6 +799,8 @@ impl<'a> RTLCompiler<'a> {
             AluBinary::XAdd => self.make_xadd_or_xmul(lhs, arg1, arg2, loc, tl::AluBinary::Add),
             AluBinary::XSub => self.make_xsub(lhs, arg1, arg2, loc),
             AluBinary::XMul => self.make_xadd_or_xmul(lhs, arg1, arg2, loc, tl::AluBinary::Mul),
+            AluBinary::SatAdd => self.make_saturating(lhs, arg1, arg2, loc, tl::AluBinary::Add),
+            AluBinary::SatSub => self.make_saturating(lhs, arg1, arg2, loc, tl::AluBinary::Sub),
         }
     }
     fn make_case_argument(
@@ -1098,6 +1299,12 @@ impl<'a> RTLCompiler<'a> {
             hf::AluUnary::Signed => unop(tl::AluUnary::Signed),
             hf::AluUnary::Unsigned => unop(tl::AluUnary::Unsigned),
             hf::AluUnary::Val => unop(tl::AluUnary::Val),
+            hf::AluUnary::CountOnes => unop(tl::AluUnary::CountOnes),
+            hf::AluUnary::LeadingZeros => unop(tl::AluUnary::LeadingZeros),
+            hf::AluUnary::TrailingZeros => unop(tl::AluUnary::TrailingZeros),
+            hf::AluUnary::ReverseBits => unop(tl::AluUnary::ReverseBits),
+            hf::AluUnary::OneHotToBinary(len) => unop(tl::AluUnary::OneHotToBinary(len)),
+            hf::AluUnary::BinaryToOneHot(len) => unop(tl::AluUnary::BinaryToOneHot(len)),
             hf::AluUnary::XExt(_) => {
                 let lhs_len = self.operand_bit_width(lhs);
                 self.lop(
@@ -1114,6 +1321,7 @@ impl<'a> RTLCompiler<'a> {
             hf::AluUnary::XShr(cnt) => self.make_xshr(lhs, arg1, cnt, loc),
             hf::AluUnary::XNeg => self.make_xneg(lhs, arg1, loc),
             hf::AluUnary::XSgn => self.make_xsgn(lhs, arg1, loc),
+            hf::AluUnary::Saturate(_) => self.make_clamp(lhs, arg1, loc)?,
         };
         Ok(())
     }
@@ -1204,10 +1412,23 @@ fn compile_rtl(object: &rhif::Object) -> Result<rtl::object::Object> {
         })
         .collect();
     let return_register = compiler.reverse_operand_map[&(object.fn_id, object.return_slot)];
+    let assertions = object
+        .assertions
+        .iter()
+        .map(|assertion| {
+            Ok(rtl::object::Assertion {
+                kind: assertion.kind,
+                message: assertion.message.clone(),
+                cond: compiler.operand(assertion.cond, assertion.loc)?,
+                loc: assertion.loc,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
     Ok(rtl::object::Object {
         symbols: compiler.symbols,
         literals: compiler.literals,
         return_register,
+        assertions,
         register_kind: compiler.registers,
         ops: compiler.ops,
         arguments,
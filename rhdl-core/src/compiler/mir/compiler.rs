@@ -14,9 +14,12 @@ use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::iter::once;
+use std::sync::Mutex;
 
 use crate::ast::ast_impl;
+use crate::ast::ast_impl::AssertKind;
 use crate::ast::ast_impl::BitsKind;
+use crate::ast::ast_impl::ExprAssert;
 use crate::ast::ast_impl::ExprBits;
 use crate::ast::ast_impl::ExprBlock;
 use crate::ast::ast_impl::ExprCast;
@@ -26,9 +29,9 @@ use crate::ast::ast_impl::ExprTypedBits;
 use crate::ast::ast_impl::NodeId;
 use crate::ast::ast_impl::WrapOp;
 use crate::ast::ast_impl::{
-    Arm, ArmKind, Block, ExprArray, ExprAssign, ExprBinary, ExprCall, ExprField, ExprForLoop,
-    ExprIf, ExprIndex, ExprMatch, ExprMethodCall, ExprPath, ExprRepeat, ExprRet, ExprStruct,
-    ExprTuple, ExprUnary, FieldValue, Local, Pat, PatKind, Stmt, StmtKind,
+    Arm, ArmEnum, ArmKind, Block, ExprArray, ExprAssign, ExprBinary, ExprCall, ExprField,
+    ExprForLoop, ExprIf, ExprIndex, ExprMatch, ExprMethodCall, ExprPath, ExprRepeat, ExprRet,
+    ExprStruct, ExprTuple, ExprUnary, FieldValue, Local, Pat, PatKind, RangeLimits, Stmt, StmtKind,
 };
 use crate::ast::source::builder::build_spanned_source_for_kernel;
 use crate::ast::source::spanned_source::SpannedSource;
@@ -42,6 +45,8 @@ use crate::compiler::stage1::CompilationMode;
 use crate::error::RHDLError;
 use crate::kernel::Kernel;
 use crate::rhif;
+use crate::rhif::object::Assertion;
+use crate::rhif::object::Branch;
 use crate::rhif::object::LocatedOpCode;
 use crate::rhif::object::SymbolMap;
 use crate::rhif::rhif_builder::op_as_bits_inferred;
@@ -64,7 +69,12 @@ use crate::rhif::{
     },
     spec::AluBinary,
 };
+use crate::types::assertion::AssertionCollector;
+use crate::types::coverage::is_coverage_enabled;
+use crate::types::coverage::BranchCollector;
+use crate::types::coverage::BranchKind;
 use crate::types::path::Path;
+use crate::util::clog2;
 use crate::KernelFnKind;
 use crate::Kind;
 use crate::TypedBits;
@@ -93,6 +103,8 @@ fn binop_to_alu(op: BinOp) -> AluBinary {
         BinOp::Add | BinOp::AddAssign => AluBinary::Add,
         BinOp::Sub | BinOp::SubAssign => AluBinary::Sub,
         BinOp::Mul | BinOp::MulAssign => AluBinary::Mul,
+        BinOp::Div | BinOp::DivAssign => AluBinary::Div,
+        BinOp::Rem | BinOp::RemAssign => AluBinary::Rem,
         BinOp::BitXor | BinOp::BitXorAssign => AluBinary::BitXor,
         BinOp::And | BinOp::BitAnd | BinOp::BitAndAssign => AluBinary::BitAnd,
         BinOp::Or | BinOp::BitOr | BinOp::BitOrAssign => AluBinary::BitOr,
@@ -169,6 +181,32 @@ impl std::fmt::Debug for Scope {
 
 const EARLY_RETURN_FLAG_NAME: &str = "__$early_return_flag";
 
+// Each assertion in a kernel is tracked by a hidden local variable, so
+// that the usual merging of locals at the end of an `if` or `match`
+// takes care of assertions that are only reached on some paths.  The
+// bindings need static names, like every other local.  These are leaked
+// once, and then shared by all kernels.
+fn hidden_name(names: &Mutex<Vec<&'static str>>, prefix: &str, ndx: usize) -> &'static str {
+    let mut names = names.lock().unwrap();
+    while names.len() <= ndx {
+        let name = format!("{prefix}_{}", names.len());
+        names.push(Box::leak(name.into_boxed_str()));
+    }
+    names[ndx]
+}
+
+fn assertion_name(ndx: usize) -> &'static str {
+    static NAMES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+    hidden_name(&NAMES, "__$assertion", ndx)
+}
+
+// When compiling for coverage, each branch of the kernel is tracked the
+// same way, by a hidden local that is set when the branch is taken.
+fn branch_name(ndx: usize) -> &'static str {
+    static NAMES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+    hidden_name(&NAMES, "__$branch", ndx)
+}
+
 type Result<T> = std::result::Result<T, RHDLError>;
 
 pub struct MirContext<'a> {
@@ -184,6 +222,8 @@ pub struct MirContext<'a> {
     slot_names: BTreeMap<Slot, String>,
     return_slot: Slot,
     arguments: Vec<Slot>,
+    assertions: Vec<(NodeId, AssertKind, &'static str)>,
+    branches: Vec<(NodeId, BranchKind)>,
     fn_id: FunctionId,
     name: &'static str,
     active_scope: ScopeId,
@@ -238,6 +278,8 @@ impl<'a> MirContext<'a> {
             stash: Default::default(),
             return_slot: Slot::Empty,
             arguments: vec![],
+            assertions: vec![],
+            branches: vec![],
             fn_id,
             name: "",
             active_scope: ROOT_SCOPE,
@@ -568,49 +610,126 @@ impl<'a> MirContext<'a> {
         self.op(op_array(lhs, elements), id);
         Ok(lhs)
     }
-    fn arm(&mut self, target: Slot, lhs: Slot, arm: &Arm) -> Result<CaseArgument> {
+    fn arm(&mut self, target: Slot, lhs: Slot, arm: &Arm) -> Result<Vec<CaseArgument>> {
         match &arm.kind {
-            ArmKind::Wild => {
+            ArmKind::Enum(arm_enum) => {
+                log::trace!("arm enum {:?}", arm_enum);
+                self.new_scope();
+                let discriminant_slot = self.bind_enum_arm(arm.id, target, arm_enum)?;
+                let result = self.expr(&arm.body)?;
+                self.op(op_assign(lhs, result), arm_enum.pat.id);
+                self.end_scope();
+                Ok(vec![CaseArgument::Slot(discriminant_slot)])
+            }
+            ArmKind::Or(or) => {
                 self.wrap_expr_in_block(lhs, &arm.body)?;
-                Ok(CaseArgument::Wild)
+                or.cases
+                    .iter()
+                    .map(|case| self.case_argument(arm.id, case))
+                    .collect()
             }
-            ArmKind::Constant(constant) => {
+            kind => {
                 self.wrap_expr_in_block(lhs, &arm.body)?;
-                let value = self.lit(arm.id, constant.value.clone());
-                let disc = self.reg(arm.id);
+                Ok(vec![self.case_argument(arm.id, kind)?])
+            }
+        }
+    }
+    fn case_argument(&mut self, id: NodeId, kind: &ArmKind) -> Result<CaseArgument> {
+        match kind {
+            ArmKind::Wild => Ok(CaseArgument::Wild),
+            ArmKind::Constant(constant) => {
+                let value = self.lit(id, constant.value.clone());
+                let disc = self.reg(id);
                 self.op(
                     op_index(
                         disc,
                         value,
                         crate::types::path::Path::default().discriminant(),
                     ),
-                    arm.id,
+                    id,
                 );
                 Ok(CaseArgument::Slot(disc))
             }
+            _ => Err(self
+                .raise_ice(ICE::UnsupportedArmKindInCase { kind: kind.clone() }, id)
+                .into()),
+        }
+    }
+    // Allocate the local bindings for an enum match pattern, and bind them
+    // to the payload of the target.  Returns the discriminant for the arm.
+    fn bind_enum_arm(&mut self, id: NodeId, target: Slot, arm_enum: &ArmEnum) -> Result<Slot> {
+        self.bind_pattern(&arm_enum.pat)?;
+        let discriminant = arm_enum.discriminant.clone();
+        let discriminant_slot = self.literal_tb(id, &discriminant);
+        let disc_as_i64 = discriminant.as_i64()?;
+        let path = crate::types::path::Path::default().payload_by_value(disc_as_i64);
+        let payload = self.reg(arm_enum.pat.id);
+        self.op(op_index(payload, target, path), arm_enum.pat.id);
+        self.initialize_local(&arm_enum.pat, payload)?;
+        Ok(discriminant_slot)
+    }
+    // Compute a boolean that is true if the arm pattern matches, binding
+    // any names the pattern introduces into the current scope.
+    fn arm_condition(
+        &mut self,
+        id: NodeId,
+        target: Slot,
+        discriminant: Slot,
+        kind: &ArmKind,
+    ) -> Result<Slot> {
+        let compare = |this: &mut Self, op: AluBinary, value: Slot| {
+            let cond = this.reg(id);
+            this.op(op_binary(op, cond, discriminant, value), id);
+            cond
+        };
+        match kind {
+            ArmKind::Wild => Ok(self.literal_bool(id, true)),
+            ArmKind::Bind(bind) => {
+                self.bind_pattern(&bind.pat)?;
+                self.initialize_local(&bind.pat, target)?;
+                Ok(self.literal_bool(id, true))
+            }
+            ArmKind::Constant(_) => {
+                let CaseArgument::Slot(value) = self.case_argument(id, kind)? else {
+                    unreachable!()
+                };
+                Ok(compare(self, AluBinary::Eq, value))
+            }
             ArmKind::Enum(arm_enum) => {
-                log::trace!("arm enum {:?}", arm_enum);
-                self.new_scope();
-                // Allocate the local bindings for the match pattern
-                self.bind_pattern(&arm_enum.pat)?;
-                let discriminant = arm_enum.discriminant.clone();
-                let discriminant_slot = self.lit(
-                    arm.id,
-                    ExprLit::TypedBits(ast_impl::ExprTypedBits {
-                        path: Box::new(ast_impl::Path { segments: vec![] }),
-                        value: discriminant.clone(),
-                        code: String::new(),
-                    }),
-                );
-                let disc_as_i64 = discriminant.as_i64()?;
-                let path = crate::types::path::Path::default().payload_by_value(disc_as_i64);
-                let payload = self.reg(arm_enum.pat.id);
-                self.op(op_index(payload, target, path), arm_enum.pat.id);
-                self.initialize_local(&arm_enum.pat, payload)?;
-                let result = self.expr(&arm.body)?;
-                self.op(op_assign(lhs, result), arm_enum.pat.id);
-                self.end_scope();
-                Ok(CaseArgument::Slot(discriminant_slot))
+                let value = self.bind_enum_arm(id, target, arm_enum)?;
+                Ok(compare(self, AluBinary::Eq, value))
+            }
+            ArmKind::Range(range) => {
+                let lower = range.start.clone().map(|start| {
+                    let start = self.lit(id, start);
+                    compare(self, AluBinary::Ge, start)
+                });
+                let upper = range.end.clone().map(|end| {
+                    let end = self.lit(id, end);
+                    match range.limits {
+                        RangeLimits::HalfOpen => compare(self, AluBinary::Lt, end),
+                        RangeLimits::Closed => compare(self, AluBinary::Le, end),
+                    }
+                });
+                Ok(match (lower, upper) {
+                    (Some(lower), Some(upper)) => {
+                        let cond = self.reg(id);
+                        self.op(op_binary(AluBinary::BitAnd, cond, lower, upper), id);
+                        cond
+                    }
+                    (Some(cond), None) | (None, Some(cond)) => cond,
+                    (None, None) => self.literal_bool(id, true),
+                })
+            }
+            ArmKind::Or(or) => {
+                let mut cond = self.literal_bool(id, false);
+                for case in &or.cases {
+                    let case = self.arm_condition(id, target, discriminant, case)?;
+                    let either = self.reg(id);
+                    self.op(op_binary(AluBinary::BitOr, either, cond, case), id);
+                    cond = either;
+                }
+                Ok(cond)
             }
         }
     }
@@ -646,6 +765,8 @@ impl<'a> MirContext<'a> {
             BinOp::AddAssign => AluBinary::Add,
             BinOp::SubAssign => AluBinary::Sub,
             BinOp::MulAssign => AluBinary::Mul,
+            BinOp::DivAssign => AluBinary::Div,
+            BinOp::RemAssign => AluBinary::Rem,
             BinOp::BitXorAssign => AluBinary::BitXor,
             BinOp::BitAndAssign => AluBinary::BitAnd,
             BinOp::BitOrAssign => AluBinary::BitOr,
@@ -697,6 +818,8 @@ impl<'a> MirContext<'a> {
             BinOp::AddAssign
                 | BinOp::SubAssign
                 | BinOp::MulAssign
+                | BinOp::DivAssign
+                | BinOp::RemAssign
                 | BinOp::BitXorAssign
                 | BinOp::BitAndAssign
                 | BinOp::ShlAssign
@@ -885,6 +1008,8 @@ impl<'a> MirContext<'a> {
                     _ => self.op(op_wrap(lhs, args[0], *wrap_op), id),
                 };
             }
+            // Fixed point values are their raw bits to the compiler
+            KernelFnKind::FixedConstructor => self.op(op_assign(lhs, args[0]), id),
         }
         Ok(lhs)
     }
@@ -911,7 +1036,14 @@ impl<'a> MirContext<'a> {
             ExprKind::Struct(_struct) => self.struct_expr(expr.id, _struct),
             ExprKind::Tuple(tuple) => self.tuple(expr.id, tuple),
             ExprKind::Unary(unary) => self.unop(expr.id, unary),
-            ExprKind::Match(_match) => self.match_expr(expr.id, _match),
+            ExprKind::Match(_match) => {
+                let branches = _match
+                    .arms
+                    .iter()
+                    .map(|arm| (arm.id, BranchKind::Arm))
+                    .collect::<Vec<_>>();
+                self.match_expr(expr.id, _match, &branches)
+            }
             ExprKind::Ret(_return) => self.return_expr(expr.id, _return),
             ExprKind::ForLoop(for_loop) => self.for_loop(for_loop),
             ExprKind::Assign(assign) => self.assign(expr.id, assign),
@@ -929,7 +1061,60 @@ impl<'a> MirContext<'a> {
             ExprKind::Try(tri) => self.try_expr(expr.id, tri),
             ExprKind::IfLet(if_let) => self.if_let_expr(expr.id, if_let),
             ExprKind::Cast(cast) => self.cast(expr.id, cast),
+            ExprKind::Assert(assert) => self.assert_expr(expr.id, assert),
+        }
+    }
+    // An assertion updates its hidden local with the condition, unless
+    // the kernel has already returned.  Assertions must hold every time
+    // they are reached (including each iteration of a loop), while a
+    // cover only needs to be reached once with the condition true.
+    fn assert_expr(&mut self, id: NodeId, assert: &ExprAssert) -> Result<Slot> {
+        let cond = self.expr(&assert.cond)?;
+        let Some(ndx) = self.assertions.iter().position(|(x, _, _)| *x == id) else {
+            return Err(self.raise_ice(ICE::AssertionNotCollected, id).into());
+        };
+        let (early_return_flag, _) = self
+            .lookup_name(EARLY_RETURN_FLAG_NAME)
+            .ok_or_else(|| self.raise_ice(ICE::NoEarlyReturnFlagFound { func: self.fn_id }, id))?;
+        let state = self.rebind(assertion_name(ndx), id)?;
+        let reached = self.reg(id);
+        match assert.kind {
+            AssertKind::Assert => {
+                self.op(
+                    op_binary(AluBinary::BitOr, reached, cond, early_return_flag),
+                    id,
+                );
+                self.op(
+                    op_binary(AluBinary::BitAnd, state.to, state.from, reached),
+                    id,
+                );
+            }
+            AssertKind::Cover => {
+                let live = self.reg(id);
+                self.op(op_unary(AluUnary::Not, live, early_return_flag), id);
+                self.op(op_binary(AluBinary::BitAnd, reached, cond, live), id);
+                self.op(
+                    op_binary(AluBinary::BitOr, state.to, state.from, reached),
+                    id,
+                );
+            }
         }
+        Ok(Slot::Empty)
+    }
+    // Mark a branch as taken, unless the kernel has already returned.
+    // Branches are only collected when compiling for coverage.
+    fn cover_branch(&mut self, id: NodeId, kind: BranchKind) -> Result<()> {
+        let Some(ndx) = self.branches.iter().position(|x| *x == (id, kind)) else {
+            return Ok(());
+        };
+        let (early_return_flag, _) = self
+            .lookup_name(EARLY_RETURN_FLAG_NAME)
+            .ok_or_else(|| self.raise_ice(ICE::NoEarlyReturnFlagFound { func: self.fn_id }, id))?;
+        let state = self.rebind(branch_name(ndx), id)?;
+        let live = self.reg(id);
+        self.op(op_unary(AluUnary::Not, live, early_return_flag), id);
+        self.op(op_binary(AluBinary::BitOr, state.to, state.from, live), id);
+        Ok(())
     }
     fn cast(&mut self, id: NodeId, cast: &ExprCast) -> Result<Slot> {
         let lhs = self.reg(id);
@@ -1059,6 +1244,7 @@ impl<'a> MirContext<'a> {
         let active_arm = Arm {
             id,
             kind: if_let_expr.kind.clone(),
+            guard: None,
             body: Box::new(Expr {
                 id: if_let_expr.then_block.id,
                 kind: ExprKind::Block(ExprBlock {
@@ -1069,6 +1255,7 @@ impl<'a> MirContext<'a> {
         let else_arm = Arm {
             id,
             kind: ArmKind::Wild,
+            guard: None,
             body: if_let_expr
                 .else_branch
                 .clone()
@@ -1078,7 +1265,14 @@ impl<'a> MirContext<'a> {
             expr: if_let_expr.test.clone(),
             arms: vec![Box::new(active_arm), Box::new(else_arm)],
         };
-        self.match_expr(id, &my_match)
+        let branches = [
+            (if_let_expr.then_block.id, BranchKind::Then),
+            match &if_let_expr.else_branch {
+                Some(else_branch) => (else_branch.id, BranchKind::Else),
+                None => (id, BranchKind::ImplicitElse),
+            },
+        ];
+        self.match_expr(id, &my_match, &branches)
     }
     fn if_expr(&mut self, id: NodeId, if_expr: &ExprIf) -> Result<Slot> {
         let op_result = self.reg(id);
@@ -1088,13 +1282,16 @@ impl<'a> MirContext<'a> {
         let cond = self.expr(&if_expr.cond)?;
         let locals_prior_to_branch = self.locals();
         debug!("Locals prior to branch {:?}", locals_prior_to_branch);
+        self.cover_branch(if_expr.then_branch.id, BranchKind::Then)?;
         self.block(then_result, &if_expr.then_branch)?;
         let locals_after_then_branch = self.locals().clone();
         debug!("Locals after then branch {:?}", locals_after_then_branch);
         self.set_locals(&locals_prior_to_branch, id)?;
         if let Some(expr) = if_expr.else_branch.as_ref() {
+            self.cover_branch(expr.id, BranchKind::Else)?;
             self.wrap_expr_in_block(else_result, expr)?;
         } else {
+            self.cover_branch(id, BranchKind::ImplicitElse)?;
             self.op(op_assign(else_result, Slot::Empty), id);
         }
         let locals_after_else_branch = self.locals();
@@ -1156,8 +1353,27 @@ impl<'a> MirContext<'a> {
         }
         Ok(())
     }
-    fn match_expr(&mut self, id: NodeId, match_expr: &ExprMatch) -> Result<Slot> {
-        let lhs = self.reg(id);
+    // The branches identify each arm for coverage.
+    fn match_expr(
+        &mut self,
+        id: NodeId,
+        match_expr: &ExprMatch,
+        branches: &[(NodeId, BranchKind)],
+    ) -> Result<Slot> {
+        let is_case_arm = |arm: &Arm| {
+            arm.guard.is_none()
+                && match &arm.kind {
+                    ArmKind::Wild | ArmKind::Constant(_) | ArmKind::Enum(_) => true,
+                    ArmKind::Or(or) => or
+                        .cases
+                        .iter()
+                        .all(|x| matches!(x, ArmKind::Wild | ArmKind::Constant(_))),
+                    ArmKind::Range(_) | ArmKind::Bind(_) => false,
+                }
+        };
+        if !match_expr.arms.iter().all(|arm| is_case_arm(arm)) {
+            return self.priority_match_expr(id, match_expr, branches);
+        }
         let target = self.expr(&match_expr.expr)?;
         let discriminant = self.reg(id);
         self.op(
@@ -1170,20 +1386,107 @@ impl<'a> MirContext<'a> {
         );
         // Need to handle local rebindings in the bodies of the arms.
         let locals_prior_to_match = self.locals().clone();
-        let mut arguments = vec![];
+        let mut table = vec![];
+        let mut arm_locals = vec![];
+        let mut arm_lhs = vec![];
+        for (ndx, arm) in match_expr.arms.iter().enumerate() {
+            self.set_locals(&locals_prior_to_match, id)?;
+            self.cover_branch(branches[ndx].0, branches[ndx].1)?;
+            let lhs = self.reg(id);
+            let arguments = self.arm(target, lhs, arm)?;
+            table.extend(arguments.into_iter().map(|x| (x, ndx)));
+            arm_lhs.push(lhs);
+            arm_locals.push(self.locals().clone());
+        }
+        self.set_locals(&locals_prior_to_match, id)?;
+        self.merge_match_arms(id, discriminant, &table, &arm_lhs, &arm_locals)
+    }
+    // Matches with guards, ranges or bindings cannot be expressed as a
+    // single case on the discriminant.  Instead, each arm computes a
+    // condition, and a priority chain of selects picks the index of the
+    // first arm that matches.  That index then drives a case, just like
+    // a regular match.  The match is exhaustive (rustc has checked the
+    // kernel), so if no earlier arm matches, the last one must.
+    fn priority_match_expr(
+        &mut self,
+        id: NodeId,
+        match_expr: &ExprMatch,
+        branches: &[(NodeId, BranchKind)],
+    ) -> Result<Slot> {
+        let target = self.expr(&match_expr.expr)?;
+        let discriminant = self.reg(id);
+        self.op(
+            op_index(
+                discriminant,
+                target,
+                crate::types::path::Path::default().discriminant(),
+            ),
+            id,
+        );
+        let locals_prior_to_match = self.locals().clone();
+        let mut conditions = vec![];
         let mut arm_locals = vec![];
         let mut arm_lhs = vec![];
-        for arm in &match_expr.arms {
+        for (arm, (branch, kind)) in match_expr.arms.iter().zip(branches) {
             self.set_locals(&locals_prior_to_match, id)?;
+            self.cover_branch(*branch, *kind)?;
             let lhs = self.reg(id);
-            let disc = self.arm(target, lhs, arm)?;
+            self.new_scope();
+            let mut cond = self.arm_condition(arm.id, target, discriminant, &arm.kind)?;
+            if let Some(guard) = &arm.guard {
+                let guard = self.expr(guard)?;
+                let both = self.reg(arm.id);
+                self.op(op_binary(AluBinary::BitAnd, both, cond, guard), arm.id);
+                cond = both;
+            }
+            let result = self.expr(&arm.body)?;
+            self.op(op_assign(lhs, result), arm.id);
+            self.end_scope();
+            conditions.push(cond);
             arm_lhs.push(lhs);
-            arguments.push(disc);
             arm_locals.push(self.locals().clone());
         }
         self.set_locals(&locals_prior_to_match, id)?;
+        let count = match_expr.arms.len();
+        let width = clog2(count).max(1);
+        let indices = (0..count)
+            .map(|ndx| {
+                let ndx = TypedBits::from(ndx as u64).unsigned_cast(width)?;
+                Ok(self.literal_tb(id, &ndx))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut selected = indices[count - 1];
+        for ndx in (0..count - 1).rev() {
+            let next = self.reg(id);
+            self.op(op_select(next, conditions[ndx], indices[ndx], selected), id);
+            selected = next;
+        }
+        let table = indices
+            .iter()
+            .enumerate()
+            .map(|(ndx, slot)| {
+                if ndx == count - 1 {
+                    (CaseArgument::Wild, ndx)
+                } else {
+                    (CaseArgument::Slot(*slot), ndx)
+                }
+            })
+            .collect::<Vec<_>>();
+        self.merge_match_arms(id, selected, &table, &arm_lhs, &arm_locals)
+    }
+    // Combine the results (and any local rebindings) of the arms of a match,
+    // using a case on the discriminant.  The table maps each case argument
+    // to the index of the arm it selects.
+    fn merge_match_arms(
+        &mut self,
+        id: NodeId,
+        discriminant: Slot,
+        table: &[(CaseArgument, usize)],
+        arm_lhs: &[Slot],
+        arm_locals: &[LocalsMap],
+    ) -> Result<Slot> {
         let mut rebound_locals = BTreeSet::new();
-        for branch_locals in &arm_locals {
+        for branch_locals in arm_locals {
             let branch_rebindings = self.get_locals_changed(id, &self.locals(), branch_locals)?;
             rebound_locals.extend(branch_rebindings);
         }
@@ -1197,7 +1500,7 @@ impl<'a> MirContext<'a> {
             let arm_bindings = arm_locals
                 .iter()
                 .map(|x| {
-                    x.get(var).ok_or_else(|| {
+                    x.get(var).cloned().ok_or_else(|| {
                         self.raise_ice(
                             ICE::MissingLocalVariableForBindingInMatchArm { var: var.clone() },
                             id,
@@ -1206,15 +1509,18 @@ impl<'a> MirContext<'a> {
                     })
                 })
                 .collect::<Result<Vec<_>>>()?;
-            let cases = arguments
+            let cases = table
                 .iter()
-                .cloned()
-                .zip(arm_bindings.into_iter().cloned())
+                .map(|(arg, ndx)| (arg.clone(), arm_bindings[*ndx]))
                 .collect::<Vec<_>>();
             let new_binding = rebind.to;
             self.op(op_case(new_binding, discriminant, cases), id);
         }
-        let match_expr_table = arguments.iter().cloned().zip(arm_lhs).collect::<Vec<_>>();
+        let lhs = self.reg(id);
+        let match_expr_table = table
+            .iter()
+            .map(|(arg, ndx)| (arg.clone(), arm_lhs[*ndx]))
+            .collect::<Vec<_>>();
         self.op(op_case(lhs, discriminant, match_expr_table), id);
         Ok(lhs)
     }
@@ -1245,13 +1551,98 @@ impl<'a> MirContext<'a> {
         self.op(op_unary(op(len), lhs, arg), id);
         Ok(lhs)
     }
+    // Rounding a fixed point value drops `len` fractional bits, rounding
+    // half up.  This is computed on the raw bits as
+    //    ((x >> (len - 1)) + 1) >> 1
+    // where the addition wraps, just as it does for the fixed point types.
+    fn round(&mut self, id: NodeId, method_call: &ExprMethodCall) -> Result<Slot> {
+        let arg = self.expr(&method_call.receiver)?;
+        let Some(len) = method_call.turbo else {
+            return Err(self
+                .raise_syntax_error(Syntax::XOpsWithoutLength, id)
+                .into());
+        };
+        let half = if len > 1 {
+            let half = self.reg(id);
+            self.op(op_unary(AluUnary::XShr(len - 1), half, arg), id);
+            half
+        } else {
+            arg
+        };
+        let one = self.literal_int(id, 1);
+        let rounded = self.reg(id);
+        self.op(op_binary(AluBinary::Add, rounded, half, one), id);
+        let lhs = self.reg(id);
+        self.op(op_unary(AluUnary::XShr(1), lhs, rounded), id);
+        Ok(lhs)
+    }
+    // The overflowing and checked forms of add and subtract.  These are
+    // computed one bit wider, so that the wrapped result is the low bits
+    // of the sum (or difference), and it overflows when the top bit (the
+    // carry, or the sign for signed values) does not agree with them.
+    // That is, when the wide result differs from the wrapped one extended
+    // back out.  The two share all but the top bit, so this is a single
+    // adder, and a check of the top bits.
+    fn overflowing(
+        &mut self,
+        id: NodeId,
+        method_call: &ExprMethodCall,
+        wrapping: AluBinary,
+    ) -> Result<Slot> {
+        let arg = self.expr(&method_call.receiver)?;
+        let rhs = self.expr(&method_call.args[0])?;
+        self.ty_equate.insert(TypeEquivalence {
+            loc: (self.fn_id, id).into(),
+            lhs: rhs,
+            rhs: arg,
+        });
+        let wide_arg = self.reg(id);
+        self.op(op_unary(AluUnary::XExt(1), wide_arg, arg), id);
+        let wide_rhs = self.reg(id);
+        self.op(op_unary(AluUnary::XExt(1), wide_rhs, rhs), id);
+        let wide = self.reg(id);
+        self.op(op_binary(wrapping, wide, wide_arg, wide_rhs), id);
+        let result = self.reg(id);
+        self.op(op_resize_inferred(result, wide), id);
+        self.ty_equate.insert(TypeEquivalence {
+            loc: (self.fn_id, id).into(),
+            lhs: result,
+            rhs: arg,
+        });
+        let extended = self.reg(id);
+        self.op(op_unary(AluUnary::XExt(1), extended, result), id);
+        let overflow = self.reg(id);
+        self.op(op_binary(AluBinary::Ne, overflow, wide, extended), id);
+        let lhs = self.reg(id);
+        if method_call.method.starts_with("overflowing") {
+            self.op(op_tuple(lhs, vec![result, overflow]), id);
+        } else {
+            let some = self.reg(id);
+            self.op(op_wrap(some, result, WrapOp::Some), id);
+            let none = self.reg(id);
+            self.op(op_wrap(none, Slot::Empty, WrapOp::None), id);
+            self.op(op_select(lhs, overflow, none, some), id);
+        }
+        Ok(lhs)
+    }
     fn method_call(&mut self, id: NodeId, method_call: &ExprMethodCall) -> Result<Slot> {
-        // Special case the `cast` method calls and the extended arithmetic ops
+        // Special case the `cast` method calls and the extended arithmetic ops.
+        // Moving between the narrow and wide bit types is just a resize.
         match method_call.method {
-            "resize" => return self.resize(id, method_call),
+            "resize" | "widen" | "narrow" | "resize_fixed" => return self.resize(id, method_call),
             "xext" => return self.xops(id, method_call, AluUnary::XExt),
-            "xshl" => return self.xops(id, method_call, AluUnary::XShl),
-            "xshr" => return self.xops(id, method_call, AluUnary::XShr),
+            "xshl" | "xfrac" => return self.xops(id, method_call, AluUnary::XShl),
+            "xshr" | "xtrunc" => return self.xops(id, method_call, AluUnary::XShr),
+            "xround" => return self.round(id, method_call),
+            "saturate" => return self.xops(id, method_call, AluUnary::Saturate),
+            "one_hot_to_binary" => return self.xops(id, method_call, AluUnary::OneHotToBinary),
+            "binary_to_one_hot" => return self.xops(id, method_call, AluUnary::BinaryToOneHot),
+            "overflowing_add" | "checked_add" => {
+                return self.overflowing(id, method_call, AluBinary::Add)
+            }
+            "overflowing_sub" | "checked_sub" => {
+                return self.overflowing(id, method_call, AluBinary::Sub)
+            }
             _ => {}
         }
         let lhs = self.reg(id);
@@ -1264,6 +1655,8 @@ impl<'a> MirContext<'a> {
             "xadd" => Some(AluBinary::XAdd),
             "xsub" => Some(AluBinary::XSub),
             "xmul" => Some(AluBinary::XMul),
+            "saturating_add" => Some(AluBinary::SatAdd),
+            "saturating_sub" => Some(AluBinary::SatSub),
             _ => None,
         } {
             let rhs = self.expr(&method_call.args[0])?;
@@ -1278,6 +1671,10 @@ impl<'a> MirContext<'a> {
             "as_signed" => AluUnary::Signed,
             "xneg" => AluUnary::XNeg,
             "xsgn" => AluUnary::XSgn,
+            "count_ones" => AluUnary::CountOnes,
+            "leading_zeros" => AluUnary::LeadingZeros,
+            "trailing_zeros" => AluUnary::TrailingZeros,
+            "reverse_bits" => AluUnary::ReverseBits,
             // The `val` method is a special case used to strip the clocking context
             // from a signal.
             "val" => AluUnary::Val,
@@ -1406,7 +1803,7 @@ impl<'a> MirContext<'a> {
         // that a phi node is inserted at the end of this synthetic `if` statement
         // to build a distributed priority encoder for the `__early_return` flag and
         // for the return slot itself.
-
+        self.cover_branch(id, BranchKind::Return)?;
         let literal_true = self.literal_bool(id, true);
         let early_return_flag = self.rebind(EARLY_RETURN_FLAG_NAME, id)?;
         let name = self.name;
@@ -1543,6 +1940,34 @@ impl Visitor for MirContext<'_> {
             self.lookup_name(node.name).unwrap().0,
             self.literal_tb(node.id, &node.ret.place_holder()),
         );
+        // Bind the hidden locals for the assertions.  An assertion starts
+        // out as holding, and a cover as not yet covered.
+        let mut collector = AssertionCollector::default();
+        collector.visit_block(&node.body)?;
+        self.assertions = collector.assertions;
+        let mut init_hidden_locals = vec![];
+        for (ndx, (id, kind, _)) in self.assertions.clone().into_iter().enumerate() {
+            self.bind(assertion_name(ndx), id);
+            let value = self.literal_bool(id, kind == AssertKind::Assert);
+            init_hidden_locals.push(op_assign(
+                self.lookup_name(assertion_name(ndx)).unwrap().0,
+                value,
+            ));
+        }
+        // Likewise for the branches, which start out as not taken.
+        if is_coverage_enabled() {
+            let mut collector = BranchCollector::default();
+            collector.visit_block(&node.body)?;
+            self.branches = collector.branches;
+        }
+        for (ndx, (id, _)) in self.branches.clone().into_iter().enumerate() {
+            self.bind(branch_name(ndx), id);
+            let value = self.literal_bool(id, false);
+            init_hidden_locals.push(op_assign(
+                self.lookup_name(branch_name(ndx)).unwrap().0,
+                value,
+            ));
+        }
         // Initialize the arguments in the main block
         for (arg, slot) in node.inputs.iter().zip(self.arguments.clone().iter()) {
             self.bind_pattern(arg)?;
@@ -1553,6 +1978,10 @@ impl Visitor for MirContext<'_> {
             .insert(0, (init_early_exit_op, (self.fn_id, node.id).into()).into());
         self.ops
             .insert(1, (init_return_slot, (self.fn_id, node.id).into()).into());
+        for (ndx, op) in init_hidden_locals.into_iter().enumerate() {
+            self.ops
+                .insert(2 + ndx, (op, (self.fn_id, node.id).into()).into());
+        }
         self.insert_implicit_return(node.body.id, block_result, node.name)?;
         self.return_slot = self
             .lookup_name(node.name)
@@ -1570,6 +1999,49 @@ impl Visitor for MirContext<'_> {
     }
 }
 
+impl MirContext<'_> {
+    // The final value of each hidden local is the result of its assertion.
+    fn final_assertions(&self) -> Result<Vec<Assertion>> {
+        self.assertions
+            .iter()
+            .enumerate()
+            .map(|(ndx, (id, kind, message))| {
+                let (cond, _) = self
+                    .lookup_name(assertion_name(ndx))
+                    .ok_or_else(|| self.raise_ice(ICE::AssertionNotCollected, *id))?;
+                Ok(Assertion {
+                    kind: *kind,
+                    message: message.to_string(),
+                    cond,
+                    loc: (self.fn_id, *id).into(),
+                })
+            })
+            .collect()
+    }
+    // The final value of each hidden local tells if its branch was taken.
+    fn final_branches(&self) -> Result<Vec<Branch>> {
+        self.branches
+            .iter()
+            .enumerate()
+            .map(|(ndx, (id, kind))| {
+                let (cond, _) = self.lookup_name(branch_name(ndx)).ok_or_else(|| {
+                    self.raise_ice(
+                        ICE::RebindOfUnboundVariable {
+                            name: branch_name(ndx).to_string(),
+                        },
+                        *id,
+                    )
+                })?;
+                Ok(Branch {
+                    kind: *kind,
+                    cond,
+                    loc: (self.fn_id, *id).into(),
+                })
+            })
+            .collect()
+    }
+}
+
 pub fn compile_mir(func: Kernel, mode: CompilationMode) -> Result<Mir> {
     let source = build_spanned_source_for_kernel(func.inner())?;
     for id in 0..func.inner().id.as_u32() {
@@ -1596,6 +2068,8 @@ pub fn compile_mir(func: Kernel, mode: CompilationMode) -> Result<Mir> {
         }
     }
     let fn_id = compiler.fn_id;
+    let assertions = compiler.final_assertions()?;
+    let branches = compiler.final_branches()?;
     let slot_map = compiler
         .reg_source_map
         .into_iter()
@@ -1613,6 +2087,8 @@ pub fn compile_mir(func: Kernel, mode: CompilationMode) -> Result<Mir> {
         arguments: compiler.arguments,
         literals: compiler.literals,
         return_slot: compiler.return_slot,
+        assertions,
+        branches,
         fn_id: compiler.fn_id,
         ty,
         ty_equate: compiler.ty_equate,
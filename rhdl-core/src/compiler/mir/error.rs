@@ -4,7 +4,7 @@ use miette::{Diagnostic, SourceSpan};
 use thiserror::Error;
 
 use crate::{
-    ast::ast_impl::{ExprCall, ExprPath, FunctionId, Pat},
+    ast::ast_impl::{ArmKind, ExprCall, ExprPath, FunctionId, Pat},
     builder::BinOp,
     rhif::spec::{AluBinary, AluUnary, OpCode, Slot},
     rtl::spec::Operand,
@@ -64,6 +64,8 @@ pub enum ICE {
     UnsupportedPatternInInitializeLocal { pat: Box<Pat> },
     #[error("No early return flag found in function {func:?}")]
     NoEarlyReturnFlagFound { func: FunctionId },
+    #[error("Assertion was not collected before compiling the kernel")]
+    AssertionNotCollected,
     #[error("Local variable {id:?} not found in branch map")]
     LocalVariableNotFoundInBranchMap { id: ScopeIndex },
     #[error("Return slot {name} not found")]
@@ -86,6 +88,8 @@ pub enum ICE {
     MissingLocalVariableForBindingInElseBranch { var: ScopeIndex },
     #[error("Missing local variable for binding {var:?} in match arm")]
     MissingLocalVariableForBindingInMatchArm { var: ScopeIndex },
+    #[error("Unsupported arm kind {kind:?} in case")]
+    UnsupportedArmKindInCase { kind: ArmKind },
     #[error("Name {name} not found in path {path:?}")]
     NameNotFoundInPath { name: String, path: ExprPath },
     #[error("Missing kernel function provided for {name}")]
@@ -116,6 +120,8 @@ pub enum ICE {
     UnsignedCastRequiresSignedArgument,
     #[error("Signed cast requires an unsigned argument")]
     SignedCastRequiresUnsignedArgument,
+    #[error("Bit manipulation operations require an unsigned argument")]
+    BitManipRequiresUnsignedArgument,
     #[error("Shift operator requires an unsigned argument instead of {kind:?}")]
     ShiftOperatorRequiresUnsignedArgument { kind: Kind },
     #[error("Index value must be unsigned")]
@@ -247,11 +253,16 @@ pub enum Syntax {
     #[error("RHDL does not support functions with empty return types")]
     #[diagnostic(help("You cannot have a function with an empty return type in RHDL.  You should return a value or a tuple of values."))]
     EmptyReturnForFunction,
-    #[error("RHDL cannot infer the number of bits in an xext/xshl/xshr operation")]
+    #[error("RHDL cannot infer the number of bits in an xext/xshl/xshr/saturate operation")]
     #[diagnostic(help(
-        "Use a turbofish to indicate how many bits you want to prepend (msb), e.g., a.xext::<W4>() or how many bits to shift left or right, as a.xshr<W2>()"
+        "Use a turbofish to indicate how many bits you want to prepend (msb), e.g., a.xext::<W4>(), how many bits to shift left or right, as a.xshr<W2>(), the width to saturate to, as a.saturate::<W4>(), or the width of a one-hot conversion, as a.one_hot_to_binary::<W3>()"
     ))]
     XOpsWithoutLength,
+    #[error("Division by a non-constant {width} bit value requires a combinational divider")]
+    #[diagnostic(help(
+        "Combinational dividers are limited to {max} bits.  Divide by a constant (which is lowered to a shift or multiply), use narrower values, or use a sequential divider instead"
+    ))]
+    CombinationalDividerTooWide { width: usize, max: usize },
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -283,6 +283,14 @@ impl<'a> MirTypeInference<'a> {
                 let lhs_ty = self.ctx.ty_with_sign_and_len(loc, a1_sign, len);
                 self.unify(loc, op.lhs, lhs_ty)?;
             }
+            AluUnary::Saturate(len) => {
+                let Some(a1_sign) = self.ctx.project_sign_flag(a1) else {
+                    return Ok(());
+                };
+                let len = self.ctx.ty_const_len(loc, len);
+                let lhs_ty = self.ctx.ty_with_sign_and_len(loc, a1_sign, len);
+                self.unify(loc, op.lhs, lhs_ty)?;
+            }
             AluUnary::XShr(diff) => {
                 let Some(a1_len) = self.ctx.project_bit_length(a1) else {
                     return Ok(());
@@ -319,7 +327,11 @@ impl<'a> MirTypeInference<'a> {
             | AluBinary::BitOr
             | AluBinary::BitXor
             | AluBinary::Mul
-            | AluBinary::Sub => {
+            | AluBinary::Div
+            | AluBinary::Rem
+            | AluBinary::Sub
+            | AluBinary::SatAdd
+            | AluBinary::SatSub => {
                 self.enforce_data_types_binary(loc, op.lhs, op.arg1, op.arg2)?;
             }
             AluBinary::XAdd => {
@@ -871,7 +883,8 @@ impl<'a> MirTypeInference<'a> {
                         | AluUnary::XShl(_)
                         | AluUnary::XShr(_)
                         | AluUnary::XNeg
-                        | AluUnary::XSgn => {
+                        | AluUnary::XSgn
+                        | AluUnary::Saturate(_) => {
                             self.type_ops.push(TypeOperation {
                                 loc: op.loc,
                                 kind: TypeOperationKind::UnaryOp(TypeUnaryOp {
@@ -917,6 +930,41 @@ impl<'a> MirTypeInference<'a> {
                                 .into());
                             }
                         }
+                        AluUnary::CountOnes
+                        | AluUnary::LeadingZeros
+                        | AluUnary::TrailingZeros
+                        | AluUnary::ReverseBits => {
+                            let len = self.ctx.ty_var(loc);
+                            let unsigned_ty = self.ctx.ty_bits(loc, len);
+                            if self.unify(loc, lhs, unsigned_ty).is_err()
+                                || self.unify(loc, arg1, unsigned_ty).is_err()
+                            {
+                                let source_span = self.mir.symbols.source_set.span(loc);
+                                return Err(Box::new(RHDLSyntaxError {
+                                    src: self.mir.symbols.source(),
+                                    cause: Syntax::RollYourOwnUnary { op: unary.op },
+                                    err_span: source_span.into(),
+                                })
+                                .into());
+                            }
+                        }
+                        AluUnary::OneHotToBinary(len) | AluUnary::BinaryToOneHot(len) => {
+                            let arg_len = self.ctx.ty_var(loc);
+                            let arg_ty = self.ctx.ty_bits(loc, arg_len);
+                            let len = self.ctx.ty_const_len(loc, len);
+                            let lhs_ty = self.ctx.ty_bits(loc, len);
+                            if self.unify(loc, lhs, lhs_ty).is_err()
+                                || self.unify(loc, arg1, arg_ty).is_err()
+                            {
+                                let source_span = self.mir.symbols.source_set.span(loc);
+                                return Err(Box::new(RHDLSyntaxError {
+                                    src: self.mir.symbols.source(),
+                                    cause: Syntax::RollYourOwnUnary { op: unary.op },
+                                    err_span: source_span.into(),
+                                })
+                                .into());
+                            }
+                        }
                         AluUnary::Val => {
                             let sig_ty = self.ctx.ty_var(loc);
                             let sig_clock = self.ctx.ty_var(loc);
@@ -1132,6 +1180,8 @@ pub fn infer(mir: Mir) -> Result<Object> {
             .map(|x| x.as_reg().unwrap())
             .collect(),
         return_slot: mir.return_slot,
+        assertions: mir.assertions,
+        branches: mir.branches,
         externals: mir.stash,
         name: mir.name,
         fn_id: mir.fn_id,
@@ -7,7 +7,7 @@ use crate::{
         source::source_location::SourceLocation,
     },
     rhif::{
-        object::{LocatedOpCode, SymbolMap},
+        object::{Assertion, Branch, LocatedOpCode, SymbolMap},
         spec::{FuncId, OpCode, Slot},
         Object,
     },
@@ -29,6 +29,8 @@ pub struct Mir {
     pub ty_equate: HashSet<TypeEquivalence>,
     pub stash: BTreeMap<FuncId, Box<Object>>,
     pub return_slot: Slot,
+    pub assertions: Vec<Assertion>,
+    pub branches: Vec<Branch>,
     pub arguments: Vec<Slot>,
     pub fn_id: FunctionId,
     pub name: String,
@@ -146,7 +146,11 @@ fn check_type_correctness(obj: &Object) -> Result<(), RHDLError> {
                     | AluBinary::BitAnd
                     | AluBinary::BitOr
                     | AluBinary::Mul
-                    | AluBinary::BitXor,
+                    | AluBinary::Div
+                    | AluBinary::Rem
+                    | AluBinary::BitXor
+                    | AluBinary::SatAdd
+                    | AluBinary::SatSub,
                 lhs,
                 arg1,
                 arg2,
@@ -258,6 +262,15 @@ fn check_type_correctness(obj: &Object) -> Result<(), RHDLError> {
                     loc,
                 )?;
             }
+            OpCode::Unary(Unary {
+                op: AluUnary::Saturate(len),
+                lhs,
+                arg1,
+            }) => {
+                let arg1_ty = slot_type(arg1);
+                let diff = *len as isize - arg1_ty.bits() as isize;
+                eq_kinds(slot_type(lhs), xops_kind(obj, loc, arg1_ty, diff)?, loc)?;
+            }
             OpCode::Unary(Unary {
                 op: AluUnary::XNeg,
                 lhs,
@@ -272,6 +285,39 @@ fn check_type_correctness(obj: &Object) -> Result<(), RHDLError> {
             }) => {
                 eq_kinds(slot_type(lhs), xneg_kind(obj, loc, slot_type(arg1))?, loc)?;
             }
+            OpCode::Unary(Unary {
+                op:
+                    AluUnary::CountOnes
+                    | AluUnary::LeadingZeros
+                    | AluUnary::TrailingZeros
+                    | AluUnary::ReverseBits,
+                lhs,
+                arg1,
+            }) => {
+                let arg1_ty = slot_type(arg1);
+                let Kind::Bits(_) = arg1_ty else {
+                    return Err(TypeCheckPass::raise_ice(
+                        obj,
+                        ICE::BitManipRequiresUnsignedArgument,
+                        loc,
+                    ));
+                };
+                eq_kinds(slot_type(lhs), arg1_ty, loc)?;
+            }
+            OpCode::Unary(Unary {
+                op: AluUnary::OneHotToBinary(len) | AluUnary::BinaryToOneHot(len),
+                lhs,
+                arg1,
+            }) => {
+                let Kind::Bits(_) = slot_type(arg1) else {
+                    return Err(TypeCheckPass::raise_ice(
+                        obj,
+                        ICE::BitManipRequiresUnsignedArgument,
+                        loc,
+                    ));
+                };
+                eq_kinds(slot_type(lhs), Kind::make_bits(*len), loc)?;
+            }
             OpCode::Unary(Unary {
                 op: AluUnary::Signed,
                 lhs,
@@ -16,9 +16,12 @@ pub struct DeadCodeEliminationPass {}
 
 impl Pass for DeadCodeEliminationPass {
     fn run(mut input: Object) -> Result<Object, RHDLError> {
-        // Start with an active set containing only the return slot
+        // Start with an active set containing the return slot
+        // and the results of any assertions and branches
         let mut active_set: HashSet<Slot> = HashSet::new();
         active_set.insert(input.return_slot);
+        active_set.extend(input.assertions.iter().map(|x| x.cond));
+        active_set.extend(input.branches.iter().map(|x| x.cond));
         // Get the mapping from slots to opcodes
         let mut alive_ops: Vec<bool> = vec![false; input.ops.len()];
         // Iterate through the ops backwards
@@ -71,6 +71,12 @@ impl Pass for RemoveExtraRegistersPass {
                 }
                 // Check the output register
                 input.return_slot = input.return_slot.rename(assign.lhs, assign.rhs);
+                for assertion in input.assertions.iter_mut() {
+                    assertion.cond = assertion.cond.rename(assign.lhs, assign.rhs);
+                }
+                for branch in input.branches.iter_mut() {
+                    branch.cond = branch.cond.rename(assign.lhs, assign.rhs);
+                }
                 // Record the alias in the symbol table
                 // This is used to find equivalent expressions when emitting error messages
                 input.symbols.alias(assign.rhs, assign.lhs);
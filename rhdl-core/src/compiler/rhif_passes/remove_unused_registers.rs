@@ -15,6 +15,8 @@ impl Pass for RemoveUnusedRegistersPass {
         let mut used_set: HashSet<Slot> = Default::default();
         used_set.extend(input.arguments.iter().map(|r| Slot::Register(*r)));
         used_set.insert(input.return_slot);
+        used_set.extend(input.assertions.iter().map(|x| x.cond));
+        used_set.extend(input.branches.iter().map(|x| x.cond));
         for lop in input.ops.iter() {
             remap_slots(lop.op.clone(), |slot| {
                 used_set.insert(slot);
@@ -0,0 +1,48 @@
+use crate::{
+    compiler::mir::error::{RHDLSyntaxError, Syntax},
+    rtl::{
+        spec::{AluBinary, Binary, OpCode},
+        Object,
+    },
+    RHDLError,
+};
+
+use super::pass::Pass;
+
+/// The widest value that can be divided by a non-constant divisor.
+/// Beyond this, a combinational divider is too large (and too slow)
+/// to be practical, and the design should use a sequential divider.
+pub const MAX_COMBINATIONAL_DIVIDER_WIDTH: usize = 32;
+
+// Division by a constant is lowered to shifts and multiplies before
+// this check runs, so any divider that remains needs to be built
+// from combinational logic.
+#[derive(Default, Debug, Clone)]
+pub struct CheckDividerWidth {}
+
+impl Pass for CheckDividerWidth {
+    fn run(input: Object) -> Result<Object, RHDLError> {
+        for lop in &input.ops {
+            if let OpCode::Binary(Binary {
+                op: AluBinary::Div | AluBinary::Rem,
+                arg1,
+                ..
+            }) = &lop.op
+            {
+                let width = input.kind(*arg1).len();
+                if width > MAX_COMBINATIONAL_DIVIDER_WIDTH {
+                    return Err(Box::new(RHDLSyntaxError {
+                        cause: Syntax::CombinationalDividerTooWide {
+                            width,
+                            max: MAX_COMBINATIONAL_DIVIDER_WIDTH,
+                        },
+                        src: input.symbols.source(),
+                        err_span: input.symbols.span(lop.loc).into(),
+                    })
+                    .into());
+                }
+            }
+        }
+        Ok(input)
+    }
+}
@@ -16,9 +16,11 @@ pub struct DeadCodeEliminationPass {}
 
 impl Pass for DeadCodeEliminationPass {
     fn run(mut input: Object) -> Result<Object, RHDLError> {
-        // Start with an active set containing only the return operand
+        // Start with an active set containing the return operand
+        // and the conditions of any assertions
         let mut active_set: HashSet<Operand> = HashSet::new();
         active_set.insert(input.return_register);
+        active_set.extend(input.assertions.iter().map(|a| a.cond));
         // Get the mapping from operands to opcodes
         let mut alive_ops: Vec<bool> = vec![false; input.ops.len()];
         // Iterate through the ops backwards
@@ -0,0 +1,224 @@
+// Division (and remainder) by a constant does not need a divider.
+// For unsigned values, a power of two divisor is a right shift (or a
+// mask for the remainder), and anything else is replaced by a multiply
+// with a fixed point reciprocal, following Granlund and Montgomery,
+// "Division by Invariant Integers using Multiplication".  With an N bit
+// dividend, and a divisor d with 2^(l-1) < d < 2^l, the multiplier
+//    m = ceil(2^(N+l) / d)
+// fits in N+1 bits, and
+//    floor(x / d) = floor(x * m / 2^(N+l))
+// for every N bit x.  Signed division works on the magnitude of the
+// dividend, and then fixes up the sign of the quotient.  In all cases,
+// the remainder is computed as x - (x / d) * d.
+use num_bigint::{BigInt, BigUint};
+
+use crate::{
+    ast::source::source_location::SourceLocation,
+    bitx::dyn_bit_manip::{from_bigint, from_biguint, to_bigint, to_biguint},
+    rtl::{
+        object::{LocatedOpCode, RegisterKind},
+        spec::{AluBinary, AluUnary, Assign, Binary, Cast, CastKind, Index, Select, Unary},
+        spec::{LiteralId, OpCode, Operand},
+        Object,
+    },
+    types::bit_string::BitString,
+    RHDLError,
+};
+
+use super::{allocate_literal, allocate_register, pass::Pass};
+
+#[derive(Default, Debug, Clone)]
+pub struct LowerDivideByConstant {}
+
+struct Emitter<'a> {
+    input: &'a mut Object,
+    loc: SourceLocation,
+}
+
+impl Emitter<'_> {
+    fn reg(&mut self, kind: RegisterKind) -> Operand {
+        Operand::Register(allocate_register(self.input, kind, self.loc))
+    }
+    fn lit(&mut self, value: BitString) -> Operand {
+        Operand::Literal(allocate_literal(self.input, self.loc, value))
+    }
+    fn unsigned_lit(&mut self, value: &BigUint, len: usize) -> Operand {
+        self.lit(BitString::Unsigned(from_biguint(value, len)))
+    }
+    fn push(&mut self, op: OpCode) {
+        self.input.ops.push(LocatedOpCode { op, loc: self.loc });
+    }
+    fn binary(&mut self, op: AluBinary, lhs: Operand, arg1: Operand, arg2: Operand) {
+        self.push(OpCode::Binary(Binary {
+            op,
+            lhs,
+            arg1,
+            arg2,
+        }));
+    }
+    fn unary(&mut self, op: AluUnary, lhs: Operand, arg1: Operand) {
+        self.push(OpCode::Unary(Unary { op, lhs, arg1 }));
+    }
+    fn resize(&mut self, lhs: Operand, arg: Operand, len: usize) {
+        self.push(OpCode::Cast(Cast {
+            lhs,
+            arg,
+            len,
+            kind: CastKind::Resize,
+        }));
+    }
+    fn select(&mut self, lhs: Operand, cond: Operand, true_value: Operand, false_value: Operand) {
+        self.push(OpCode::Select(Select {
+            lhs,
+            cond,
+            true_value,
+            false_value,
+        }));
+    }
+    // Compute x - q * d, which is the remainder for the quotient q.
+    fn remainder(&mut self, lhs: Operand, x: Operand, q: Operand, d: Operand) {
+        let kind = self.input.kind(lhs);
+        let product = self.reg(kind);
+        self.binary(AluBinary::Mul, product, q, d);
+        self.binary(AluBinary::Sub, lhs, x, product);
+    }
+    fn unsigned_quotient(&mut self, lhs: Operand, x: Operand, divisor: &BigUint) {
+        let len = self.input.kind(x).len();
+        let log2 = divisor.bits() as usize - 1;
+        if divisor.count_ones() == 1 {
+            if log2 == 0 {
+                self.push(OpCode::Assign(Assign { lhs, rhs: x }));
+            } else {
+                let shift_len = (usize::BITS - log2.leading_zeros()) as usize;
+                let shift = self.unsigned_lit(&BigUint::from(log2), shift_len);
+                self.binary(AluBinary::Shr, lhs, x, shift);
+            }
+            return;
+        }
+        let shift = len + log2 + 1;
+        let wide = 2 * len + 1;
+        let multiplier = ((BigUint::from(1_u32) << shift) + divisor - 1_u32) / divisor;
+        let extended = self.reg(RegisterKind::Unsigned(wide));
+        self.resize(extended, x, wide);
+        let multiplier = self.unsigned_lit(&multiplier, wide);
+        let product = self.reg(RegisterKind::Unsigned(wide));
+        self.binary(AluBinary::Mul, product, extended, multiplier);
+        let top = self.reg(RegisterKind::Unsigned(wide - shift));
+        self.push(OpCode::Index(Index {
+            lhs: top,
+            arg: product,
+            bit_range: shift..wide,
+        }));
+        self.resize(lhs, top, len);
+    }
+    fn unsigned(&mut self, op: AluBinary, lhs: Operand, x: Operand, divisor: &BigUint) {
+        let len = self.input.kind(x).len();
+        match op {
+            AluBinary::Div => self.unsigned_quotient(lhs, x, divisor),
+            _ if divisor.count_ones() == 1 => {
+                let mask = self.unsigned_lit(&(divisor - 1_u32), len);
+                self.binary(AluBinary::BitAnd, lhs, x, mask);
+            }
+            _ => {
+                let quotient = self.reg(RegisterKind::Unsigned(len));
+                self.unsigned_quotient(quotient, x, divisor);
+                let divisor = self.unsigned_lit(divisor, len);
+                self.remainder(lhs, x, quotient, divisor);
+            }
+        }
+    }
+    fn signed(&mut self, op: AluBinary, lhs: Operand, x: Operand, divisor: &BigInt) {
+        let len = self.input.kind(x).len();
+        let divisor_lit = self.lit(BitString::Signed(from_bigint(divisor, len)));
+        let zero = self.lit(BitString::Signed(from_bigint(&BigInt::ZERO, len)));
+        if *divisor == BigInt::from(1) {
+            let rhs = if op == AluBinary::Div { x } else { zero };
+            self.push(OpCode::Assign(Assign { lhs, rhs }));
+            return;
+        }
+        // Take the magnitude of the dividend.  The most negative value
+        // is its own negation, but reinterpreted as unsigned, it is
+        // still the correct magnitude.
+        let is_negative = self.reg(RegisterKind::Unsigned(1));
+        self.binary(AluBinary::Lt, is_negative, x, zero);
+        let negated = self.reg(RegisterKind::Signed(len));
+        self.unary(AluUnary::Neg, negated, x);
+        let abs_signed = self.reg(RegisterKind::Signed(len));
+        self.select(abs_signed, is_negative, negated, x);
+        let abs = self.reg(RegisterKind::Unsigned(len));
+        self.unary(AluUnary::Unsigned, abs, abs_signed);
+        let abs_quotient = self.reg(RegisterKind::Unsigned(len));
+        self.unsigned_quotient(abs_quotient, abs, divisor.magnitude());
+        let quotient = self.reg(RegisterKind::Signed(len));
+        self.unary(AluUnary::Signed, quotient, abs_quotient);
+        let neg_quotient = self.reg(RegisterKind::Signed(len));
+        self.unary(AluUnary::Neg, neg_quotient, quotient);
+        let result = if op == AluBinary::Div {
+            lhs
+        } else {
+            self.reg(RegisterKind::Signed(len))
+        };
+        // The quotient is negative if exactly one of the dividend and
+        // divisor is negative.
+        if divisor.sign() == num_bigint::Sign::Minus {
+            self.select(result, is_negative, quotient, neg_quotient);
+        } else {
+            self.select(result, is_negative, neg_quotient, quotient);
+        }
+        if op == AluBinary::Rem {
+            self.remainder(lhs, x, result, divisor_lit);
+        }
+    }
+}
+
+impl LowerDivideByConstant {
+    fn lower(
+        input: &mut Object,
+        op: AluBinary,
+        lhs: Operand,
+        arg1: Operand,
+        lit: LiteralId,
+        loc: SourceLocation,
+    ) -> bool {
+        let divisor = &input.literals[&lit];
+        let signed = divisor.is_signed();
+        let (Some(value), Some(magnitude)) =
+            (to_bigint(divisor.bits()), to_biguint(divisor.bits()))
+        else {
+            return false;
+        };
+        if magnitude == BigUint::ZERO {
+            // Division by zero is handled by the select that guards
+            // the divider, and will be removed as dead code.
+            return false;
+        }
+        let mut emitter = Emitter { input, loc };
+        if signed {
+            emitter.signed(op, lhs, arg1, &value);
+        } else {
+            emitter.unsigned(op, lhs, arg1, &magnitude);
+        }
+        true
+    }
+}
+
+impl Pass for LowerDivideByConstant {
+    fn run(mut input: Object) -> Result<Object, RHDLError> {
+        let ops = std::mem::take(&mut input.ops);
+        for lop in ops {
+            if let OpCode::Binary(Binary {
+                op: op @ (AluBinary::Div | AluBinary::Rem),
+                lhs,
+                arg1: arg1 @ Operand::Register(_),
+                arg2: Operand::Literal(lit),
+            }) = lop.op
+            {
+                if Self::lower(&mut input, op, lhs, arg1, lit, lop.loc) {
+                    continue;
+                }
+            }
+            input.ops.push(lop);
+        }
+        Ok(input)
+    }
+}
@@ -8,9 +8,11 @@ use crate::{
     types::bit_string::BitString,
 };
 
+pub(crate) mod check_divider_width;
 pub(crate) mod check_no_zero_resize;
 pub(crate) mod constant_propagation;
 pub(crate) mod dead_code_elimination;
+pub(crate) mod lower_divide_by_constant;
 pub(crate) mod lower_empty_splice_to_copy;
 pub(crate) mod lower_index_all_to_copy;
 pub(crate) mod lower_multiply_to_shift;
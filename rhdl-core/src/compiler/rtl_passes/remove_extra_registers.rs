@@ -76,6 +76,11 @@ impl Pass for RemoveExtraRegistersPass {
                 } else {
                     input.return_register
                 };
+                for assertion in input.assertions.iter_mut() {
+                    if assertion.cond == assign.lhs {
+                        assertion.cond = assign.rhs;
+                    }
+                }
             }
         }
         Ok(input)
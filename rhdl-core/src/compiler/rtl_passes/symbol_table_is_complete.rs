@@ -22,6 +22,7 @@ impl Pass for SymbolTableIsComplete {
                 .map(|r| Operand::Register(*r)),
         );
         used_set.insert(input.return_register);
+        used_set.extend(input.assertions.iter().map(|a| a.cond));
         for lop in input.ops.iter() {
             remap_operands(lop.op.clone(), |slot| {
                 used_set.insert(slot);
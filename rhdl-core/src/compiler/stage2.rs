@@ -2,8 +2,10 @@ use crate::{
     compiler::{
         lower_rhif_to_rtl::compile_to_rtl,
         rtl_passes::{
-            check_no_zero_resize::CheckNoZeroResize, constant_propagation::ConstantPropagationPass,
+            check_divider_width::CheckDividerWidth, check_no_zero_resize::CheckNoZeroResize,
+            constant_propagation::ConstantPropagationPass,
             dead_code_elimination::DeadCodeEliminationPass,
+            lower_divide_by_constant::LowerDivideByConstant,
             lower_empty_splice_to_copy::LowerEmptySpliceToCopy,
             lower_index_all_to_copy::LowerIndexAllToCopy,
             lower_multiply_to_shift::LowerMultiplyToShift,
@@ -25,6 +27,12 @@ use log::debug;
 
 type Result<T> = std::result::Result<T, RHDLError>;
 
+// Lower to RTL without running any of the passes.  This is only useful
+// as a reference to check the passes against.
+pub(crate) fn lower(object: &crate::rhif::Object) -> Result<rtl::Object> {
+    compile_to_rtl(object)
+}
+
 pub(crate) fn compile(object: &crate::rhif::Object) -> Result<rtl::Object> {
     let mut rtl = compile_to_rtl(object)?;
     let mut hash = rtl.hash_value();
@@ -39,6 +47,7 @@ pub(crate) fn compile(object: &crate::rhif::Object) -> Result<rtl::Object> {
         rtl = LowerSingleConcatToCopy::run(rtl)?;
         rtl = LowerIndexAllToCopy::run(rtl)?;
         rtl = RemoveEmptyFunctionArguments::run(rtl)?;
+        rtl = LowerDivideByConstant::run(rtl)?;
         rtl = LowerMultiplyToShift::run(rtl)?;
         rtl = LowerShiftByConstant::run(rtl)?;
         rtl = LowerShiftsByZeroToCopy::run(rtl)?;
@@ -51,6 +60,7 @@ pub(crate) fn compile(object: &crate::rhif::Object) -> Result<rtl::Object> {
         hash = new_hash;
     }
     rtl = CheckNoZeroResize::run(rtl)?;
+    rtl = CheckDividerWidth::run(rtl)?;
     debug!("{rtl:?}");
     Ok(rtl)
 }
@@ -59,6 +59,26 @@ pub enum RHDLError {
     FlowGraphError(#[from] Box<crate::flow_graph::error::FlowGraphError>),
     #[error("Verilog verification error: {0}")]
     VerilogVerificationErrorString(String),
+    #[error("Verilog verification error: {message}\n{divergence}")]
+    VerilogWaveformMismatch {
+        message: String,
+        divergence: Box<crate::trace::compare::Divergence>,
+    },
+    #[error("VHDL verification error: {0}")]
+    VHDLVerificationErrorString(String),
+    #[error("Flow Graph Verification Error at time {time}: Expected {expected:?} got {actual:?}")]
+    FlowGraphVerificationError {
+        time: u64,
+        expected: TypedBits,
+        actual: TypedBits,
+    },
+    #[error("Compiled Rust Verification Error: Expected {expected:?} got {actual:?}")]
+    RustVerificationError {
+        expected: BitString,
+        actual: BitString,
+    },
+    #[error("Rust code generation error: {0}")]
+    RustCodegenError(String),
     #[error("Testbench Construction Error: {0}")]
     TestbenchConstructionError(String),
     #[error("Circuits with no outputs are not synthesizable")]
@@ -67,6 +87,14 @@ pub enum RHDLError {
     SynError(#[from] syn::Error),
     #[error("Inputs are not covered in exported core:\n{0}")]
     InputsNotCovered(String),
+    #[error("Outputs are not covered in black box core:\n{0}")]
+    OutputsNotCovered(String),
+    #[error("Black box error: {0}")]
+    BlackBoxError(String),
+    #[error("Bounded model check error: {0}")]
+    BoundedModelCheckError(String),
+    #[error("Equivalence check error: {0}")]
+    EquivalenceCheckError(String),
 }
 
 pub fn rhdl_error<T>(error: T) -> RHDLError
@@ -355,6 +355,11 @@ impl<'a> FlowGraphBuilder<'a> {
                 self.fg.edge(*rhs, comp, EdgeKind::ArgBit(0, 0));
                 self.fg.edge(comp, *lhs, EdgeKind::ArgBit(0, 0));
             }
+        } else if unary.op == AluUnary::ReverseBits {
+            // Reversing the bits is just wiring
+            for (lhs, rhs) in lhs.iter().zip(arg1.iter().rev()) {
+                self.fg.edge(*rhs, *lhs, EdgeKind::ArgBit(0, 0));
+            }
         } else {
             let comp = self.fg.new_component(
                 ComponentKind::Unary(Unary {
@@ -53,6 +53,11 @@ pub enum FlowGraphICE {
     ExpectedCaseComponent,
     #[error("Black box nodes are not supported")]
     BlackBoxNotSupported,
+    #[error("Flow graph simulation did not settle")]
+    #[diagnostic(help(
+        "The outputs of the black boxes in the flow graph kept changing without any change to the inputs.  Check for clocks that are derived from the outputs of black boxes"
+    ))]
+    SimulationDidNotSettle,
 }
 
 #[derive(Debug, Error)]
@@ -11,7 +11,8 @@ use crate::{
     ast::source::{source_location::SourceLocation, spanned_source_set::SpannedSourceSet},
     hdl::ast::Module,
     rtl::object::RegisterKind,
-    Circuit, Digital, HDLDescriptor, RHDLError, Synchronous,
+    types::bit_string::BitString,
+    Circuit, Digital, HDLDescriptor, Kind, RHDLError, Synchronous,
 };
 
 use super::{
@@ -30,12 +31,24 @@ pub enum BlackBoxMode {
     Asynchronous,
 }
 
+// How a black box behaves when the flow graph is simulated.  Opaque
+// black boxes drive their outputs with X.
+#[derive(Clone, Hash, Debug, Default)]
+pub enum BlackBoxModel {
+    #[default]
+    Opaque,
+    Dff {
+        reset: BitString,
+    },
+}
+
 #[derive(Clone, Hash)]
 pub struct BlackBox {
     pub inputs: Vec<Vec<FlowIx>>,
     pub outputs: Vec<FlowIx>,
     pub code: HDLDescriptor,
     pub mode: BlackBoxMode,
+    pub model: BlackBoxModel,
 }
 
 #[derive(Clone, Default)]
@@ -133,6 +146,7 @@ impl FlowGraph {
             outputs: outputs.clone(),
             code,
             mode: BlackBoxMode::Asynchronous,
+            model: BlackBoxModel::Opaque,
         });
         self.zip(arg0.clone().into_iter(), inputs.into_iter());
         self.zip(outputs.into_iter(), out.clone().into_iter());
@@ -155,12 +169,27 @@ impl FlowGraph {
             outputs: outputs.clone(),
             code,
             mode: BlackBoxMode::Synchronous,
+            model: BlackBoxModel::Opaque,
         });
         self.zip(arg0.clone().into_iter(), clock_reset.into_iter());
         self.zip(arg1.clone().into_iter(), inputs.into_iter());
         self.zip(outputs.into_iter(), out.clone().into_iter());
         (arg0, arg1, out)
     }
+    // A synchronous black box that holds a positive edge triggered flip flop
+    // with the given reset value.  The model allows the flow graph to be
+    // simulated without the HDL.
+    pub fn dff_black_box<S: Synchronous>(
+        &mut self,
+        code: HDLDescriptor,
+        reset: BitString,
+    ) -> (Vec<FlowIx>, Vec<FlowIx>, Vec<FlowIx>) {
+        let ports = self.synchronous_black_box::<S>(code);
+        if let Some(bb) = self.black_boxes.last_mut() {
+            bb.model = BlackBoxModel::Dff { reset };
+        }
+        ports
+    }
     pub fn new_component(
         &mut self,
         kind: ComponentKind,
@@ -253,6 +282,18 @@ impl FlowGraph {
         file.write_all(&s)?;
         Ok(())
     }
+    // Export as SMT-LIB2.  The arguments are named `|name.in0|`, ...
+    // and the output `|name.out|`.
+    pub fn smt(&self, name: &str) -> Result<String, RHDLError> {
+        let inputs = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(ndx, input)| (format!("in{ndx}"), Kind::make_bits(input.len())))
+            .collect::<Vec<_>>();
+        let output = ("out", Kind::make_bits(self.output.len()));
+        crate::smt::flow_graph::export(self, name, &format!("flow graph {name}"), &inputs, output)
+    }
     pub fn hash_value(&self) -> u64 {
         let mut hasher = FnvHasher::default();
         for node in self.graph.node_indices() {
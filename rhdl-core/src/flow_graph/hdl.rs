@@ -9,9 +9,9 @@ use crate::{
     flow_graph::{component::CaseEntry, edge_kind::EdgeKind, error::FlowGraphError},
     hdl::ast::{
         always, assign, binary, bit_string, case, component_instance, concatenate, connection,
-        constant, declaration, dynamic_index, dynamic_splice, id, index_bit, initial, port, select,
-        unary, unsigned_width, CaseItem, Declaration, Direction, Events, Expression, HDLKind,
-        Module, Statement,
+        constant, declaration, dynamic_index, dynamic_splice, id, index_bit, initial, intrinsic,
+        port, select, unary, unsigned_width, CaseItem, Declaration, Direction, Events, Expression,
+        HDLKind, Module, Statement,
     },
     FlowGraph, RHDLError,
 };
@@ -300,6 +300,11 @@ impl<'a> FlowGraphHDLBuilder<'a> {
             uny.arg_len.len(),
             |x| arg_fun(0, x),
         )?));
+        if uny.op.is_intrinsic() {
+            let expr = intrinsic(uny.op, arg, uny.arg_len.len(), component.width);
+            self.stmt(assign(&node(index), expr));
+            return Ok(());
+        }
         let arg = if uny.arg_len.is_signed() {
             unary(crate::rtl::spec::AluUnary::Signed, arg)
         } else {
@@ -4,6 +4,7 @@ pub mod dot;
 pub mod edge_kind;
 pub mod flow_graph_impl;
 pub mod passes;
+pub mod sim;
 pub use builder::build_rtl_flow_graph;
 pub mod error;
 pub mod flow_cost;
@@ -0,0 +1,473 @@
+// A native simulator for flow graphs.
+//
+// The flow graph is evaluated in topological order using three-valued
+// (0, 1, X) logic.  Bitwise operations follow the usual rules (e.g.,
+// `0 & X = 0`), while arithmetic and comparison operations with any
+// unknown input bit produce an unknown result.  Selects and cases with
+// an unknown control merge their candidate values, so that only the bits
+// that agree remain known.
+//
+// Black boxes break the graph into combinational and sequential parts.
+// Black boxes with a `BlackBoxModel::Dff` model are simulated as positive
+// edge triggered flip flops with a synchronous reset.  All other black
+// boxes are opaque, and drive their outputs with X.
+use petgraph::visit::{EdgeRef, NodeIndexable};
+
+use crate::{
+    ast::source::source_location::SourceLocation,
+    bitx::BitX,
+    error::rhdl_error,
+    hdl::ast::SignedWidth,
+    rtl::{
+        runtime_ops::{binary, unary},
+        spec::{AluBinary, AluUnary},
+    },
+    Kind, RHDLError, TypedBits,
+};
+
+use super::{
+    component::{CaseEntry, ComponentKind},
+    edge_kind::EdgeKind,
+    error::{FlowGraphError, FlowGraphICE},
+    flow_graph_impl::{BlackBox, BlackBoxMode, BlackBoxModel, FlowGraph, FlowIx},
+};
+
+type Result<T> = std::result::Result<T, RHDLError>;
+
+// The maximum number of times the combinational logic is re-evaluated in
+// a single step while waiting for the black box outputs to settle.
+const MAX_SETTLE_ITERATIONS: usize = 64;
+
+fn merge(a: &[BitX], b: &[BitX]) -> Vec<BitX> {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| if a == b { *a } else { BitX::X })
+        .collect()
+}
+
+fn unknown(len: usize) -> Vec<BitX> {
+    vec![BitX::X; len]
+}
+
+fn typed(bits: Vec<BitX>, width: SignedWidth) -> TypedBits {
+    let kind = match width {
+        SignedWidth::Signed(len) => Kind::Signed(len),
+        SignedWidth::Unsigned(len) => Kind::Bits(len),
+    };
+    TypedBits { bits, kind }
+}
+
+// Resize a result to the width of the component that holds it.
+fn fit(mut bits: Vec<BitX>, width: usize) -> Vec<BitX> {
+    bits.resize(width, BitX::Zero);
+    bits
+}
+
+// Offsets that do not fit in a usize are out of range for any argument.
+fn offset(bits: &[BitX]) -> Option<usize> {
+    bits.iter()
+        .enumerate()
+        .try_fold(0_usize, |acc, (ndx, bit)| match bit.to_bool()? {
+            false => Some(acc),
+            true if ndx < usize::BITS as usize => Some(acc | (1 << ndx)),
+            true => Some(usize::MAX),
+        })
+}
+
+// The sources of the incoming edges of a node, grouped by edge kind and
+// indexed by bit, so that they need not be searched for on every step.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Ports {
+    pub(crate) parent: Option<FlowIx>,
+    pub(crate) args: Vec<Vec<Option<FlowIx>>>,
+    pub(crate) selector: Vec<Option<FlowIx>>,
+    pub(crate) splice: Vec<Option<FlowIx>>,
+    pub(crate) offset: Vec<Option<FlowIx>>,
+    pub(crate) true_value: Option<FlowIx>,
+    pub(crate) false_value: Option<FlowIx>,
+}
+
+fn place(ports: &mut Vec<Option<FlowIx>>, bit: usize, src: FlowIx) {
+    if ports.len() <= bit {
+        ports.resize(bit + 1, None);
+    }
+    ports[bit].get_or_insert(src);
+}
+
+impl Ports {
+    pub(crate) fn new(edges: impl Iterator<Item = (EdgeKind, FlowIx)>) -> Self {
+        let mut ports = Self::default();
+        for (kind, src) in edges {
+            ports.parent.get_or_insert(src);
+            match kind {
+                EdgeKind::ArgBit(arg, bit) => {
+                    if ports.args.len() <= arg {
+                        ports.args.resize(arg + 1, vec![]);
+                    }
+                    place(&mut ports.args[arg], bit, src);
+                }
+                EdgeKind::Selector(bit) => place(&mut ports.selector, bit, src),
+                EdgeKind::Splice(bit) => place(&mut ports.splice, bit, src),
+                EdgeKind::DynamicOffset(bit) => place(&mut ports.offset, bit, src),
+                EdgeKind::True => {
+                    ports.true_value.get_or_insert(src);
+                }
+                EdgeKind::False => {
+                    ports.false_value.get_or_insert(src);
+                }
+                EdgeKind::Clock | EdgeKind::Reset | EdgeKind::Virtual => {}
+            }
+        }
+        ports
+    }
+    pub(crate) fn arg(&self, ndx: usize) -> &[Option<FlowIx>] {
+        self.args.get(ndx).map(|x| x.as_slice()).unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DffState {
+    reset_value: Vec<BitX>,
+    clock: BitX,
+    reset: BitX,
+    next: Vec<BitX>,
+    current: Vec<BitX>,
+}
+
+impl DffState {
+    fn new(reset_value: Vec<BitX>) -> Self {
+        Self {
+            clock: BitX::X,
+            reset: BitX::X,
+            next: unknown(reset_value.len()),
+            current: reset_value.clone(),
+            reset_value,
+        }
+    }
+    // The value loaded into the flip flop on a clock edge.
+    fn captured(&self) -> Vec<BitX> {
+        match self.reset {
+            BitX::One => self.reset_value.clone(),
+            BitX::Zero => self.next.clone(),
+            BitX::X => merge(&self.reset_value, &self.next),
+        }
+    }
+    fn update(&mut self, clock: BitX, reset: BitX, data: Vec<BitX>) {
+        match (self.clock, clock) {
+            (BitX::Zero, BitX::One) => self.current = self.captured(),
+            (BitX::Zero, BitX::X) | (BitX::X, BitX::One) => {
+                self.current = merge(&self.current, &self.captured())
+            }
+            _ => {}
+        }
+        if clock != BitX::One {
+            self.next = data;
+            self.reset = reset;
+        }
+        self.clock = clock;
+    }
+}
+
+#[derive(Clone)]
+pub struct FlowGraphSimulator {
+    fg: FlowGraph,
+    order: Vec<FlowIx>,
+    ports: Vec<Ports>,
+    is_input: Vec<bool>,
+    values: Vec<Vec<BitX>>,
+    dffs: Vec<Option<DffState>>,
+}
+
+impl FlowGraphSimulator {
+    pub fn new(fg: FlowGraph) -> Result<Self> {
+        let graph = &fg.graph;
+        let order = petgraph::algo::toposort(graph, None).map_err(|cycle| {
+            let elements = graph[cycle.node_id()]
+                .location
+                .map(|location| fg.code.span(location).into())
+                .into_iter()
+                .collect();
+            rhdl_error(FlowGraphError {
+                cause: FlowGraphICE::LogicLoop,
+                src: fg.code.source(),
+                elements,
+            })
+        })?;
+        let bound = graph.node_bound();
+        let mut ports = vec![Ports::default(); bound];
+        let mut values = vec![vec![]; bound];
+        for node in graph.node_indices() {
+            ports[node.index()] = Ports::new(
+                graph
+                    .edges_directed(node, petgraph::Direction::Incoming)
+                    .map(|edge| (edge.weight().clone(), edge.source())),
+            );
+            values[node.index()] = unknown(graph[node].width.max(1));
+        }
+        let mut is_input = vec![false; bound];
+        for node in fg.inputs.iter().flatten() {
+            is_input[node.index()] = true;
+        }
+        let dffs = fg
+            .black_boxes
+            .iter()
+            .map(|bb| match &bb.model {
+                BlackBoxModel::Dff { reset } if bb.mode == BlackBoxMode::Synchronous => {
+                    Some(DffState::new(reset.bits().to_vec()))
+                }
+                _ => None,
+            })
+            .collect();
+        let mut sim = Self {
+            fg,
+            order,
+            ports,
+            is_input,
+            values,
+            dffs,
+        };
+        sim.drive_black_box_outputs();
+        Ok(sim)
+    }
+    pub fn flow_graph(&self) -> &FlowGraph {
+        &self.fg
+    }
+    // Apply a new set of inputs (one bit vector per flow graph argument,
+    // LSB first), and return the resulting outputs once the black boxes
+    // have settled.
+    pub fn step(&mut self, inputs: &[Vec<BitX>]) -> Result<Vec<BitX>> {
+        assert_eq!(
+            inputs.len(),
+            self.fg.inputs.len(),
+            "flow graph simulation expects one input per argument"
+        );
+        for (nodes, bits) in self.fg.inputs.iter().zip(inputs) {
+            for (ndx, node) in nodes.iter().enumerate() {
+                self.values[node.index()] = vec![bits.get(ndx).copied().unwrap_or(BitX::X)];
+            }
+        }
+        for _ in 0..MAX_SETTLE_ITERATIONS {
+            self.evaluate()?;
+            if !self.update_black_boxes() {
+                return Ok(self.fg.output.iter().map(|node| self.bit(*node)).collect());
+            }
+        }
+        Err(self.raise_ice(FlowGraphICE::SimulationDidNotSettle, None))
+    }
+    fn raise_ice(&self, cause: FlowGraphICE, location: Option<SourceLocation>) -> RHDLError {
+        rhdl_error(FlowGraphError {
+            cause,
+            src: self.fg.code.source(),
+            elements: location
+                .map(|loc| self.fg.code.span(loc).into())
+                .into_iter()
+                .collect(),
+        })
+    }
+    fn bit(&self, node: FlowIx) -> BitX {
+        self.values[node.index()]
+            .first()
+            .copied()
+            .unwrap_or(BitX::X)
+    }
+    fn bits(&self, nodes: &[FlowIx]) -> Vec<BitX> {
+        nodes.iter().map(|node| self.bit(*node)).collect()
+    }
+    // Note that unlike the HDL generator, arguments are collected LSB first.
+    fn argument(&self, node: FlowIx, ports: &[Option<FlowIx>], width: usize) -> Result<Vec<BitX>> {
+        (0..width)
+            .map(|bit| match ports.get(bit).copied().flatten() {
+                Some(src) => Ok(self.bit(src)),
+                None => Err(self.raise_ice(
+                    FlowGraphICE::MissingArgument { bit },
+                    self.fg.graph[node].location,
+                )),
+            })
+            .collect()
+    }
+    fn evaluate(&mut self) -> Result<()> {
+        for ndx in 0..self.order.len() {
+            let node = self.order[ndx];
+            if let Some(value) = self.component(node)? {
+                self.values[node.index()] = value;
+            }
+        }
+        Ok(())
+    }
+    // Returns `None` if the value of the node is not computed from its
+    // inputs (i.e., it is an argument or the output of a black box).
+    fn component(&self, node: FlowIx) -> Result<Option<Vec<BitX>>> {
+        let component = &self.fg.graph[node];
+        let width = component.width;
+        let ports = &self.ports[node.index()];
+        let value = match &component.kind {
+            ComponentKind::Constant(value) => vec![*value; width.max(1)],
+            ComponentKind::BitString(bs) => bs.bits().to_vec(),
+            ComponentKind::Buffer(_) if self.is_input[node.index()] => return Ok(None),
+            ComponentKind::BBOutput(_) => return Ok(None),
+            ComponentKind::Buffer(_) | ComponentKind::BBInput(_) => {
+                vec![ports.parent.map_or(BitX::X, |src| self.bit(src))]
+            }
+            ComponentKind::BitSelect(select) => {
+                let parent = ports.parent.ok_or_else(|| {
+                    self.raise_ice(FlowGraphICE::BufferParentNotFound, component.location)
+                })?;
+                vec![self.values[parent.index()]
+                    .get(select.bit_index)
+                    .copied()
+                    .unwrap_or(BitX::X)]
+            }
+            ComponentKind::Select => {
+                let find = |port: Option<FlowIx>, cause| {
+                    port.ok_or_else(|| self.raise_ice(cause, component.location))
+                };
+                let control = find(
+                    ports.selector.first().copied().flatten(),
+                    FlowGraphICE::SelectControlNodeNotFound,
+                )?;
+                let true_value = find(ports.true_value, FlowGraphICE::SelectTrueNodeNotFound)?;
+                let false_value = find(ports.false_value, FlowGraphICE::SelectFalseNodeNotFound)?;
+                let (true_value, false_value) = (self.bit(true_value), self.bit(false_value));
+                vec![match self.bit(control) {
+                    BitX::One => true_value,
+                    BitX::Zero => false_value,
+                    BitX::X => merge(&[true_value], &[false_value])[0],
+                }]
+            }
+            ComponentKind::Case(kase) => {
+                let discriminant =
+                    self.argument(node, &ports.selector, kase.discriminant_width.len())?;
+                let values = (0..kase.entries.len())
+                    .map(|ndx| Ok(self.argument(node, ports.arg(ndx), 1)?[0]))
+                    .collect::<Result<Vec<_>>>()?;
+                if discriminant.contains(&BitX::X) {
+                    let first = values.first().copied().unwrap_or(BitX::X);
+                    vec![values.iter().fold(first, |acc, x| merge(&[acc], &[*x])[0])]
+                } else {
+                    let matched = kase
+                        .entries
+                        .iter()
+                        .position(|entry| match entry {
+                            CaseEntry::Literal(literal) => literal.bits() == discriminant,
+                            CaseEntry::WildCard => false,
+                        })
+                        .or_else(|| {
+                            kase.entries
+                                .iter()
+                                .position(|entry| matches!(entry, CaseEntry::WildCard))
+                        });
+                    vec![matched.map_or(BitX::X, |ndx| values[ndx])]
+                }
+            }
+            ComponentKind::Binary(bin) => {
+                let left = self.argument(node, ports.arg(0), bin.left_len.len())?;
+                let right = self.argument(node, ports.arg(1), bin.right_len.len())?;
+                match bin.op {
+                    AluBinary::BitAnd => left.iter().zip(&right).map(|(a, b)| *a & *b).collect(),
+                    AluBinary::BitOr => left.iter().zip(&right).map(|(a, b)| *a | *b).collect(),
+                    AluBinary::BitXor => left.iter().zip(&right).map(|(a, b)| *a ^ *b).collect(),
+                    _ if left.contains(&BitX::X) || right.contains(&BitX::X) => unknown(width),
+                    op => {
+                        let result =
+                            binary(op, typed(left, bin.left_len), typed(right, bin.right_len))?;
+                        fit(result.bits, width)
+                    }
+                }
+            }
+            ComponentKind::Unary(uny) => {
+                let arg = self.argument(node, ports.arg(0), uny.arg_len.len())?;
+                match uny.op {
+                    AluUnary::Not => arg.iter().map(|x| !*x).collect(),
+                    AluUnary::Signed | AluUnary::Unsigned | AluUnary::Val => fit(arg, width),
+                    AluUnary::All => vec![arg.iter().fold(BitX::One, |acc, x| acc & *x)],
+                    AluUnary::Any => vec![arg.iter().fold(BitX::Zero, |acc, x| acc | *x)],
+                    AluUnary::Xor => vec![arg.iter().fold(BitX::Zero, |acc, x| acc ^ *x)],
+                    _ if arg.contains(&BitX::X) => unknown(width),
+                    op => fit(unary(op, typed(arg, uny.arg_len))?.bits, width),
+                }
+            }
+            ComponentKind::DynamicIndex(dyn_ndx) => {
+                let base = self.argument(node, ports.arg(0), dyn_ndx.arg_len)?;
+                let offset_bits = self.argument(node, &ports.offset, dyn_ndx.offset_len)?;
+                match offset(&offset_bits) {
+                    Some(offset) => (0..width)
+                        .map(|bit| {
+                            offset
+                                .checked_add(bit)
+                                .and_then(|ndx| base.get(ndx))
+                                .copied()
+                                .unwrap_or(BitX::X)
+                        })
+                        .collect(),
+                    None => unknown(width),
+                }
+            }
+            ComponentKind::DynamicSplice(splice) => {
+                let mut base = self.argument(node, ports.arg(0), width)?;
+                let value = self.argument(node, &ports.splice, splice.splice_len)?;
+                let offset_bits = self.argument(node, &ports.offset, splice.offset_len)?;
+                match offset(&offset_bits) {
+                    Some(offset) => {
+                        for (bit, value) in value.into_iter().enumerate() {
+                            if let Some(target) =
+                                offset.checked_add(bit).and_then(|ndx| base.get_mut(ndx))
+                            {
+                                *target = value;
+                            }
+                        }
+                        base
+                    }
+                    None => unknown(width),
+                }
+            }
+        };
+        Ok(Some(value))
+    }
+    fn drive_black_box_outputs(&mut self) -> bool {
+        let mut changed = false;
+        for (bb, dff) in self.fg.black_boxes.iter().zip(&self.dffs) {
+            for (ndx, node) in bb.outputs.iter().enumerate() {
+                let value = dff
+                    .as_ref()
+                    .and_then(|dff| dff.current.get(ndx))
+                    .copied()
+                    .unwrap_or(BitX::X);
+                if self.values[node.index()] != [value] {
+                    self.values[node.index()] = vec![value];
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+    // Clock the black boxes with the current values of their inputs.
+    // Returns true if any of their outputs changed.
+    fn update_black_boxes(&mut self) -> bool {
+        let inputs = |bb: &BlackBox, ndx: usize| {
+            bb.inputs
+                .get(ndx)
+                .map(|nodes| self.bits(nodes))
+                .unwrap_or_default()
+        };
+        let updates = self
+            .fg
+            .black_boxes
+            .iter()
+            .map(|bb| (inputs(bb, 0), inputs(bb, 1)))
+            .collect::<Vec<_>>();
+        for (dff, (clock_reset, data)) in self.dffs.iter_mut().zip(updates) {
+            if let Some(dff) = dff {
+                let clock = clock_reset.first().copied().unwrap_or(BitX::X);
+                let reset = clock_reset.get(1).copied().unwrap_or(BitX::X);
+                dff.update(clock, reset, data);
+            }
+        }
+        self.drive_black_box_outputs()
+    }
+}
+
+impl FlowGraph {
+    pub fn simulator(&self) -> Result<FlowGraphSimulator> {
+        FlowGraphSimulator::new(self.clone())
+    }
+}
@@ -1,9 +1,9 @@
 use crate::{
-    bitx::BitX, rtl::object::RegisterKind, rtl::spec::AluBinary, rtl::spec::AluUnary,
-    types::bit_string::BitString,
+    ast::ast_impl::AssertKind, bitx::BitX, rtl::object::RegisterKind, rtl::spec::AluBinary,
+    rtl::spec::AluUnary, types::bit_string::BitString, Kind,
 };
 
-use super::formatter;
+use super::{formatter, system_verilog, vhdl};
 
 #[derive(Debug, Clone, Hash, Default)]
 pub struct Module {
@@ -14,12 +14,28 @@ pub struct Module {
     pub statements: Vec<Statement>,
     pub functions: Vec<Function>,
     pub submodules: Vec<Module>,
+    // Verbatim HDL source (e.g., an imported core) that is emitted once
+    // alongside this module, regardless of how many times it is instantiated.
+    pub verbatim: Vec<String>,
+    // The Rust types carried by the named ports and declarations, where
+    // they are known.  The SystemVerilog backend uses these to emit
+    // packed types in place of flat bit vectors.
+    pub kinds: Vec<(String, Kind)>,
+    // The assertions and cover points of the kernel.  Only the
+    // SystemVerilog backend emits these.
+    pub assertions: Vec<Assertion>,
 }
 
 impl Module {
     pub fn as_verilog(&self) -> String {
         formatter::module(self)
     }
+    pub fn as_vhdl(&self) -> String {
+        vhdl::module(self)
+    }
+    pub fn as_system_verilog(&self) -> String {
+        system_verilog::module(self)
+    }
 }
 
 impl std::fmt::Display for Module {
@@ -28,6 +44,18 @@ impl std::fmt::Display for Module {
     }
 }
 
+// assert property (@(posedge clock) disable iff (reset) signal) else $error("message");
+
+#[derive(Debug, Clone, Hash)]
+pub struct Assertion {
+    pub kind: AssertKind,
+    pub message: String,
+    pub signal: Expression,
+    // The clock and reset of a synchronous module.  Without them, the
+    // assertion is checked whenever the signal changes.
+    pub clock_reset: Option<(Expression, Expression)>,
+}
+
 // function {signed} [width-1:0] name(args);
 
 #[derive(Debug, Clone, Hash)]
@@ -44,6 +72,9 @@ impl Function {
     pub fn as_verilog(&self) -> String {
         formatter::function(self)
     }
+    pub fn as_vhdl(&self) -> String {
+        vhdl::function_body(self)
+    }
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -219,6 +250,7 @@ pub enum Expression {
     Identifier(String),
     Literal(BitString),
     Unary(Unary),
+    Intrinsic(Intrinsic),
     Select(Select),
     Binary(Binary),
     Concat(Vec<Expression>),
@@ -514,6 +546,31 @@ pub fn unary(operator: AluUnary, operand: Expression) -> Expression {
     })
 }
 
+// The bit manipulation intrinsics (count_ones, one_hot_to_binary, etc.)
+// have no operator in Verilog.  They are rendered as calls to helper
+// functions, which depend on the widths of the argument and result.
+#[derive(Debug, Clone, Hash)]
+pub struct Intrinsic {
+    pub operator: AluUnary,
+    pub operand: Box<Expression>,
+    pub arg_width: usize,
+    pub width: usize,
+}
+
+pub fn intrinsic(
+    operator: AluUnary,
+    operand: Expression,
+    arg_width: usize,
+    width: usize,
+) -> Expression {
+    Expression::Intrinsic(Intrinsic {
+        operator,
+        operand: Box::new(operand),
+        arg_width,
+        width,
+    })
+}
+
 #[derive(Debug, Clone, Hash)]
 pub struct Binary {
     pub operator: AluBinary,
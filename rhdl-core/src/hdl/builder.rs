@@ -5,7 +5,7 @@ use crate::{
     error::rhdl_error,
     hdl::ast::{
         self, assign, concatenate, constant, declaration, dynamic_index, id, index, index_bit,
-        input_reg, literal, repeat, unary, CaseItem, Function, HDLKind,
+        input_reg, literal, repeat, unary, unsigned_width, CaseItem, Expression, Function, HDLKind,
     },
     rtl::{
         self,
@@ -224,9 +224,17 @@ impl TranslationContext<'_> {
     fn translate_unary(&mut self, unary: &tl::Unary) -> Result<()> {
         let lhs = self.rtl.op_name(unary.lhs);
         let arg1 = self.rtl.op_name(unary.arg1);
-        self.func
-            .block
-            .push(assign(&lhs, ast::unary(unary.op, id(&arg1))));
+        let expr = if unary.op.is_intrinsic() {
+            ast::intrinsic(
+                unary.op,
+                id(&arg1),
+                self.rtl.kind(unary.arg1).len(),
+                self.rtl.kind(unary.lhs).len(),
+            )
+        } else {
+            ast::unary(unary.op, id(&arg1))
+        };
+        self.func.block.push(assign(&lhs, expr));
         Ok(())
     }
     fn translate_comment(&mut self, comment: &str) -> Result<()> {
@@ -254,7 +262,7 @@ impl TranslationContext<'_> {
     fn translate_block(&mut self, block: &[LocatedOpCode]) -> Result<()> {
         block.iter().try_for_each(|lop| self.translate_op(lop))
     }
-    fn translate_kernel_for_object(mut self) -> Result<Function> {
+    fn translate_kernel_for_object(mut self, result: Expression) -> Result<Function> {
         let arg_decls = self
             .rtl
             .arguments
@@ -295,10 +303,7 @@ impl TranslationContext<'_> {
             }
         }
         self.translate_block(&self.rtl.ops)?;
-        self.func.block.push(assign(
-            &self.func.name,
-            id(&self.rtl.op_name(self.rtl.return_register)),
-        ));
+        self.func.block.push(assign(&self.func.name, result));
         Ok(self.func)
     }
 }
@@ -315,9 +320,38 @@ fn translate(object: &crate::rtl::Object) -> Result<Function> {
         },
         rtl: object,
     };
-    context.translate_kernel_for_object()
+    context.translate_kernel_for_object(id(&object.op_name(object.return_register)))
 }
 
 pub fn generate_verilog(object: &crate::rtl::Object) -> Result<Function> {
     translate(object)
 }
+
+// The assertions in a kernel are computed by a second function that takes
+// the same arguments as the kernel, and returns the condition of each
+// assertion as a single bit, with the first assertion in the LSB.
+pub fn generate_assertions(object: &crate::rtl::Object) -> Result<Option<Function>> {
+    if object.assertions.is_empty() {
+        return Ok(None);
+    }
+    let context = TranslationContext {
+        func: Function {
+            name: format!("kernel_{}_assertions", object.name),
+            width: unsigned_width(object.assertions.len()),
+            arguments: vec![],
+            registers: vec![],
+            literals: vec![],
+            block: vec![],
+        },
+        rtl: object,
+    };
+    let conditions = object
+        .assertions
+        .iter()
+        .rev()
+        .map(|assertion| id(&object.op_name(assertion.cond)))
+        .collect();
+    context
+        .translate_kernel_for_object(concatenate(conditions))
+        .map(Some)
+}
@@ -8,7 +8,7 @@ use super::ast::{
     Module, Statement,
 };
 
-fn build_coverage_error(kind: Kind, coverage: &[bool]) -> String {
+pub(crate) fn build_coverage_error(kind: Kind, coverage: &[bool]) -> String {
     let paths = leaf_paths(&kind, Path::default());
     let mut details = String::new();
     for path in paths {
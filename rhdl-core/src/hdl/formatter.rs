@@ -1,3 +1,5 @@
+use std::collections::BTreeSet;
+
 use crate::{
     bitx::{bitx_string, BitX},
     hdl::ast::Events,
@@ -9,8 +11,8 @@ use crate::{
 use super::ast::{
     Always, Assert, Assignment, Binary, Case, CaseItem, ComponentInstance, Connection, Declaration,
     Direction, Display, DynamicIndex, DynamicSplice, Expression, Function, FunctionCall, HDLKind,
-    If, Index, Initial, Literals, Module, Port, Repeat, Select, SignedWidth, Splice, Statement,
-    Unary,
+    If, Index, Initial, Intrinsic, Literals, Module, Port, Repeat, Select, SignedWidth, Splice,
+    Statement, Unary,
 };
 
 const VERILOG_INDENT_INCREASERS: [&str; 4] = ["module", "begin", "function", "case"];
@@ -40,6 +42,10 @@ fn argument(ast: &Declaration) -> String {
 }
 
 fn register(ast: &Declaration) -> String {
+    typed_register(ast, None)
+}
+
+fn typed_register(ast: &Declaration, ty: Option<String>) -> String {
     let alias = ast
         .alias
         .as_ref()
@@ -48,7 +54,7 @@ fn register(ast: &Declaration) -> String {
     format!(
         "{} {} {}; {}",
         kind(&ast.kind),
-        signed_width(&ast.width),
+        ty.unwrap_or_else(|| signed_width(&ast.width)),
         ast.name,
         alias
     )
@@ -62,12 +68,12 @@ fn direction(ast: Direction) -> &'static str {
     }
 }
 
-fn port(ast: &Port) -> String {
+fn typed_port(ast: &Port, ty: Option<String>) -> String {
     format!(
         "{} {} {} {}",
         direction(ast.direction),
         kind(&ast.kind),
-        signed_width(&ast.width),
+        ty.unwrap_or_else(|| signed_width(&ast.width)),
         ast.name
     )
 }
@@ -239,6 +245,8 @@ fn binop(ast: AluBinary) -> &'static str {
         AluBinary::Add => "+",
         AluBinary::Sub => "-",
         AluBinary::Mul => "*",
+        AluBinary::Div => "/",
+        AluBinary::Rem => "%",
         AluBinary::BitAnd => "&",
         AluBinary::BitOr => "|",
         AluBinary::BitXor => "^",
@@ -272,6 +280,7 @@ fn unop(ast: AluUnary) -> &'static str {
         AluUnary::Signed => "$signed",
         AluUnary::Unsigned => "$unsigned",
         AluUnary::Val => "",
+        _ => unreachable!("bit manipulation intrinsics are not unary operators in Verilog"),
     }
 }
 
@@ -279,6 +288,186 @@ fn unary(ast: &Unary) -> String {
     format!("{}({})", unop(ast.operator), expression(&ast.operand))
 }
 
+const INTRINSICS: [&str; 6] = [
+    "count_ones",
+    "leading_zeros",
+    "trailing_zeros",
+    "reverse_bits",
+    "one_hot_to_binary",
+    "binary_to_one_hot",
+];
+
+fn intrinsic_name(op: AluUnary) -> &'static str {
+    match op {
+        AluUnary::CountOnes => INTRINSICS[0],
+        AluUnary::LeadingZeros => INTRINSICS[1],
+        AluUnary::TrailingZeros => INTRINSICS[2],
+        AluUnary::ReverseBits => INTRINSICS[3],
+        AluUnary::OneHotToBinary(_) => INTRINSICS[4],
+        AluUnary::BinaryToOneHot(_) => INTRINSICS[5],
+        _ => unreachable!("{op:?} is not a bit manipulation intrinsic"),
+    }
+}
+
+// An intrinsic is a call to a helper function named for the operation
+// and the widths of the argument and result, e.g., `rhdl_count_ones_8_8`.
+fn intrinsic(ast: &Intrinsic) -> String {
+    format!(
+        "rhdl_{}_{}_{}({})",
+        intrinsic_name(ast.operator),
+        ast.arg_width,
+        ast.width,
+        expression(&ast.operand)
+    )
+}
+
+// The body of the helper function for an intrinsic.  Each is a single
+// loop, so that the size of the generated code does not depend on
+// the width of the argument.
+fn intrinsic_function(name: &str, arg_width: usize, width: usize) -> String {
+    let func = format!("rhdl_{name}_{arg_width}_{width}");
+    let body = match name {
+        "count_ones" => format!(
+            "{func} = 0;\nfor (i = 0; i < {arg_width}; i = i + 1) begin\n{func} = {func} + a[i];\nend"
+        ),
+        "leading_zeros" => format!(
+            "{func} = {arg_width};\nfor (i = 0; i < {arg_width}; i = i + 1) begin\nif (a[i]) {func} = {} - i;\nend",
+            arg_width - 1
+        ),
+        "trailing_zeros" => format!(
+            "{func} = {arg_width};\nfor (i = {}; i >= 0; i = i - 1) begin\nif (a[i]) {func} = i;\nend",
+            arg_width - 1
+        ),
+        "reverse_bits" => format!(
+            "for (i = 0; i < {arg_width}; i = i + 1) begin\n{func}[i] = a[{} - i];\nend",
+            arg_width - 1
+        ),
+        "one_hot_to_binary" => format!(
+            "{func} = 0;\nfor (i = 0; i < {arg_width}; i = i + 1) begin\nif (a[i]) {func} = {func} | i;\nend"
+        ),
+        _ => format!(
+            "{func} = 0;\nfor (i = 0; i < {width}; i = i + 1) begin\nif (a == i) {func}[i] = 1'b1;\nend"
+        ),
+    };
+    format!(
+        "function [{}:0] {func}(input [{}:0] a);\ninteger i;\nbegin\n{body}\nend\nendfunction",
+        width - 1,
+        arg_width - 1
+    )
+}
+
+// An intrinsic called in a module, with the widths of its argument and
+// result.
+type IntrinsicUse = (&'static str, usize, usize);
+
+fn collect_expression_intrinsics(ast: &Expression, found: &mut BTreeSet<IntrinsicUse>) {
+    match ast {
+        Expression::Intrinsic(intrinsic) => {
+            found.insert((
+                intrinsic_name(intrinsic.operator),
+                intrinsic.arg_width,
+                intrinsic.width,
+            ));
+            collect_expression_intrinsics(&intrinsic.operand, found);
+        }
+        Expression::FunctionCall(call) => {
+            for arg in &call.arguments {
+                collect_expression_intrinsics(arg, found);
+            }
+        }
+        Expression::Unary(unary) => collect_expression_intrinsics(&unary.operand, found),
+        Expression::Select(select) => {
+            collect_expression_intrinsics(&select.condition, found);
+            collect_expression_intrinsics(&select.true_expr, found);
+            collect_expression_intrinsics(&select.false_expr, found);
+        }
+        Expression::Binary(binary) => {
+            collect_expression_intrinsics(&binary.left, found);
+            collect_expression_intrinsics(&binary.right, found);
+        }
+        Expression::Concat(elements) => {
+            for element in elements {
+                collect_expression_intrinsics(element, found);
+            }
+        }
+        Expression::DynamicIndex(index) => collect_expression_intrinsics(&index.offset, found),
+        Expression::Repeat(repeat) => collect_expression_intrinsics(&repeat.target, found),
+        Expression::MemoryIndex(index) => collect_expression_intrinsics(&index.address, found),
+        Expression::Identifier(_)
+        | Expression::Literal(_)
+        | Expression::Index(_)
+        | Expression::Const(_) => {}
+    }
+}
+
+fn collect_statement_intrinsics(ast: &Statement, found: &mut BTreeSet<IntrinsicUse>) {
+    match ast {
+        Statement::ContinuousAssignment(assign)
+        | Statement::Assignment(assign)
+        | Statement::NonblockingAssignment(assign) => {
+            collect_expression_intrinsics(&assign.source, found)
+        }
+        Statement::ComponentInstance(instance) => {
+            for connection in &instance.connections {
+                collect_expression_intrinsics(&connection.source, found);
+            }
+        }
+        Statement::DynamicSplice(splice) => {
+            collect_expression_intrinsics(&splice.arg, found);
+            collect_expression_intrinsics(&splice.offset, found);
+            collect_expression_intrinsics(&splice.value, found);
+        }
+        Statement::Splice(splice) => {
+            collect_expression_intrinsics(&splice.source, found);
+            collect_expression_intrinsics(&splice.value, found);
+        }
+        Statement::Initial(initial) => collect_block_intrinsics(&initial.block, found),
+        Statement::Always(always) => collect_block_intrinsics(&always.block, found),
+        Statement::Case(case) => {
+            for (_, statement) in &case.cases {
+                collect_statement_intrinsics(statement, found);
+            }
+            collect_expression_intrinsics(&case.discriminant, found);
+        }
+        Statement::If(if_statement) => {
+            collect_block_intrinsics(&if_statement.true_expr, found);
+            collect_block_intrinsics(&if_statement.false_expr, found);
+            collect_expression_intrinsics(&if_statement.condition, found);
+        }
+        Statement::Display(display) => {
+            for arg in &display.args {
+                collect_expression_intrinsics(arg, found);
+            }
+        }
+        Statement::Assert(assert) => {
+            collect_expression_intrinsics(&assert.left, found);
+            collect_expression_intrinsics(&assert.right, found);
+        }
+        Statement::Delay(_) | Statement::Custom(_) | Statement::Finish | Statement::Comment(_) => {}
+    }
+}
+
+fn collect_block_intrinsics(ast: &[Statement], found: &mut BTreeSet<IntrinsicUse>) {
+    for statement in ast {
+        collect_statement_intrinsics(statement, found);
+    }
+}
+
+// The definitions of the helper functions for the intrinsics called in
+// a module (once for each set of widths).
+fn intrinsic_functions(ast: &Module) -> String {
+    let mut found = BTreeSet::new();
+    collect_block_intrinsics(&ast.statements, &mut found);
+    for function in &ast.functions {
+        collect_block_intrinsics(&function.block, &mut found);
+    }
+    found
+        .into_iter()
+        .map(|(name, arg_width, width)| intrinsic_function(name, arg_width, width))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 fn select(ast: &Select) -> String {
     format!(
         "({}) ? ({}) : ({})",
@@ -324,10 +513,11 @@ fn repeat(ast: &Repeat) -> String {
     )
 }
 
-fn expression(ast: &Expression) -> String {
+pub(crate) fn expression(ast: &Expression) -> String {
     match ast {
         Expression::Binary(ast) => binary(ast),
         Expression::Unary(ast) => unary(ast),
+        Expression::Intrinsic(ast) => intrinsic(ast),
         Expression::Literal(ast) => bit_string(ast),
         Expression::Identifier(ast) => ast.clone(),
         Expression::Select(ast) => select(ast),
@@ -389,20 +579,62 @@ pub fn function(ast: &Function) -> String {
     format!("{header}\n{registers}\n{literals}\nbegin\n{statements}\nend\nendfunction",)
 }
 
+// Collect the verbatim sources of a module tree, dropping duplicates
+// so that cores instantiated more than once are only defined once.
+pub(crate) fn collect_verbatim<'a>(ast: &'a Module, sources: &mut Vec<&'a str>) {
+    for source in &ast.verbatim {
+        if !sources.contains(&source.as_str()) {
+            sources.push(source);
+        }
+    }
+    for submodule in &ast.submodules {
+        collect_verbatim(submodule, sources);
+    }
+}
+
 pub fn module(ast: &Module) -> String {
+    let mut sources = vec![];
+    collect_verbatim(ast, &mut sources);
+    let mut result = module_body(ast, "", &|_, _| None, &|_| String::new());
+    for source in sources {
+        result.push_str(source);
+        if !source.ends_with('\n') {
+            result.push('\n');
+        }
+    }
+    result
+}
+
+// Render a module (and its submodules).  The `header` is inserted between
+// the module name and the port list (e.g., a package import), `ty`
+// can supply a named type for a port or declaration in place of the
+// flat bit vector, and `trailer` is inserted at the end of the module
+// body (e.g., assertions).
+pub(crate) fn module_body(
+    ast: &Module,
+    header: &str,
+    ty: &dyn Fn(&Module, &str) -> Option<String>,
+    trailer: &dyn Fn(&Module) -> String,
+) -> String {
     let name = &ast.name;
     let description = &ast.description;
-    let ports = apply(&ast.ports, port, ", ");
-    let declarations = apply(&ast.declarations, register, "\n");
+    let ports = apply(&ast.ports, |x| typed_port(x, ty(ast, &x.name)), ", ");
+    let declarations = apply(
+        &ast.declarations,
+        |x| typed_register(x, ty(ast, &x.name)),
+        "\n",
+    );
     let statements = apply(&ast.statements, statement, "\n");
     let functions = apply(&ast.functions, function, "\n");
+    let intrinsics = intrinsic_functions(ast);
     let sub_modules = ast
         .submodules
         .iter()
-        .map(module)
+        .map(|x| module_body(x, header, ty, trailer))
         .collect::<Vec<_>>()
         .join("\n");
+    let trailer = trailer(ast);
     reformat_verilog(&format!(
-        "// {description}\nmodule {name}({ports});\n{declarations}\n{statements}\n{functions}\nendmodule\n{sub_modules}\n",
+        "// {description}\nmodule {name}{header}({ports});\n{declarations}\n{statements}\n{intrinsics}\n{functions}\n{trailer}\nendmodule\n{sub_modules}\n",
     ))
 }
@@ -2,3 +2,5 @@ pub mod ast;
 pub mod builder;
 pub mod export;
 pub mod formatter;
+pub mod system_verilog;
+pub mod vhdl;
@@ -0,0 +1,333 @@
+-- Support package for RHDL generated VHDL.  These functions
+-- provide the Verilog semantics that have no direct VHDL equivalent.
+library ieee;
+use ieee.std_logic_1164.all;
+use ieee.numeric_std.all;
+
+package rhdl_support is
+    function rhdl_resize(v : std_logic_vector; n : natural) return std_logic_vector;
+    function rhdl_sresize(v : std_logic_vector; n : natural) return std_logic_vector;
+    function rhdl_true(v : std_logic_vector) return boolean;
+    function rhdl_bool(b : boolean) return std_logic_vector;
+    function rhdl_select(c : std_logic_vector; t : std_logic_vector; f : std_logic_vector) return std_logic_vector;
+    function rhdl_all(v : std_logic_vector) return std_logic_vector;
+    function rhdl_any(v : std_logic_vector) return std_logic_vector;
+    function rhdl_xor(v : std_logic_vector) return std_logic_vector;
+    function rhdl_neg(v : std_logic_vector) return std_logic_vector;
+    function rhdl_mul(a : std_logic_vector; b : std_logic_vector) return std_logic_vector;
+    function rhdl_div(a : std_logic_vector; b : std_logic_vector) return std_logic_vector;
+    function rhdl_rem(a : std_logic_vector; b : std_logic_vector) return std_logic_vector;
+    function rhdl_sdiv(a : std_logic_vector; b : std_logic_vector) return std_logic_vector;
+    function rhdl_srem(a : std_logic_vector; b : std_logic_vector) return std_logic_vector;
+    function rhdl_shl(v : std_logic_vector; s : std_logic_vector) return std_logic_vector;
+    function rhdl_shr(v : std_logic_vector; s : std_logic_vector) return std_logic_vector;
+    function rhdl_sshr(v : std_logic_vector; s : std_logic_vector) return std_logic_vector;
+    function rhdl_repeat(v : std_logic_vector; n : natural) return std_logic_vector;
+    function rhdl_dyn_index(v : std_logic_vector; offset : std_logic_vector; len : natural) return std_logic_vector;
+    function rhdl_dyn_splice(v : std_logic_vector; offset : std_logic_vector; value : std_logic_vector) return std_logic_vector;
+    function rhdl_count_ones(v : std_logic_vector) return std_logic_vector;
+    function rhdl_leading_zeros(v : std_logic_vector) return std_logic_vector;
+    function rhdl_trailing_zeros(v : std_logic_vector) return std_logic_vector;
+    function rhdl_reverse_bits(v : std_logic_vector) return std_logic_vector;
+    function rhdl_one_hot_to_binary(v : std_logic_vector; n : natural) return std_logic_vector;
+    function rhdl_binary_to_one_hot(v : std_logic_vector; n : natural) return std_logic_vector;
+    procedure rhdl_display(msg : string);
+end package rhdl_support;
+
+package body rhdl_support is
+    constant RHDL_MAX_INDEX : natural := 2 ** 30;
+
+    -- Saturating conversion of an unsigned value to a natural.
+    function rhdl_natural(v : std_logic_vector) return natural is
+        variable a : std_logic_vector(v'length - 1 downto 0) := v;
+        variable result : natural := 0;
+    begin
+        for ndx in a'high downto 0 loop
+            if result >= RHDL_MAX_INDEX then
+                return RHDL_MAX_INDEX;
+            end if;
+            result := result * 2;
+            if a(ndx) = '1' then
+                result := result + 1;
+            end if;
+        end loop;
+        return result;
+    end function;
+
+    function rhdl_resize(v : std_logic_vector; n : natural) return std_logic_vector is
+        variable a : std_logic_vector(v'length - 1 downto 0) := v;
+        variable result : std_logic_vector(n - 1 downto 0) := (others => '0');
+    begin
+        for ndx in 0 to n - 1 loop
+            if ndx < a'length then
+                result(ndx) := a(ndx);
+            end if;
+        end loop;
+        return result;
+    end function;
+
+    function rhdl_sresize(v : std_logic_vector; n : natural) return std_logic_vector is
+        variable a : std_logic_vector(v'length - 1 downto 0) := v;
+        variable result : std_logic_vector(n - 1 downto 0) := (others => '0');
+    begin
+        for ndx in 0 to n - 1 loop
+            if ndx < a'length then
+                result(ndx) := a(ndx);
+            elsif a'length > 0 then
+                result(ndx) := a(a'high);
+            end if;
+        end loop;
+        return result;
+    end function;
+
+    function rhdl_true(v : std_logic_vector) return boolean is
+    begin
+        for ndx in v'range loop
+            if v(ndx) = '1' then
+                return true;
+            end if;
+        end loop;
+        return false;
+    end function;
+
+    function rhdl_bool(b : boolean) return std_logic_vector is
+    begin
+        if b then
+            return "1";
+        end if;
+        return "0";
+    end function;
+
+    function rhdl_select(c : std_logic_vector; t : std_logic_vector; f : std_logic_vector) return std_logic_vector is
+    begin
+        if rhdl_true(c) then
+            return t;
+        end if;
+        return f;
+    end function;
+
+    function rhdl_all(v : std_logic_vector) return std_logic_vector is
+        variable result : std_logic := '1';
+    begin
+        for ndx in v'range loop
+            result := result and v(ndx);
+        end loop;
+        return (0 => result);
+    end function;
+
+    function rhdl_any(v : std_logic_vector) return std_logic_vector is
+        variable result : std_logic := '0';
+    begin
+        for ndx in v'range loop
+            result := result or v(ndx);
+        end loop;
+        return (0 => result);
+    end function;
+
+    function rhdl_xor(v : std_logic_vector) return std_logic_vector is
+        variable result : std_logic := '0';
+    begin
+        for ndx in v'range loop
+            result := result xor v(ndx);
+        end loop;
+        return (0 => result);
+    end function;
+
+    function rhdl_neg(v : std_logic_vector) return std_logic_vector is
+    begin
+        return std_logic_vector(unsigned(not v) + 1);
+    end function;
+
+    function rhdl_mul(a : std_logic_vector; b : std_logic_vector) return std_logic_vector is
+    begin
+        return std_logic_vector(resize(unsigned(a) * unsigned(b), a'length));
+    end function;
+
+    -- Division by zero gives all ones, and the remainder is the
+    -- dividend, to match the RHDL semantics.
+    function rhdl_div(a : std_logic_vector; b : std_logic_vector) return std_logic_vector is
+        variable ones : std_logic_vector(a'length - 1 downto 0) := (others => '1');
+    begin
+        if unsigned(b) = 0 then
+            return ones;
+        end if;
+        return std_logic_vector(unsigned(a) / unsigned(b));
+    end function;
+
+    function rhdl_rem(a : std_logic_vector; b : std_logic_vector) return std_logic_vector is
+    begin
+        if unsigned(b) = 0 then
+            return a;
+        end if;
+        return std_logic_vector(unsigned(a) rem unsigned(b));
+    end function;
+
+    function rhdl_sdiv(a : std_logic_vector; b : std_logic_vector) return std_logic_vector is
+        variable ones : std_logic_vector(a'length - 1 downto 0) := (others => '1');
+    begin
+        if signed(b) = 0 then
+            return ones;
+        end if;
+        return std_logic_vector(resize(signed(a) / signed(b), a'length));
+    end function;
+
+    function rhdl_srem(a : std_logic_vector; b : std_logic_vector) return std_logic_vector is
+    begin
+        if signed(b) = 0 then
+            return a;
+        end if;
+        return std_logic_vector(signed(a) rem signed(b));
+    end function;
+
+    function rhdl_shl(v : std_logic_vector; s : std_logic_vector) return std_logic_vector is
+        variable unknown : std_logic_vector(v'length - 1 downto 0) := (others => 'X');
+    begin
+        if is_x(s) then
+            return unknown;
+        end if;
+        return std_logic_vector(shift_left(unsigned(v), rhdl_natural(s)));
+    end function;
+
+    function rhdl_shr(v : std_logic_vector; s : std_logic_vector) return std_logic_vector is
+        variable unknown : std_logic_vector(v'length - 1 downto 0) := (others => 'X');
+    begin
+        if is_x(s) then
+            return unknown;
+        end if;
+        return std_logic_vector(shift_right(unsigned(v), rhdl_natural(s)));
+    end function;
+
+    function rhdl_sshr(v : std_logic_vector; s : std_logic_vector) return std_logic_vector is
+        variable unknown : std_logic_vector(v'length - 1 downto 0) := (others => 'X');
+    begin
+        if is_x(s) then
+            return unknown;
+        end if;
+        return std_logic_vector(shift_right(signed(v), rhdl_natural(s)));
+    end function;
+
+    function rhdl_repeat(v : std_logic_vector; n : natural) return std_logic_vector is
+        variable a : std_logic_vector(v'length - 1 downto 0) := v;
+        variable result : std_logic_vector(v'length * n - 1 downto 0);
+    begin
+        for ndx in 0 to n - 1 loop
+            result((ndx + 1) * a'length - 1 downto ndx * a'length) := a;
+        end loop;
+        return result;
+    end function;
+
+    function rhdl_dyn_index(v : std_logic_vector; offset : std_logic_vector; len : natural) return std_logic_vector is
+        variable a : std_logic_vector(v'length - 1 downto 0) := v;
+        variable result : std_logic_vector(len - 1 downto 0) := (others => 'X');
+        variable base : natural;
+    begin
+        if is_x(offset) then
+            return result;
+        end if;
+        base := rhdl_natural(offset);
+        for ndx in 0 to len - 1 loop
+            if base + ndx < a'length then
+                result(ndx) := a(base + ndx);
+            end if;
+        end loop;
+        return result;
+    end function;
+
+    function rhdl_dyn_splice(v : std_logic_vector; offset : std_logic_vector; value : std_logic_vector) return std_logic_vector is
+        variable result : std_logic_vector(v'length - 1 downto 0) := v;
+        variable a : std_logic_vector(value'length - 1 downto 0) := value;
+        variable base : natural;
+    begin
+        if is_x(offset) then
+            return rhdl_sresize("X", result'length);
+        end if;
+        base := rhdl_natural(offset);
+        for ndx in 0 to a'length - 1 loop
+            if base + ndx < result'length then
+                result(base + ndx) := a(ndx);
+            end if;
+        end loop;
+        return result;
+    end function;
+
+    -- The counts are returned with the width of the argument.
+    function rhdl_count_ones(v : std_logic_vector) return std_logic_vector is
+        variable a : std_logic_vector(v'length - 1 downto 0) := v;
+        variable result : natural := 0;
+    begin
+        for ndx in 0 to a'high loop
+            if a(ndx) = '1' then
+                result := result + 1;
+            end if;
+        end loop;
+        return std_logic_vector(to_unsigned(result, a'length));
+    end function;
+
+    function rhdl_leading_zeros(v : std_logic_vector) return std_logic_vector is
+        variable a : std_logic_vector(v'length - 1 downto 0) := v;
+        variable result : natural := a'length;
+    begin
+        for ndx in 0 to a'high loop
+            if a(ndx) = '1' then
+                result := a'high - ndx;
+            end if;
+        end loop;
+        return std_logic_vector(to_unsigned(result, a'length));
+    end function;
+
+    function rhdl_trailing_zeros(v : std_logic_vector) return std_logic_vector is
+        variable a : std_logic_vector(v'length - 1 downto 0) := v;
+        variable result : natural := a'length;
+    begin
+        for ndx in a'high downto 0 loop
+            if a(ndx) = '1' then
+                result := ndx;
+            end if;
+        end loop;
+        return std_logic_vector(to_unsigned(result, a'length));
+    end function;
+
+    function rhdl_reverse_bits(v : std_logic_vector) return std_logic_vector is
+        variable a : std_logic_vector(v'length - 1 downto 0) := v;
+        variable result : std_logic_vector(v'length - 1 downto 0);
+    begin
+        for ndx in 0 to a'high loop
+            result(ndx) := a(a'high - ndx);
+        end loop;
+        return result;
+    end function;
+
+    function rhdl_one_hot_to_binary(v : std_logic_vector; n : natural) return std_logic_vector is
+        variable a : std_logic_vector(v'length - 1 downto 0) := v;
+        variable result : unsigned(n - 1 downto 0) := (others => '0');
+    begin
+        for ndx in 0 to a'high loop
+            if a(ndx) = '1' then
+                result := result or resize(to_unsigned(ndx, a'length + 1), n);
+            end if;
+        end loop;
+        return std_logic_vector(result);
+    end function;
+
+    -- An index that is out of range gives zero.
+    function rhdl_binary_to_one_hot(v : std_logic_vector; n : natural) return std_logic_vector is
+        variable result : std_logic_vector(n - 1 downto 0) := (others => '0');
+        variable ndx : natural;
+    begin
+        if is_x(v) then
+            return rhdl_sresize("X", n);
+        end if;
+        ndx := rhdl_natural(v);
+        if ndx < n then
+            result(ndx) := '1';
+        end if;
+        return result;
+    end function;
+
+    procedure rhdl_display(msg : string) is
+        variable l : std.textio.line;
+    begin
+        std.textio.write(l, msg);
+        std.textio.writeline(std.textio.output, l);
+    end procedure;
+end package body rhdl_support;
@@ -0,0 +1,463 @@
+// Render the HDL AST as SystemVerilog.
+//
+// The generated code is the same as the Verilog backend, except that ports
+// and signals whose Rust type is known (see `Module::kinds`) are declared
+// using packed types.  These are the ports of each module and the internal
+// wires built by the circuit backends (such as `od`, `d` and `q`, or the
+// `pipe` of a chain).  The registers inside kernel functions only carry a
+// width, and remain flat bit vectors.  The types are derived from the `Kind`
+// of each signal, and are collected into a package that is emitted ahead of
+// the modules.  Because packed types are laid out exactly like the flat bit
+// vectors, the bodies of the modules do not change:
+//
+//  - Structs and tuples become `struct packed`, with the first field
+//    declared last, so that it occupies the least significant bits.
+//  - Arrays become packed arrays with element 0 in the least significant
+//    position.
+//  - Enums become a `struct packed` holding a discriminant (an `enum`
+//    with the width and signedness of the `DiscriminantLayout`) and, if
+//    any variant carries data, a `union packed` of the per-variant
+//    payloads.  The alignment of the layout determines if the
+//    discriminant sits above or below the payload.
+use std::collections::HashSet;
+
+use crate::{
+    ast::ast_impl::AssertKind,
+    types::kind::{DiscriminantAlignment, DiscriminantType, Enum, Field},
+    Kind,
+};
+
+use super::{ast::Module, formatter};
+
+const RESERVED: [&str; 104] = [
+    "alias",
+    "always",
+    "always_comb",
+    "always_ff",
+    "always_latch",
+    "and",
+    "assert",
+    "assign",
+    "assume",
+    "automatic",
+    "begin",
+    "bit",
+    "break",
+    "buf",
+    "byte",
+    "case",
+    "casex",
+    "casez",
+    "cell",
+    "chandle",
+    "class",
+    "config",
+    "const",
+    "constraint",
+    "continue",
+    "cover",
+    "default",
+    "defparam",
+    "design",
+    "disable",
+    "do",
+    "edge",
+    "else",
+    "end",
+    "endcase",
+    "endfunction",
+    "endmodule",
+    "endpackage",
+    "enum",
+    "event",
+    "export",
+    "extern",
+    "final",
+    "for",
+    "force",
+    "foreach",
+    "forever",
+    "fork",
+    "function",
+    "generate",
+    "genvar",
+    "if",
+    "import",
+    "initial",
+    "inout",
+    "input",
+    "int",
+    "integer",
+    "interface",
+    "join",
+    "local",
+    "localparam",
+    "logic",
+    "longint",
+    "module",
+    "nand",
+    "negedge",
+    "nor",
+    "not",
+    "null",
+    "or",
+    "output",
+    "package",
+    "packed",
+    "parameter",
+    "posedge",
+    "priority",
+    "property",
+    "real",
+    "reg",
+    "repeat",
+    "return",
+    "sequence",
+    "shortint",
+    "signed",
+    "static",
+    "string",
+    "struct",
+    "super",
+    "task",
+    "this",
+    "time",
+    "type",
+    "typedef",
+    "union",
+    "unique",
+    "unsigned",
+    "var",
+    "virtual",
+    "void",
+    "wait",
+    "while",
+    "wire",
+    "xor",
+];
+
+// SystemVerilog identifiers must start with a letter or underscore, and
+// may not be keywords.  Anything else is written as an escaped identifier.
+fn ident(name: &str) -> String {
+    let legal = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !RESERVED.contains(&name);
+    if legal {
+        name.to_string()
+    } else {
+        format!("\\{name} ")
+    }
+}
+
+// The fields of tuples and tuple structs are numbered.
+fn field_name(name: &str) -> String {
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        ident(name)
+    }
+}
+
+// Rust type names are fully qualified, and may carry generic arguments.
+// Only the bare name is used for the SystemVerilog type.
+fn base_name(name: &str) -> String {
+    let name = name.split('<').next().unwrap_or_default();
+    let name = name.rsplit("::").next().unwrap_or_default();
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn width(bits: usize) -> String {
+    format!("[{}:0]", bits.saturating_sub(1))
+}
+
+// A SystemVerilog type is a base type followed by zero or more
+// packed dimensions, e.g., `logic signed [3:0][7:0]`.
+struct SvType {
+    base: String,
+    dims: Vec<String>,
+}
+
+impl std::fmt::Display for SvType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.base)?;
+        if !self.dims.is_empty() {
+            write!(f, " {}", self.dims.join(""))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Package {
+    names: HashSet<String>,
+    types: Vec<(Kind, String)>,
+    typedefs: Vec<String>,
+}
+
+impl Package {
+    fn unique_name(&mut self, name: &str) -> String {
+        let mut candidate = name.to_string();
+        let mut ndx = 1;
+        while self.names.contains(&candidate) || RESERVED.contains(&candidate.as_str()) {
+            candidate = format!("{name}_{ndx}");
+            ndx += 1;
+        }
+        self.names.insert(candidate.clone());
+        candidate
+    }
+    fn lookup(&self, kind: &Kind) -> Option<String> {
+        self.types
+            .iter()
+            .find(|(k, _)| k == kind)
+            .map(|(_, name)| name.clone())
+    }
+    // Returns `None` for kinds that occupy no bits.
+    fn sv_type(&mut self, kind: &Kind) -> Option<SvType> {
+        if kind.bits() == 0 {
+            return None;
+        }
+        match kind {
+            Kind::Bits(1) => Some(SvType {
+                base: "logic".into(),
+                dims: vec![],
+            }),
+            Kind::Bits(n) => Some(SvType {
+                base: "logic".into(),
+                dims: vec![width(*n)],
+            }),
+            Kind::Signed(n) => Some(SvType {
+                base: "logic signed".into(),
+                dims: vec![width(*n)],
+            }),
+            Kind::Signal(kind, _) => self.sv_type(kind),
+            Kind::Array(array) => {
+                let mut base = self.sv_type(&array.base)?;
+                base.dims.insert(0, width(array.size));
+                Some(base)
+            }
+            Kind::Tuple(_) | Kind::Struct(_) | Kind::Enum(_) => Some(SvType {
+                base: self.named_type(kind),
+                dims: vec![],
+            }),
+            Kind::Empty => None,
+        }
+    }
+    fn named_type(&mut self, kind: &Kind) -> String {
+        if let Some(name) = self.lookup(kind) {
+            return name;
+        }
+        let name = match kind {
+            Kind::Tuple(tuple) => {
+                let name = self.unique_name(&format!("tuple{}_t", tuple.elements.len()));
+                let fields = tuple
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(|(ndx, kind)| Kind::make_field(&ndx.to_string(), *kind))
+                    .collect::<Vec<_>>();
+                let members = self.members(&fields);
+                self.push_struct(&name, members);
+                name
+            }
+            Kind::Struct(strukt) => {
+                let name = self.unique_name(&format!("{}_t", base_name(&strukt.name)));
+                let members = self.members(&strukt.fields);
+                self.push_struct(&name, members);
+                name
+            }
+            Kind::Enum(enum_kind) => self.enum_type(enum_kind),
+            _ => unreachable!("only tuples, structs and enums are named"),
+        };
+        self.types.push((*kind, name.clone()));
+        name
+    }
+    // Member declarations, in field order.  Fields without bits are dropped.
+    fn members(&mut self, fields: &[Field]) -> Vec<String> {
+        fields
+            .iter()
+            .filter_map(|field| {
+                self.sv_type(&field.kind)
+                    .map(|ty| format!("{ty} {};", field_name(&field.name)))
+            })
+            .collect()
+    }
+    // Members are listed from least to most significant, but SystemVerilog
+    // places the first member of a packed struct in the most significant bits.
+    fn push_struct(&mut self, name: &str, members: Vec<String>) {
+        let members = members
+            .into_iter()
+            .rev()
+            .map(|x| format!("    {x}\n"))
+            .collect::<String>();
+        self.typedefs
+            .push(format!("typedef struct packed {{\n{members}}} {name};"));
+    }
+    fn enum_type(&mut self, kind: &Enum) -> String {
+        let base = base_name(&kind.name);
+        let layout = kind.discriminant_layout;
+        let payload_bits = kind
+            .variants
+            .iter()
+            .map(|x| x.kind.bits())
+            .max()
+            .unwrap_or_default();
+        let mut members = vec![];
+        if layout.width != 0 {
+            let discriminant = self.unique_name(&format!("{base}_discriminant_t"));
+            let signed = match layout.ty {
+                DiscriminantType::Signed => " signed",
+                DiscriminantType::Unsigned => "",
+            };
+            let marker = match layout.ty {
+                DiscriminantType::Signed => "s",
+                DiscriminantType::Unsigned => "",
+            };
+            let variants = kind
+                .variants
+                .iter()
+                .map(|variant| {
+                    let name = self.unique_name(&format!("{base}_{}", variant.name));
+                    let bits = (0..layout.width)
+                        .rev()
+                        .map(|ndx| {
+                            if ndx < 64 && (variant.discriminant >> ndx) & 1 == 1
+                                || ndx >= 64 && variant.discriminant < 0
+                            {
+                                '1'
+                            } else {
+                                '0'
+                            }
+                        })
+                        .collect::<String>();
+                    format!("    {name} = {}'{marker}b{bits}", layout.width)
+                })
+                .collect::<Vec<_>>()
+                .join(",\n");
+            self.typedefs.push(format!(
+                "typedef enum logic{signed} {} {{\n{variants}\n}} {discriminant};",
+                width(layout.width)
+            ));
+            members.push(format!("{discriminant} discriminant;"));
+        }
+        if payload_bits != 0 {
+            // Each variant payload is padded (in the most significant bits)
+            // to the size of the largest one, as required for a packed union.
+            let payloads = kind
+                .variants
+                .iter()
+                .filter(|variant| variant.kind.bits() != 0)
+                .map(|variant| {
+                    let name = self.unique_name(&format!("{base}_{}_t", variant.name));
+                    let fields = match &variant.kind {
+                        Kind::Struct(strukt) => strukt.fields.clone(),
+                        Kind::Tuple(tuple) => tuple
+                            .elements
+                            .iter()
+                            .enumerate()
+                            .map(|(ndx, kind)| Kind::make_field(&ndx.to_string(), *kind))
+                            .collect(),
+                        kind => vec![Kind::make_field("value", *kind)],
+                    };
+                    let mut members = self.members(&fields);
+                    let pad = payload_bits - variant.kind.bits();
+                    if pad != 0 {
+                        members.push(format!("logic {} pad;", width(pad)));
+                    }
+                    self.push_struct(&name, members);
+                    format!("    {name} {};\n", field_name(&variant.name))
+                })
+                .collect::<String>();
+            let payload = self.unique_name(&format!("{base}_payload_t"));
+            self.typedefs
+                .push(format!("typedef union packed {{\n{payloads}}} {payload};"));
+            members.push(format!("{payload} payload;"));
+        }
+        if layout.alignment == DiscriminantAlignment::Msb {
+            members.reverse();
+        }
+        let name = self.unique_name(&format!("{base}_t"));
+        self.push_struct(&name, members);
+        name
+    }
+}
+
+// Plain bit vectors are left as they are in the Verilog output, so
+// only the composite kinds are given named types.
+fn collect_kinds(ast: &Module, package: &mut Package, signals: &mut Vec<(Kind, String)>) {
+    for (_, kind) in &ast.kinds {
+        if kind.signal_data().is_composite() && !signals.iter().any(|(k, _)| k == kind) {
+            if let Some(ty) = package.sv_type(kind) {
+                signals.push((*kind, ty.to_string()));
+            }
+        }
+    }
+    for submodule in &ast.submodules {
+        collect_kinds(submodule, package, signals);
+    }
+}
+
+// Assertions in a synchronous module are concurrent assertions on its
+// clock, and are disabled during reset.  Otherwise, they are immediate
+// assertions in an `always_comb` block.
+fn assertions(ast: &Module) -> String {
+    ast.assertions
+        .iter()
+        .map(|assertion| {
+            let signal = formatter::expression(&assertion.signal);
+            let message = if assertion.message.is_empty() {
+                format!("assertion failed in {}", ast.name)
+            } else {
+                assertion.message.replace('\\', "\\\\").replace('"', "\\\"")
+            };
+            let clocking = assertion.clock_reset.as_ref().map(|(clock, reset)| {
+                format!(
+                    "@(posedge {}) disable iff ({})",
+                    formatter::expression(clock),
+                    formatter::expression(reset)
+                )
+            });
+            match (assertion.kind, clocking) {
+                (AssertKind::Assert, Some(clocking)) => {
+                    format!("assert property ({clocking} {signal}) else $error(\"{message}\");")
+                }
+                (AssertKind::Cover, Some(clocking)) => {
+                    format!("cover property ({clocking} {signal});")
+                }
+                (AssertKind::Assert, None) => {
+                    format!("always_comb assert ({signal}) else $error(\"{message}\");")
+                }
+                (AssertKind::Cover, None) => format!("always_comb cover ({signal});"),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn module(ast: &Module) -> String {
+    let mut package = Package::default();
+    let mut signals = vec![];
+    collect_kinds(ast, &mut package, &mut signals);
+    let package_name = format!("{}_pkg", ast.name);
+    let typedefs = package.typedefs.join("\n");
+    let mut result = format!("package {package_name};\n{typedefs}\nendpackage\n");
+    let header = format!(" import {package_name}::*; ");
+    let lookup = |module: &Module, name: &str| {
+        let (_, kind) = module.kinds.iter().find(|(x, _)| x == name)?;
+        signals
+            .iter()
+            .find(|(k, _)| k == kind)
+            .map(|(_, ty)| ty.clone())
+    };
+    result.push_str(&formatter::module_body(ast, &header, &lookup, &assertions));
+    let mut sources = vec![];
+    formatter::collect_verbatim(ast, &mut sources);
+    for source in sources {
+        result.push_str(source);
+        if !source.ends_with('\n') {
+            result.push('\n');
+        }
+    }
+    result
+}
@@ -0,0 +1,1187 @@
+// Render the HDL AST as VHDL-2008.
+//
+// The AST was designed around Verilog, which is far more permissive about
+// widths and signedness than VHDL.  To bridge the gap, every signal is
+// represented as a `std_logic_vector`, and the Verilog rules for sizing
+// and sign extension are applied when the expressions are rendered.  The
+// operations that have no direct VHDL equivalent (resizing, dynamic
+// indexing, reductions, etc.) are provided by a small support package that
+// is emitted at the top of every file.
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    bitx::{bitx_string, BitX},
+    rtl::spec::{AluBinary, AluUnary},
+    types::bit_string::BitString,
+};
+
+use super::{
+    ast::{
+        Always, Assert, Assignment, Binary, Case, CaseItem, ComponentInstance, Declaration,
+        Direction, Display, DynamicSplice, Events, Expression, Function, FunctionCall, If, Initial,
+        Intrinsic, Module, Port, Select, SignedWidth, Splice, Statement, Unary,
+    },
+    formatter,
+};
+
+const SUPPORT_PACKAGE: &str = include_str!("rhdl_support.vhd");
+
+const RESERVED: [&str; 115] = [
+    "abs",
+    "access",
+    "after",
+    "alias",
+    "all",
+    "and",
+    "architecture",
+    "array",
+    "assert",
+    "assume",
+    "assume_guarantee",
+    "attribute",
+    "begin",
+    "block",
+    "body",
+    "buffer",
+    "bus",
+    "case",
+    "component",
+    "configuration",
+    "constant",
+    "context",
+    "cover",
+    "default",
+    "disconnect",
+    "downto",
+    "else",
+    "elsif",
+    "end",
+    "entity",
+    "exit",
+    "fairness",
+    "file",
+    "for",
+    "force",
+    "function",
+    "generate",
+    "generic",
+    "group",
+    "guarded",
+    "if",
+    "impure",
+    "in",
+    "inertial",
+    "inout",
+    "is",
+    "label",
+    "library",
+    "linkage",
+    "literal",
+    "loop",
+    "map",
+    "mod",
+    "nand",
+    "new",
+    "next",
+    "nor",
+    "not",
+    "null",
+    "of",
+    "on",
+    "open",
+    "or",
+    "others",
+    "out",
+    "package",
+    "parameter",
+    "port",
+    "postponed",
+    "procedure",
+    "process",
+    "property",
+    "protected",
+    "pure",
+    "range",
+    "record",
+    "register",
+    "reject",
+    "release",
+    "rem",
+    "report",
+    "restrict",
+    "restrict_guarantee",
+    "return",
+    "rol",
+    "ror",
+    "select",
+    "sequence",
+    "severity",
+    "shared",
+    "signal",
+    "sla",
+    "sll",
+    "sra",
+    "srl",
+    "strong",
+    "subtype",
+    "then",
+    "to",
+    "transport",
+    "type",
+    "unaffected",
+    "units",
+    "until",
+    "use",
+    "variable",
+    "vmode",
+    "vprop",
+    "vunit",
+    "wait",
+    "when",
+    "while",
+    "with",
+    "xnor",
+    "xor",
+];
+
+// Map a Verilog identifier to a VHDL one.  Anything that is not
+// a legal basic identifier (or is a reserved word) is written as
+// an extended identifier.
+fn ident(name: &str) -> String {
+    let legal = name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.contains("__")
+        && !name.ends_with('_')
+        && !RESERVED.contains(&name.to_ascii_lowercase().as_str());
+    if legal {
+        name.to_string()
+    } else {
+        format!("\\{}\\", name.replace('\\', "\\\\"))
+    }
+}
+
+// Verilog renders a zero width signal as a single bit.
+fn decl_width(width: &SignedWidth) -> usize {
+    width.len().max(1)
+}
+
+fn slv(width: usize) -> String {
+    format!("std_logic_vector({} downto 0)", width as isize - 1)
+}
+
+fn vhdl_bits(bits: &[BitX]) -> String {
+    bitx_string(bits).to_ascii_uppercase()
+}
+
+pub fn bit_string(bs: &BitString) -> String {
+    format!("std_logic_vector'(\"{}\")", vhdl_bits(bs.bits()))
+}
+
+fn string_literal(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+fn comment(text: &str, writer: &mut Writer) {
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() {
+            writer.line("--");
+        } else {
+            writer.line(&format!("-- {line}"));
+        }
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    lines: Vec<String>,
+    indent: usize,
+}
+
+impl Writer {
+    fn line(&mut self, text: &str) {
+        self.lines
+            .push(format!("{}{}", "    ".repeat(self.indent), text));
+    }
+    fn push(&mut self) {
+        self.indent += 1;
+    }
+    fn pop(&mut self) {
+        self.indent = self.indent.saturating_sub(1);
+    }
+    fn finish(self) -> String {
+        let mut result = self.lines.join("\n");
+        result.push('\n');
+        result
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Symbol {
+    width: usize,
+    signed: bool,
+}
+
+impl From<&SignedWidth> for Symbol {
+    fn from(width: &SignedWidth) -> Self {
+        Symbol {
+            width: decl_width(width),
+            signed: width.is_signed(),
+        }
+    }
+}
+
+struct Signature {
+    arguments: Vec<Symbol>,
+    result: Symbol,
+}
+
+// Rendered expression, along with its Verilog width and signedness.
+struct Value {
+    text: String,
+    width: Option<usize>,
+    signed: bool,
+}
+
+impl Value {
+    fn new(text: String, width: Option<usize>, signed: bool) -> Self {
+        Self {
+            text,
+            width,
+            signed,
+        }
+    }
+}
+
+// Sign or zero extend (or truncate) the value to the given width.
+fn fit(value: Value, width: Option<usize>, signed: bool) -> String {
+    match width {
+        Some(width) if value.width != Some(width) => {
+            let func = if signed {
+                "rhdl_sresize"
+            } else {
+                "rhdl_resize"
+            };
+            format!("{func}({}, {width})", value.text)
+        }
+        _ => value.text,
+    }
+}
+
+fn max_width(widths: &[Option<usize>]) -> Option<usize> {
+    widths.iter().flatten().copied().max()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Context {
+    Process,
+    Function,
+}
+
+struct Scope<'a> {
+    symbols: HashMap<String, Symbol>,
+    functions: &'a HashMap<String, Signature>,
+    result: Option<&'a str>,
+}
+
+impl Scope<'_> {
+    fn name(&self, name: &str) -> String {
+        if Some(name) == self.result {
+            "rhdl_result".into()
+        } else {
+            ident(name)
+        }
+    }
+    fn width(&self, name: &str) -> Option<usize> {
+        self.symbols.get(name).map(|s| s.width)
+    }
+    fn signed(&self, name: &str) -> bool {
+        self.symbols.get(name).map(|s| s.signed).unwrap_or_default()
+    }
+    // Assignment targets may address a memory word, as in `mem[addr]`.
+    fn target(&self, target: &str) -> (String, Option<usize>) {
+        match target.split_once('[') {
+            Some((name, address)) => {
+                let address = address.trim_end_matches(']');
+                let address = if address.chars().all(|c| c.is_ascii_digit()) {
+                    address.to_string()
+                } else {
+                    format!("to_integer(unsigned({}))", self.name(address))
+                };
+                (format!("{}({address})", self.name(name)), self.width(name))
+            }
+            None => (self.name(target), self.width(target)),
+        }
+    }
+    fn expression(&self, ast: &Expression, width: Option<usize>) -> String {
+        let value = self.eval(ast, width);
+        let signed = value.signed;
+        fit(value, width, signed)
+    }
+    fn eval(&self, ast: &Expression, context: Option<usize>) -> Value {
+        match ast {
+            Expression::Identifier(name) => {
+                Value::new(self.name(name), self.width(name), self.signed(name))
+            }
+            Expression::Literal(bs) => Value::new(bit_string(bs), Some(bs.len()), bs.is_signed()),
+            Expression::Const(bit) => Value::new(
+                format!("std_logic_vector'(\"{}\")", vhdl_bits(&[*bit])),
+                Some(1),
+                false,
+            ),
+            Expression::Index(index) => Value::new(
+                format!(
+                    "{}({} downto {})",
+                    self.name(&index.target),
+                    index.range.end.saturating_sub(1),
+                    index.range.start
+                ),
+                Some(index.range.len()),
+                false,
+            ),
+            Expression::DynamicIndex(index) => Value::new(
+                format!(
+                    "rhdl_dyn_index({}, {}, {})",
+                    self.name(&index.argument),
+                    self.expression(&index.offset, None),
+                    index.len
+                ),
+                Some(index.len),
+                false,
+            ),
+            Expression::MemoryIndex(index) => Value::new(
+                format!(
+                    "{}(to_integer(unsigned({})))",
+                    self.name(&index.target),
+                    self.expression(&index.address, None)
+                ),
+                self.width(&index.target),
+                false,
+            ),
+            Expression::Concat(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| self.eval(item, None))
+                    .collect::<Vec<_>>();
+                let width = items
+                    .iter()
+                    .try_fold(0, |acc, item| item.width.map(|w| acc + w));
+                let text = if items.len() == 1 {
+                    items[0].text.clone()
+                } else {
+                    format!(
+                        "({})",
+                        items
+                            .iter()
+                            .map(|item| item.text.as_str())
+                            .collect::<Vec<_>>()
+                            .join(" & ")
+                    )
+                };
+                Value::new(text, width, false)
+            }
+            Expression::Repeat(repeat) => {
+                let target = self.eval(&repeat.target, None);
+                Value::new(
+                    format!("rhdl_repeat({}, {})", target.text, repeat.count),
+                    target.width.map(|w| w * repeat.count),
+                    false,
+                )
+            }
+            Expression::Unary(unary) => self.unary(unary, context),
+            Expression::Intrinsic(intrinsic) => self.intrinsic(intrinsic),
+            Expression::Binary(binary) => self.binary(binary, context),
+            Expression::Select(select) => self.select(select, context),
+            Expression::FunctionCall(call) => self.function_call(call),
+        }
+    }
+    fn unary(&self, ast: &Unary, context: Option<usize>) -> Value {
+        match ast.operator {
+            AluUnary::Not | AluUnary::Neg => {
+                let arg = self.eval(&ast.operand, None);
+                let signed = arg.signed;
+                let width = max_width(&[arg.width, context]);
+                let arg = fit(arg, width, signed);
+                let text = if ast.operator == AluUnary::Not {
+                    format!("(not {arg})")
+                } else {
+                    format!("rhdl_neg({arg})")
+                };
+                Value::new(text, width, signed)
+            }
+            AluUnary::All | AluUnary::Any | AluUnary::Xor => {
+                let func = match ast.operator {
+                    AluUnary::All => "rhdl_all",
+                    AluUnary::Any => "rhdl_any",
+                    _ => "rhdl_xor",
+                };
+                let arg = self.expression(&ast.operand, None);
+                Value::new(format!("{func}({arg})"), Some(1), false)
+            }
+            AluUnary::Signed | AluUnary::Unsigned => {
+                let arg = self.eval(&ast.operand, None);
+                Value {
+                    signed: ast.operator == AluUnary::Signed,
+                    ..arg
+                }
+            }
+            AluUnary::Val => self.eval(&ast.operand, context),
+            _ => unreachable!("bit manipulation intrinsics are not unary operators in VHDL"),
+        }
+    }
+    fn intrinsic(&self, ast: &Intrinsic) -> Value {
+        let arg = self.expression(&ast.operand, Some(ast.arg_width));
+        let text = match ast.operator {
+            AluUnary::CountOnes => format!("rhdl_count_ones({arg})"),
+            AluUnary::LeadingZeros => format!("rhdl_leading_zeros({arg})"),
+            AluUnary::TrailingZeros => format!("rhdl_trailing_zeros({arg})"),
+            AluUnary::ReverseBits => format!("rhdl_reverse_bits({arg})"),
+            AluUnary::OneHotToBinary(len) => format!("rhdl_one_hot_to_binary({arg}, {len})"),
+            AluUnary::BinaryToOneHot(len) => format!("rhdl_binary_to_one_hot({arg}, {len})"),
+            op => unreachable!("{op:?} is not a bit manipulation intrinsic"),
+        };
+        Value::new(text, Some(ast.width), false)
+    }
+    fn binary(&self, ast: &Binary, context: Option<usize>) -> Value {
+        let left = self.eval(&ast.left, None);
+        let right = self.eval(&ast.right, None);
+        match ast.operator {
+            AluBinary::Shl | AluBinary::Shr => {
+                let signed = left.signed;
+                let width = max_width(&[left.width, context]);
+                let func = match (ast.operator, signed) {
+                    (AluBinary::Shl, _) => "rhdl_shl",
+                    (_, true) => "rhdl_sshr",
+                    (_, false) => "rhdl_shr",
+                };
+                let left = fit(left, width, signed);
+                Value::new(format!("{func}({left}, {})", right.text), width, signed)
+            }
+            AluBinary::Eq
+            | AluBinary::Ne
+            | AluBinary::Lt
+            | AluBinary::Le
+            | AluBinary::Gt
+            | AluBinary::Ge => {
+                let signed = left.signed && right.signed;
+                let width = max_width(&[left.width, right.width]);
+                let left = fit(left, width, signed);
+                let right = fit(right, width, signed);
+                let op = match ast.operator {
+                    AluBinary::Eq => "=",
+                    AluBinary::Ne => "/=",
+                    AluBinary::Lt => "<",
+                    AluBinary::Le => "<=",
+                    AluBinary::Gt => ">",
+                    _ => ">=",
+                };
+                let text = match ast.operator {
+                    AluBinary::Eq | AluBinary::Ne => format!("rhdl_bool({left} {op} {right})"),
+                    _ if signed => format!("rhdl_bool(signed({left}) {op} signed({right}))"),
+                    _ => format!("rhdl_bool(unsigned({left}) {op} unsigned({right}))"),
+                };
+                Value::new(text, Some(1), false)
+            }
+            _ => {
+                let signed = left.signed && right.signed;
+                let width = max_width(&[left.width, right.width, context]);
+                let left = fit(left, width, signed);
+                let right = fit(right, width, signed);
+                let text = match ast.operator {
+                    AluBinary::Add => {
+                        format!("std_logic_vector(unsigned({left}) + unsigned({right}))")
+                    }
+                    AluBinary::Sub => {
+                        format!("std_logic_vector(unsigned({left}) - unsigned({right}))")
+                    }
+                    AluBinary::Mul => format!("rhdl_mul({left}, {right})"),
+                    AluBinary::Div if signed => format!("rhdl_sdiv({left}, {right})"),
+                    AluBinary::Div => format!("rhdl_div({left}, {right})"),
+                    AluBinary::Rem if signed => format!("rhdl_srem({left}, {right})"),
+                    AluBinary::Rem => format!("rhdl_rem({left}, {right})"),
+                    AluBinary::BitAnd => format!("({left} and {right})"),
+                    AluBinary::BitOr => format!("({left} or {right})"),
+                    _ => format!("({left} xor {right})"),
+                };
+                Value::new(text, width, signed)
+            }
+        }
+    }
+    fn select(&self, ast: &Select, context: Option<usize>) -> Value {
+        let condition = self.expression(&ast.condition, None);
+        let true_value = self.eval(&ast.true_expr, None);
+        let false_value = self.eval(&ast.false_expr, None);
+        let signed = true_value.signed && false_value.signed;
+        let width = max_width(&[true_value.width, false_value.width, context]);
+        let true_value = fit(true_value, width, signed);
+        let false_value = fit(false_value, width, signed);
+        Value::new(
+            format!("rhdl_select({condition}, {true_value}, {false_value})"),
+            width,
+            signed,
+        )
+    }
+    fn function_call(&self, ast: &FunctionCall) -> Value {
+        let signature = self.functions.get(&ast.name);
+        let args = ast
+            .arguments
+            .iter()
+            .enumerate()
+            .map(|(ndx, arg)| {
+                let width = signature
+                    .and_then(|s| s.arguments.get(ndx))
+                    .map(|s| s.width);
+                self.expression(arg, width)
+            })
+            .collect::<Vec<_>>();
+        let text = if args.is_empty() {
+            ident(&ast.name)
+        } else {
+            format!("{}({})", ident(&ast.name), args.join(", "))
+        };
+        Value::new(
+            text,
+            signature.map(|s| s.result.width),
+            signature.map(|s| s.result.signed).unwrap_or_default(),
+        )
+    }
+    fn assignment(&self, ast: &Assignment, context: Context, writer: &mut Writer) {
+        let (target, width) = self.target(&ast.target);
+        let op = assign_op(context);
+        writer.line(&format!(
+            "{target} {op} {};",
+            self.expression(&ast.source, width)
+        ));
+    }
+    fn splice(&self, ast: &Splice, context: Context, writer: &mut Writer) {
+        let (target, width) = self.target(&ast.target);
+        let op = assign_op(context);
+        writer.line(&format!(
+            "{target} {op} {};",
+            self.expression(&ast.source, width)
+        ));
+        writer.line(&format!(
+            "{target}({} downto {}) {op} {};",
+            ast.replace_range.end.saturating_sub(1),
+            ast.replace_range.start,
+            self.expression(&ast.value, Some(ast.replace_range.len()))
+        ));
+    }
+    fn dynamic_splice(&self, ast: &DynamicSplice, context: Context, writer: &mut Writer) {
+        let (target, width) = self.target(&ast.lhs);
+        let op = assign_op(context);
+        writer.line(&format!(
+            "{target} {op} rhdl_dyn_splice({}, {}, {});",
+            self.expression(&ast.arg, width),
+            self.expression(&ast.offset, None),
+            self.expression(&ast.value, Some(ast.len))
+        ));
+    }
+    // Verilog case statements are rendered as if/elsif chains, since
+    // VHDL places restrictions on the case selector (and requires
+    // the choices to be exhaustive).
+    fn case(&self, ast: &Case, context: Context, writer: &mut Writer) {
+        let discriminant = self.eval(&ast.discriminant, None);
+        let width = discriminant.width;
+        let signed = discriminant.signed;
+        let discriminant = fit(discriminant, width, signed);
+        let mut first = true;
+        let mut closed = false;
+        for (item, statement) in &ast.cases {
+            match item {
+                CaseItem::Literal(value) => {
+                    let keyword = if first { "if" } else { "elsif" };
+                    let value = fit(
+                        Value::new(bit_string(value), Some(value.len()), false),
+                        width,
+                        false,
+                    );
+                    writer.line(&format!("{keyword} {discriminant} = {value} then"));
+                }
+                CaseItem::Wild => {
+                    if first {
+                        self.statement(statement, context, writer);
+                        return;
+                    }
+                    writer.line("else");
+                    closed = true;
+                }
+            }
+            first = false;
+            writer.push();
+            self.statement(statement, context, writer);
+            writer.pop();
+            if closed {
+                break;
+            }
+        }
+        if !first {
+            writer.line("end if;");
+        }
+    }
+    fn if_statement(&self, ast: &If, context: Context, writer: &mut Writer) {
+        writer.line(&format!(
+            "if rhdl_true({}) then",
+            self.expression(&ast.condition, None)
+        ));
+        self.block(&ast.true_expr, context, writer);
+        if !ast.false_expr.is_empty() {
+            writer.line("else");
+            self.block(&ast.false_expr, context, writer);
+        }
+        writer.line("end if;");
+    }
+    fn display(&self, ast: &Display) -> String {
+        // Translate the Verilog format string, with each format
+        // specifier replaced by the hex (or binary) value of the
+        // corresponding argument.
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut args = ast.args.iter();
+        let mut chars = ast.format.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                text.push(c);
+                continue;
+            }
+            let mut spec = String::new();
+            while let Some(&d) = chars.peek() {
+                chars.next();
+                if d.is_ascii_alphabetic() || d == '%' {
+                    spec.push(d);
+                    break;
+                }
+            }
+            if spec == "%" {
+                text.push('%');
+                continue;
+            }
+            parts.push(string_literal(&std::mem::take(&mut text)));
+            if let Some(arg) = args.next() {
+                let arg = self.expression(arg, None);
+                if spec.eq_ignore_ascii_case("b") {
+                    parts.push(format!("to_string({arg})"));
+                } else {
+                    parts.push(format!("to_hstring({arg})"));
+                }
+            }
+        }
+        parts.push(string_literal(&text));
+        format!("rhdl_display({});", parts.join(" & "))
+    }
+    fn assert(&self, ast: &Assert, writer: &mut Writer) {
+        let left = self.eval(&ast.left, None);
+        let right = self.eval(&ast.right, None);
+        let width = max_width(&[left.width, right.width]);
+        let left = fit(left, width, false);
+        let right = fit(right, width, false);
+        writer.line(&format!("if {left} /= {right} then"));
+        writer.push();
+        writer.line(&format!(
+            "rhdl_display(\"ASSERTION FAILED 0x\" & to_hstring({left}) & \" !== 0x\" & to_hstring({right}) & {});",
+            string_literal(&format!(" CASE {}", ast.cause))
+        ));
+        writer.line("std.env.finish;");
+        writer.pop();
+        writer.line("end if;");
+    }
+    fn block(&self, ast: &[Statement], context: Context, writer: &mut Writer) {
+        writer.push();
+        if ast.is_empty() {
+            writer.line("null;");
+        }
+        for statement in ast {
+            self.statement(statement, context, writer);
+        }
+        writer.pop();
+    }
+    fn statement(&self, ast: &Statement, context: Context, writer: &mut Writer) {
+        match ast {
+            Statement::Assignment(ast)
+            | Statement::NonblockingAssignment(ast)
+            | Statement::ContinuousAssignment(ast) => self.assignment(ast, context, writer),
+            Statement::Splice(ast) => self.splice(ast, context, writer),
+            Statement::DynamicSplice(ast) => self.dynamic_splice(ast, context, writer),
+            Statement::Case(ast) => self.case(ast, context, writer),
+            Statement::If(ast) => self.if_statement(ast, context, writer),
+            Statement::Delay(time) => writer.line(&format!("wait for {time} ns;")),
+            Statement::Display(ast) => writer.line(&self.display(ast)),
+            Statement::Finish => writer.line("std.env.finish;"),
+            Statement::Assert(ast) => self.assert(ast, writer),
+            Statement::Comment(text) => comment(text, writer),
+            Statement::Custom(text) => comment(&format!("verilog: {text}"), writer),
+            Statement::ComponentInstance(_) | Statement::Always(_) | Statement::Initial(_) => {
+                comment("unsupported nested statement", writer)
+            }
+        }
+    }
+}
+
+fn assign_op(context: Context) -> &'static str {
+    match context {
+        Context::Process => "<=",
+        Context::Function => ":=",
+    }
+}
+
+fn direction(ast: Direction) -> &'static str {
+    match ast {
+        Direction::Input => "in",
+        Direction::Output => "out",
+        Direction::Inout => "inout",
+    }
+}
+
+fn is_constant(ast: &Expression) -> bool {
+    matches!(ast, Expression::Literal(_) | Expression::Const(_))
+}
+
+fn has_timing(ast: &[Statement]) -> bool {
+    ast.iter().any(|s| {
+        matches!(
+            s,
+            Statement::Delay(_) | Statement::Finish | Statement::Display(_) | Statement::Assert(_)
+        )
+    })
+}
+
+// An initial block that only assigns constants is rendered as
+// initial values in the declarations of the signals.
+fn initial_values(ast: &Initial) -> Option<Vec<(&str, &Expression)>> {
+    ast.block
+        .iter()
+        .map(|s| match s {
+            Statement::Assignment(a) | Statement::NonblockingAssignment(a)
+                if is_constant(&a.source) =>
+            {
+                Some((a.target.as_str(), a.source.as_ref()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn function(ast: &Function, functions: &HashMap<String, Signature>, writer: &mut Writer) {
+    let name = ident(&ast.name);
+    let args = ast
+        .arguments
+        .iter()
+        .map(|arg| format!("{} : {}", ident(&arg.name), slv(decl_width(&arg.width))))
+        .collect::<Vec<_>>()
+        .join("; ");
+    if args.is_empty() {
+        writer.line(&format!("function {name} return std_logic_vector is"));
+    } else {
+        writer.line(&format!(
+            "function {name}({args}) return std_logic_vector is"
+        ));
+    }
+    writer.push();
+    writer.line(&format!(
+        "variable rhdl_result : {};",
+        slv(decl_width(&ast.width))
+    ));
+    for register in &ast.registers {
+        let alias = register
+            .alias
+            .as_ref()
+            .map(|x| format!(" -- {}", x.replace('\n', " ").trim_end()))
+            .unwrap_or_default();
+        writer.line(&format!(
+            "variable {} : {};{alias}",
+            ident(&register.name),
+            slv(decl_width(&register.width))
+        ));
+    }
+    for literal in &ast.literals {
+        writer.line(&format!(
+            "constant {} : {} := {};",
+            ident(&literal.name),
+            slv(literal.value.len().max(1)),
+            bit_string(&literal.value)
+        ));
+    }
+    writer.pop();
+    writer.line("begin");
+    let symbols = ast
+        .arguments
+        .iter()
+        .chain(ast.registers.iter())
+        .map(|decl| (decl.name.clone(), Symbol::from(&decl.width)))
+        .chain(ast.literals.iter().map(|lit| {
+            (
+                lit.name.clone(),
+                Symbol {
+                    width: lit.value.len(),
+                    signed: lit.value.is_signed(),
+                },
+            )
+        }))
+        .chain(std::iter::once((
+            ast.name.clone(),
+            Symbol::from(&ast.width),
+        )))
+        .collect();
+    let scope = Scope {
+        symbols,
+        functions,
+        result: Some(&ast.name),
+    };
+    scope.block(&ast.block, Context::Function, writer);
+    writer.push();
+    writer.line("return rhdl_result;");
+    writer.pop();
+    writer.line(&format!("end function {name};"));
+}
+
+fn port(ast: &Port, init: Option<&String>) -> String {
+    let init = init.map(|x| format!(" := {x}")).unwrap_or_default();
+    format!(
+        "{} : {} {}{init}",
+        ident(&ast.name),
+        direction(ast.direction),
+        slv(decl_width(&ast.width))
+    )
+}
+
+// Verilog memories are declared with the address range in the name,
+// as in `mem[15:0]`.  These become an array type in VHDL.
+fn memory_declaration(ast: &Declaration) -> Option<(&str, usize)> {
+    let (name, range) = ast.name.split_once('[')?;
+    let (high, low) = range.trim_end_matches(']').split_once(':')?;
+    let high: usize = high.trim().parse().ok()?;
+    let low: usize = low.trim().parse().ok()?;
+    Some((name, high.max(low) + 1))
+}
+
+fn output_target(scope: &Scope, ast: &Expression) -> Option<Vec<(String, usize)>> {
+    match ast {
+        Expression::Identifier(name) => Some(vec![(scope.name(name), scope.width(name)?)]),
+        Expression::Index(index) => Some(vec![(
+            format!(
+                "{}({} downto {})",
+                scope.name(&index.target),
+                index.range.end.saturating_sub(1),
+                index.range.start
+            ),
+            index.range.len(),
+        )]),
+        Expression::Concat(items) => items
+            .iter()
+            .map(|item| output_target(scope, item))
+            .collect::<Option<Vec<_>>>()
+            .map(|x| x.into_iter().flatten().collect()),
+        _ => None,
+    }
+}
+
+fn component_instance(
+    scope: &Scope,
+    ast: &ComponentInstance,
+    entities: &HashMap<&str, &Module>,
+    declarations: &mut Writer,
+    writer: &mut Writer,
+) {
+    let entity = entities.get(ast.name.as_str());
+    let mut bindings = vec![];
+    let mut splits = vec![];
+    for connection in &ast.connections {
+        let port = entity.and_then(|m| m.ports.iter().find(|p| p.name == connection.target));
+        let width = port.map(|p| decl_width(&p.width));
+        let is_output = port.is_some_and(|p| p.direction != Direction::Input);
+        let actual = if is_output {
+            match output_target(scope, &connection.source) {
+                Some(targets) if targets.len() == 1 => targets[0].0.clone(),
+                Some(targets) => {
+                    // Concatenations are not allowed as the actual of an
+                    // output port, so route the port through a temporary.
+                    let temp = format!("{}_{}", ast.instance_name, connection.target);
+                    let temp_width = targets.iter().map(|(_, w)| w).sum::<usize>();
+                    declarations.line(&format!("signal {} : {};", ident(&temp), slv(temp_width)));
+                    let mut offset = temp_width;
+                    for (target, width) in targets {
+                        splits.push(format!(
+                            "{target} <= {}({} downto {});",
+                            ident(&temp),
+                            offset - 1,
+                            offset - width
+                        ));
+                        offset -= width;
+                    }
+                    ident(&temp)
+                }
+                None => scope.expression(&connection.source, width),
+            }
+        } else {
+            scope.expression(&connection.source, width)
+        };
+        bindings.push(format!("{} => {actual}", ident(&connection.target)));
+    }
+    writer.line(&format!(
+        "{}: entity work.{}",
+        ident(&ast.instance_name),
+        ident(&ast.name)
+    ));
+    writer.push();
+    if bindings.is_empty() {
+        writer.line(";");
+    } else {
+        writer.line("port map (");
+        writer.push();
+        let last = bindings.len() - 1;
+        for (ndx, binding) in bindings.iter().enumerate() {
+            let sep = if ndx == last { "" } else { "," };
+            writer.line(&format!("{binding}{sep}"));
+        }
+        writer.pop();
+        writer.line(");");
+    }
+    writer.pop();
+    for split in splits {
+        writer.line(&split);
+    }
+}
+
+fn process(scope: &Scope, ast: &Always, writer: &mut Writer) {
+    let edges = ast
+        .sensitivity
+        .iter()
+        .filter_map(|event| match event {
+            Events::Posedge(signal) => Some(format!("rising_edge({}(0))", ident(signal))),
+            Events::Negedge(signal) => Some(format!("falling_edge({}(0))", ident(signal))),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let sensitivity = if ast.sensitivity.iter().any(|e| matches!(e, Events::Star)) {
+        "all".to_string()
+    } else {
+        ast.sensitivity
+            .iter()
+            .filter_map(|event| match event {
+                Events::Posedge(signal) | Events::Negedge(signal) | Events::Change(signal) => {
+                    Some(ident(signal))
+                }
+                Events::Star => None,
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    writer.line(&format!("process ({sensitivity}) is"));
+    writer.line("begin");
+    if edges.is_empty() {
+        scope.block(&ast.block, Context::Process, writer);
+    } else {
+        writer.push();
+        writer.line(&format!("if {} then", edges.join(" or ")));
+        scope.block(&ast.block, Context::Process, writer);
+        writer.line("end if;");
+        writer.pop();
+    }
+    writer.line("end process;");
+}
+
+fn entity(ast: &Module, entities: &HashMap<&str, &Module>, writer: &mut Writer) {
+    let name = ident(&ast.name);
+    let functions = ast
+        .functions
+        .iter()
+        .map(|f| {
+            (
+                f.name.clone(),
+                Signature {
+                    arguments: f.arguments.iter().map(|a| (&a.width).into()).collect(),
+                    result: (&f.width).into(),
+                },
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let mut symbols: HashMap<String, Symbol> = ast
+        .ports
+        .iter()
+        .map(|p| (p.name.clone(), (&p.width).into()))
+        .collect();
+    for decl in &ast.declarations {
+        let name = memory_declaration(decl)
+            .map(|(name, _)| name)
+            .unwrap_or(&decl.name);
+        symbols.insert(name.to_string(), (&decl.width).into());
+    }
+    let scope = Scope {
+        symbols,
+        functions: &functions,
+        result: None,
+    };
+    // Gather the initial values for the signals
+    let mut inits: BTreeMap<&str, String> = BTreeMap::new();
+    let mut memory_inits: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for statement in &ast.statements {
+        if let Statement::Initial(initial) = statement {
+            for (target, value) in initial_values(initial).into_iter().flatten() {
+                match target.split_once('[') {
+                    Some((memory, address)) => {
+                        let address = address.trim_end_matches(']');
+                        let value = scope.expression(value, scope.width(memory));
+                        memory_inits
+                            .entry(memory)
+                            .or_default()
+                            .push(format!("{address} => {value}"));
+                    }
+                    None => {
+                        inits.insert(target, scope.expression(value, scope.width(target)));
+                    }
+                }
+            }
+        }
+    }
+    writer.line("library ieee;");
+    writer.line("use ieee.std_logic_1164.all;");
+    writer.line("use ieee.numeric_std.all;");
+    writer.line("use work.rhdl_support.all;");
+    writer.line("");
+    comment(&ast.description, writer);
+    writer.line(&format!("entity {name} is"));
+    writer.push();
+    if !ast.ports.is_empty() {
+        writer.line("port (");
+        writer.push();
+        let last = ast.ports.len() - 1;
+        for (ndx, p) in ast.ports.iter().enumerate() {
+            let sep = if ndx == last { "" } else { ";" };
+            writer.line(&format!("{}{sep}", port(p, inits.get(p.name.as_str()))));
+        }
+        writer.pop();
+        writer.line(");");
+    }
+    writer.pop();
+    writer.line(&format!("end entity {name};"));
+    writer.line("");
+    writer.line(&format!("architecture rtl of {name} is"));
+    let mut declarations = Writer {
+        indent: 1,
+        ..Default::default()
+    };
+    for decl in &ast.declarations {
+        let alias = decl
+            .alias
+            .as_ref()
+            .map(|x| format!(" -- {}", x.replace('\n', " ").trim_end()))
+            .unwrap_or_default();
+        let width = decl_width(&decl.width);
+        if let Some((memory, depth)) = memory_declaration(decl) {
+            let memory_type = ident(&format!("{memory}_type"));
+            declarations.line(&format!(
+                "type {memory_type} is array (0 to {}) of {};",
+                depth - 1,
+                slv(width)
+            ));
+            let init = memory_inits
+                .get(memory)
+                .map(|inits| format!(" := ({}, others => (others => 'X'))", inits.join(", ")))
+                .unwrap_or_default();
+            declarations.line(&format!(
+                "signal {} : {memory_type}{init};{alias}",
+                ident(memory)
+            ));
+        } else {
+            let init = inits
+                .get(decl.name.as_str())
+                .map(|x| format!(" := {x}"))
+                .unwrap_or_default();
+            declarations.line(&format!(
+                "signal {} : {}{init};{alias}",
+                ident(&decl.name),
+                slv(width)
+            ));
+        }
+    }
+    for func in &ast.functions {
+        function(func, &functions, &mut declarations);
+    }
+    let mut body = Writer {
+        indent: 1,
+        ..Default::default()
+    };
+    for statement in &ast.statements {
+        match statement {
+            Statement::ContinuousAssignment(ast)
+            | Statement::Assignment(ast)
+            | Statement::NonblockingAssignment(ast) => {
+                scope.assignment(ast, Context::Process, &mut body)
+            }
+            Statement::ComponentInstance(ast) => {
+                component_instance(&scope, ast, entities, &mut declarations, &mut body)
+            }
+            Statement::Always(ast) => process(&scope, ast, &mut body),
+            Statement::Initial(ast) => {
+                if initial_values(ast).is_some() {
+                    continue;
+                }
+                if has_timing(&ast.block) {
+                    body.line("process is");
+                    body.line("begin");
+                    scope.block(&ast.block, Context::Process, &mut body);
+                    body.push();
+                    body.line("wait;");
+                    body.pop();
+                } else {
+                    body.line("process (all) is");
+                    body.line("begin");
+                    scope.block(&ast.block, Context::Process, &mut body);
+                }
+                body.line("end process;");
+            }
+            Statement::Comment(text) => comment(text, &mut body),
+            Statement::Custom(text) => comment(&format!("verilog: {text}"), &mut body),
+            _ => comment("unsupported concurrent statement", &mut body),
+        }
+    }
+    writer.lines.extend(declarations.lines);
+    writer.line("begin");
+    writer.lines.extend(body.lines);
+    writer.line("end architecture rtl;");
+    writer.line("");
+}
+
+// Collect the modules of the tree so that each entity
+// is defined before it is used.
+fn collect<'a>(ast: &'a Module, modules: &mut Vec<&'a Module>) {
+    for submodule in &ast.submodules {
+        collect(submodule, modules);
+    }
+    if !modules.iter().any(|m| m.name == ast.name) {
+        modules.push(ast);
+    }
+}
+
+pub fn function_body(ast: &Function) -> String {
+    let mut writer = Writer::default();
+    function(ast, &Default::default(), &mut writer);
+    writer.finish()
+}
+
+pub fn module(ast: &Module) -> String {
+    let mut modules = vec![];
+    collect(ast, &mut modules);
+    let entities = modules
+        .iter()
+        .map(|m| (m.name.as_str(), *m))
+        .collect::<HashMap<_, _>>();
+    let mut writer = Writer::default();
+    writer
+        .lines
+        .extend(SUPPORT_PACKAGE.lines().map(String::from));
+    writer.line("");
+    let mut sources = vec![];
+    formatter::collect_verbatim(ast, &mut sources);
+    for source in sources {
+        comment(
+            "The following Verilog source is instantiated in this design,\nand must be compiled separately with a mixed-language tool:",
+            &mut writer,
+        );
+        comment(source, &mut writer);
+        writer.line("");
+    }
+    for module in modules {
+        entity(module, &entities, &mut writer);
+    }
+    writer.finish()
+}
@@ -10,8 +10,10 @@ pub use circuit::synchronous::Synchronous;
 pub use circuit::synchronous::SynchronousDQ;
 pub use circuit::synchronous::SynchronousIO;
 pub use clock_details::ClockDetails;
+pub use types::assertion::kernel_assertion_failed;
 pub use types::bitz::BitZ;
 pub use types::clock::Clock;
+pub use types::coverage::{coverage_init, kernel_branch_taken, Coverage, CoverageGuard};
 pub use types::digital::Digital;
 pub use types::digital_fn::DigitalFn;
 pub use types::digital_fn::DigitalFn2;
@@ -32,6 +34,7 @@ pub use types::reset_n::ResetN;
 pub use types::signal::Signal;
 pub use types::timed::Timed;
 pub mod ast;
+pub mod bmc;
 pub mod circuit;
 pub mod compiler;
 pub mod types;
@@ -41,11 +44,16 @@ pub use util::id;
 pub use compiler::compile_design;
 pub use trace::db::trace;
 pub use trace::db::trace_init_db;
+pub use trace::db::trace_init_db_with_filter;
+pub use trace::db::trace_init_streaming_db;
+pub use trace::db::trace_init_streaming_db_with_filter;
 pub use trace::db::trace_pop_path;
 pub use trace::db::trace_push_path;
 pub use trace::db::trace_time;
 pub use trace::db::TraceDB;
+pub use trace::filter::TraceFilter;
 pub use trace::key::TraceKey;
+pub use trace::reader::Waveform;
 pub use types::kind::DiscriminantType;
 pub use types::typed_bits::TypedBits;
 pub mod rhif;
@@ -74,6 +82,7 @@ pub mod sim;
 pub use types::timed_sample::timed_sample;
 pub use types::timed_sample::TimedSample;
 pub mod hdl;
+pub mod smt;
 pub mod trace;
 pub use bitx::dyn_bit_manip::move_nbits_to_msb;
 pub use flow_graph::flow_cost::trivial_cost;
@@ -167,6 +167,8 @@ impl std::fmt::Debug for AluBinary {
             AluBinary::Add => write!(f, "+"),
             AluBinary::Sub => write!(f, "-"),
             AluBinary::Mul => write!(f, "*"),
+            AluBinary::Div => write!(f, "/"),
+            AluBinary::Rem => write!(f, "%"),
             AluBinary::BitAnd => write!(f, "&"),
             AluBinary::BitOr => write!(f, "|"),
             AluBinary::BitXor => write!(f, "^"),
@@ -181,6 +183,8 @@ impl std::fmt::Debug for AluBinary {
             AluBinary::XAdd => write!(f, "xadd"),
             AluBinary::XSub => write!(f, "xsub"),
             AluBinary::XMul => write!(f, "xmul"),
+            AluBinary::SatAdd => write!(f, "sat+"),
+            AluBinary::SatSub => write!(f, "sat-"),
         }
     }
 }
@@ -207,6 +211,19 @@ impl std::fmt::Debug for AluUnary {
             }
             AluUnary::XNeg => write!(f, "xneg "),
             AluUnary::XSgn => write!(f, "xsgn "),
+            AluUnary::Saturate(len) => {
+                write!(f, "saturate<W{}> ", len)
+            }
+            AluUnary::CountOnes => write!(f, "count_ones "),
+            AluUnary::LeadingZeros => write!(f, "leading_zeros "),
+            AluUnary::TrailingZeros => write!(f, "trailing_zeros "),
+            AluUnary::ReverseBits => write!(f, "reverse_bits "),
+            AluUnary::OneHotToBinary(len) => {
+                write!(f, "one_hot_to_binary<W{}> ", len)
+            }
+            AluUnary::BinaryToOneHot(len) => {
+                write!(f, "binary_to_one_hot<W{}> ", len)
+            }
         }
     }
 }
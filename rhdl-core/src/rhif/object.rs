@@ -7,9 +7,10 @@ use std::ops::Range;
 
 use crate::ast::source::source_location::SourceLocation;
 use crate::ast::source::spanned_source_set::SpannedSourceSet;
+use crate::types::coverage::BranchKind;
 use crate::SourcePool;
 use crate::{
-    ast::ast_impl::{FunctionId, NodeId},
+    ast::ast_impl::{AssertKind, FunctionId, NodeId},
     rhif::spec::Slot,
     Kind, TypedBits,
 };
@@ -94,12 +95,34 @@ impl From<(OpCode, SourceLocation)> for LocatedOpCode {
     }
 }
 
+// An `assert!` or `cover!` in the kernel.  The condition slot holds
+// the result for the whole kernel, i.e., it accounts for the paths
+// on which the assertion is not reached.
+#[derive(Debug, Clone, Hash)]
+pub struct Assertion {
+    pub kind: AssertKind,
+    pub message: String,
+    pub cond: Slot,
+    pub loc: SourceLocation,
+}
+
+// A branch of the kernel, instrumented for coverage.  The condition
+// slot is true if the branch was taken.
+#[derive(Debug, Clone, Hash)]
+pub struct Branch {
+    pub kind: BranchKind,
+    pub cond: Slot,
+    pub loc: SourceLocation,
+}
+
 #[derive(Clone, Hash)]
 pub struct Object {
     pub symbols: SymbolMap,
     pub literals: BTreeMap<LiteralId, TypedBits>,
     pub kind: BTreeMap<RegisterId, Kind>,
     pub return_slot: Slot,
+    pub assertions: Vec<Assertion>,
+    pub branches: Vec<Branch>,
     pub externals: BTreeMap<FuncId, Box<Object>>,
     pub ops: Vec<LocatedOpCode>,
     pub arguments: Vec<RegisterId>,
@@ -133,6 +156,16 @@ impl std::fmt::Debug for Object {
         writeln!(f, "Object {}", self.name)?;
         writeln!(f, "  fn_id {:?}", self.fn_id)?;
         writeln!(f, "  return_slot {:?}", self.return_slot)?;
+        for assertion in &self.assertions {
+            writeln!(
+                f,
+                "  {:?} {:?} {:?}",
+                assertion.kind, assertion.cond, assertion.message
+            )?;
+        }
+        for branch in &self.branches {
+            writeln!(f, "  {} {:?}", branch.kind, branch.cond)?;
+        }
         for regs in self.kind.keys() {
             let slot_name = self
                 .symbols
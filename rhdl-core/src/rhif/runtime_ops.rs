@@ -158,6 +158,123 @@ fn xmul(a: TypedBits, b: TypedBits) -> Result<TypedBits, RHDLError> {
     }
 }
 
+fn to_int(a: &TypedBits) -> Result<BigInt, RHDLError> {
+    if a.kind.is_signed() {
+        to_bigint(&a.bits)
+    } else {
+        to_biguint(&a.bits).map(BigInt::from)
+    }
+    .ok_or_else(|| rhdl_error(DynamicTypeError::CannotConvertUninitToInt { value: a.clone() }))
+}
+
+// Clamp the value to the range that can be represented by the
+// given (signed or unsigned) kind.
+fn clamp_to_kind(value: BigInt, kind: Kind) -> TypedBits {
+    let len = kind.bits();
+    let (min, max) = if kind.is_signed() {
+        let half = BigInt::from(1) << (len - 1);
+        (-half.clone(), half - 1)
+    } else {
+        (BigInt::ZERO, (BigInt::from(1) << len) - 1)
+    };
+    let value = value.clamp(min, max);
+    TypedBits {
+        bits: from_bigint(&value, len),
+        kind,
+    }
+}
+
+fn sat_add(a: TypedBits, b: TypedBits) -> Result<TypedBits, RHDLError> {
+    // The wrapping sum checks that the arguments are compatible
+    let kind = (a.clone() + b.clone())?.kind;
+    Ok(clamp_to_kind(to_int(&a)? + to_int(&b)?, kind))
+}
+
+fn sat_sub(a: TypedBits, b: TypedBits) -> Result<TypedBits, RHDLError> {
+    let kind = (a.clone() - b.clone())?.kind;
+    Ok(clamp_to_kind(to_int(&a)? - to_int(&b)?, kind))
+}
+
+// Division and remainder follow the rhdl-bits conventions, so that
+// dividing by zero gives all ones (and leaves the remainder as the
+// dividend), and the most negative value divided by -1 wraps.  Any
+// X in either argument makes the whole result X.
+fn div_rem(a: TypedBits, b: TypedBits, op: AluBinary) -> Result<TypedBits, RHDLError> {
+    // The sum is only used to check that the arguments have compatible
+    // kinds, and to get the kind of the result.
+    let kind = (a.clone() + b.clone())?.kind;
+    let (Ok(dividend), Ok(divisor)) = (to_int(&a), to_int(&b)) else {
+        return Ok(TypedBits::dont_care_from_kind(kind));
+    };
+    let result = match (op, divisor == BigInt::ZERO) {
+        (AluBinary::Div, true) => BigInt::from(-1),
+        (AluBinary::Div, false) => dividend / divisor,
+        (_, true) => dividend,
+        (_, false) => dividend % divisor,
+    };
+    Ok(TypedBits {
+        bits: from_bigint(&result, kind.bits()),
+        kind,
+    })
+}
+
+fn saturate(a: TypedBits, len: usize) -> Result<TypedBits, RHDLError> {
+    let kind = match a.kind {
+        Kind::Bits(_) => Kind::make_bits(len),
+        Kind::Signed(_) => Kind::make_signed(len),
+        _ => {
+            return Err(rhdl_error(DynamicTypeError::ReinterpretCastFailed {
+                value: a.clone(),
+                len,
+            }))
+        }
+    };
+    Ok(clamp_to_kind(to_int(&a)?, kind))
+}
+
+// The bit manipulation intrinsics.  The counts have the same width as
+// the argument, and the one-hot conversions have the given width.
+fn bit_manip(a: TypedBits, op: AluUnary) -> Result<TypedBits, RHDLError> {
+    let len = a.bits.len();
+    if !a.kind.is_unsigned() {
+        return Err(rhdl_error(
+            DynamicTypeError::CannotApplyBitManipToNonUnsigned { value: a.clone() },
+        ));
+    }
+    if op == AluUnary::ReverseBits {
+        return Ok(TypedBits {
+            bits: a.bits.iter().rev().copied().collect(),
+            kind: a.kind,
+        });
+    }
+    let value = to_biguint(&a.bits).ok_or_else(|| {
+        rhdl_error(DynamicTypeError::CannotConvertUninitToInt { value: a.clone() })
+    })?;
+    let (result, len) = match op {
+        AluUnary::CountOnes => (BigUint::from(value.count_ones()), len),
+        AluUnary::LeadingZeros => (BigUint::from(len as u64 - value.bits()), len),
+        AluUnary::TrailingZeros => (
+            BigUint::from(value.trailing_zeros().unwrap_or(len as u64)),
+            len,
+        ),
+        AluUnary::OneHotToBinary(out) => (
+            (0..len as u64)
+                .filter(|ndx| value.bit(*ndx))
+                .fold(BigUint::ZERO, |acc, ndx| acc | BigUint::from(ndx)),
+            out,
+        ),
+        AluUnary::BinaryToOneHot(out) => match u64::try_from(&value) {
+            Ok(ndx) if ndx < out as u64 => (BigUint::from(1_u8) << ndx, out),
+            _ => (BigUint::ZERO, out),
+        },
+        _ => unreachable!(),
+    };
+    Ok(TypedBits {
+        bits: from_biguint(&result, len),
+        kind: Kind::make_bits(len),
+    })
+}
+
 pub fn binary(
     op: crate::rhif::spec::AluBinary,
     arg1: TypedBits,
@@ -178,9 +295,12 @@ pub fn binary(
         AluBinary::Gt => Ok((arg1 > arg2).typed_bits()),
         AluBinary::Ge => Ok((arg1 >= arg2).typed_bits()),
         AluBinary::Mul => mul(arg1, arg2),
+        AluBinary::Div | AluBinary::Rem => div_rem(arg1, arg2, op),
         AluBinary::XAdd => xadd(arg1, arg2),
         AluBinary::XSub => xsub(arg1, arg2),
         AluBinary::XMul => xmul(arg1, arg2),
+        AluBinary::SatAdd => sat_add(arg1, arg2),
+        AluBinary::SatSub => sat_sub(arg1, arg2),
     }
 }
 
@@ -210,6 +330,13 @@ pub fn unary(op: AluUnary, arg1: TypedBits) -> Result<TypedBits, RHDLError> {
             let arg1 = arg1.xext(1)?;
             arg1.as_signed()
         }
+        AluUnary::Saturate(len) => saturate(arg1, len),
+        AluUnary::CountOnes
+        | AluUnary::LeadingZeros
+        | AluUnary::TrailingZeros
+        | AluUnary::ReverseBits
+        | AluUnary::OneHotToBinary(_)
+        | AluUnary::BinaryToOneHot(_) => bit_manip(arg1, op),
     }
 }
 
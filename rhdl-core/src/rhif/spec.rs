@@ -155,6 +155,8 @@ pub enum AluBinary {
     Add,
     Sub,
     Mul,
+    Div,
+    Rem,
     BitXor,
     BitAnd,
     BitOr,
@@ -169,6 +171,8 @@ pub enum AluBinary {
     XAdd,
     XSub,
     XMul,
+    SatAdd,
+    SatSub,
 }
 
 impl AluBinary {
@@ -204,6 +208,13 @@ pub enum AluUnary {
     XShr(usize),
     XNeg,
     XSgn,
+    Saturate(usize),
+    CountOnes,
+    LeadingZeros,
+    TrailingZeros,
+    ReverseBits,
+    OneHotToBinary(usize),
+    BinaryToOneHot(usize),
 }
 
 #[derive(Clone, Copy, Eq, Hash, PartialEq, PartialOrd, Ord)]
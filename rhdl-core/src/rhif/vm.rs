@@ -9,6 +9,7 @@ use crate::rhif::spec::{
     Array, Assign, Binary, Case, CaseArgument, Cast, Enum, Exec, Index, Member, OpCode, Repeat,
     Slot, Struct, Tuple, Unary,
 };
+use crate::types::coverage::record_vm_branches;
 use crate::types::path::Path;
 use crate::{BitX, TypedBits};
 use crate::{Kind, RHDLError};
@@ -312,6 +313,7 @@ pub fn execute(obj: &Object, arguments: Vec<TypedBits>) -> Result<TypedBits> {
         obj,
     };
     execute_block(&obj.ops, &mut state)?;
+    record_vm_branches(obj, &reg_stack);
     match obj.return_slot {
         Slot::Empty => Ok(TypedBits::EMPTY),
         Slot::Register(r) => reg_stack
@@ -0,0 +1,441 @@
+// Generate straight line Rust code from an RTL object.
+//
+// Every register and literal of the object becomes a local `u128` that
+// holds the bits of the value, zero extended to 128 bits.  Each opcode is
+// translated into a single assignment using fixed width integer operations,
+// with the result masked back to the width of the target register.  Signed
+// values are sign extended only where the operation needs it (comparisons,
+// arithmetic right shifts and resizes).
+//
+// The generated code is wrapped in a module named after the object, and
+// exposes a single function
+//
+//    pub fn eval(args: &[u128]) -> u128
+//
+// that takes one value per kernel argument and returns the bits of the
+// result.
+//
+// The generated code has three limitations:
+//
+//  - It is two-valued.  Unknown (X) bits, in literals or in the arguments
+//    passed to `eval`, are treated as zeros, so the result never carries
+//    an X where the RHIF and RTL simulators would.
+//  - Every register, literal and argument must be at most 128 bits wide.
+//    Wider objects are rejected by `generate_rust` with an error.
+//  - It covers a single RTL object, i.e., one kernel with the kernels it
+//    calls inlined.  The registers and child circuits of a
+//    `CircuitDescriptor` hierarchy are not translated.
+use std::fmt::Write;
+
+use crate::{
+    ast::source::source_location::SourceLocation,
+    bitx::BitX,
+    compiler::mir::error::{RHDLCompileError, ICE},
+    error::rhdl_error,
+    rtl::{
+        object::LocatedOpCode,
+        spec::{AluBinary, AluUnary, CaseArgument, CastKind, OpCode, Operand},
+        Object,
+    },
+    types::bit_string::BitString,
+    RHDLError,
+};
+
+use super::spec as tl;
+
+type Result<T> = std::result::Result<T, RHDLError>;
+
+const MAX_WIDTH: usize = 128;
+
+const HELPERS: &str = "
+    #[inline(always)]
+    fn mask(x: u128, n: usize) -> u128 {
+        if n >= 128 {
+            x
+        } else {
+            x & ((1 << n) - 1)
+        }
+    }
+
+    #[inline(always)]
+    fn sext(x: u128, n: usize) -> i128 {
+        if n == 0 || n >= 128 {
+            x as i128
+        } else {
+            ((x << (128 - n)) as i128) >> (128 - n)
+        }
+    }
+
+    #[inline(always)]
+    fn shl(x: u128, n: u128) -> u128 {
+        if n >= 128 {
+            0
+        } else {
+            x << n
+        }
+    }
+
+    #[inline(always)]
+    fn shr(x: u128, n: u128) -> u128 {
+        if n >= 128 {
+            0
+        } else {
+            x >> n
+        }
+    }
+
+    #[inline(always)]
+    fn sshr(x: u128, w: usize, n: u128) -> u128 {
+        (sext(x, w) >> n.min(127)) as u128
+    }
+
+    #[inline(always)]
+    fn sdiv(x: u128, y: u128, w: usize) -> u128 {
+        if y == 0 {
+            mask(!0, w)
+        } else {
+            mask(sext(x, w).wrapping_div(sext(y, w)) as u128, w)
+        }
+    }
+
+    #[inline(always)]
+    fn srem(x: u128, y: u128, w: usize) -> u128 {
+        if y == 0 {
+            x
+        } else {
+            mask(sext(x, w).wrapping_rem(sext(y, w)) as u128, w)
+        }
+    }
+
+    #[inline(always)]
+    fn one_hot_to_binary(x: u128) -> u128 {
+        (0..128_u128)
+            .filter(|ndx| x & (1 << ndx) != 0)
+            .fold(0, |acc, ndx| acc | ndx)
+    }
+";
+
+// The value of a bit string, with unknown bits set to zero.
+pub(crate) fn bit_string_value(bs: &BitString) -> u128 {
+    bs.bits()
+        .iter()
+        .take(MAX_WIDTH)
+        .enumerate()
+        .fold(0, |acc, (ndx, bit)| match bit {
+            BitX::One => acc | (1 << ndx),
+            _ => acc,
+        })
+}
+
+// The module name for an object.  Kernel names are valid Rust identifiers,
+// but generic kernels carry their arguments in the name.
+pub fn module_name(obj: &Object) -> String {
+    let name: String = obj
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("rtl_{name}")
+}
+
+struct TranslationContext<'a> {
+    body: String,
+    rtl: &'a Object,
+}
+
+impl TranslationContext<'_> {
+    fn raise_ice(&self, cause: ICE, id: SourceLocation) -> RHDLError {
+        rhdl_error(RHDLCompileError {
+            cause,
+            src: self.rtl.symbols.source(),
+            err_span: self.rtl.symbols.span(id).into(),
+        })
+    }
+    fn name(&self, op: Operand) -> String {
+        self.rtl.op_name(op)
+    }
+    fn width(&self, op: Operand) -> usize {
+        self.rtl.kind(op).len()
+    }
+    fn push(&mut self, lhs: Operand, expr: String) {
+        writeln!(self.body, "        {} = {};", self.name(lhs), expr).unwrap();
+    }
+    fn translate_binary(&mut self, binary: &tl::Binary) {
+        let w = self.width(binary.lhs);
+        let w1 = self.width(binary.arg1);
+        let w2 = self.width(binary.arg2);
+        let signed = self.rtl.kind(binary.arg1).is_signed();
+        let a = self.name(binary.arg1);
+        let b = self.name(binary.arg2);
+        let compare = |op: &str| {
+            if signed {
+                format!("(sext({a}, {w1}) {op} sext({b}, {w2})) as u128")
+            } else {
+                format!("({a} {op} {b}) as u128")
+            }
+        };
+        let expr = match binary.op {
+            AluBinary::Add => format!("mask({a}.wrapping_add({b}), {w})"),
+            AluBinary::Sub => format!("mask({a}.wrapping_sub({b}), {w})"),
+            AluBinary::Mul => format!("mask({a}.wrapping_mul({b}), {w})"),
+            AluBinary::Div if signed => format!("sdiv({a}, {b}, {w})"),
+            AluBinary::Div => format!("{a}.checked_div({b}).unwrap_or(mask(!0, {w}))"),
+            AluBinary::Rem if signed => format!("srem({a}, {b}, {w})"),
+            AluBinary::Rem => format!("{a}.checked_rem({b}).unwrap_or({a})"),
+            AluBinary::BitXor => format!("{a} ^ {b}"),
+            AluBinary::BitAnd => format!("{a} & {b}"),
+            AluBinary::BitOr => format!("{a} | {b}"),
+            AluBinary::Shl => format!("mask(shl({a}, {b}), {w})"),
+            AluBinary::Shr if signed => format!("mask(sshr({a}, {w1}, {b}), {w})"),
+            AluBinary::Shr => format!("shr({a}, {b})"),
+            AluBinary::Eq => format!("({a} == {b}) as u128"),
+            AluBinary::Ne => format!("({a} != {b}) as u128"),
+            AluBinary::Lt => compare("<"),
+            AluBinary::Le => compare("<="),
+            AluBinary::Gt => compare(">"),
+            AluBinary::Ge => compare(">="),
+        };
+        self.push(binary.lhs, expr);
+    }
+    fn translate_unary(&mut self, unary: &tl::Unary) {
+        let w = self.width(unary.lhs);
+        let w1 = self.width(unary.arg1);
+        let a = self.name(unary.arg1);
+        let expr = match unary.op {
+            AluUnary::Neg => format!("mask({a}.wrapping_neg(), {w})"),
+            AluUnary::Not => format!("mask(!{a}, {w})"),
+            AluUnary::All => format!("({a} == mask(!0, {w1})) as u128"),
+            AluUnary::Any => format!("({a} != 0) as u128"),
+            AluUnary::Xor => format!("({a}.count_ones() & 1) as u128"),
+            AluUnary::Signed | AluUnary::Unsigned | AluUnary::Val => a,
+            // An argument without bits has nothing to count or reverse (and
+            // the shifts below would overflow).
+            AluUnary::CountOnes
+            | AluUnary::LeadingZeros
+            | AluUnary::TrailingZeros
+            | AluUnary::ReverseBits
+                if w1 == 0 =>
+            {
+                "0".into()
+            }
+            AluUnary::CountOnes => format!("{a}.count_ones() as u128"),
+            AluUnary::LeadingZeros => {
+                format!("{a}.leading_zeros() as u128 - {}", MAX_WIDTH - w1)
+            }
+            AluUnary::TrailingZeros => format!("({a}.trailing_zeros() as u128).min({w1})"),
+            AluUnary::ReverseBits => format!("{a}.reverse_bits() >> {}", MAX_WIDTH - w1),
+            AluUnary::OneHotToBinary(_) => format!("mask(one_hot_to_binary({a}), {w})"),
+            AluUnary::BinaryToOneHot(_) => format!("mask(shl(1, {a}), {w})"),
+        };
+        self.push(unary.lhs, expr);
+    }
+    fn translate_case(&mut self, case: &tl::Case) {
+        let mut arms = String::new();
+        let mut has_wild = false;
+        for (arg, value) in &case.table {
+            let value = self.name(*value);
+            match arg {
+                CaseArgument::Literal(lit) => {
+                    let pattern = bit_string_value(&self.rtl.literals[lit]);
+                    writeln!(arms, "            {pattern:#x} => {value},").unwrap();
+                }
+                CaseArgument::Wild => {
+                    has_wild = true;
+                    writeln!(arms, "            _ => {value},").unwrap();
+                }
+            }
+        }
+        if !has_wild {
+            arms.push_str("            _ => 0,\n");
+        }
+        let expr = format!(
+            "match {} {{\n{arms}        }}",
+            self.name(case.discriminant)
+        );
+        self.push(case.lhs, expr);
+    }
+    // Casts follow the Verilog backend: signed casts may only truncate,
+    // while unsigned casts zero extend, and resizes extend according to
+    // the signedness of the argument.
+    fn translate_cast(&mut self, cast: &tl::Cast, id: SourceLocation) -> Result<()> {
+        let w1 = self.width(cast.arg);
+        let a = self.name(cast.arg);
+        let len = cast.len;
+        let expr = match cast.kind {
+            CastKind::Signed if len > w1 => {
+                return Err(self.raise_ice(
+                    ICE::InvalidSignedCast {
+                        lhs: cast.lhs,
+                        arg: cast.arg,
+                        len,
+                    },
+                    id,
+                ))
+            }
+            CastKind::Resize if len == 0 => {
+                return Err(self.raise_ice(
+                    ICE::InvalidResize {
+                        lhs: cast.lhs,
+                        arg: cast.arg,
+                        len,
+                    },
+                    id,
+                ))
+            }
+            CastKind::Resize if self.rtl.kind(cast.arg).is_signed() => {
+                format!("mask(sext({a}, {w1}) as u128, {len})")
+            }
+            _ => format!("mask({a}, {len})"),
+        };
+        self.push(cast.lhs, expr);
+        Ok(())
+    }
+    fn translate_concat(&mut self, concat: &tl::Concat) {
+        let mut offset = 0;
+        let mut terms = vec![];
+        for arg in &concat.args {
+            let width = self.width(*arg);
+            if width != 0 {
+                terms.push(format!("shl({}, {offset})", self.name(*arg)));
+            }
+            offset += width;
+        }
+        if terms.is_empty() {
+            terms.push("0".into());
+        }
+        self.push(concat.lhs, terms.join(" | "));
+    }
+    fn translate_index(&mut self, index: &tl::Index) {
+        let expr = format!(
+            "mask(shr({}, {}), {})",
+            self.name(index.arg),
+            index.bit_range.start,
+            index.bit_range.len()
+        );
+        self.push(index.lhs, expr);
+    }
+    fn translate_dynamic_index(&mut self, index: &tl::DynamicIndex) {
+        let expr = format!(
+            "mask(shr({}, {}), {})",
+            self.name(index.arg),
+            self.name(index.offset),
+            index.len
+        );
+        self.push(index.lhs, expr);
+    }
+    fn splice(&mut self, lhs: Operand, orig: Operand, value: Operand, offset: &str, len: usize) {
+        let w = self.width(lhs);
+        let orig = self.name(orig);
+        let value = self.name(value);
+        let expr = format!(
+            "{{
+            let m = shl(mask(!0, {len}), {offset});
+            mask(({orig} & !m) | (shl({value}, {offset}) & m), {w})
+        }}"
+        );
+        self.push(lhs, expr);
+    }
+    fn translate_op(&mut self, lop: &LocatedOpCode) -> Result<()> {
+        match &lop.op {
+            OpCode::Noop => {}
+            OpCode::Comment(comment) => {
+                for line in comment.lines() {
+                    writeln!(self.body, "        // {line}").unwrap();
+                }
+            }
+            OpCode::Assign(assign) => self.push(assign.lhs, self.name(assign.rhs)),
+            OpCode::Binary(binary) => self.translate_binary(binary),
+            OpCode::Unary(unary) => self.translate_unary(unary),
+            OpCode::Case(case) => self.translate_case(case),
+            OpCode::Cast(cast) => self.translate_cast(cast, lop.loc)?,
+            OpCode::Concat(concat) => self.translate_concat(concat),
+            OpCode::Index(index) => self.translate_index(index),
+            OpCode::DynamicIndex(index) => self.translate_dynamic_index(index),
+            OpCode::Select(select) => {
+                let expr = format!(
+                    "if {} != 0 {{ {} }} else {{ {} }}",
+                    self.name(select.cond),
+                    self.name(select.true_value),
+                    self.name(select.false_value)
+                );
+                self.push(select.lhs, expr);
+            }
+            OpCode::Splice(splice) => {
+                let offset = splice.bit_range.start.to_string();
+                self.splice(
+                    splice.lhs,
+                    splice.orig,
+                    splice.value,
+                    &offset,
+                    splice.bit_range.len(),
+                )
+            }
+            OpCode::DynamicSplice(splice) => {
+                let offset = self.name(splice.offset);
+                self.splice(splice.lhs, splice.arg, splice.value, &offset, splice.len)
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_width(name: String, width: usize) -> Result<()> {
+    if width > MAX_WIDTH {
+        return Err(RHDLError::RustCodegenError(format!(
+            "{name} is {width} bits wide, but at most {MAX_WIDTH} bits are supported"
+        )));
+    }
+    Ok(())
+}
+
+pub fn generate_rust(obj: &Object) -> Result<String> {
+    for (reg, kind) in &obj.register_kind {
+        check_width(format!("Register {reg:?}"), kind.len())?;
+    }
+    for (lit, value) in &obj.literals {
+        check_width(format!("Literal {lit:?}"), value.len())?;
+    }
+    let mut context = TranslationContext {
+        body: String::new(),
+        rtl: obj,
+    };
+    for (lit, value) in &obj.literals {
+        writeln!(
+            context.body,
+            "        let {}: u128 = {:#x};",
+            obj.op_name((*lit).into()),
+            bit_string_value(value)
+        )
+        .unwrap();
+    }
+    for reg in obj.register_kind.keys() {
+        let arg = obj.arguments.iter().position(|x| *x == Some(*reg));
+        let init = arg.map_or("0".to_string(), |ndx| format!("args[{ndx}]"));
+        writeln!(
+            context.body,
+            "        let mut {}: u128 = {init};",
+            obj.op_name((*reg).into())
+        )
+        .unwrap();
+    }
+    for lop in &obj.ops {
+        context.translate_op(lop)?;
+    }
+    let body = context.body;
+    let ret = obj.op_name(obj.return_register);
+    let name = module_name(obj);
+    let num_args = obj.arguments.len();
+    Ok(format!(
+        "// Generated from the RTL of {:?}
+#[allow(unused, unused_mut, unused_assignments, unreachable_patterns, non_snake_case)]
+#[allow(clippy::all)]
+pub mod {name} {{{HELPERS}
+    pub fn eval(args: &[u128]) -> u128 {{
+        assert_eq!(args.len(), {num_args});
+{body}        {ret}
+    }}
+}}
+",
+        obj.name
+    ))
+}
@@ -11,6 +11,8 @@ impl std::fmt::Debug for AluBinary {
             AluBinary::Add => write!(f, "+"),
             AluBinary::Sub => write!(f, "-"),
             AluBinary::Mul => write!(f, "*"),
+            AluBinary::Div => write!(f, "/"),
+            AluBinary::Rem => write!(f, "%"),
             AluBinary::BitAnd => write!(f, "&"),
             AluBinary::BitOr => write!(f, "|"),
             AluBinary::BitXor => write!(f, "^"),
@@ -28,20 +30,22 @@ impl std::fmt::Debug for AluBinary {
 
 impl std::fmt::Debug for AluUnary {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                AluUnary::Neg => "-",
-                AluUnary::Not => "!",
-                AluUnary::All => "&",
-                AluUnary::Any => "|",
-                AluUnary::Xor => "^",
-                AluUnary::Signed => "signed ",
-                AluUnary::Unsigned => "unsigned ",
-                AluUnary::Val => "val",
-            }
-        )
+        match self {
+            AluUnary::Neg => write!(f, "-"),
+            AluUnary::Not => write!(f, "!"),
+            AluUnary::All => write!(f, "&"),
+            AluUnary::Any => write!(f, "|"),
+            AluUnary::Xor => write!(f, "^"),
+            AluUnary::Signed => write!(f, "signed "),
+            AluUnary::Unsigned => write!(f, "unsigned "),
+            AluUnary::Val => write!(f, "val"),
+            AluUnary::CountOnes => write!(f, "count_ones "),
+            AluUnary::LeadingZeros => write!(f, "leading_zeros "),
+            AluUnary::TrailingZeros => write!(f, "trailing_zeros "),
+            AluUnary::ReverseBits => write!(f, "reverse_bits "),
+            AluUnary::OneHotToBinary(len) => write!(f, "one_hot_to_binary<W{len}> "),
+            AluUnary::BinaryToOneHot(len) => write!(f, "binary_to_one_hot<W{len}> "),
+        }
     }
 }
 
@@ -1,3 +1,4 @@
+pub mod codegen;
 pub mod display;
 pub mod object;
 pub mod spec;
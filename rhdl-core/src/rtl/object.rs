@@ -5,7 +5,7 @@ use std::hash::Hasher;
 
 use fnv::FnvHasher;
 
-use crate::ast::ast_impl::{FunctionId, NodeId};
+use crate::ast::ast_impl::{AssertKind, FunctionId, NodeId};
 use crate::ast::source::source_location::SourceLocation;
 use crate::types::bit_string::BitString;
 use crate::{Digital, Kind};
@@ -107,12 +107,23 @@ impl std::fmt::Debug for RegisterKind {
     }
 }
 
+// An `assert!` or `cover!` in the kernel, lowered from the RHIF
+// assertion of the same name.  The condition is a single bit.
+#[derive(Debug, Clone, Hash)]
+pub struct Assertion {
+    pub kind: AssertKind,
+    pub message: String,
+    pub cond: Operand,
+    pub loc: SourceLocation,
+}
+
 #[derive(Clone, Hash)]
 pub struct Object {
     pub symbols: SymbolMap,
     pub literals: BTreeMap<LiteralId, BitString>,
     pub register_kind: BTreeMap<RegisterId, RegisterKind>,
     pub return_register: Operand,
+    pub assertions: Vec<Assertion>,
     pub ops: Vec<LocatedOpCode>,
     pub arguments: Vec<Option<RegisterId>>,
     pub name: String,
@@ -159,6 +170,13 @@ impl std::fmt::Debug for Object {
         writeln!(f, "  fn_id {:?}", self.fn_id)?;
         writeln!(f, "  arguments {:?}", self.arguments)?;
         writeln!(f, "  return_register {:?}", self.return_register)?;
+        for assertion in &self.assertions {
+            writeln!(
+                f,
+                "  {:?} {:?} {:?}",
+                assertion.kind, assertion.cond, assertion.message
+            )?;
+        }
         for (reg, kind) in &self.register_kind {
             writeln!(f, "Reg {reg:?} : {kind:?}")?;
         }
@@ -112,6 +112,8 @@ pub enum AluBinary {
     Add,
     Sub,
     Mul,
+    Div,
+    Rem,
     BitXor,
     BitAnd,
     BitOr,
@@ -131,6 +133,8 @@ impl From<AluBinary> for crate::rhif::spec::AluBinary {
             AluBinary::Add => crate::rhif::spec::AluBinary::Add,
             AluBinary::Sub => crate::rhif::spec::AluBinary::Sub,
             AluBinary::Mul => crate::rhif::spec::AluBinary::Mul,
+            AluBinary::Div => crate::rhif::spec::AluBinary::Div,
+            AluBinary::Rem => crate::rhif::spec::AluBinary::Rem,
             AluBinary::BitXor => crate::rhif::spec::AluBinary::BitXor,
             AluBinary::BitAnd => crate::rhif::spec::AluBinary::BitAnd,
             AluBinary::BitOr => crate::rhif::spec::AluBinary::BitOr,
@@ -164,6 +168,28 @@ pub enum AluUnary {
     Signed,
     Unsigned,
     Val,
+    CountOnes,
+    LeadingZeros,
+    TrailingZeros,
+    ReverseBits,
+    OneHotToBinary(usize),
+    BinaryToOneHot(usize),
+}
+
+impl AluUnary {
+    // The bit manipulation operations have no operator in the HDLs,
+    // and are rendered as calls to helper functions instead.
+    pub fn is_intrinsic(&self) -> bool {
+        matches!(
+            self,
+            AluUnary::CountOnes
+                | AluUnary::LeadingZeros
+                | AluUnary::TrailingZeros
+                | AluUnary::ReverseBits
+                | AluUnary::OneHotToBinary(_)
+                | AluUnary::BinaryToOneHot(_)
+        )
+    }
 }
 
 impl From<AluUnary> for crate::rhif::spec::AluUnary {
@@ -177,6 +203,12 @@ impl From<AluUnary> for crate::rhif::spec::AluUnary {
             AluUnary::Signed => crate::rhif::spec::AluUnary::Signed,
             AluUnary::Unsigned => crate::rhif::spec::AluUnary::Unsigned,
             AluUnary::Val => crate::rhif::spec::AluUnary::Val,
+            AluUnary::CountOnes => crate::rhif::spec::AluUnary::CountOnes,
+            AluUnary::LeadingZeros => crate::rhif::spec::AluUnary::LeadingZeros,
+            AluUnary::TrailingZeros => crate::rhif::spec::AluUnary::TrailingZeros,
+            AluUnary::ReverseBits => crate::rhif::spec::AluUnary::ReverseBits,
+            AluUnary::OneHotToBinary(len) => crate::rhif::spec::AluUnary::OneHotToBinary(len),
+            AluUnary::BinaryToOneHot(len) => crate::rhif::spec::AluUnary::BinaryToOneHot(len),
         }
     }
 }
@@ -1,7 +1,10 @@
 use std::path::{Path, PathBuf};
 
 use crate::{
-    trace::db::{with_trace_db, TraceDBGuard},
+    trace::{
+        db::{with_trace_db, TraceDBGuard},
+        fst::is_fst_path,
+    },
     trace_init_db, Digital, TimedSample,
 };
 
@@ -36,7 +39,11 @@ where
             with_trace_db(|db| {
                 let fs = std::fs::File::create(&self.file_name).unwrap();
                 let buf = std::io::BufWriter::new(fs);
-                db.dump_vcd(buf, Some(&self.time_set)).unwrap();
+                if is_fst_path(&self.file_name) {
+                    db.dump_fst(buf, Some(&self.time_set)).unwrap();
+                } else {
+                    db.dump_vcd(buf, Some(&self.time_set)).unwrap();
+                }
             });
             None
         }
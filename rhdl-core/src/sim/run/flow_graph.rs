@@ -0,0 +1,164 @@
+// Run the optimized flow graph of a circuit on the same input streams
+// accepted by `run`.  The outputs are returned as `TypedBits` since they
+// may contain unknown (X) bits, and are paired with the input sample
+// that produced them.
+use std::marker::PhantomData;
+
+use crate::{
+    bitx::BitX, flow_graph::sim::FlowGraphSimulator, trace_time, Circuit, CircuitIO, ClockReset,
+    Digital, Kind, RHDLError, Synchronous, SynchronousIO, TimedSample, TypedBits,
+};
+
+#[derive(Clone)]
+struct Simulation {
+    sim: FlowGraphSimulator,
+    time: u64,
+}
+
+impl Simulation {
+    fn step(
+        &mut self,
+        time: u64,
+        inputs: &[Vec<BitX>],
+        kind: Kind,
+    ) -> Result<TypedBits, RHDLError> {
+        assert!(time >= self.time, "input time must be non-decreasing");
+        self.time = time;
+        trace_time(time);
+        let bits = self.sim.step(inputs)?;
+        Ok(TypedBits { bits, kind })
+    }
+}
+
+pub type FlowGraphSample<T> = Result<(TimedSample<T>, TypedBits), RHDLError>;
+
+#[must_use = "To run the simulation, you must exhaust the iterator"]
+pub struct RunFlowGraph<T, I> {
+    simulation: Simulation,
+    inputs: I,
+    marker: PhantomData<T>,
+}
+
+impl<T, I: Clone> Clone for RunFlowGraph<T, I> {
+    fn clone(&self) -> Self {
+        RunFlowGraph {
+            simulation: self.simulation.clone(),
+            inputs: self.inputs.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, I> Iterator for RunFlowGraph<T, I>
+where
+    T: Circuit,
+    I: Iterator<Item = TimedSample<<T as CircuitIO>::I>>,
+{
+    type Item = FlowGraphSample<<T as CircuitIO>::I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.inputs.next()?;
+        let inputs = [sample.value.typed_bits().bits];
+        let kind = <<T as CircuitIO>::O as Digital>::static_kind();
+        Some(
+            self.simulation
+                .step(sample.time, &inputs, kind)
+                .map(|output| (sample, output)),
+        )
+    }
+}
+
+#[must_use = "To run the simulation, you must exhaust the iterator"]
+pub struct RunSynchronousFlowGraph<T, I> {
+    simulation: Simulation,
+    inputs: I,
+    marker: PhantomData<T>,
+}
+
+impl<T, I: Clone> Clone for RunSynchronousFlowGraph<T, I> {
+    fn clone(&self) -> Self {
+        RunSynchronousFlowGraph {
+            simulation: self.simulation.clone(),
+            inputs: self.inputs.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, I> Iterator for RunSynchronousFlowGraph<T, I>
+where
+    T: Synchronous,
+    I: Iterator<Item = TimedSample<(ClockReset, <T as SynchronousIO>::I)>>,
+{
+    type Item = FlowGraphSample<(ClockReset, <T as SynchronousIO>::I)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.inputs.next()?;
+        let (cr, i) = sample.value;
+        let inputs = [cr.typed_bits().bits, i.typed_bits().bits];
+        let kind = <T as SynchronousIO>::O::static_kind();
+        Some(
+            self.simulation
+                .step(sample.time, &inputs, kind)
+                .map(|output| (sample, output)),
+        )
+    }
+}
+
+pub trait RunFlowGraphExt<I>: Circuit + Sized {
+    fn run_flow_graph(
+        &self,
+        iter: I,
+    ) -> Result<RunFlowGraph<Self, <I as IntoIterator>::IntoIter>, RHDLError>
+    where
+        I: IntoIterator;
+}
+
+impl<T, I> RunFlowGraphExt<I> for T
+where
+    T: Circuit,
+    I: IntoIterator<Item = TimedSample<<T as CircuitIO>::I>>,
+{
+    fn run_flow_graph(
+        &self,
+        iter: I,
+    ) -> Result<RunFlowGraph<Self, <I as IntoIterator>::IntoIter>, RHDLError> {
+        Ok(RunFlowGraph {
+            simulation: Simulation {
+                sim: self.flow_graph("top")?.simulator()?,
+                time: 0,
+            },
+            inputs: iter.into_iter(),
+            marker: PhantomData,
+        })
+    }
+}
+
+pub trait RunSynchronousFlowGraphExt<I>: Synchronous + Sized {
+    fn run_flow_graph(
+        &self,
+        iter: I,
+    ) -> Result<RunSynchronousFlowGraph<Self, <I as IntoIterator>::IntoIter>, RHDLError>
+    where
+        I: IntoIterator;
+}
+
+impl<T, I> RunSynchronousFlowGraphExt<I> for T
+where
+    T: Synchronous,
+    I: IntoIterator<Item = TimedSample<(ClockReset, <T as SynchronousIO>::I)>>,
+{
+    fn run_flow_graph(
+        &self,
+        iter: I,
+    ) -> Result<RunSynchronousFlowGraph<Self, <I as IntoIterator>::IntoIter>, RHDLError> {
+        Ok(RunSynchronousFlowGraph {
+            simulation: Simulation {
+                sim: self.flow_graph("top")?.simulator()?,
+                time: 0,
+            },
+            inputs: iter.into_iter(),
+            marker: PhantomData,
+        })
+    }
+}
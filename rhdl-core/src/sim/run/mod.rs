@@ -1,3 +1,4 @@
 pub mod asynchronous;
+pub mod flow_graph;
 pub mod sync_fn;
 pub mod synchronous;
@@ -1,22 +1,121 @@
-use crate::{hdl::ast::Module, RHDLError};
+use rhdl_trace_type::RTT;
 
-pub struct TestModule(Module);
+use crate::{
+    hdl::ast::Module,
+    trace::{
+        compare::{first_divergence, Divergence},
+        reader::Waveform,
+    },
+    RHDLError, TraceDB,
+};
+
+// The name of the waveform dumped by a test bench, if the test bench
+// options do not name one.  It is written to the directory the test
+// bench runs in.
+pub(crate) const TESTBENCH_VCD: &str = "testbench.vcd";
+
+pub struct TestModule {
+    module: Module,
+    // The waveform file named in the test bench options, if any.
+    vcd_file: Option<String>,
+    // The types of the test bench signals in the waveform.
+    rtt: Option<RTT>,
+    // The times at which the output is checked, paired with the time of
+    // the sample that gave the expected value.
+    checks: Vec<(u64, u64)>,
+}
 
 impl From<Module> for TestModule {
-    fn from(m: Module) -> Self {
-        Self(m)
+    fn from(module: Module) -> Self {
+        Self {
+            module,
+            vcd_file: None,
+            rtt: None,
+            checks: vec![],
+        }
+    }
+}
+
+impl TestModule {
+    pub(crate) fn with_waveform(
+        self,
+        vcd_file: Option<String>,
+        rtt: RTT,
+        checks: Vec<(u64, u64)>,
+    ) -> Self {
+        Self {
+            vcd_file,
+            rtt: Some(rtt),
+            checks,
+            ..self
+        }
+    }
+    pub fn as_vhdl(&self) -> String {
+        self.module.as_vhdl()
+    }
+    // Align the output of the test bench (as dumped in its waveform)
+    // against a signal of the trace of the Rust simulation (e.g.
+    // `top.outputs`), and return the first place they differ.
+    pub fn compare_trace(
+        &self,
+        waveform: &Waveform,
+        db: &TraceDB,
+        name: &str,
+    ) -> Result<Option<Divergence>, RHDLError> {
+        let expected = Waveform::from_trace_db(db)?;
+        Ok(first_divergence(
+            &expected,
+            name,
+            waveform,
+            "testbench.o",
+            self.checks.iter().map(|(sample, check)| (*sample, *check)),
+        ))
+    }
+    // The first place the output of the test bench differs from the
+    // output of the Rust simulation it was built from.
+    fn divergence(&self, waveform: &Waveform) -> Option<Divergence> {
+        first_divergence(
+            waveform,
+            "testbench.rust_out",
+            waveform,
+            "testbench.o",
+            self.checks.iter().map(|(_, check)| (*check, *check)),
+        )
+    }
+    fn read_waveform(&self, dir: &std::path::Path) -> Result<Waveform, RHDLError> {
+        let path = match &self.vcd_file {
+            Some(file) => std::path::PathBuf::from(file),
+            None => dir.join(TESTBENCH_VCD),
+        };
+        let waveform = Waveform::read_file(&path)?;
+        Ok(match &self.rtt {
+            Some(rtt) => waveform.with_rtt(rtt),
+            None => waveform,
+        })
     }
 }
 
 impl std::fmt::Display for TestModule {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.module)
     }
 }
 
 #[cfg(feature = "iverilog")]
 impl TestModule {
     pub fn run_iverilog(&self) -> Result<(), RHDLError> {
+        self.run_vvp(|_| Ok(()))
+    }
+    // Run the test bench, and return the waveform it dumped.
+    pub fn run_iverilog_waveform(&self) -> Result<Waveform, RHDLError> {
+        self.run_vvp(|dir| self.read_waveform(dir))
+    }
+    // If the test bench fails, the error reports where the output first
+    // diverges from the expected value.
+    fn run_vvp<T>(
+        &self,
+        on_success: impl FnOnce(&std::path::Path) -> Result<T, RHDLError>,
+    ) -> Result<T, RHDLError> {
         let d = tempfile::tempdir()?;
         // Write the test bench to a file
         let d_path = d.path();
@@ -34,14 +133,29 @@ impl TestModule {
         }
         let mut cmd = std::process::Command::new("vvp");
         cmd.arg(d_path.join("testbench"));
+        // The default waveform goes in the temporary directory.  A named
+        // one is relative to the current directory.
+        if self.rtt.is_some() && self.vcd_file.is_none() {
+            cmd.current_dir(d_path);
+        }
         let output = cmd.output()?;
         let output_stdout = String::from_utf8_lossy(&output.stdout);
         for line in output_stdout.lines() {
             if line.contains("FAILED") {
-                return Err(RHDLError::VerilogVerificationErrorString(line.into()));
+                let divergence = self
+                    .read_waveform(d_path)
+                    .ok()
+                    .and_then(|waveform| self.divergence(&waveform));
+                return Err(match divergence {
+                    Some(divergence) => RHDLError::VerilogWaveformMismatch {
+                        message: line.into(),
+                        divergence: Box::new(divergence),
+                    },
+                    None => RHDLError::VerilogVerificationErrorString(line.into()),
+                });
             }
             if line.starts_with("TESTBENCH OK") {
-                return Ok(());
+                return on_success(d_path);
             }
         }
         Err(RHDLError::VerilogVerificationErrorString(
@@ -49,3 +163,46 @@ impl TestModule {
         ))
     }
 }
+
+#[cfg(feature = "ghdl")]
+impl TestModule {
+    pub fn run_ghdl(&self) -> Result<(), RHDLError> {
+        let d = tempfile::tempdir()?;
+        // Write the test bench to a file
+        let d_path = d.path();
+        std::fs::write(d_path.join("testbench.vhd"), self.as_vhdl())?;
+        // Analyze and elaborate the test bench
+        for step in ["-a", "-e"] {
+            let mut cmd = std::process::Command::new("ghdl");
+            cmd.current_dir(d_path).arg(step).arg("--std=08");
+            if step == "-a" {
+                cmd.arg("testbench.vhd");
+            } else {
+                cmd.arg("testbench");
+            }
+            let status = cmd
+                .status()
+                .expect("GHDL should be installed and in your PATH.");
+            if !status.success() {
+                return Err(anyhow::anyhow!("Failed to compile testbench with {}", status).into());
+            }
+        }
+        let mut cmd = std::process::Command::new("ghdl");
+        cmd.current_dir(d_path)
+            .arg("-r")
+            .arg("--std=08")
+            .arg("testbench")
+            .arg("--ieee-asserts=disable");
+        let output = cmd.output()?;
+        let output_stdout = String::from_utf8_lossy(&output.stdout);
+        for line in output_stdout.lines() {
+            if line.contains("FAILED") {
+                return Err(RHDLError::VHDLVerificationErrorString(line.into()));
+            }
+            if line.starts_with("TESTBENCH OK") {
+                return Ok(());
+            }
+        }
+        Err(RHDLError::VHDLVerificationErrorString("No output".into()))
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use rhdl_trace_type::RTT;
 
 use crate::{
@@ -5,9 +7,10 @@ use crate::{
         assert, assign, bit_string, component_instance, connection, declaration, delay, display,
         dump_file, dump_vars, finish, id, initial, unsigned_width, Direction, HDLKind, Module,
     },
-    sim::test_module::TestModule,
+    sim::test_module::{TestModule, TESTBENCH_VCD},
+    trace::gtkwave::write_gtkwave_save,
     types::bit_string::BitString,
-    Circuit, CircuitIO, Digital, RHDLError, TimedSample,
+    Circuit, CircuitIO, Digital, RHDLError, TimedSample, TypedBits,
 };
 
 use super::TestBenchOptions;
@@ -87,26 +90,39 @@ impl<I: Digital, O: Digital> TestBench<I, O> {
                 None,
             )),
         ];
+        // The types of the test bench signals, so that the waveform it
+        // dumps can be read back.
+        let rtt = RTT::TraceInfo(
+            [
+                ("testbench.i".to_string(), I::static_trace_type()),
+                ("testbench.o".to_string(), O::static_trace_type()),
+                ("testbench.rust_out".to_string(), O::static_trace_type()),
+            ]
+            .into_iter()
+            .collect(),
+        );
         let mut test_cases = vec![];
         if let Some(vcd_file) = &options.vcd_file {
             test_cases.push(dump_file(vcd_file));
             test_cases.push(dump_vars(0));
             // Also write out an RTT file for this VCD that can be loaded
             // afterwards to provide type information for the VCD
-            let rtt = RTT::TraceInfo(
-                [
-                    ("testbench.i".to_string(), I::static_trace_type()),
-                    ("testbench.o".to_string(), O::static_trace_type()),
-                    ("testbench.rust_out".to_string(), O::static_trace_type()),
-                ]
-                .into_iter()
-                .collect(),
-            );
             std::fs::write(
                 vcd_file.clone() + ".rtt",
                 ron::ser::to_string(&rtt).unwrap(),
             )?;
+            // And a GTKWave save file that decodes the values using it
+            write_gtkwave_save(
+                &rtt,
+                Path::new(vcd_file),
+                Path::new(&(vcd_file.clone() + ".gtkw")),
+            )?;
+        } else {
+            // Only the test bench signals are needed to explain a failure
+            test_cases.push(dump_file(TESTBENCH_VCD));
+            test_cases.push(dump_vars(1));
         }
+        let mut checks = vec![];
         let mut absolute_time = 0;
         for (test_case_counter, timed_entry) in self.samples.iter().enumerate() {
             let sample_time = timed_entry.time;
@@ -123,6 +139,7 @@ impl<I: Digital, O: Digital> TestBench<I, O> {
                     id("rust_out"),
                     &format!("Test {test_case_counter} at time {absolute_time}"),
                 ));
+                checks.push((absolute_time, absolute_time + options.hold_time));
                 absolute_time += options.hold_time;
             }
             test_cases.push(delay(sample_time.saturating_sub(absolute_time)));
@@ -148,7 +165,7 @@ impl<I: Digital, O: Digital> TestBench<I, O> {
             submodules: vec![hdl.clone()],
             ..Default::default()
         };
-        Ok(module.into())
+        Ok(TestModule::from(module).with_waveform(options.vcd_file.clone(), rtt, checks))
     }
 
     pub fn rtl<T>(&self, uut: &T, options: &TestBenchOptions) -> Result<TestModule, RHDLError>
@@ -172,4 +189,29 @@ impl<I: Digital, O: Digital> TestBench<I, O> {
         let hdl = uut.flow_graph("uut")?.hdl("dut")?;
         self.build_test_module(&hdl, options)
     }
+    // Replay the samples through a native simulation of the optimized
+    // flow graph, and check that the outputs match.
+    pub fn run_flow_graph<T>(&self, uut: &T, options: &TestBenchOptions) -> Result<(), RHDLError>
+    where
+        T: Circuit,
+        T: CircuitIO<I = I, O = O>,
+    {
+        let mut sim = uut.flow_graph("uut")?.simulator()?;
+        for (ndx, sample) in self.samples.iter().enumerate() {
+            let (i, o) = sample.value;
+            let bits = sim.step(&[i.typed_bits().bits])?;
+            let expected = o.typed_bits();
+            if ndx >= options.skip_first_cases && bits != expected.bits {
+                return Err(RHDLError::FlowGraphVerificationError {
+                    time: sample.time,
+                    expected,
+                    actual: TypedBits {
+                        bits,
+                        kind: O::static_kind(),
+                    },
+                });
+            }
+        }
+        Ok(())
+    }
 }
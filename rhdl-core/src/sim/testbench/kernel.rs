@@ -1,9 +1,13 @@
-use log::debug;
-use std::iter::once;
+use log::{debug, warn};
+use std::{io::Write, iter::once, process::Stdio};
 
 use crate::{
+    bitx::BitX,
+    bmc::equivalence::{check_equivalence, Verdict},
     build_rtl_flow_graph,
-    compiler::driver::{compile_design_stage1, compile_design_stage2},
+    compiler::driver::{
+        compile_design_stage1, compile_design_stage2, compile_design_stage2_unoptimized,
+    },
     flow_graph::{hdl::generate_hdl, optimization::optimize_flow_graph},
     hdl::{
         ast::{
@@ -13,6 +17,7 @@ use crate::{
         },
         builder::generate_verilog,
     },
+    rtl::codegen::{bit_string_value, generate_rust, module_name},
     sim::test_module::TestModule,
     types::bit_string::BitString,
     Digital, DigitalFn, RHDLError, TypedBits,
@@ -367,6 +372,15 @@ where
     top.into()
 }
 
+// The number of conflicts the SAT solver may run into while checking
+// that the compiler passes did not change the function of a kernel.
+const EQUIVALENCE_CONFLICT_LIMIT: usize = 2_000;
+
+// The flow graph simulator is run on (an evenly spaced sample of) at most
+// this many of the test vectors.  The unoptimized flow graph is covered
+// by the equivalence check instead.
+const FLOW_GRAPH_SIM_VECTORS: usize = 4_096;
+
 fn test_kernel_vm_and_verilog_with_mode<K, F, Args, T0>(
     uut: F,
     vals: impl Iterator<Item = Args> + Clone,
@@ -409,6 +423,50 @@ where
             return Err(RHDLError::VerilogVerificationErrorRTL { expected, actual });
         }
     }
+    debug!("Running flow graph simulation checks");
+    let flow_graph = build_rtl_flow_graph(&rtl);
+    let optimized = optimize_flow_graph(flow_graph.clone())?;
+    let mut sim = optimized.simulator()?;
+    let stride = vals.clone().count().div_ceil(FLOW_GRAPH_SIM_VECTORS).max(1);
+    for (ndx, input) in vals.clone().enumerate().step_by(stride) {
+        let args_for_fg = input
+            .vec_tb()
+            .into_iter()
+            .map(|x| x.bits)
+            .collect::<Vec<_>>();
+        let expected = uut.apply(input).typed_bits();
+        let bits = sim.step(&args_for_fg)?;
+        if expected.bits != bits {
+            return Err(RHDLError::FlowGraphVerificationError {
+                time: ndx as u64,
+                actual: TypedBits {
+                    bits,
+                    kind: expected.kind,
+                },
+                expected,
+            });
+        }
+    }
+    debug!("Checking the RTL and flow graph passes for equivalence");
+    let unoptimized = build_rtl_flow_graph(&compile_design_stage2_unoptimized(&design)?);
+    for (stage, flow_graph) in [("RTL", flow_graph), ("flow graph", optimized)] {
+        match check_equivalence(&unoptimized, &flow_graph, EQUIVALENCE_CONFLICT_LIMIT)? {
+            Verdict::Equivalent => {}
+            Verdict::Distinguished(inputs) => {
+                return Err(RHDLError::EquivalenceCheckError(format!(
+                    "the {stage} passes changed the output for the inputs {inputs:?}"
+                )))
+            }
+            // The solver gives up on some designs within the limit.  These
+            // are kernels that divide by a constant, which the passes lower
+            // to a multiplication by its reciprocal, e.g., `a / 3` on a `b8`
+            // or `a / 1000` on a `b64`.  The simulations still cover them.
+            Verdict::Unknown => warn!(
+                "Equivalence of the {stage} passes for {} is inconclusive",
+                std::any::type_name::<K>()
+            ),
+        }
+    }
     debug!("Generating Verilog to run external checks");
     let hdl = generate_verilog(&rtl)?;
     let tm = test_module(&uut, hdl, vals.clone());
@@ -455,3 +513,126 @@ where
         crate::CompilationMode::Synchronous,
     )
 }
+
+// Run the VHDL versions of the kernel (generated from its RTL and from
+// its flow graph) in GHDL test benches, checking each against the Rust
+// version of the kernel.
+pub fn test_kernel_vhdl<K, F, Args, T0>(
+    uut: F,
+    vals: impl Iterator<Item = Args> + Clone,
+) -> Result<(), RHDLError>
+where
+    F: Testable<Args, T0>,
+    T0: Digital,
+    K: DigitalFn,
+    Args: TestArg,
+{
+    let design = compile_design_stage1::<K>(crate::CompilationMode::Asynchronous)?;
+    let rtl = compile_design_stage2(&design)?;
+    let hdl = generate_verilog(&rtl)?;
+    test_module(&uut, hdl, vals.clone()).run_ghdl()?;
+    let flow_graph = optimize_flow_graph(build_rtl_flow_graph(&rtl))?;
+    let desc = generate_hdl("dut", &flow_graph)?;
+    test_module_for_flowgraph(uut, desc, vals).run_ghdl()
+}
+
+// Translate the RTL of the kernel into Rust, compile it with `rustc`, and
+// compare the outputs of the resulting binary with those of the kernel.
+// The test vectors are streamed to the binary, so that long regressions
+// do not need to be embedded in the generated source.
+pub fn test_kernel_compiled<K, F, Args, T0>(
+    uut: F,
+    vals: impl Iterator<Item = Args>,
+) -> Result<(), RHDLError>
+where
+    F: Testable<Args, T0>,
+    T0: Digital,
+    K: DigitalFn,
+    Args: TestArg,
+{
+    let design = compile_design_stage1::<K>(crate::CompilationMode::Asynchronous)?;
+    let rtl = compile_design_stage2(&design)?;
+    let code = generate_rust(&rtl)?;
+    let name = module_name(&rtl);
+    let main = format!(
+        "{code}
+fn main() {{
+    use std::io::{{BufRead, Write}};
+    let mut out = std::io::BufWriter::new(std::io::stdout().lock());
+    for line in std::io::stdin().lock().lines() {{
+        let args = line
+            .unwrap()
+            .split_whitespace()
+            .map(|x| u128::from_str_radix(x, 16).unwrap())
+            .collect::<Vec<_>>();
+        writeln!(out, \"{{:x}}\", {name}::eval(&args)).unwrap();
+    }}
+}}
+"
+    );
+    let d = tempfile::tempdir()?;
+    let d_path = d.path();
+    std::fs::write(d_path.join("main.rs"), main)?;
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let status = std::process::Command::new(rustc)
+        .arg("-O")
+        .arg("--edition=2021")
+        .arg("-o")
+        .arg(d_path.join("kernel"))
+        .arg(d_path.join("main.rs"))
+        .status()?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("Failed to compile generated Rust with {}", status).into());
+    }
+    let mut input = String::new();
+    let mut expected = vec![];
+    for args in vals {
+        let line = args
+            .vec_tb()
+            .into_iter()
+            .map(|x| format!("{:x}", bit_string_value(&x.into())))
+            .collect::<Vec<_>>()
+            .join(" ");
+        input.push_str(&line);
+        input.push('\n');
+        let q: BitString = uut.apply(args).typed_bits().into();
+        expected.push(q);
+    }
+    let mut child = std::process::Command::new(d_path.join("kernel"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .expect("stdin of the test binary is piped");
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output()?;
+    writer.join().expect("writer thread panicked")?;
+    let output = String::from_utf8_lossy(&output.stdout);
+    let mut lines = output.lines();
+    for expected in expected {
+        let value = lines
+            .next()
+            .map(|line| u128::from_str_radix(line, 16))
+            .transpose()?
+            .ok_or_else(|| anyhow::anyhow!("Compiled kernel produced too few outputs"))?;
+        let bits = (0..expected.len())
+            .map(|ndx| {
+                if ndx < 128 && (value >> ndx) & 1 == 1 {
+                    BitX::One
+                } else {
+                    BitX::Zero
+                }
+            })
+            .collect();
+        let actual = match expected {
+            BitString::Signed(_) => BitString::Signed(bits),
+            BitString::Unsigned(_) => BitString::Unsigned(bits),
+        };
+        if expected.bits() != actual.bits() {
+            return Err(RHDLError::RustVerificationError { expected, actual });
+        }
+    }
+    Ok(())
+}
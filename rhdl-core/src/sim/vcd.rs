@@ -1,12 +1,26 @@
-use std::{io::Write, path::Path};
+use std::{
+    fs::File,
+    io::{BufWriter, Seek, Write},
+    path::Path,
+};
 
 use sha2::Digest;
 
-use crate::{trace::db::TraceDBGuard, trace_init_db, Digital, TimedSample};
+use crate::{
+    trace::{
+        db::{with_trace_db, TraceDBGuard},
+        fst::is_fst_path,
+        gtkwave::write_gtkwave_save,
+    },
+    trace_init_db, trace_init_streaming_db, Digital, TimedSample,
+};
 
 pub struct Vcd {
     guard: TraceDBGuard,
-    time_set: fnv::FnvHashSet<u64>,
+    // The times of the samples that were collected.  Only these times are
+    // written out.  A streaming run writes out every time that was traced
+    // instead, since the set would grow as long as the run.
+    time_set: Option<fnv::FnvHashSet<u64>>,
 }
 
 impl<A> FromIterator<TimedSample<A>> for Vcd
@@ -20,21 +34,86 @@ where
     {
         let guard = trace_init_db();
         let iter = iter.into_iter();
-        let time_set = iter.map(|sample| sample.time).collect();
+        let time_set = Some(iter.map(|sample| sample.time).collect());
         Vcd { guard, time_set }
     }
 }
 
 impl Vcd {
+    // Like `collect`, but the trace is kept in a streaming database that
+    // spills to disk, so that long runs do not exhaust memory.  Every time
+    // that was traced is written out, including those of any samples that
+    // were dropped before they reached here.
+    pub fn streaming<A: Digital>(
+        iter: impl IntoIterator<Item = TimedSample<A>>,
+    ) -> std::io::Result<Self> {
+        let guard = trace_init_streaming_db()?;
+        iter.into_iter().for_each(drop);
+        Ok(Vcd {
+            guard,
+            time_set: None,
+        })
+    }
     pub fn dump<W: Write>(self, writer: W) -> std::io::Result<()> {
         let db = self.guard.take();
-        db.dump_vcd(writer, Some(&self.time_set))
+        db.dump_vcd(writer, self.time_set.as_ref())
+    }
+    pub fn dump_fst<W: Write + Seek>(self, writer: W) -> std::io::Result<()> {
+        let db = self.guard.take();
+        db.dump_fst(writer, self.time_set.as_ref())
     }
+    // The trace is written as FST if the path ends in `.fst`, and as VCD
+    // otherwise.  It goes straight to the file, and the hash of the file
+    // is returned.  A VCD is hashed as it is written, but the FST writer
+    // goes back to fill in its headers, so it is read back instead.
     pub fn dump_to_file(self, path: &Path) -> std::io::Result<String> {
-        let mut buf = vec![];
-        self.dump(&mut buf)?;
-        let hash = sha2::Sha256::digest(&buf);
-        std::fs::write(path, &buf)?;
+        let mut file = BufWriter::new(File::create(path)?);
+        let hash = if is_fst_path(path) {
+            self.dump_fst(&mut file)?;
+            file.flush()?;
+            drop(file);
+            let mut hasher = sha2::Sha256::new();
+            std::io::copy(&mut File::open(path)?, &mut hasher)?;
+            hasher.finalize()
+        } else {
+            let mut writer = HashingWriter {
+                inner: &mut file,
+                hasher: sha2::Sha256::new(),
+            };
+            self.dump(&mut writer)?;
+            let hash = writer.hasher.finalize();
+            file.flush()?;
+            hash
+        };
         Ok(format!("{:x}", hash))
     }
+    // Like `dump_to_file`, but also writes a GTKWave save file (with the
+    // extension `.gtkw`) next to the waveform, which shows enums by the
+    // names of their variants and groups the fields of structs.
+    pub fn dump_to_file_with_gtkwave(self, path: &Path) -> std::io::Result<String> {
+        let mut rtt = None;
+        with_trace_db(|db| rtt = Some(db.collect_rtt_info()));
+        let hash = self.dump_to_file(path)?;
+        if let Some(rtt) = rtt {
+            write_gtkwave_save(&rtt, path, &path.with_extension("gtkw"))?;
+        }
+        Ok(hash)
+    }
+}
+
+// Hashes the bytes that are written through it.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: sha2::Sha256,
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.hasher.update(&buf[..len]);
+        Ok(len)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
 }
@@ -0,0 +1,296 @@
+// SMT-LIB2 export of a flow graph.
+//
+// Each node of the graph is defined as a bit vector of the width of the
+// component, and the flip flop black boxes hold the state of the design
+// from one step to the next.  The semantics follow the native flow
+// graph simulator, with `X` values (`X` constants, cases with no matching
+// entry and out of range dynamic indices) left to the solver.
+use petgraph::visit::{EdgeRef, NodeIndexable};
+
+use crate::{
+    error::rhdl_error,
+    flow_graph::{
+        component::{CaseEntry, ComponentKind},
+        error::{FlowGraphError, FlowGraphICE},
+        flow_graph_impl::{BlackBoxMode, BlackBoxModel, FlowGraph, FlowIx},
+        sim::Ports,
+    },
+    hdl::ast::SignedWidth,
+    Digital, Kind, RHDLError, Synchronous, SynchronousIO,
+};
+
+use super::writer::{binary, dynamic_index, dynamic_splice, unary, Word, Writer};
+
+type Result<T> = std::result::Result<T, RHDLError>;
+
+struct Exporter<'a> {
+    fg: &'a FlowGraph,
+    writer: Writer,
+    ports: Vec<Ports>,
+    values: Vec<Option<Word>>,
+}
+
+impl Exporter<'_> {
+    fn raise_ice(&self, cause: FlowGraphICE, node: Option<FlowIx>) -> RHDLError {
+        rhdl_error(FlowGraphError {
+            cause,
+            src: self.fg.code.source(),
+            elements: node
+                .and_then(|node| self.fg.graph[node].location)
+                .map(|loc| self.fg.code.span(loc).into())
+                .into_iter()
+                .collect(),
+        })
+    }
+    // Downstream components read the first bit of their sources.
+    fn bit(&mut self, src: Option<FlowIx>) -> Word {
+        match src.and_then(|src| self.values[src.index()].as_ref()) {
+            Some(value) => value.bit(0),
+            None => self.writer.unknown(1),
+        }
+    }
+    fn argument(&mut self, node: FlowIx, ports: &[Option<FlowIx>], width: usize) -> Result<Word> {
+        let bits = (0..width)
+            .map(|bit| match ports.get(bit).copied().flatten() {
+                Some(src) => Ok(self.bit(Some(src))),
+                None => Err(self.raise_ice(FlowGraphICE::MissingArgument { bit }, Some(node))),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Word::concat(bits))
+    }
+    fn component(&mut self, node: FlowIx) -> Result<Word> {
+        let component = &self.fg.graph[node];
+        let width = component.width;
+        let ports = self.ports[node.index()].clone();
+        let value = match &component.kind {
+            ComponentKind::Constant(value) => self.writer.literal(&vec![*value; width.max(1)]),
+            ComponentKind::BitString(bs) => self.writer.literal(bs.bits()),
+            ComponentKind::Buffer(_) | ComponentKind::BBInput(_) | ComponentKind::BBOutput(_) => {
+                self.bit(ports.parent)
+            }
+            ComponentKind::BitSelect(select) => {
+                let parent = ports.parent.ok_or_else(|| {
+                    self.raise_ice(FlowGraphICE::BufferParentNotFound, Some(node))
+                })?;
+                match &self.values[parent.index()] {
+                    Some(value) if select.bit_index < value.width => value.bit(select.bit_index),
+                    _ => self.writer.unknown(1),
+                }
+            }
+            ComponentKind::Select => {
+                let find = |port: Option<FlowIx>, cause| {
+                    port.ok_or_else(|| self.raise_ice(cause, Some(node)))
+                };
+                let control = find(
+                    ports.selector.first().copied().flatten(),
+                    FlowGraphICE::SelectControlNodeNotFound,
+                )?;
+                let true_value = find(ports.true_value, FlowGraphICE::SelectTrueNodeNotFound)?;
+                let false_value = find(ports.false_value, FlowGraphICE::SelectFalseNodeNotFound)?;
+                let [control, true_value, false_value] =
+                    [control, true_value, false_value].map(|src| self.bit(Some(src)));
+                Word::select(&control, &true_value, &false_value)
+            }
+            ComponentKind::Case(kase) => {
+                let discriminant =
+                    self.argument(node, &ports.selector, kase.discriminant_width.len())?;
+                let mut arms = vec![];
+                for (ndx, entry) in kase.entries.iter().enumerate() {
+                    arms.push((entry, self.argument(node, ports.arg(ndx), 1)?));
+                }
+                let mut value = match arms
+                    .iter()
+                    .find(|(entry, _)| matches!(entry, CaseEntry::WildCard))
+                {
+                    Some((_, arm)) => arm.clone(),
+                    None => self.writer.unknown(1),
+                };
+                for (entry, arm) in arms.into_iter().rev() {
+                    let CaseEntry::Literal(literal) = entry else {
+                        continue;
+                    };
+                    let Some(literal) = literal
+                        .bits()
+                        .iter()
+                        .map(|bit| bit.to_bool())
+                        .collect::<Option<Vec<_>>>()
+                        .filter(|literal| literal.len() == discriminant.width)
+                    else {
+                        continue;
+                    };
+                    let matched = discriminant.equals(&Word::constant(literal.into_iter()));
+                    value = Word::select(&matched, &arm, &value);
+                }
+                value
+            }
+            ComponentKind::Binary(bin) => {
+                let left = self.argument(node, ports.arg(0), bin.left_len.len())?;
+                let right = self.argument(node, ports.arg(1), bin.right_len.len())?;
+                let signed = matches!(bin.left_len, SignedWidth::Signed(_));
+                binary(bin.op, &left, &right, signed).resize(width, false)
+            }
+            ComponentKind::Unary(uny) => {
+                let arg = self.argument(node, ports.arg(0), uny.arg_len.len())?;
+                unary(uny.op, &arg).resize(width, false)
+            }
+            ComponentKind::DynamicIndex(dyn_ndx) => {
+                let arg = self.argument(node, ports.arg(0), dyn_ndx.arg_len)?;
+                let offset = self.argument(node, &ports.offset, dyn_ndx.offset_len)?;
+                let unknown = self.writer.unknown(width);
+                dynamic_index(&arg, &offset, width, unknown)
+            }
+            ComponentKind::DynamicSplice(splice) => {
+                let arg = self.argument(node, ports.arg(0), width)?;
+                let value = self.argument(node, &ports.splice, splice.splice_len)?;
+                let offset = self.argument(node, &ports.offset, splice.offset_len)?;
+                dynamic_splice(&arg, &offset, &value)
+            }
+        };
+        Ok(value)
+    }
+}
+
+// Export a flow graph, naming its arguments and output, and using their
+// kinds to define views of the leaves of their types.
+pub(crate) fn export(
+    fg: &FlowGraph,
+    name: &str,
+    description: &str,
+    inputs: &[(String, Kind)],
+    output: (&str, Kind),
+) -> Result<String> {
+    let graph = &fg.graph;
+    let mut exporter = Exporter {
+        fg,
+        writer: Writer::new(name, description),
+        ports: vec![Ports::default(); graph.node_bound()],
+        values: vec![None; graph.node_bound()],
+    };
+    let order = petgraph::algo::toposort(graph, None)
+        .map_err(|cycle| exporter.raise_ice(FlowGraphICE::LogicLoop, Some(cycle.node_id())))?;
+    // Only flip flops driven by the circuit clock have a model.
+    for bb in &fg.black_boxes {
+        if bb.mode != BlackBoxMode::Synchronous || !matches!(bb.model, BlackBoxModel::Dff { .. }) {
+            return Err(exporter.raise_ice(
+                FlowGraphICE::BlackBoxNotSupported,
+                bb.outputs.first().copied(),
+            ));
+        }
+    }
+    for node in graph.node_indices() {
+        exporter.ports[node.index()] = Ports::new(
+            graph
+                .edges_directed(node, petgraph::Direction::Incoming)
+                .map(|edge| (edge.weight().clone(), edge.source())),
+        );
+    }
+    let mut fixed = vec![false; graph.node_bound()];
+    for (nodes, (input, kind)) in fg.inputs.iter().zip(inputs) {
+        let name = format!(".{input}");
+        let word = exporter.writer.declare(&name, nodes.len());
+        exporter.writer.views(&name, *kind, &word);
+        for (bit, node) in nodes.iter().enumerate() {
+            exporter.values[node.index()] = Some(word.bit(bit));
+            fixed[node.index()] = true;
+        }
+    }
+    let mut state = vec![];
+    for (ndx, bb) in fg.black_boxes.iter().enumerate() {
+        exporter
+            .writer
+            .comment(&format!("state of flip flop {}", bb.code.name));
+        let word = exporter
+            .writer
+            .declare(&format!(".q{ndx}"), bb.outputs.len());
+        for (bit, node) in bb.outputs.iter().enumerate() {
+            exporter.values[node.index()] = Some(word.bit(bit));
+            fixed[node.index()] = true;
+        }
+        state.push(word);
+    }
+    for node in order {
+        if !fixed[node.index()] {
+            let value = exporter.component(node)?;
+            let value = exporter.writer.define(&format!("#{}", node.index()), value);
+            exporter.values[node.index()] = Some(value);
+        }
+    }
+    let bits = fg
+        .output
+        .iter()
+        .map(|node| exporter.bit(Some(*node)))
+        .collect::<Vec<_>>();
+    let (output, kind) = output;
+    let name = format!(".{output}");
+    let word = exporter.writer.define(&name, Word::concat(bits));
+    exporter.writer.views(&name, kind, &word);
+    // A flip flop that is held in reset loads its reset value on the
+    // next clock edge, and starts out in it.  Unknown bits of the reset
+    // value are left unconstrained.
+    let mut init = vec![];
+    let mut trans = vec![];
+    for (ndx, (bb, q)) in fg.black_boxes.iter().zip(&state).enumerate() {
+        let BlackBoxModel::Dff { reset: reset_value } = &bb.model else {
+            continue;
+        };
+        if q.width == 0 {
+            continue;
+        }
+        let reset = exporter.bit(bb.inputs.first().and_then(|cr| cr.get(1)).copied());
+        let data = (0..q.width)
+            .map(|bit| {
+                let src = bb.inputs.get(1).and_then(|nodes| nodes.get(bit)).copied();
+                exporter.bit(src)
+            })
+            .collect::<Vec<_>>();
+        let value = exporter.writer.literal(reset_value.bits());
+        let next = Word::select(&reset, &value, &Word::concat(data));
+        exporter.writer.define(&format!(".d{ndx}"), next);
+        let q_symbol = exporter.writer.symbol(&format!(".q{ndx}"));
+        let d_symbol = exporter.writer.symbol(&format!(".d{ndx}"));
+        match reset_value
+            .bits()
+            .iter()
+            .map(|bit| bit.to_bool())
+            .collect::<Option<Vec<_>>>()
+        {
+            Some(bits) => init.push(format!(
+                "(= ({q_symbol} state) {})",
+                Word::constant(bits.into_iter()).term
+            )),
+            None => {
+                for (bit, value) in reset_value.bits().iter().enumerate() {
+                    if let Some(value) = value.to_bool() {
+                        init.push(format!(
+                            "(= ((_ extract {bit} {bit}) ({q_symbol} state)) #b{})",
+                            value as u8
+                        ));
+                    }
+                }
+            }
+        }
+        trans.push(format!("(= ({q_symbol} next_state) ({d_symbol} state))"));
+    }
+    exporter.writer.predicate("_init", &["state"], init);
+    exporter
+        .writer
+        .predicate("_trans", &["state", "next_state"], trans);
+    Ok(exporter.writer.finish())
+}
+
+// Export a synchronous circuit.  The arguments of the transition are the
+// steps before and after a clock edge, and the inputs of the circuit are
+// `|name.clock_reset|` and `|name.i|`, and its output is `|name.o|`.
+pub fn synchronous_smt<T: Synchronous>(uut: &T, name: &str) -> Result<String> {
+    let fg = uut.flow_graph(name)?;
+    export(
+        &fg,
+        name,
+        &uut.description(),
+        &[
+            ("clock_reset".into(), crate::ClockReset::static_kind()),
+            ("i".into(), <T as SynchronousIO>::I::static_kind()),
+        ],
+        ("o", <T as SynchronousIO>::O::static_kind()),
+    )
+}
@@ -0,0 +1,23 @@
+// Export of kernels and flow graphs as SMT-LIB2 bit vector formulas, so
+// that they can be handed to an external solver (e.g., z3 or bitwuzla).
+//
+// The formulas follow the conventions of the Yosys SMT-LIB2 backend.
+// The design gets an uninterpreted sort `|name_s|`, which stands for
+// one time step.  The inputs, the state of the flip flops and any `X`
+// values are declared as functions of the step, and every other value
+// is defined in terms of them.  Synchronous designs also get an
+// initial state predicate `|name_init|` and a transition relation
+// `|name_trans|` between two steps, so that a check can be unrolled to
+// any depth by declaring one constant of the sort per step:
+//
+//   (declare-const s0 |top_s|)
+//   (declare-const s1 |top_s|)
+//   (assert (|top_init| s0))
+//   (assert (|top_trans| s0 s1))
+//
+// Every leaf of the RHDL type of an input or output is also defined
+// under the name of its path (e.g., `|top.i.a[2]|`), so that the values
+// in a model can be mapped back to the fields they came from.
+pub mod flow_graph;
+pub mod rtl;
+mod writer;
@@ -0,0 +1,265 @@
+// SMT-LIB2 export of the RTL of a kernel.
+//
+// The kernel is combinational, so its arguments are the only inputs,
+// and there is no initial state or transition relation.
+use std::collections::BTreeMap;
+
+use crate::{
+    ast::source::source_location::SourceLocation,
+    compiler::{
+        driver::{compile_design_stage1, compile_design_stage2},
+        mir::error::{RHDLCompileError, ICE},
+    },
+    rhif::spec::Slot,
+    rtl::{
+        spec::{
+            Assign, Binary, Case, CaseArgument, Cast, CastKind, Concat, DynamicIndex,
+            DynamicSplice, Index, OpCode, Operand, RegisterId, Select, Splice, Unary,
+        },
+        Object,
+    },
+    CompilationMode, DigitalFn, Kind, RHDLError,
+};
+
+use super::writer::{binary, dynamic_index, dynamic_splice, unary, Word, Writer};
+
+type Result<T> = std::result::Result<T, RHDLError>;
+
+struct Exporter<'a> {
+    obj: &'a Object,
+    writer: Writer,
+    registers: BTreeMap<RegisterId, Word>,
+    versions: BTreeMap<RegisterId, usize>,
+}
+
+impl Exporter<'_> {
+    fn raise_ice(&self, cause: ICE, loc: SourceLocation) -> RHDLError {
+        let symbols = &self.obj.symbols;
+        RHDLError::RHDLInternalCompilerError(Box::new(RHDLCompileError {
+            cause,
+            src: symbols.source(),
+            err_span: symbols.span(loc).into(),
+        }))
+    }
+    fn read(&mut self, operand: Operand, loc: SourceLocation) -> Result<Word> {
+        match operand {
+            Operand::Literal(lit) => {
+                let bits = self.obj.literals[&lit].bits().to_vec();
+                Ok(self.writer.literal(&bits))
+            }
+            Operand::Register(r) => self
+                .registers
+                .get(&r)
+                .cloned()
+                .ok_or_else(|| self.raise_ice(ICE::UninitializedRTLRegister { r }, loc)),
+        }
+    }
+    fn signed(&self, operand: Operand) -> bool {
+        self.obj.kind(operand).is_signed()
+    }
+    // Registers that are written more than once get a new definition
+    // for each value.
+    fn write(&mut self, lhs: Operand, word: Word, loc: SourceLocation) -> Result<()> {
+        let r = match lhs {
+            Operand::Register(r) => r,
+            Operand::Literal(ndx) => {
+                return Err(self.raise_ice(ICE::CannotWriteToRTLLiteral { ndx }, loc))
+            }
+        };
+        let version = self.versions.entry(r).or_default();
+        let name = match *version {
+            0 => format!("#{r:?}"),
+            n => format!("#{r:?}_{n}"),
+        };
+        *version += 1;
+        let word = self.writer.define(&name, word);
+        self.registers.insert(r, word);
+        Ok(())
+    }
+    fn op(&mut self, op: &OpCode, loc: SourceLocation) -> Result<()> {
+        match op {
+            OpCode::Noop | OpCode::Comment(_) => {}
+            OpCode::Assign(Assign { lhs, rhs }) => {
+                let rhs = self.read(*rhs, loc)?;
+                self.write(*lhs, rhs, loc)?;
+            }
+            OpCode::Binary(Binary {
+                op,
+                lhs,
+                arg1,
+                arg2,
+            }) => {
+                let signed = self.signed(*arg1);
+                let arg1 = self.read(*arg1, loc)?;
+                let arg2 = self.read(*arg2, loc)?;
+                self.write(*lhs, binary(*op, &arg1, &arg2, signed), loc)?;
+            }
+            OpCode::Unary(Unary { op, lhs, arg1 }) => {
+                let arg1 = self.read(*arg1, loc)?;
+                self.write(*lhs, unary(*op, &arg1), loc)?;
+            }
+            OpCode::Case(Case {
+                lhs,
+                discriminant,
+                table,
+            }) => {
+                let discriminant = self.read(*discriminant, loc)?;
+                // Build the chain from the last entry, so that the first
+                // matching entry wins.
+                let mut value = match table.last() {
+                    Some((CaseArgument::Wild, _)) => Word::empty(),
+                    _ => self.writer.unknown(self.obj.kind(*lhs).len()),
+                };
+                for (entry, arm) in table.iter().rev() {
+                    let arm = self.read(*arm, loc)?;
+                    value = match entry {
+                        CaseArgument::Wild => arm,
+                        CaseArgument::Literal(lit) => {
+                            let literal = self.read(Operand::Literal(*lit), loc)?;
+                            Word::select(&discriminant.equals(&literal), &arm, &value)
+                        }
+                    };
+                }
+                self.write(*lhs, value, loc)?;
+            }
+            OpCode::Cast(Cast {
+                lhs,
+                arg,
+                len,
+                kind,
+            }) => {
+                let signed = match kind {
+                    CastKind::Signed => true,
+                    CastKind::Unsigned => false,
+                    CastKind::Resize => self.signed(*arg),
+                };
+                let arg = self.read(*arg, loc)?;
+                self.write(*lhs, arg.resize(*len, signed), loc)?;
+            }
+            OpCode::Concat(Concat { lhs, args }) => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.read(*arg, loc))
+                    .collect::<Result<Vec<_>>>()?;
+                self.write(*lhs, Word::concat(args), loc)?;
+            }
+            OpCode::Index(Index {
+                lhs,
+                arg,
+                bit_range,
+            }) => {
+                let arg = self.read(*arg, loc)?;
+                self.write(*lhs, arg.extract(bit_range.clone()), loc)?;
+            }
+            OpCode::Splice(Splice {
+                lhs,
+                orig,
+                bit_range,
+                value,
+            }) => {
+                let orig = self.read(*orig, loc)?;
+                let value = self.read(*value, loc)?;
+                let spliced = Word::concat([
+                    orig.extract(0..bit_range.start),
+                    value,
+                    orig.extract(bit_range.end..orig.width),
+                ]);
+                self.write(*lhs, spliced, loc)?;
+            }
+            OpCode::Select(Select {
+                lhs,
+                cond,
+                true_value,
+                false_value,
+            }) => {
+                let cond = self.read(*cond, loc)?;
+                let true_value = self.read(*true_value, loc)?;
+                let false_value = self.read(*false_value, loc)?;
+                self.write(*lhs, Word::select(&cond, &true_value, &false_value), loc)?;
+            }
+            OpCode::DynamicIndex(DynamicIndex {
+                lhs,
+                arg,
+                offset,
+                len,
+            }) => {
+                let arg = self.read(*arg, loc)?;
+                let offset = self.read(*offset, loc)?;
+                let unknown = self.writer.unknown(*len);
+                self.write(*lhs, dynamic_index(&arg, &offset, *len, unknown), loc)?;
+            }
+            OpCode::DynamicSplice(DynamicSplice {
+                lhs,
+                arg,
+                offset,
+                len: _,
+                value,
+            }) => {
+                let arg = self.read(*arg, loc)?;
+                let offset = self.read(*offset, loc)?;
+                let value = self.read(*value, loc)?;
+                self.write(*lhs, dynamic_splice(&arg, &offset, &value), loc)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn export(obj: &Object, arguments: &[Kind], ret: Kind) -> Result<String> {
+    let mut exporter = Exporter {
+        obj,
+        writer: Writer::new(&obj.name, &format!("SMT-LIB2 model of kernel {}", obj.name)),
+        registers: BTreeMap::new(),
+        versions: BTreeMap::new(),
+    };
+    for (ndx, (reg, kind)) in obj.arguments.iter().zip(arguments).enumerate() {
+        let name = format!(".arg{ndx}");
+        let width = reg
+            .map(|reg| obj.register_kind[&reg].len())
+            .unwrap_or(kind.bits());
+        let word = exporter.writer.declare(&name, width);
+        exporter.writer.views(&name, *kind, &word);
+        if let Some(reg) = reg {
+            exporter.registers.insert(*reg, word);
+        }
+    }
+    for lop in &obj.ops {
+        exporter.op(&lop.op, lop.loc)?;
+    }
+    let loc = obj.symbols.fallback(obj.fn_id);
+    let value = exporter.read(obj.return_register, loc)?;
+    let value = exporter.writer.define(".out", value);
+    exporter.writer.views(".out", ret, &value);
+    Ok(exporter.writer.finish())
+}
+
+// Export the RTL of a kernel.  The RTL object does not know the types
+// of the arguments, so they are exported as plain bits.
+pub fn rtl_smt(obj: &Object) -> Result<String> {
+    let arguments = obj
+        .arguments
+        .iter()
+        .map(|reg| {
+            Kind::make_bits(
+                reg.map(|reg| obj.register_kind[&reg].len())
+                    .unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let ret = Kind::make_bits(obj.kind(obj.return_register).len());
+    export(obj, &arguments, ret)
+}
+
+// Export a kernel.  The arguments are named `|name.arg0|`, ... and the
+// result `|name.out|`, and every leaf of their types gets a view named
+// by its path (e.g., `|name.arg0.a[2]|`).
+pub fn kernel_smt<K: DigitalFn>() -> Result<String> {
+    let rhif = compile_design_stage1::<K>(CompilationMode::Synchronous)?;
+    let arguments = rhif
+        .arguments
+        .iter()
+        .map(|reg| rhif.kind(Slot::Register(*reg)))
+        .collect::<Vec<_>>();
+    let ret = rhif.kind(rhif.return_slot);
+    export(&compile_design_stage2(&rhif)?, &arguments, ret)
+}
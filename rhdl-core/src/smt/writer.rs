@@ -0,0 +1,403 @@
+// Bit vector terms, and the writer that collects the declarations and
+// definitions of an exported design.
+//
+// Words are kept LSB first, like everywhere else in RHDL, and are only
+// turned around when they are rendered as SMT-LIB2 literals.  SMT-LIB2
+// has no zero width bit vectors, so an empty word is never declared or
+// defined, and simply vanishes from concatenations.
+use std::{fmt::Write, ops::Range};
+
+use crate::{
+    bitx::BitX,
+    rtl::spec::{AluBinary, AluUnary},
+    types::path::{bit_range, leaf_paths, Path},
+    Kind,
+};
+
+#[derive(Clone, Debug)]
+pub(crate) struct Word {
+    pub(crate) term: String,
+    pub(crate) width: usize,
+}
+
+fn sort(width: usize) -> String {
+    format!("(_ BitVec {width})")
+}
+
+fn bool_term(word: &Word) -> String {
+    format!("(= {} #b1)", word.bit(0).term)
+}
+
+impl Word {
+    fn new(term: String, width: usize) -> Self {
+        Self { term, width }
+    }
+    pub(crate) fn empty() -> Self {
+        Self::new("#b0".into(), 0)
+    }
+    pub(crate) fn constant(bits: impl DoubleEndedIterator<Item = bool>) -> Self {
+        let digits = bits
+            .rev()
+            .map(|bit| if bit { '1' } else { '0' })
+            .collect::<String>();
+        match digits.len() {
+            0 => Self::empty(),
+            width => Self::new(format!("#b{digits}"), width),
+        }
+    }
+    pub(crate) fn number(value: usize, width: usize) -> Self {
+        Self::constant((0..width).map(|bit| bit < usize::BITS as usize && (value >> bit) & 1 == 1))
+    }
+    pub(crate) fn zeros(width: usize) -> Self {
+        Self::constant((0..width).map(|_| false))
+    }
+    fn ones(width: usize) -> Self {
+        Self::constant((0..width).map(|_| true))
+    }
+    // A single bit that is set when the condition holds.
+    fn flag(cond: String) -> Self {
+        Self::new(format!("(ite {cond} #b1 #b0)"), 1)
+    }
+    pub(crate) fn extract(&self, range: Range<usize>) -> Self {
+        if range.is_empty() {
+            Self::empty()
+        } else if range.start == 0 && range.end == self.width {
+            self.clone()
+        } else {
+            Self::new(
+                format!(
+                    "((_ extract {} {}) {})",
+                    range.end - 1,
+                    range.start,
+                    self.term
+                ),
+                range.len(),
+            )
+        }
+    }
+    pub(crate) fn bit(&self, ndx: usize) -> Self {
+        self.extract(ndx..ndx + 1)
+    }
+    // Concatenate words given LSB first.
+    pub(crate) fn concat(words: impl IntoIterator<Item = Word>) -> Self {
+        words
+            .into_iter()
+            .filter(|word| word.width != 0)
+            .reduce(|lsb, msb| {
+                Self::new(
+                    format!("(concat {} {})", msb.term, lsb.term),
+                    lsb.width + msb.width,
+                )
+            })
+            .unwrap_or_else(Self::empty)
+    }
+    // Truncate or extend the word to the given width.
+    pub(crate) fn resize(&self, width: usize, signed: bool) -> Self {
+        if width <= self.width {
+            self.extract(0..width)
+        } else if self.width == 0 {
+            Self::zeros(width)
+        } else {
+            let extend = if signed { "sign_extend" } else { "zero_extend" };
+            Self::new(
+                format!("((_ {extend} {}) {})", width - self.width, self.term),
+                width,
+            )
+        }
+    }
+    pub(crate) fn select(cond: &Word, true_value: &Word, false_value: &Word) -> Self {
+        if true_value.width == 0 {
+            return Self::empty();
+        }
+        Self::new(
+            format!(
+                "(ite {} {} {})",
+                bool_term(cond),
+                true_value.term,
+                false_value.term
+            ),
+            true_value.width,
+        )
+    }
+    pub(crate) fn equals(&self, other: &Word) -> Self {
+        if self.width == 0 {
+            Self::constant([true].into_iter())
+        } else {
+            Self::flag(format!("(= {} {})", self.term, other.term))
+        }
+    }
+    fn apply(&self, op: &str, other: &Word) -> Self {
+        Self::new(format!("({op} {} {})", self.term, other.term), self.width)
+    }
+    // A shift is carried out at the wider of the two widths, so that
+    // shifting by the width of the value or more fills it completely.
+    fn shift(&self, op: &str, amount: &Word, signed: bool) -> Self {
+        let width = self.width.max(amount.width);
+        self.resize(width, signed)
+            .apply(op, &amount.resize(width, false))
+            .extract(0..self.width)
+    }
+}
+
+// The result of a binary operator has the width of the left argument,
+// except for the comparisons, which produce a single bit.  The shift
+// amount is always unsigned.  Division by zero gives all ones, and the
+// remainder is then the dividend, as in the RHDL runtime.
+pub(crate) fn binary(op: AluBinary, a: &Word, b: &Word, signed: bool) -> Word {
+    let compare = |less: &str, swap: bool, negate: bool| {
+        if a.width == 0 {
+            return Word::constant([negate].into_iter());
+        }
+        let less = if signed {
+            less.replace('u', "s")
+        } else {
+            less.into()
+        };
+        let (a, b) = if swap { (b, a) } else { (a, b) };
+        let cond = format!("({less} {} {})", a.term, b.term);
+        Word::flag(if negate {
+            format!("(not {cond})")
+        } else {
+            cond
+        })
+    };
+    match op {
+        AluBinary::Eq => return a.equals(b),
+        AluBinary::Ne => return compare("distinct", false, false),
+        AluBinary::Lt => return compare("bvult", false, false),
+        AluBinary::Ge => return compare("bvult", false, true),
+        AluBinary::Gt => return compare("bvult", true, false),
+        AluBinary::Le => return compare("bvult", true, true),
+        _ => {}
+    }
+    if a.width == 0 {
+        return Word::empty();
+    }
+    match op {
+        AluBinary::Add => a.apply("bvadd", b),
+        AluBinary::Sub => a.apply("bvsub", b),
+        AluBinary::Mul => a.apply("bvmul", b),
+        AluBinary::BitAnd => a.apply("bvand", b),
+        AluBinary::BitOr => a.apply("bvor", b),
+        AluBinary::BitXor => a.apply("bvxor", b),
+        AluBinary::Div if signed => Word::select(
+            &b.equals(&Word::zeros(b.width)),
+            &Word::ones(a.width),
+            &a.apply("bvsdiv", b),
+        ),
+        AluBinary::Div => a.apply("bvudiv", b),
+        AluBinary::Rem if signed => a.apply("bvsrem", b),
+        AluBinary::Rem => a.apply("bvurem", b),
+        AluBinary::Shl => a.shift("bvshl", b, signed),
+        AluBinary::Shr if signed => a.shift("bvashr", b, signed),
+        AluBinary::Shr => a.shift("bvlshr", b, signed),
+        _ => unreachable!("comparisons are handled above"),
+    }
+}
+
+// The bit manipulation intrinsics follow the RHDL runtime: the counts
+// have the width of the argument, and the one-hot conversions the
+// width given by the operator.
+pub(crate) fn unary(op: AluUnary, a: &Word) -> Word {
+    let len = a.width;
+    let bits = (0..len).map(|ndx| a.bit(ndx)).collect::<Vec<_>>();
+    let fold = |op: &str, init: Word, words: Vec<Word>| {
+        words
+            .into_iter()
+            .fold(init, |acc, word| acc.apply(op, &word))
+    };
+    let chain = |init: Word, cases: Vec<(usize, Word)>| {
+        cases.into_iter().fold(init, |acc, (ndx, value)| {
+            Word::select(&bits[ndx], &value, &acc)
+        })
+    };
+    match op {
+        AluUnary::Signed | AluUnary::Unsigned | AluUnary::Val => a.clone(),
+        AluUnary::Not if len == 0 => Word::empty(),
+        AluUnary::Not => Word::new(format!("(bvnot {})", a.term), len),
+        AluUnary::Neg if len == 0 => Word::empty(),
+        AluUnary::Neg => Word::new(format!("(bvneg {})", a.term), len),
+        AluUnary::All => a.equals(&Word::ones(len)),
+        AluUnary::Any => binary(AluBinary::Ne, a, &Word::zeros(len), false),
+        AluUnary::Xor => fold("bvxor", Word::zeros(1), bits),
+        AluUnary::ReverseBits => Word::concat(bits.into_iter().rev()),
+        AluUnary::CountOnes => fold(
+            "bvadd",
+            Word::zeros(len),
+            bits.iter().map(|bit| bit.resize(len, false)).collect(),
+        ),
+        // The highest set bit is tested last, so that it takes priority.
+        AluUnary::LeadingZeros => chain(
+            Word::number(len, len),
+            (0..len)
+                .map(|ndx| (ndx, Word::number(len - 1 - ndx, len)))
+                .collect(),
+        ),
+        AluUnary::TrailingZeros => chain(
+            Word::number(len, len),
+            (0..len)
+                .rev()
+                .map(|ndx| (ndx, Word::number(ndx, len)))
+                .collect(),
+        ),
+        AluUnary::OneHotToBinary(0) => Word::empty(),
+        AluUnary::OneHotToBinary(out) => fold(
+            "bvor",
+            Word::zeros(out),
+            (0..len)
+                .map(|ndx| Word::select(&bits[ndx], &Word::number(ndx, out), &Word::zeros(out)))
+                .collect(),
+        ),
+        AluUnary::BinaryToOneHot(out) => (0..out)
+            .filter(|ndx| len >= usize::BITS as usize || ndx >> len == 0)
+            .fold(Word::zeros(out), |acc, ndx| {
+                let one_hot = Word::constant((0..out).map(|bit| bit == ndx));
+                Word::select(&a.equals(&Word::number(ndx, len)), &one_hot, &acc)
+            }),
+    }
+}
+
+// Read `len` bits starting at a variable offset.  Reads that run past
+// the end of the argument give the unknown value instead.
+pub(crate) fn dynamic_index(arg: &Word, offset: &Word, len: usize, unknown: Word) -> Word {
+    if len == 0 || len > arg.width {
+        return unknown;
+    }
+    let width = arg.width.max(offset.width);
+    let shifted = binary(
+        AluBinary::Shr,
+        &arg.resize(width, false),
+        &offset.resize(width, false),
+        false,
+    )
+    .extract(0..len);
+    let last = Word::number(arg.width - len, width);
+    let in_range = binary(AluBinary::Le, &offset.resize(width, false), &last, false);
+    Word::select(&in_range, &shifted, &unknown)
+}
+
+// Overwrite the bits starting at a variable offset.  Bits of the value
+// that land past the end of the argument are dropped.
+pub(crate) fn dynamic_splice(arg: &Word, offset: &Word, value: &Word) -> Word {
+    if arg.width == 0 || value.width == 0 {
+        return arg.clone();
+    }
+    let place = |word: &Word| {
+        binary(
+            AluBinary::Shl,
+            &word.resize(arg.width, false),
+            offset,
+            false,
+        )
+    };
+    let mask = place(&Word::constant((0..value.width).map(|_| true)));
+    let kept = binary(AluBinary::BitAnd, arg, &unary(AluUnary::Not, &mask), false);
+    binary(AluBinary::BitOr, &kept, &place(value), false)
+}
+
+// Collects the SMT-LIB2 text for a design.  Every value is a function
+// of the step of the design, which is an uninterpreted sort.
+pub(crate) struct Writer {
+    name: String,
+    text: String,
+    unknowns: usize,
+}
+
+impl Writer {
+    pub(crate) fn new(name: &str, description: &str) -> Self {
+        let mut writer = Self {
+            name: name.into(),
+            text: String::new(),
+            unknowns: 0,
+        };
+        writer.comment(description);
+        let sort = writer.sort();
+        writeln!(writer.text, "(declare-sort {sort} 0)").unwrap();
+        writer
+    }
+    fn sort(&self) -> String {
+        format!("|{}_s|", self.name)
+    }
+    pub(crate) fn symbol(&self, name: &str) -> String {
+        format!("|{}{name}|", self.name)
+    }
+    pub(crate) fn comment(&mut self, comment: &str) {
+        writeln!(self.text, "; {comment}").unwrap();
+    }
+    // A value that is chosen freely in every step (i.e., an input).
+    pub(crate) fn declare(&mut self, name: &str, width: usize) -> Word {
+        if width == 0 {
+            return Word::empty();
+        }
+        let symbol = self.symbol(name);
+        writeln!(
+            self.text,
+            "(declare-fun {symbol} ({}) {})",
+            self.sort(),
+            sort(width)
+        )
+        .unwrap();
+        Word::new(format!("({symbol} state)"), width)
+    }
+    // An `X`, which the solver is free to choose in every step.
+    pub(crate) fn unknown(&mut self, width: usize) -> Word {
+        self.unknowns += 1;
+        self.declare(&format!("#x{}", self.unknowns), width)
+    }
+    pub(crate) fn literal(&mut self, bits: &[BitX]) -> Word {
+        if bits.iter().all(|bit| bit.to_bool().is_some()) {
+            return Word::constant(bits.iter().map(|bit| *bit == BitX::One));
+        }
+        let unknown = self.unknown(bits.len());
+        let known = Word::constant(bits.iter().map(|bit| bit.to_bool().is_some()));
+        let value = Word::constant(bits.iter().map(|bit| *bit == BitX::One));
+        let mask = Word::new(format!("(bvnot {})", known.term), known.width);
+        unknown.apply("bvand", &mask).apply("bvor", &value)
+    }
+    pub(crate) fn define(&mut self, name: &str, word: Word) -> Word {
+        if word.width == 0 {
+            return word;
+        }
+        let symbol = self.symbol(name);
+        writeln!(
+            self.text,
+            "(define-fun {symbol} ((state {})) {} {})",
+            self.sort(),
+            sort(word.width),
+            word.term
+        )
+        .unwrap();
+        Word::new(format!("({symbol} state)"), word.width)
+    }
+    // Define a value for every leaf of the kind, named by its path, so
+    // that a model can be read back in terms of the RHDL type.
+    pub(crate) fn views(&mut self, name: &str, kind: Kind, word: &Word) {
+        for path in leaf_paths(&kind, Path::default()) {
+            let Ok((range, _)) = bit_range(kind, &path) else {
+                continue;
+            };
+            if !path.is_empty() {
+                self.define(&format!("{name}{path:?}"), word.extract(range));
+            }
+        }
+    }
+    // Define a predicate over one or more steps, which are named by
+    // the parameters.
+    pub(crate) fn predicate(&mut self, name: &str, params: &[&str], clauses: Vec<String>) {
+        let params = params
+            .iter()
+            .map(|param| format!("({param} {})", self.sort()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let body = match clauses.len() {
+            0 => "true".to_string(),
+            1 => clauses[0].clone(),
+            _ => format!("(and {})", clauses.join(" ")),
+        };
+        let symbol = self.symbol(name);
+        writeln!(self.text, "(define-fun {symbol} ({params}) Bool {body})").unwrap();
+    }
+    pub(crate) fn finish(self) -> String {
+        self.text
+    }
+}
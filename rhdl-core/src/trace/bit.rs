@@ -27,3 +27,14 @@ impl From<BitX> for TraceBit {
         }
     }
 }
+
+// A high impedance bit has no logical value.
+impl From<TraceBit> for BitX {
+    fn from(b: TraceBit) -> Self {
+        match b {
+            TraceBit::Zero => BitX::Zero,
+            TraceBit::One => BitX::One,
+            TraceBit::X | TraceBit::Z => BitX::X,
+        }
+    }
+}
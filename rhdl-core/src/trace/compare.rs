@@ -0,0 +1,165 @@
+use crate::{bitx::BitX, rtt::trace_to_kind, types::path::Path, Kind, TypedBits};
+
+use super::reader::Waveform;
+
+// Where two waveforms first disagree.  The `path` names the innermost
+// part of the value that differs (e.g. `o.axi.read.data`), and the
+// expected and actual values are those of that part.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    pub time: u64,
+    pub path: String,
+    pub expected: TypedBits,
+    pub actual: TypedBits,
+}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "At time {}, {} expected {:?} got {:?}",
+            self.time, self.path, self.expected, self.actual
+        )
+    }
+}
+
+fn typed_value(waveform: &Waveform, name: &str, time: u64, kind: Kind) -> TypedBits {
+    // A signal that has no value yet (or is missing bits) is unknown.
+    let mut bits = waveform
+        .value_at(name, time)
+        .map(|value| value.iter().map(|b| (*b).into()).collect::<Vec<_>>())
+        .unwrap_or_default();
+    bits.resize(kind.bits(), BitX::X);
+    TypedBits { bits, kind }
+}
+
+// Descend into the value to find the innermost part in which the two
+// values differ.  Enums are only descended into if both values are of
+// the same variant.
+fn divergent_part(
+    expected: TypedBits,
+    actual: TypedBits,
+    path: Path,
+) -> (Path, TypedBits, TypedBits) {
+    let parts = match expected.kind {
+        Kind::Struct(strukt) => strukt
+            .fields
+            .iter()
+            .map(|field| Path::default().field(&field.name))
+            .collect(),
+        Kind::Tuple(tuple) => (0..tuple.elements.len())
+            .map(|ndx| Path::default().tuple_index(ndx))
+            .collect(),
+        Kind::Array(array) => (0..array.size)
+            .map(|ndx| Path::default().index(ndx))
+            .collect(),
+        Kind::Signal(..) => vec![Path::default().signal_value()],
+        Kind::Enum(enumerate) => {
+            let discriminant = Path::default().discriminant();
+            match (expected.path(&discriminant), actual.path(&discriminant)) {
+                (Ok(e), Ok(a)) if e.bits == a.bits => e
+                    .as_i64()
+                    .ok()
+                    .and_then(|value| {
+                        enumerate
+                            .variants
+                            .iter()
+                            .find(|variant| variant.discriminant == value)
+                    })
+                    .map(|variant| Path::default().payload(&variant.name))
+                    .into_iter()
+                    .collect(),
+                _ => vec![],
+            }
+        }
+        _ => vec![],
+    };
+    for part in parts {
+        if let (Ok(e), Ok(a)) = (expected.path(&part), actual.path(&part)) {
+            if e.bits != a.bits {
+                return divergent_part(e, a, path.join(&part));
+            }
+        }
+    }
+    (path, expected, actual)
+}
+
+// Compare a signal of one waveform against a signal of another.  The two
+// are aligned by the pairs of (expected time, actual time), and the first
+// pair at which their values differ is returned.  The values are typed
+// using the trace type of the expected signal (or the actual one, if the
+// expected signal has none).  Paths are reported relative to the last
+// part of the name of the actual signal.
+pub fn first_divergence(
+    expected: &Waveform,
+    expected_name: &str,
+    actual: &Waveform,
+    actual_name: &str,
+    times: impl IntoIterator<Item = (u64, u64)>,
+) -> Option<Divergence> {
+    let signal = expected
+        .signal(expected_name)
+        .or_else(|| actual.signal(actual_name))?;
+    let kind = expected
+        .signal(expected_name)
+        .and_then(|signal| signal.trace_type.as_ref())
+        .or_else(|| actual.signal(actual_name)?.trace_type.as_ref())
+        .map(trace_to_kind)
+        .unwrap_or(Kind::Bits(signal.width));
+    let root = actual_name.rsplit('.').next().unwrap_or(actual_name);
+    times
+        .into_iter()
+        .find_map(|(expected_time, actual_time)| {
+            let e = typed_value(expected, expected_name, expected_time, kind);
+            let a = typed_value(actual, actual_name, actual_time, kind);
+            (e.bits != a.bits).then_some((actual_time, e, a))
+        })
+        .map(|(time, e, a)| {
+            let (path, expected, actual) = divergent_part(e, a, Path::default());
+            Divergence {
+                time,
+                path: format!("{root}{path:?}"),
+                expected,
+                actual,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_divergence_path() {
+        let kind = Kind::make_struct(
+            "Out",
+            vec![
+                Kind::make_field("valid", Kind::make_bool()),
+                Kind::make_field(
+                    "axi",
+                    Kind::make_struct(
+                        "Axi",
+                        vec![Kind::make_field("data", Kind::make_array(Kind::Bits(4), 2))],
+                    ),
+                ),
+            ],
+        );
+        let value = |valid: bool, data: [u8; 2]| {
+            let mut bits = vec![valid.into()];
+            bits.extend(
+                data.iter()
+                    .flat_map(|d| (0..4).map(move |ndx| BitX::from(d & (1 << ndx) != 0))),
+            );
+            TypedBits { bits, kind }
+        };
+        let (path, expected, actual) =
+            divergent_part(value(true, [3, 5]), value(true, [3, 6]), Path::default());
+        assert_eq!(format!("o{path:?}"), "o.axi.data[1]");
+        assert_eq!(expected.kind, Kind::Bits(4));
+        assert_eq!(expected.bits, value(true, [5, 0]).bits[1..5]);
+        assert_eq!(actual.bits, value(true, [6, 0]).bits[1..5]);
+        let (path, _, _) =
+            divergent_part(value(true, [3, 5]), value(false, [0, 0]), Path::default());
+        assert_eq!(format!("{path:?}"), ".valid");
+    }
+}
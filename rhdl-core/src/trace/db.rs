@@ -2,8 +2,9 @@ use std::{
     any::Any,
     cell::RefCell,
     collections::{hash_map::Entry, BTreeMap},
+    fs::File,
     hash::{Hash, Hasher},
-    io::Write,
+    io::{Read, Seek, SeekFrom, Write},
 };
 
 use rhdl_trace_type::{TraceType, RTT};
@@ -11,28 +12,90 @@ use smallvec::SmallVec;
 
 use crate::Digital;
 
-use super::{bit::TraceBit, key::TraceKey, vcd::VCDWrite};
+use super::{
+    bit::TraceBit,
+    filter::{Selection, TraceFilter, TriggerState, Verdict},
+    fst::{FSTHandle, FSTWrite},
+    key::TraceKey,
+    reader::WaveformSignal,
+    toggle::{SignalToggles, ToggleCoverage},
+    vcd::VCDWrite,
+};
 
 type TimeSeriesHash = u32;
 
+// A streaming database keeps at most this many samples of each time series
+// in memory, and spills the rest to disk in chunks of this size.  The time
+// series are walked in blocks of the same size when they are written out.
+const BLOCK_SAMPLES: usize = 1024;
+
 // This trait object captures those methods that are needed to walk the time series.
 trait TimeSeriesWalk {
-    fn cursor(
-        &self,
-        details: &TimeSeriesDetails,
-        name: &str,
-        writer: &mut dyn VCDWrite,
-        start_time: u64,
-    ) -> Option<Cursor>;
-    fn advance_cursor(&self, cursor: &mut Cursor);
-    fn write_vcd(&self, cursor: &mut Cursor, writer: &mut dyn VCDWrite) -> std::io::Result<()>;
+    fn width(&self) -> u32;
+    // Decode the samples from `start` up to the end of their block.
+    fn load(&self, start: usize, spill: Option<&File>, block: &mut Block) -> std::io::Result<()>;
 }
 
-struct Cursor {
+// A block of samples of a time series, decoded into trace bits.
+#[derive(Default)]
+struct Block {
+    start: usize,
+    width: usize,
+    times: Vec<u64>,
+    bits: Vec<TraceBit>,
+}
+
+// A cursor walks a time series, and carries the code that identifies the
+// series in the output (the VCD id code, or the FST handle).
+struct Cursor<C> {
     next_time: Option<u64>,
     hash: TimeSeriesHash,
     ptr: usize,
-    code_as_bytes: Vec<u8>,
+    code: C,
+    block: Block,
+}
+
+impl<C> Cursor<C> {
+    // Fast forward the cursor to the first time in the range
+    fn new(
+        series: &dyn AnyTimeSeries,
+        spill: Option<&File>,
+        hash: TimeSeriesHash,
+        code: C,
+        start_time: u64,
+    ) -> std::io::Result<Option<Self>> {
+        let mut cursor = Cursor {
+            next_time: None,
+            hash,
+            ptr: 0,
+            code,
+            block: Block::default(),
+        };
+        cursor.seek(series, spill)?;
+        while let Some(time) = cursor.next_time {
+            if start_time <= time {
+                return Ok(Some(cursor));
+            }
+            cursor.advance(series, spill)?;
+        }
+        Ok(None)
+    }
+    fn advance(&mut self, series: &dyn AnyTimeSeries, spill: Option<&File>) -> std::io::Result<()> {
+        self.ptr += 1;
+        self.seek(series, spill)
+    }
+    fn seek(&mut self, series: &dyn AnyTimeSeries, spill: Option<&File>) -> std::io::Result<()> {
+        if self.ptr - self.block.start >= self.block.times.len() {
+            series.load(self.ptr, spill, &mut self.block)?;
+        }
+        self.next_time = self.block.times.get(self.ptr - self.block.start).copied();
+        Ok(())
+    }
+    fn value(&self) -> &[TraceBit] {
+        let width = self.block.width;
+        let ndx = self.ptr - self.block.start;
+        &self.block.bits[ndx * width..(ndx + 1) * width]
+    }
 }
 
 trait AsAny {
@@ -45,7 +108,11 @@ impl<T: Digital> AsAny for TimeSeries<T> {
     }
 }
 
-struct TimeSeries<T: Digital>(Vec<(u64, T)>);
+struct TimeSeries<T: Digital> {
+    // The file offsets of the chunks of samples spilled to disk.
+    chunks: Vec<u64>,
+    samples: Vec<(u64, T)>,
+}
 
 struct TimeSeriesDetails {
     hash: TimeSeriesHash,
@@ -54,19 +121,80 @@ struct TimeSeriesDetails {
     key: String,
 }
 
+impl TimeSeriesDetails {
+    // The full hierarchical name of the time series, e.g. `top.fifo.full`.
+    fn full_name(&self) -> String {
+        format!(
+            "{}.{}",
+            [&["top"], &self.path[..]].concat().join("."),
+            self.key
+        )
+    }
+}
+
+fn trace_code(bit: TraceBit) -> u8 {
+    match bit {
+        TraceBit::Zero => 0,
+        TraceBit::One => 1,
+        TraceBit::X => 2,
+        TraceBit::Z => 3,
+    }
+}
+
+fn trace_bit(code: u8) -> TraceBit {
+    match code & 3 {
+        0 => TraceBit::Zero,
+        1 => TraceBit::One,
+        2 => TraceBit::X,
+        _ => TraceBit::Z,
+    }
+}
+
 impl<T: Digital> TimeSeries<T> {
+    // Spilled samples are stored as the time, followed by the trace
+    // bits packed four to a byte.
+    const RECORD_LEN: usize = 8 + T::TRACE_BITS.div_ceil(4);
     fn new(time: u64, value: T) -> Self {
-        let mut values = Vec::with_capacity(1_000_000);
-        values.push((time, value));
-        TimeSeries(values)
+        TimeSeries {
+            chunks: vec![],
+            samples: vec![(time, value)],
+        }
     }
-    fn push_if_changed(&mut self, time: u64, value: T) {
-        if let Some((_, last_value)) = self.0.last() {
+    fn push_if_changed(
+        &mut self,
+        time: u64,
+        value: T,
+        spill: Option<&File>,
+    ) -> std::io::Result<()> {
+        if let Some((_, last_value)) = self.samples.last() {
             if last_value == &value {
-                return;
+                return Ok(());
             }
         }
-        self.0.push((time, value));
+        self.samples.push((time, value));
+        match spill {
+            Some(file) if self.samples.len() > BLOCK_SAMPLES => self.spill(file),
+            _ => Ok(()),
+        }
+    }
+    // Move the oldest samples to disk, keeping the last one to compare
+    // new values against.
+    fn spill(&mut self, mut file: &File) -> std::io::Result<()> {
+        let mut buf = Vec::with_capacity(BLOCK_SAMPLES * Self::RECORD_LEN);
+        for (time, value) in self.samples.drain(..BLOCK_SAMPLES) {
+            buf.extend(time.to_le_bytes());
+            let bits = value.trace();
+            buf.extend(bits.chunks(4).map(|nibble| {
+                nibble
+                    .iter()
+                    .enumerate()
+                    .fold(0, |byte, (ndx, bit)| byte | (trace_code(*bit) << (2 * ndx)))
+            }));
+        }
+        let offset = file.seek(SeekFrom::End(0))?;
+        file.write_all(&buf)?;
+        self.chunks.push(offset);
+        Ok(())
     }
 }
 
@@ -75,78 +203,125 @@ trait AnyTimeSeries: AsAny + TimeSeriesWalk {}
 impl<T: Digital> AnyTimeSeries for TimeSeries<T> {}
 
 impl<T: Digital> TimeSeriesWalk for TimeSeries<T> {
-    fn cursor(
-        &self,
-        details: &TimeSeriesDetails,
-        name: &str,
-        writer: &mut dyn VCDWrite,
-        start_time: u64,
-    ) -> Option<Cursor> {
-        let name_sanitized = name.replace("::", "__");
-        let code = writer
-            .add_wire((T::TRACE_BITS as u32).max(1), &name_sanitized)
-            .ok()?;
-        self.0
-            .first()
-            .map(|x| Cursor {
-                next_time: Some(x.0),
-                hash: details.hash,
-                ptr: 0,
-                code_as_bytes: code.to_string().into_bytes(),
-            })
-            // Fast forward the cursor to the first time in the range
-            .and_then(|mut cursor| {
-                while let Some((time, _)) = self.0.get(cursor.ptr) {
-                    if start_time <= *time {
-                        cursor.next_time = Some(*time);
-                        return Some(cursor);
-                    }
-                    cursor.ptr += 1;
-                }
-                None
-            })
+    fn width(&self) -> u32 {
+        (T::TRACE_BITS as u32).max(1)
     }
-    fn advance_cursor(&self, cursor: &mut Cursor) {
-        cursor.ptr += 1;
-        if let Some((time, _)) = self.0.get(cursor.ptr) {
-            cursor.next_time = Some(*time);
-        } else {
-            cursor.next_time = None;
-        }
-    }
-    fn write_vcd(&self, cursor: &mut Cursor, writer: &mut dyn VCDWrite) -> std::io::Result<()> {
-        let mut sbuf = SmallVec::<[u8; 64]>::new();
-        if let Some((_time, value)) = self.0.get(cursor.ptr) {
-            if T::BITS != 0 {
-                sbuf.push(b'b');
-                sbuf.extend(value.trace().into_iter().rev().map(|v| match v {
-                    TraceBit::Zero => b'0',
-                    TraceBit::One => b'1',
-                    TraceBit::X => b'x',
-                    TraceBit::Z => b'z',
-                }));
-                sbuf.push(b' ');
-                writer.write_all(&sbuf[..])?;
-                writer.write_all(&cursor.code_as_bytes)?;
-                writer.write_all(b"\n")?;
+    fn load(&self, start: usize, spill: Option<&File>, block: &mut Block) -> std::io::Result<()> {
+        block.start = start;
+        block.width = T::TRACE_BITS;
+        block.times.clear();
+        block.bits.clear();
+        let spilled = self.chunks.len() * BLOCK_SAMPLES;
+        if start >= spilled {
+            for (time, value) in self
+                .samples
+                .iter()
+                .skip(start - spilled)
+                .take(BLOCK_SAMPLES)
+            {
+                block.times.push(*time);
+                block.bits.extend(value.trace());
             }
-            self.advance_cursor(cursor);
-            Ok(())
-        } else {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::UnexpectedEof,
-                "No more values",
-            ))
+            return Ok(());
+        }
+        let mut file = spill.ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "Missing trace spill file")
+        })?;
+        let first = start % BLOCK_SAMPLES;
+        let mut buf = vec![0; (BLOCK_SAMPLES - first) * Self::RECORD_LEN];
+        let offset = self.chunks[start / BLOCK_SAMPLES] + (first * Self::RECORD_LEN) as u64;
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut buf)?;
+        for record in buf.chunks(Self::RECORD_LEN) {
+            block
+                .times
+                .push(u64::from_le_bytes(record[..8].try_into().unwrap()));
+            block.bits.extend(
+                (0..T::TRACE_BITS).map(|ndx| trace_bit(record[8 + ndx / 4] >> (2 * (ndx % 4)))),
+            );
         }
+        Ok(())
     }
 }
 
+fn write_vcd_value(
+    writer: &mut dyn VCDWrite,
+    code: &[u8],
+    value: &[TraceBit],
+) -> std::io::Result<()> {
+    if value.is_empty() {
+        return Ok(());
+    }
+    let mut sbuf = SmallVec::<[u8; 64]>::new();
+    sbuf.push(b'b');
+    sbuf.extend(value.iter().rev().map(|v| match v {
+        TraceBit::Zero => b'0',
+        TraceBit::One => b'1',
+        TraceBit::X => b'x',
+        TraceBit::Z => b'z',
+    }));
+    sbuf.push(b' ');
+    writer.write_all(&sbuf[..])?;
+    writer.write_all(code)?;
+    writer.write_all(b"\n")
+}
+
+// The parts of a waveform writer that declare the scopes and signals.
+trait WaveformScope {
+    type Code;
+    fn open_scope(&mut self, name: &str) -> std::io::Result<()>;
+    fn close_scope(&mut self) -> std::io::Result<()>;
+    fn declare_wire(&mut self, width: u32, name: &str) -> std::io::Result<Self::Code>;
+}
+
+impl<W: Write> WaveformScope for vcd::Writer<W> {
+    type Code = Vec<u8>;
+    fn open_scope(&mut self, name: &str) -> std::io::Result<()> {
+        VCDWrite::add_module(self, name)
+    }
+    fn close_scope(&mut self) -> std::io::Result<()> {
+        VCDWrite::upscope(self)
+    }
+    fn declare_wire(&mut self, width: u32, name: &str) -> std::io::Result<Vec<u8>> {
+        VCDWrite::add_wire(self, width, name).map(|code| code.to_string().into_bytes())
+    }
+}
+
+impl<W: Write + Seek> WaveformScope for super::fst::Writer<W> {
+    type Code = FSTHandle;
+    fn open_scope(&mut self, name: &str) -> std::io::Result<()> {
+        FSTWrite::add_module(self, name)
+    }
+    fn close_scope(&mut self) -> std::io::Result<()> {
+        FSTWrite::upscope(self)
+    }
+    fn declare_wire(&mut self, width: u32, name: &str) -> std::io::Result<FSTHandle> {
+        FSTWrite::add_wire(self, width, name)
+    }
+}
+
+// A sample that is stored later, if at all.
+type HeldSample = Box<dyn FnOnce(&mut TraceDB)>;
+
+// The events of a walk over the time series, in time order.
+enum Change<'a, C> {
+    Time(u64),
+    Value(&'a Cursor<C>),
+}
+
 #[derive(Default)]
 pub struct TraceDB {
     db: fnv::FnvHashMap<TimeSeriesHash, Box<dyn AnyTimeSeries>>,
     details: fnv::FnvHashMap<TimeSeriesHash, TimeSeriesDetails>,
     path: Vec<&'static str>,
     time: u64,
+    spill: Option<File>,
+    filter: Option<TraceFilter>,
+    selections: fnv::FnvHashMap<TimeSeriesHash, Selection>,
+    triggers: TriggerState,
+    // Samples of the current time step that are stored only if the start
+    // trigger of the filter fires later in the step.
+    held: Vec<HeldSample>,
 }
 
 impl TraceDB {
@@ -162,98 +337,133 @@ impl TraceDB {
         key.hash(&mut hasher);
         hasher.finish() as TimeSeriesHash
     }
-    fn trace(&mut self, key: impl TraceKey, value: &impl Digital) {
+    // Decides what to do with this sample, according to the filter (if any).
+    fn verdict(
+        &mut self,
+        hash: TimeSeriesHash,
+        key: &impl TraceKey,
+        value: &impl Digital,
+    ) -> Verdict {
+        let Some(filter) = &self.filter else {
+            return Verdict::Store;
+        };
+        let selection = *self.selections.entry(hash).or_insert_with(|| {
+            let name = format!(
+                "{}.{}",
+                [&["top"], &self.path[..]].concat().join("."),
+                key.as_string()
+            );
+            filter.select(&name)
+        });
+        filter.observe(&mut self.triggers, selection, self.time, value);
+        filter.verdict(&self.triggers, selection, self.time)
+    }
+    fn trace<T: Digital>(&mut self, key: impl TraceKey, value: &T) {
         let hash = self.key_hash(&key);
+        match self.verdict(hash, &key, value) {
+            Verdict::Store => {
+                // The start trigger fired in this time step, so the samples
+                // traced before it in this step are stored too.
+                for held in std::mem::take(&mut self.held) {
+                    held(self);
+                }
+                self.store(hash, *value, self.details_for(hash, &key, value));
+            }
+            Verdict::Hold => {
+                let details = self.details_for(hash, &key, value);
+                let value = *value;
+                self.held.push(Box::new(move |db: &mut TraceDB| {
+                    db.store(hash, value, details)
+                }));
+            }
+            Verdict::Skip => {}
+        }
+    }
+    fn details_for(
+        &self,
+        hash: TimeSeriesHash,
+        key: &impl TraceKey,
+        value: &impl Digital,
+    ) -> Option<TimeSeriesDetails> {
+        (!self.details.contains_key(&hash)).then(|| TimeSeriesDetails {
+            hash,
+            path: self.path.clone(),
+            key: key.as_string(),
+            trace_type: value.trace_type(),
+        })
+    }
+    // Appends the sample to its time series.  The details are only
+    // needed (and only computed) the first time the series is seen.
+    fn store<T: Digital>(
+        &mut self,
+        hash: TimeSeriesHash,
+        value: T,
+        details: Option<TimeSeriesDetails>,
+    ) {
         match self.db.entry(hash) {
             Entry::Occupied(mut entry) => {
+                let key = &self.details[&hash].key;
                 entry
                     .get_mut()
                     .as_any_mut()
                     .downcast_mut::<TimeSeries<_>>()
-                    .unwrap_or_else(|| panic!("Type mismatch for {}", key.as_string()))
-                    .push_if_changed(self.time, *value);
+                    .unwrap_or_else(|| panic!("Type mismatch for {key}"))
+                    .push_if_changed(self.time, value, self.spill.as_ref())
+                    .unwrap_or_else(|err| panic!("Unable to spill trace of {key}: {err}"));
             }
             Entry::Vacant(entry) => {
-                let details = TimeSeriesDetails {
-                    hash,
-                    path: self.path.clone(),
-                    key: key.as_string().to_string(),
-                    trace_type: value.trace_type(),
-                };
+                let details = details.expect("details are given for a new time series");
                 self.details.insert(hash, details);
-                entry.insert(Box::new(TimeSeries::new(self.time, *value)));
+                entry.insert(Box::new(TimeSeries::new(self.time, value)));
             }
         }
     }
-    fn setup_cursor<W: Write>(
+    fn series(&self, hash: TimeSeriesHash) -> &dyn AnyTimeSeries {
+        self.db.get(&hash).unwrap().as_ref()
+    }
+    fn setup_cursor<S: WaveformScope>(
         &self,
         name: &str,
         details: &TimeSeriesDetails,
-        writer: &mut vcd::Writer<W>,
+        writer: &mut S,
         start_time: u64,
-    ) -> Option<Cursor> {
-        self.db
-            .get(&details.hash)
-            .and_then(|series| series.cursor(details, name, writer, start_time))
-    }
-    fn write_advance_cursor<W: Write>(
-        &self,
-        cursor: &mut Cursor,
-        writer: &mut vcd::Writer<W>,
-    ) -> std::io::Result<()> {
-        let series = self.db.get(&cursor.hash).unwrap();
-        series.write_vcd(cursor, writer)
-    }
-    fn advance_cursor(&self, cursor: &mut Cursor) {
-        let series = self.db.get(&cursor.hash).unwrap();
-        series.advance_cursor(cursor);
+    ) -> std::io::Result<Option<Cursor<S::Code>>> {
+        let Some(series) = self.db.get(&details.hash) else {
+            return Ok(None);
+        };
+        let series = series.as_ref();
+        let name_sanitized = name.replace("::", "__");
+        let code = writer.declare_wire(series.width(), &name_sanitized)?;
+        Cursor::new(series, self.spill.as_ref(), details.hash, code, start_time)
     }
-    fn setup_cursors<W: Write>(
+    fn setup_cursors<S: WaveformScope>(
         &self,
         name: &str,
         scope: &Scope,
-        cursors: &mut Vec<Cursor>,
-        writer: &mut vcd::Writer<W>,
+        cursors: &mut Vec<Cursor<S::Code>>,
+        writer: &mut S,
         start_time: u64,
     ) -> std::io::Result<()> {
-        writer.add_module(name)?;
+        writer.open_scope(name)?;
         for (name, hash) in &scope.signals {
             let details = self.details.get(hash).unwrap();
-            if let Some(cursor) = self.setup_cursor(name, details, writer, start_time) {
+            if let Some(cursor) = self.setup_cursor(name, details, writer, start_time)? {
                 cursors.push(cursor);
             }
         }
         for (name, child) in &scope.children {
             self.setup_cursors(name, child, cursors, writer, start_time)?;
         }
-        writer.upscope()?;
+        writer.close_scope()?;
         Ok(())
     }
-    fn collect_rtt_info(&self) -> RTT {
-        RTT::TraceInfo(
-            self.details
-                .values()
-                .map(|details| {
-                    let name = format!(
-                        "{}.{}",
-                        [&["top"], &details.path[..]].concat().join("."),
-                        details.key
-                    );
-                    let ty = details.trace_type.clone();
-                    (name, ty)
-                })
-                .collect(),
-        )
-    }
-    pub fn dump_vcd<W: Write>(
+    // Declare the hierarchy of signals, and set up a cursor for each of
+    // them.
+    fn declare<S: WaveformScope>(
         &self,
-        w: W,
+        writer: &mut S,
         time_set: Option<&fnv::FnvHashSet<u64>>,
-    ) -> std::io::Result<()> {
-        let mut writer = vcd::Writer::new(w);
-        writer.timescale(1, vcd::TimescaleUnit::PS)?;
-        let rtt = self.collect_rtt_info();
-        writer.comment(&ron::ser::to_string(&rtt).unwrap())?;
+    ) -> std::io::Result<Vec<Cursor<S::Code>>> {
         let root_scope = hierarchical_walk(self.details.iter().map(|(hash, details)| TSItem {
             path: &details.path,
             name: &details.key,
@@ -261,9 +471,18 @@ impl TraceDB {
         }));
         let mut cursors = vec![];
         let min_time = time_set.and_then(|x| x.iter().copied().min()).unwrap_or(0);
-        self.setup_cursors("top", &root_scope, &mut cursors, &mut writer, min_time)?;
-        writer.enddefinitions()?;
-        writer.timestamp(0)?;
+        self.setup_cursors("top", &root_scope, &mut cursors, writer, min_time)?;
+        Ok(cursors)
+    }
+    // Walk the cursors in time order, passing each time in the time set,
+    // and the values that change at that time, to `emit`.
+    fn replay<C>(
+        &self,
+        cursors: &mut [Cursor<C>],
+        time_set: Option<&fnv::FnvHashSet<u64>>,
+        mut emit: impl FnMut(Change<C>) -> std::io::Result<()>,
+    ) -> std::io::Result<()> {
+        emit(Change::Time(0))?;
         let mut current_time = 0;
         let mut keep_running = true;
         while keep_running {
@@ -272,13 +491,12 @@ impl TraceDB {
             let mut found_match = true;
             while found_match {
                 found_match = false;
-                for cursor in &mut cursors {
+                for cursor in cursors.iter_mut() {
                     if cursor.next_time == Some(current_time) {
                         if time_set.map(|x| x.contains(&current_time)).unwrap_or(true) {
-                            self.write_advance_cursor(cursor, &mut writer)?;
-                        } else {
-                            self.advance_cursor(cursor);
+                            emit(Change::Value(cursor))?;
                         }
+                        cursor.advance(self.series(cursor.hash), self.spill.as_ref())?;
                         found_match = true;
                     } else if let Some(time) = cursor.next_time {
                         next_time = next_time.min(time);
@@ -291,12 +509,113 @@ impl TraceDB {
             if next_time != !0 {
                 current_time = next_time;
                 if time_set.map(|x| x.contains(&current_time)).unwrap_or(true) {
-                    writer.timestamp(current_time)?;
+                    emit(Change::Time(current_time))?;
                 }
             }
         }
         Ok(())
     }
+    pub fn collect_rtt_info(&self) -> RTT {
+        RTT::TraceInfo(
+            self.details
+                .values()
+                .map(|details| (details.full_name(), details.trace_type.clone()))
+                .collect(),
+        )
+    }
+    // Count the toggles of each bit of each time series.
+    pub fn toggle_coverage(&self) -> std::io::Result<ToggleCoverage> {
+        let mut coverage = ToggleCoverage::default();
+        let spill = self.spill.as_ref();
+        for details in self.details.values() {
+            let series = self.series(details.hash);
+            let mut toggles = SignalToggles::new(details.trace_type.clone());
+            if let Some(mut cursor) = Cursor::new(series, spill, details.hash, (), 0)? {
+                let mut last = vec![];
+                while cursor.next_time.is_some() {
+                    toggles.observe(&last, cursor.value());
+                    last.clear();
+                    last.extend_from_slice(cursor.value());
+                    cursor.advance(series, spill)?;
+                }
+            }
+            // Signals without bits (e.g., of an empty type) cannot toggle.
+            if !toggles.bits.is_empty() {
+                coverage.signals.insert(details.full_name(), toggles);
+            }
+        }
+        Ok(coverage)
+    }
+    // The signals of the trace, as they would be read back from a VCD or
+    // FST file written by `dump_vcd` or `dump_fst`.  The time series are
+    // walked block by block, so a streaming database is never loaded into
+    // memory as a whole.
+    pub(crate) fn waveform_signals(&self) -> std::io::Result<BTreeMap<String, WaveformSignal>> {
+        let mut signals = BTreeMap::new();
+        let spill = self.spill.as_ref();
+        for details in self.details.values() {
+            let series = self.series(details.hash);
+            let mut signal = WaveformSignal {
+                width: series.width() as usize,
+                trace_type: Some(details.trace_type.clone()),
+                changes: vec![],
+            };
+            if let Some(mut cursor) = Cursor::new(series, spill, details.hash, (), 0)? {
+                while let Some(time) = cursor.next_time {
+                    // Signals without bits have no values in the waveform.
+                    if !cursor.value().is_empty() {
+                        match signal.changes.last_mut() {
+                            Some((last, value)) if *last == time => {
+                                value.clear();
+                                value.extend_from_slice(cursor.value());
+                            }
+                            _ => signal.changes.push((time, cursor.value().to_vec())),
+                        }
+                    }
+                    cursor.advance(series, spill)?;
+                }
+            }
+            signals.insert(details.full_name().replace("::", "__"), signal);
+        }
+        Ok(signals)
+    }
+    pub fn dump_vcd<W: Write>(
+        &self,
+        w: W,
+        time_set: Option<&fnv::FnvHashSet<u64>>,
+    ) -> std::io::Result<()> {
+        let mut writer = vcd::Writer::new(w);
+        writer.timescale(1, vcd::TimescaleUnit::PS)?;
+        let rtt = self.collect_rtt_info();
+        writer.comment(&ron::ser::to_string(&rtt).unwrap())?;
+        let mut cursors = self.declare(&mut writer, time_set)?;
+        writer.enddefinitions()?;
+        self.replay(&mut cursors, time_set, |change| match change {
+            Change::Time(time) => writer.timestamp(time),
+            Change::Value(cursor) => write_vcd_value(&mut writer, &cursor.code, cursor.value()),
+        })
+    }
+    // Write the trace as an FST file, which is much smaller than the
+    // equivalent VCD for long runs.
+    pub fn dump_fst<W: Write + Seek>(
+        &self,
+        w: W,
+        time_set: Option<&fnv::FnvHashSet<u64>>,
+    ) -> std::io::Result<()> {
+        let mut writer = super::fst::Writer::new(w, -12)?;
+        // The type information is too large to embed for very big designs.
+        let rtt = ron::ser::to_string(&self.collect_rtt_info()).unwrap();
+        if rtt.len() <= super::fst::MAX_COMMENT_LENGTH {
+            writer.comment(&rtt)?;
+        }
+        let mut cursors = self.declare(&mut writer, time_set)?;
+        self.replay(&mut cursors, time_set, |change| match change {
+            Change::Time(time) => writer.timestamp(time),
+            Change::Value(cursor) if cursor.value().is_empty() => Ok(()),
+            Change::Value(cursor) => writer.change(cursor.code, cursor.value()),
+        })?;
+        writer.finish()
+    }
 }
 
 struct TSItem<'a> {
@@ -330,6 +649,7 @@ fn hierarchical_walk<'a>(paths: impl Iterator<Item = TSItem<'a>>) -> Scope {
 thread_local! {
     static DB: RefCell<Option<TraceDB>> = const { RefCell::new(None) };
 }
+#[must_use]
 pub struct TraceDBGuard;
 
 impl TraceDBGuard {
@@ -348,12 +668,45 @@ impl Drop for TraceDBGuard {
     }
 }
 
-#[must_use]
 pub fn trace_init_db() -> TraceDBGuard {
     DB.replace(Some(TraceDB::default()));
     TraceDBGuard {}
 }
 
+// Like `trace_init_db`, but only the signals and times selected by the
+// filter are stored.
+pub fn trace_init_db_with_filter(filter: TraceFilter) -> TraceDBGuard {
+    DB.replace(Some(TraceDB {
+        filter: Some(filter),
+        ..Default::default()
+    }));
+    TraceDBGuard {}
+}
+
+// Like `trace_init_db`, but the time series are spilled to a temporary
+// file as they grow, so that the memory used by the trace does not depend
+// on the length of the simulation.
+pub fn trace_init_streaming_db() -> std::io::Result<TraceDBGuard> {
+    let spill = tempfile::tempfile()?;
+    DB.replace(Some(TraceDB {
+        spill: Some(spill),
+        ..Default::default()
+    }));
+    Ok(TraceDBGuard {})
+}
+
+// Like `trace_init_streaming_db`, but only the signals and times selected
+// by the filter are stored.
+pub fn trace_init_streaming_db_with_filter(filter: TraceFilter) -> std::io::Result<TraceDBGuard> {
+    let spill = tempfile::tempfile()?;
+    DB.replace(Some(TraceDB {
+        spill: Some(spill),
+        filter: Some(filter),
+        ..Default::default()
+    }));
+    Ok(TraceDBGuard {})
+}
+
 pub fn with_trace_db<F: FnMut(&TraceDB)>(mut f: F) {
     DB.with(|db| {
         let db = db.borrow();
@@ -385,6 +738,9 @@ pub fn trace_time(time: u64) {
     DB.with(|db| {
         let mut db = db.borrow_mut();
         if let Some(db) = db.as_mut() {
+            if db.time != time {
+                db.held.clear();
+            }
             db.time = time;
         }
     })
@@ -425,7 +781,6 @@ mod tests {
         let mut vcd = vec![];
         let db = guard.take();
         db.dump_vcd(&mut vcd, None).unwrap();
-        std::fs::write("test.vcd", vcd).unwrap();
     }
 
     #[test]
@@ -557,7 +912,6 @@ mod tests {
         let mut vcd = vec![];
         let db = guard.take();
         db.dump_vcd(&mut vcd, None).unwrap();
-        std::fs::write("test_enum.vcd", vcd).unwrap();
     }
 
     #[test]
@@ -575,6 +929,246 @@ mod tests {
         let mut vcd = vec![];
         let db = guard.take();
         db.dump_vcd(&mut vcd, None).unwrap();
-        std::fs::write("test_nested_paths.vcd", vcd).unwrap();
+    }
+
+    // The value changes in an FST file, by the full name of the signal.
+    pub(crate) fn fst_changes(fst: Vec<u8>) -> BTreeMap<String, Vec<(u64, String)>> {
+        let mut reader = fst_reader::FstReader::open(std::io::Cursor::new(fst)).unwrap();
+        let mut scope = vec![];
+        let mut names = BTreeMap::new();
+        reader
+            .read_hierarchy(|entry| match entry {
+                fst_reader::FstHierarchyEntry::Scope { name, .. } => scope.push(name),
+                fst_reader::FstHierarchyEntry::UpScope => {
+                    scope.pop();
+                }
+                fst_reader::FstHierarchyEntry::Var { name, handle, .. } => {
+                    names.insert(handle.get_index(), format!("{}.{name}", scope.join(".")));
+                }
+                _ => {}
+            })
+            .unwrap();
+        let mut changes = BTreeMap::<String, Vec<_>>::new();
+        reader
+            .read_signals(&fst_reader::FstFilter::all(), |time, handle, value| {
+                let fst_reader::FstSignalValue::String(value) = value else {
+                    panic!("Unexpected real value");
+                };
+                changes
+                    .entry(names[&handle.get_index()].clone())
+                    .or_default()
+                    .push((time, String::from_utf8(value.to_vec()).unwrap()));
+            })
+            .unwrap();
+        changes
+    }
+
+    #[test]
+    fn test_fst_write() {
+        let guard = trace_init_db();
+        // Enough time steps to fill more than one section
+        for i in 0..70_000 {
+            trace_time(i * 1000);
+            trace_push_path("fn1");
+            trace("a", &(i % 2 == 0));
+            trace_pop_path();
+            trace("b", &b6((i / 7 % 64) as u128));
+        }
+        let mut fst = std::io::Cursor::new(vec![]);
+        let db = guard.take();
+        db.dump_fst(&mut fst, None).unwrap();
+        let changes = fst_changes(fst.into_inner());
+        let a = &changes["top.fn1.a"];
+        assert_eq!(a.len(), 70_000);
+        assert!(a
+            .iter()
+            .enumerate()
+            .all(|(i, (time, value))| *time == i as u64 * 1000
+                && *value == if i % 2 == 0 { "1" } else { "0" }));
+        let b = &changes["top.b"];
+        assert_eq!(b.len(), 10_000);
+        assert_eq!(b[0], (0, "000000".into()));
+        assert_eq!(b[9_999], (69_993_000, format!("{:06b}", 9_999 % 64)));
+    }
+
+    #[test]
+    fn test_fst_four_state_values() {
+        use TraceBit::*;
+        let mut fst = std::io::Cursor::new(vec![]);
+        let mut writer = crate::trace::fst::Writer::new(&mut fst, -12).unwrap();
+        writer.add_module("top").unwrap();
+        let bit = writer.add_wire(1, "bit").unwrap();
+        let word = writer.add_wire(3, "word").unwrap();
+        writer.upscope().unwrap();
+        for (time, value) in [
+            (0, [X, One, Z]),
+            (10, [Z, Zero, One]),
+            (20, [One, One, Zero]),
+        ] {
+            writer.timestamp(time).unwrap();
+            writer.change(bit, &value[..1]).unwrap();
+            writer.change(word, &value).unwrap();
+        }
+        writer.finish().unwrap();
+        let changes = fst_changes(fst.into_inner());
+        assert_eq!(
+            changes["top.bit"],
+            vec![(0, "x".into()), (10, "z".into()), (20, "1".into())]
+        );
+        assert_eq!(
+            changes["top.word"],
+            vec![(0, "z1x".into()), (10, "10z".into()), (20, "011".into())]
+        );
+    }
+
+    #[test]
+    fn test_fst_with_time_set() {
+        let guard = trace_init_db();
+        for i in 0..10 {
+            trace_time(i * 1000);
+            trace("a", &b6(i as u128));
+        }
+        let time_set = [3000, 4000, 7000].into_iter().collect();
+        let mut fst = std::io::Cursor::new(vec![]);
+        let db = guard.take();
+        db.dump_fst(&mut fst, Some(&time_set)).unwrap();
+        let changes = fst_changes(fst.into_inner());
+        assert_eq!(
+            changes["top.a"],
+            vec![
+                (3000, "000011".into()),
+                (4000, "000100".into()),
+                (7000, "000111".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_streaming_db_matches_in_memory_db() {
+        fn run() {
+            for i in 0..5_000 {
+                trace_time(i * 1000);
+                trace_push_path("fn1");
+                trace("a", &(i % 2 == 0));
+                trace_pop_path();
+                trace("b", &b6((i / 3 % 64) as u128));
+                trace(
+                    "z",
+                    &crate::BitZ::<rhdl_typenum::W5> {
+                        value: b5((i % 32) as u128),
+                        mask: b5((i / 5 % 32) as u128),
+                    },
+                );
+            }
+        }
+        let time_set = (0..5_000).step_by(3).map(|i| i * 1000).collect();
+        let guard = trace_init_db();
+        run();
+        let db = guard.take();
+        let (mut vcd, mut fst) = (vec![], std::io::Cursor::new(vec![]));
+        db.dump_vcd(&mut vcd, Some(&time_set)).unwrap();
+        db.dump_fst(&mut fst, None).unwrap();
+        let guard = trace_init_streaming_db().unwrap();
+        run();
+        let db = guard.take();
+        let (mut streamed_vcd, mut streamed_fst) = (vec![], std::io::Cursor::new(vec![]));
+        db.dump_vcd(&mut streamed_vcd, Some(&time_set)).unwrap();
+        db.dump_fst(&mut streamed_fst, None).unwrap();
+        assert!(vcd == streamed_vcd);
+        assert!(fst.into_inner() == streamed_fst.into_inner());
+    }
+
+    #[test]
+    fn test_trace_filter() {
+        fn run(filter: TraceFilter) -> BTreeMap<String, Vec<(u64, String)>> {
+            let changes = run_with(trace_init_db_with_filter(filter.clone()));
+            let streamed = run_with(trace_init_streaming_db_with_filter(filter).unwrap());
+            assert_eq!(changes, streamed);
+            changes
+        }
+        fn run_with(guard: TraceDBGuard) -> BTreeMap<String, Vec<(u64, String)>> {
+            for i in 0..20 {
+                trace_time(i * 10);
+                trace_push_path("fifo");
+                trace_push_path("inner");
+                trace("write_enable", &(i % 2 == 0));
+                trace("read_enable", &(i % 2 == 1));
+                trace_pop_path();
+                trace_pop_path();
+                trace("count", &b6(i as u128));
+            }
+            let mut fst = std::io::Cursor::new(vec![]);
+            guard.take().dump_fst(&mut fst, None).unwrap();
+            fst_changes(fst.into_inner())
+        }
+        let changes = run(TraceFilter::default().path("top.fifo.*.write_*"));
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes["top.fifo.inner.write_enable"].len(), 20);
+        let changes = run(TraceFilter::default().path("top.count").window(50..80));
+        assert_eq!(
+            changes["top.count"],
+            vec![
+                (50, "000101".into()),
+                (60, "000110".into()),
+                (70, "000111".into())
+            ]
+        );
+        let changes = run(TraceFilter::default()
+            .path("top.count")
+            .start_when("top.count", b6(3))
+            .stop_when("top.count", b6(5)));
+        assert_eq!(
+            changes["top.count"],
+            vec![
+                (30, "000011".into()),
+                (40, "000100".into()),
+                (50, "000101".into())
+            ]
+        );
+        // The samples traced before the start trigger in its time step
+        // are stored, as are the samples after the stop trigger in its step.
+        let changes = run(TraceFilter::default()
+            .start_when("top.count", b6(3))
+            .stop_when("top.fifo.inner.write_enable", true));
+        assert_eq!(
+            changes["top.fifo.inner.write_enable"],
+            vec![(30, "0".into()), (40, "1".into())]
+        );
+        assert_eq!(
+            changes["top.count"],
+            vec![(30, "000011".into()), (40, "000100".into())]
+        );
+    }
+
+    #[test]
+    fn test_toggle_coverage() {
+        // An address that only reaches 0..8, and a strobe that only rises
+        fn run(count: u128, streaming: bool) -> ToggleCoverage {
+            let guard = if streaming {
+                trace_init_streaming_db().unwrap()
+            } else {
+                trace_init_db()
+            };
+            for i in 0..count {
+                trace_time(i as u64 * 10);
+                trace_push_path("axi");
+                trace("addr", &(b6(i % 8), i > 2));
+                trace_pop_path();
+            }
+            guard.take().toggle_coverage().unwrap()
+        }
+        let coverage = run(3_000, true);
+        assert_eq!(coverage.summary(), (3, 7));
+        let addr = &coverage.signals["top.axi.addr"];
+        assert_eq!(addr.bits[0].rises, 1_500);
+        assert_eq!(addr.bits[3].stuck_at(), Some(TraceBit::Zero));
+        assert_eq!((addr.bits[6].rises, addr.bits[6].falls), (1, 0));
+        let mut coverage = run(3, false);
+        assert_eq!(coverage.summary(), (1, 7));
+        coverage.merge(&run(3_000, false));
+        assert_eq!(coverage.signals["top.axi.addr"].bits[0].rises, 1_501);
+        let report = coverage.report();
+        assert!(report.contains("! top.axi.addr.0: 3 of 6 bits toggled, stuck at 0 [5:3]\n"));
+        assert!(report.contains("! top.axi.addr.1: 0 of 1 bits toggled, toggled one way [0]\n"));
     }
 }
@@ -0,0 +1,180 @@
+use std::ops::Range;
+
+use crate::Digital;
+
+use super::bit::TraceBit;
+
+/// A filter that decides which of the traced signals are stored in the
+/// trace database.  Signals are named by their full hierarchical path, as
+/// they appear in the waveform, e.g. `top.fifo.write_enable`.  Patterns are
+/// matched against these names, where `*` matches any run of characters
+/// within one level of the hierarchy, `**` matches across levels, and `?`
+/// matches a single character.
+///
+/// Samples can also be limited in time, either by fixed windows, or by a
+/// pair of triggers.  Both triggers include the time step in which they
+/// fire, so that the recording covers the closed interval from the step
+/// in which the start trigger fires up to and including the step in which
+/// the stop trigger fires.
+#[derive(Clone, Debug, Default)]
+pub struct TraceFilter {
+    patterns: Vec<String>,
+    windows: Vec<Range<u64>>,
+    start: Option<Trigger>,
+    stop: Option<Trigger>,
+}
+
+#[derive(Clone, Debug)]
+struct Trigger {
+    pattern: String,
+    value: Vec<TraceBit>,
+}
+
+// How the filter treats one signal.  This only depends on the name of
+// the signal, so it is computed once per time series.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct Selection {
+    pub(crate) stored: bool,
+    pub(crate) starts: bool,
+    pub(crate) stops: bool,
+}
+
+// The state of the triggers of a filter, as the simulation runs.
+#[derive(Copy, Clone, Debug, Default)]
+pub(crate) struct TriggerState {
+    started: Option<u64>,
+    stopped: Option<u64>,
+}
+
+// What becomes of one sample that passed through the filter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Verdict {
+    Store,
+    // The sample is stored if the start trigger fires later in the
+    // same time step.
+    Hold,
+    Skip,
+}
+
+impl TraceFilter {
+    /// Store the signals whose names match the pattern.  If no patterns
+    /// are given, all signals are stored.
+    pub fn path(mut self, pattern: &str) -> Self {
+        self.patterns.push(pattern.into());
+        self
+    }
+    /// Store the samples that fall in the window.  If no windows are
+    /// given, samples are stored at all times.
+    pub fn window(mut self, window: Range<u64>) -> Self {
+        self.windows.push(window);
+        self
+    }
+    /// Start storing samples when a signal matching the pattern takes on
+    /// the given value.  The time step in which this happens is stored,
+    /// including the samples traced before the trigger signal in that step.
+    pub fn start_when(mut self, pattern: &str, value: impl Digital) -> Self {
+        self.start = Some(Trigger {
+            pattern: pattern.into(),
+            value: value.trace(),
+        });
+        self
+    }
+    /// Stop storing samples when a signal matching the pattern takes on
+    /// the given value.  The time step in which this happens is the last
+    /// one stored.  If there is a start trigger, this trigger is only
+    /// armed once the start trigger has fired.
+    pub fn stop_when(mut self, pattern: &str, value: impl Digital) -> Self {
+        self.stop = Some(Trigger {
+            pattern: pattern.into(),
+            value: value.trace(),
+        });
+        self
+    }
+    pub(crate) fn select(&self, name: &str) -> Selection {
+        Selection {
+            stored: self.patterns.is_empty()
+                || self
+                    .patterns
+                    .iter()
+                    .any(|pattern| glob_match(pattern.as_bytes(), name.as_bytes())),
+            starts: self
+                .start
+                .as_ref()
+                .is_some_and(|trigger| glob_match(trigger.pattern.as_bytes(), name.as_bytes())),
+            stops: self
+                .stop
+                .as_ref()
+                .is_some_and(|trigger| glob_match(trigger.pattern.as_bytes(), name.as_bytes())),
+        }
+    }
+    // Update the triggers with a value traced at the given time, by a
+    // signal with the given selection.
+    pub(crate) fn observe(
+        &self,
+        state: &mut TriggerState,
+        selection: Selection,
+        time: u64,
+        value: &impl Digital,
+    ) {
+        if let (true, None, Some(trigger)) = (selection.starts, state.started, &self.start) {
+            if value.trace() == trigger.value {
+                state.started = Some(time);
+            }
+        }
+        let armed = self.start.is_none() || state.started.is_some();
+        if let (true, true, None, Some(trigger)) =
+            (armed, selection.stops, state.stopped, &self.stop)
+        {
+            if value.trace() == trigger.value {
+                state.stopped = Some(time);
+            }
+        }
+    }
+    // Decides what to do with a sample taken at the given time, by a
+    // signal with the given selection.
+    pub(crate) fn verdict(&self, state: &TriggerState, selection: Selection, time: u64) -> Verdict {
+        if !selection.stored
+            || !(self.windows.is_empty() || self.windows.iter().any(|w| w.contains(&time)))
+            || state.stopped.is_some_and(|t| t < time)
+        {
+            return Verdict::Skip;
+        }
+        if self.start.is_none() || state.started.is_some() {
+            Verdict::Store
+        } else {
+            Verdict::Hold
+        }
+    }
+}
+
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    match pattern {
+        [] => name.is_empty(),
+        [b'*', b'*', rest @ ..] => (0..=name.len()).any(|ndx| glob_match(rest, &name[ndx..])),
+        [b'*', rest @ ..] => {
+            let level = name.iter().position(|c| *c == b'.').unwrap_or(name.len());
+            (0..=level).any(|ndx| glob_match(rest, &name[ndx..]))
+        }
+        [b'?', rest @ ..] => matches!(name, [c, tail @ ..] if *c != b'.' && glob_match(rest, tail)),
+        [p, rest @ ..] => matches!(name, [c, tail @ ..] if c == p && glob_match(rest, tail)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_match() {
+        let matches = |pattern: &str, name: &str| glob_match(pattern.as_bytes(), name.as_bytes());
+        assert!(matches("top.fifo.*.write_*", "top.fifo.inner.write_enable"));
+        assert!(!matches("top.fifo.*.write_*", "top.fifo.inner.read_enable"));
+        assert!(!matches("top.fifo.*.write_*", "top.fifo.a.b.write_enable"));
+        assert!(matches("top.fifo.**.write_*", "top.fifo.a.b.write_enable"));
+        assert!(matches("top.**", "top.a"));
+        assert!(matches("top.?", "top.a"));
+        assert!(!matches("top?a", "top.a"));
+        assert!(matches("top.a", "top.a"));
+        assert!(!matches("top.a", "top.ab"));
+    }
+}
@@ -0,0 +1,393 @@
+// A writer for FST (Fast Signal Trace) files, the compressed waveform
+// format read by GTKWave and Surfer.
+//
+// The value changes are buffered in memory one section at a time, and
+// each section is written out (with the changes of every signal zlib
+// compressed separately) once it holds `SECTION_TIMES` time steps.  The
+// header block is rewritten at the end, once the time range and number
+// of sections are known, which is why the output must be seekable.
+use std::{
+    io::{Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use super::bit::TraceBit;
+
+// The handle of a signal, in the order the wires are added.
+pub type FSTHandle = u32;
+
+// This Trait object captures the interface back to the FST writer.
+pub trait FSTWrite {
+    fn add_module(&mut self, name: &str) -> std::io::Result<()>;
+    fn upscope(&mut self) -> std::io::Result<()>;
+    fn add_wire(&mut self, width: u32, name: &str) -> std::io::Result<FSTHandle>;
+    fn timestamp(&mut self, time: u64) -> std::io::Result<()>;
+    fn change(&mut self, handle: FSTHandle, value: &[TraceBit]) -> std::io::Result<()>;
+}
+
+const SECTION_TIMES: usize = 1 << 16;
+const COMPRESSION_LEVEL: u8 = 4;
+
+const BLOCK_HEADER: u8 = 0;
+const BLOCK_GEOMETRY: u8 = 3;
+const BLOCK_HIERARCHY: u8 = 4;
+const BLOCK_VALUE_CHANGES: u8 = 8;
+
+const HEADER_LENGTH: u64 = 329;
+const HEADER_VERSION_LENGTH: usize = 128;
+const HEADER_DATE_LENGTH: usize = 119;
+
+const SCOPE: u8 = 254;
+const UPSCOPE: u8 = 255;
+const SCOPE_MODULE: u8 = 0;
+const VAR_WIRE: u8 = 16;
+const DIRECTION_IMPLICIT: u8 = 0;
+const ATTRIBUTE_BEGIN: u8 = 252;
+const ATTRIBUTE_MISC: u8 = 0;
+const MISC_COMMENT: u8 = 0;
+
+// Readers refuse attributes longer than this.
+pub const MAX_COMMENT_LENGTH: usize = 65536;
+
+// FST files written by RHDL end in `.fst`.  Any other file is a VCD.
+pub fn is_fst_path(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "fst")
+}
+
+fn varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value > 0x7f {
+        buf.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn signed_varint(buf: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn c_str(buf: &mut Vec<u8>, text: &str) {
+    buf.extend(text.bytes().filter(|b| *b != 0));
+    buf.push(0);
+}
+
+// Zlib compress the bytes, unless that does not make them any shorter.
+fn pack(bytes: &[u8]) -> Option<Vec<u8>> {
+    let packed = miniz_oxide::deflate::compress_to_vec_zlib(bytes, COMPRESSION_LEVEL);
+    (packed.len() < bytes.len()).then_some(packed)
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0_u32, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| {
+            (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+// The hierarchy block is a gzip stream rather than a zlib one.
+fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut buf = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 255];
+    buf.extend(miniz_oxide::deflate::compress_to_vec(
+        bytes,
+        COMPRESSION_LEVEL,
+    ));
+    buf.extend(crc32(bytes).to_le_bytes());
+    buf.extend((bytes.len() as u32).to_le_bytes());
+    buf
+}
+
+fn value_char(bit: &TraceBit) -> u8 {
+    match bit {
+        TraceBit::Zero => b'0',
+        TraceBit::One => b'1',
+        TraceBit::X => b'x',
+        TraceBit::Z => b'z',
+    }
+}
+
+pub struct Writer<W: Write + Seek> {
+    inner: W,
+    timescale: i8,
+    hierarchy: Vec<u8>,
+    scopes: u64,
+    widths: Vec<u32>,
+    // The current value of each signal, as VCD style characters (MSB first).
+    values: Vec<Vec<u8>>,
+    // The values of the signals at the start of the current section.
+    frame: Vec<u8>,
+    times: Vec<u64>,
+    // The encoded changes of each signal in the current section, and the
+    // index of the time of its last change.
+    changes: Vec<Vec<u8>>,
+    last_change: Vec<usize>,
+    time_range: Option<(u64, u64)>,
+    sections: u64,
+}
+
+impl<W: Write + Seek> Writer<W> {
+    // Create a writer with a timescale of 10^timescale seconds.
+    pub fn new(inner: W, timescale: i8) -> std::io::Result<Self> {
+        let mut writer = Writer {
+            inner,
+            timescale,
+            hierarchy: vec![],
+            scopes: 0,
+            widths: vec![],
+            values: vec![],
+            frame: vec![],
+            times: vec![],
+            changes: vec![],
+            last_change: vec![],
+            time_range: None,
+            sections: 0,
+        };
+        // A placeholder, which is filled in by `finish`.
+        writer.write_header()?;
+        Ok(writer)
+    }
+    // Add a comment to the hierarchy, like the `$comment` of a VCD.
+    pub fn comment(&mut self, text: &str) -> std::io::Result<()> {
+        if text.len() > MAX_COMMENT_LENGTH {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "FST comments are limited to 64KiB",
+            ));
+        }
+        self.hierarchy
+            .extend([ATTRIBUTE_BEGIN, ATTRIBUTE_MISC, MISC_COMMENT]);
+        c_str(&mut self.hierarchy, text);
+        varint(&mut self.hierarchy, 0);
+        Ok(())
+    }
+    fn write_header(&mut self) -> std::io::Result<()> {
+        let (start_time, end_time) = self.time_range.unwrap_or_default();
+        let mut buf = vec![BLOCK_HEADER];
+        buf.extend(HEADER_LENGTH.to_be_bytes());
+        buf.extend(start_time.to_be_bytes());
+        buf.extend(end_time.to_be_bytes());
+        buf.extend(std::f64::consts::E.to_le_bytes());
+        buf.extend(0_u64.to_be_bytes());
+        buf.extend(self.scopes.to_be_bytes());
+        buf.extend((self.widths.len() as u64).to_be_bytes());
+        buf.extend((self.widths.len() as u64).to_be_bytes());
+        buf.extend(self.sections.to_be_bytes());
+        buf.push(self.timescale as u8);
+        let mut version = b"RHDL".to_vec();
+        version.resize(HEADER_VERSION_LENGTH, 0);
+        buf.extend(version);
+        // The date is left out, so that the same run gives the same file.
+        buf.extend([0; HEADER_DATE_LENGTH]);
+        buf.push(0);
+        buf.extend(0_u64.to_be_bytes());
+        self.inner.write_all(&buf)
+    }
+    fn write_block(&mut self, block: u8, body: &[u8]) -> std::io::Result<()> {
+        self.inner.write_all(&[block])?;
+        self.inner
+            .write_all(&(body.len() as u64 + 8).to_be_bytes())?;
+        self.inner.write_all(body)
+    }
+    fn flush_section(&mut self) -> std::io::Result<()> {
+        let (Some(start_time), Some(end_time)) = (self.times.first(), self.times.last()) else {
+            return Ok(());
+        };
+        let signals = self.widths.len() as u64;
+        let mut body = vec![];
+        body.extend(start_time.to_be_bytes());
+        body.extend(end_time.to_be_bytes());
+        let traversal = self.frame.len() + self.changes.iter().map(Vec::len).sum::<usize>();
+        body.extend((traversal as u64).to_be_bytes());
+        let frame = pack(&self.frame);
+        varint(&mut body, self.frame.len() as u64);
+        varint(
+            &mut body,
+            frame.as_ref().map_or(self.frame.len(), Vec::len) as u64,
+        );
+        varint(&mut body, signals);
+        body.extend(frame.as_deref().unwrap_or(&self.frame));
+        varint(&mut body, signals);
+        // The changes of each signal are located by their offset from the
+        // pack type, in a chain of offset deltas and runs of signals that
+        // did not change.
+        let start = body.len();
+        body.push(b'Z');
+        let mut chain = vec![];
+        let mut unchanged = 0;
+        let mut offset = 0;
+        for changes in &self.changes {
+            if changes.is_empty() {
+                unchanged += 1;
+                continue;
+            }
+            if unchanged > 0 {
+                varint(&mut chain, unchanged << 1);
+                unchanged = 0;
+            }
+            let position = body.len() - start;
+            signed_varint(&mut chain, (((position - offset) as i64) << 1) | 1);
+            offset = position;
+            match pack(changes) {
+                Some(packed) => {
+                    varint(&mut body, changes.len() as u64);
+                    body.extend(packed);
+                }
+                None => {
+                    varint(&mut body, 0);
+                    body.extend(changes);
+                }
+            }
+        }
+        if unchanged > 0 {
+            varint(&mut chain, unchanged << 1);
+        }
+        body.extend(&chain);
+        body.extend((chain.len() as u64).to_be_bytes());
+        let mut times = vec![];
+        let mut previous = 0;
+        for time in &self.times {
+            varint(&mut times, time - previous);
+            previous = *time;
+        }
+        let packed = pack(&times);
+        body.extend(packed.as_deref().unwrap_or(&times));
+        body.extend((times.len() as u64).to_be_bytes());
+        body.extend((packed.as_ref().map_or(times.len(), Vec::len) as u64).to_be_bytes());
+        body.extend((self.times.len() as u64).to_be_bytes());
+        self.write_block(BLOCK_VALUE_CHANGES, &body)?;
+        self.sections += 1;
+        self.times.clear();
+        self.changes.iter_mut().for_each(Vec::clear);
+        self.last_change.iter_mut().for_each(|ndx| *ndx = 0);
+        Ok(())
+    }
+    // Write out the buffered changes, the geometry and the hierarchy,
+    // and fill in the header.
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.flush_section()?;
+        let mut geometry = vec![];
+        for width in &self.widths {
+            varint(&mut geometry, *width as u64);
+        }
+        let mut body = vec![];
+        body.extend((geometry.len() as u64).to_be_bytes());
+        body.extend((self.widths.len() as u64).to_be_bytes());
+        body.extend(pack(&geometry).unwrap_or(geometry));
+        self.write_block(BLOCK_GEOMETRY, &body)?;
+        let mut body = vec![];
+        body.extend((self.hierarchy.len() as u64).to_be_bytes());
+        body.extend(gzip(&self.hierarchy));
+        self.write_block(BLOCK_HIERARCHY, &body)?;
+        let end = self.inner.stream_position()?;
+        self.inner.seek(SeekFrom::Start(0))?;
+        self.write_header()?;
+        self.inner.seek(SeekFrom::Start(end))?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write + Seek> FSTWrite for Writer<W> {
+    fn add_module(&mut self, name: &str) -> std::io::Result<()> {
+        self.hierarchy.extend([SCOPE, SCOPE_MODULE]);
+        c_str(&mut self.hierarchy, name);
+        c_str(&mut self.hierarchy, "");
+        self.scopes += 1;
+        Ok(())
+    }
+    fn upscope(&mut self) -> std::io::Result<()> {
+        self.hierarchy.push(UPSCOPE);
+        Ok(())
+    }
+    fn add_wire(&mut self, width: u32, name: &str) -> std::io::Result<FSTHandle> {
+        if width == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "FST wires must be at least one bit wide",
+            ));
+        }
+        self.hierarchy.extend([VAR_WIRE, DIRECTION_IMPLICIT]);
+        c_str(&mut self.hierarchy, name);
+        varint(&mut self.hierarchy, width as u64);
+        // Not an alias of another wire
+        varint(&mut self.hierarchy, 0);
+        let handle = self.widths.len() as FSTHandle;
+        self.widths.push(width);
+        self.values.push(vec![b'x'; width as usize]);
+        self.changes.push(vec![]);
+        self.last_change.push(0);
+        Ok(handle)
+    }
+    fn timestamp(&mut self, time: u64) -> std::io::Result<()> {
+        match self.times.last() {
+            Some(last) if *last == time => return Ok(()),
+            Some(last) if *last > time => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("FST timestamp {time} is before {last}"),
+                ))
+            }
+            _ => {}
+        }
+        if self.times.len() >= SECTION_TIMES {
+            self.flush_section()?;
+        }
+        if self.times.is_empty() {
+            self.frame = self.values.concat();
+        }
+        self.times.push(time);
+        let start = self.time_range.map_or(time, |(start, _)| start);
+        self.time_range = Some((start, time));
+        Ok(())
+    }
+    fn change(&mut self, handle: FSTHandle, value: &[TraceBit]) -> std::io::Result<()> {
+        let ndx = handle as usize;
+        let width = self.widths[ndx] as usize;
+        if value.len() != width {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("FST value of {} bits for a {width} bit wire", value.len()),
+            ));
+        }
+        if self.times.is_empty() {
+            let time = self.time_range.map(|(_, end)| end).unwrap_or_default();
+            self.timestamp(time)?;
+        }
+        let time_ndx = self.times.len() - 1;
+        let delta = (time_ndx - self.last_change[ndx]) as u64;
+        self.last_change[ndx] = time_ndx;
+        let chars = value.iter().rev().map(value_char).collect::<Vec<_>>();
+        let changes = &mut self.changes[ndx];
+        let two_state = chars.iter().all(|c| matches!(c, b'0' | b'1'));
+        match (width, two_state) {
+            (1, true) => varint(changes, (delta << 2) | (((chars[0] - b'0') as u64) << 1)),
+            (1, false) => {
+                let code = if chars[0] == b'z' { 1 } else { 0 };
+                varint(changes, (delta << 4) | (code << 1) | 1)
+            }
+            (_, true) => {
+                varint(changes, delta << 1);
+                changes.extend(chars.chunks(8).map(|chunk| {
+                    chunk
+                        .iter()
+                        .enumerate()
+                        .fold(0_u8, |byte, (bit, c)| byte | ((c - b'0') << (7 - bit)))
+                }));
+            }
+            (_, false) => {
+                varint(changes, (delta << 1) | 1);
+                changes.extend(&chars);
+            }
+        }
+        self.values[ndx] = chars;
+        Ok(())
+    }
+}
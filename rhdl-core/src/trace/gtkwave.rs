@@ -0,0 +1,420 @@
+// Viewer side decoding of RHDL waveforms.  A VCD (or FST) file only holds
+// bit vectors, so an enum shows up as a raw number, and a struct as one
+// wide vector.  Using the trace types of the signals, we write a GTKWave
+// save file that splits each signal into its parts, grouped by the
+// hierarchy of the type, and a translate filter file for each enum that
+// maps its discriminants to the names of the variants.  Fixed point
+// values are shown as decimals, with the binary point in place.
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use rhdl_trace_type::{Color, DiscriminantAlignment, Enum, TraceType, RTT};
+
+use super::rtt::trace_to_kind;
+
+// Flags of a trace in a GTKWave save file.
+const TR_HEX: u32 = 0x2;
+const TR_DEC: u32 = 0x4;
+const TR_BIN: u32 = 0x8;
+const TR_RJUSTIFY: u32 = 0x20;
+const TR_BLANK: u32 = 0x200;
+const TR_SIGNED: u32 = 0x400;
+const TR_FTRANSLATED: u32 = 0x2000;
+const TR_GRP_BEGIN: u32 = 0x80_0000;
+const TR_GRP_END: u32 = 0x100_0000;
+const TR_FPDECSHIFT: u32 = 0x4000_0000;
+
+// A part of a signal to show in the viewer.
+struct Slice<'a> {
+    // The name shown in the viewer, e.g. `top.o.axi.data`.
+    name: String,
+    // The name of the signal in the waveform file.
+    signal: &'a str,
+    width: usize,
+    bits: Range<usize>,
+    color: Option<Color>,
+}
+
+struct Layout<'a> {
+    save_file: &'a Path,
+    lines: Vec<String>,
+    // The enums that need a translate filter file, and the files they are
+    // written to, in the order of their filter index.
+    filters: Vec<(Enum, PathBuf)>,
+}
+
+fn color_index(color: Color) -> u32 {
+    match color {
+        Color::Red => 1,
+        Color::Orange => 2,
+        Color::Yellow => 3,
+        Color::Green => 4,
+        Color::Blue => 5,
+        Color::Indigo => 6,
+        _ => 7,
+    }
+}
+
+// The translate filter for an enum.  Each line holds the discriminant (in
+// binary, as the discriminant is shown in that format) and the name of
+// the variant.
+fn translate_filter(enumerate: &Enum) -> String {
+    let width = enumerate.discriminant_layout.width;
+    enumerate
+        .variants
+        .iter()
+        .map(|variant| {
+            let value = (0..width)
+                .rev()
+                .map(|ndx| match (variant.discriminant >> ndx.min(63)) & 1 {
+                    0 => '0',
+                    _ => '1',
+                })
+                .collect::<String>();
+            format!("{value} {}\n", variant.name)
+        })
+        .collect()
+}
+
+// Sanitize a type name so it can be part of a file name.
+fn file_name_part(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+impl Layout<'_> {
+    fn begin_group(&mut self, name: &str) {
+        self.lines.push(format!("@{:x}", TR_GRP_BEGIN | TR_BLANK));
+        self.lines.push(format!("-{name}"));
+    }
+    fn end_group(&mut self, name: &str) {
+        self.lines.push(format!("@{:x}", TR_GRP_END | TR_BLANK));
+        self.lines.push(format!("-{name}"));
+    }
+    // Select the translate filter for the enum, for the traces that follow.
+    fn use_filter(&mut self, enumerate: &Enum) {
+        let ndx = match self.filters.iter().position(|(e, _)| e == enumerate) {
+            Some(ndx) => ndx,
+            None => {
+                let stem = self
+                    .save_file
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let file_name = format!(
+                    "{stem}_{}_{}.txt",
+                    self.filters.len() + 1,
+                    file_name_part(&enumerate.name)
+                );
+                let path = self.save_file.with_file_name(file_name);
+                self.filters.push((enumerate.clone(), path));
+                self.filters.len() - 1
+            }
+        };
+        self.lines
+            .push(format!("^{} {}", ndx + 1, self.filters[ndx].1.display()));
+    }
+    // Show the slice as a decimal shifted right by `fraction` bits.  The
+    // shift applies to the traces added after it is set.
+    fn trace_fixed(&mut self, flags: u32, fraction: usize, slice: &Slice) {
+        self.lines.push(format!("[fpshift_count] {fraction}"));
+        self.trace(flags | TR_FPDECSHIFT | TR_DEC | TR_RJUSTIFY, slice);
+    }
+    fn trace(&mut self, flags: u32, slice: &Slice) {
+        if let Some(color) = slice.color {
+            self.lines.push(format!("[color] {}", color_index(color)));
+        }
+        self.lines.push(format!("@{flags:x}"));
+        if slice.width == 1 {
+            self.lines.push(slice.signal.into());
+        } else if slice.bits.len() == 1 {
+            self.lines
+                .push(format!("{}[{}]", slice.signal, slice.bits.start));
+        } else {
+            // A concatenation of the bits of the slice, most significant first
+            let bits = slice
+                .bits
+                .clone()
+                .rev()
+                .map(|ndx| format!("{}[{ndx}]", slice.signal))
+                .collect::<Vec<_>>()
+                .join(" ");
+            self.lines.push(format!(
+                "#{{{}[{}:{}]}} {bits}",
+                slice.name,
+                slice.bits.end - 1,
+                slice.bits.start
+            ));
+        }
+    }
+    fn walk(&mut self, ty: &TraceType, slice: Slice) {
+        if slice.bits.is_empty() {
+            return;
+        }
+        let start = slice.bits.start;
+        let part = |name: String, ty: &TraceType, offset: usize| {
+            let width = trace_to_kind(ty).bits();
+            (
+                ty.clone(),
+                Slice {
+                    name,
+                    signal: slice.signal,
+                    width: slice.width,
+                    bits: start + offset..start + offset + width,
+                    color: slice.color,
+                },
+            )
+        };
+        let parts = match ty {
+            TraceType::Struct(strukt) => {
+                let mut offset = 0;
+                strukt
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        let part =
+                            part(format!("{}.{}", slice.name, field.name), &field.ty, offset);
+                        offset += part.1.bits.len();
+                        part
+                    })
+                    .collect::<Vec<_>>()
+            }
+            TraceType::Tuple(tuple) => {
+                let mut offset = 0;
+                tuple
+                    .elements
+                    .iter()
+                    .enumerate()
+                    .map(|(ndx, element)| {
+                        let part = part(format!("{}.{ndx}", slice.name), element, offset);
+                        offset += part.1.bits.len();
+                        part
+                    })
+                    .collect()
+            }
+            TraceType::Array(array) => {
+                let width = trace_to_kind(&array.base).bits();
+                (0..array.size)
+                    .map(|ndx| part(format!("{}[{ndx}]", slice.name), &array.base, ndx * width))
+                    .collect()
+            }
+            TraceType::Enum(enumerate) => return self.walk_enum(enumerate, slice),
+            TraceType::Signal(ty, color) => {
+                let slice = Slice {
+                    color: Some(*color),
+                    ..slice
+                };
+                return self.walk(ty, slice);
+            }
+            TraceType::Signed(_) => return self.trace(TR_SIGNED | TR_DEC | TR_RJUSTIFY, &slice),
+            TraceType::UFixed(fixed) => return self.trace_fixed(0, fixed.fraction, &slice),
+            TraceType::SFixed(fixed) => return self.trace_fixed(TR_SIGNED, fixed.fraction, &slice),
+            _ if slice.bits.len() == 1 => return self.trace(TR_BIN | TR_RJUSTIFY, &slice),
+            _ => return self.trace(TR_HEX | TR_RJUSTIFY, &slice),
+        };
+        self.begin_group(&slice.name);
+        for (ty, part) in parts {
+            self.walk(&ty, part);
+        }
+        self.end_group(&slice.name);
+    }
+    fn walk_enum(&mut self, enumerate: &Enum, slice: Slice) {
+        let layout = enumerate.discriminant_layout;
+        let Range { start, end } = slice.bits;
+        let (discriminant, payload) = match layout.alignment {
+            DiscriminantAlignment::Lsb => (start..start + layout.width, start + layout.width),
+            DiscriminantAlignment::Msb => (end - layout.width..end, start),
+        };
+        let payloads = enumerate
+            .variants
+            .iter()
+            .map(|variant| (variant, trace_to_kind(&variant.ty).bits()))
+            .filter(|(_, width)| *width > 0)
+            .collect::<Vec<_>>();
+        if payloads.is_empty() {
+            self.use_filter(enumerate);
+            return self.trace(
+                TR_FTRANSLATED | TR_BIN | TR_RJUSTIFY,
+                &Slice {
+                    bits: discriminant,
+                    ..slice
+                },
+            );
+        }
+        self.begin_group(&slice.name);
+        self.use_filter(enumerate);
+        self.trace(
+            TR_FTRANSLATED | TR_BIN | TR_RJUSTIFY,
+            &Slice {
+                name: format!("{}#", slice.name),
+                signal: slice.signal,
+                width: slice.width,
+                bits: discriminant,
+                color: slice.color,
+            },
+        );
+        for (variant, width) in payloads {
+            self.walk(
+                &variant.ty,
+                Slice {
+                    name: format!("{}#{}", slice.name, variant.name),
+                    signal: slice.signal,
+                    width: slice.width,
+                    bits: payload..payload + width,
+                    color: slice.color,
+                },
+            );
+        }
+        self.end_group(&slice.name);
+    }
+}
+
+// The contents of a GTKWave save file for the signals described by the
+// RTT, along with the translate filter files it refers to.  The filter
+// files are placed next to the save file.
+fn gtkwave_save(rtt: &RTT, dump_file: &Path, save_file: &Path) -> (String, Vec<(PathBuf, String)>) {
+    let RTT::TraceInfo(info) = rtt;
+    let mut layout = Layout {
+        save_file,
+        lines: vec![],
+        filters: vec![],
+    };
+    for (name, ty) in info {
+        // Match the sanitizing of names done when the waveform is written.
+        let name = name.replace("::", "__");
+        let width = trace_to_kind(ty).bits();
+        layout.walk(
+            ty,
+            Slice {
+                name: name.clone(),
+                signal: &name,
+                width,
+                bits: 0..width,
+                color: None,
+            },
+        );
+    }
+    let mut save = format!("[dumpfile] \"{}\"\n[timestart] 0\n", dump_file.display());
+    for line in layout.lines {
+        save.push_str(&line);
+        save.push('\n');
+    }
+    let filters = layout
+        .filters
+        .iter()
+        .map(|(enumerate, path)| (path.clone(), translate_filter(enumerate)))
+        .collect();
+    (save, filters)
+}
+
+// Write a GTKWave save file (`.gtkw`) for the waveform in `dump_file`,
+// along with the translate filter files for the enums in it.  The RTT
+// provides the types of the signals, e.g. from `TraceDB::collect_rtt_info`.
+pub fn write_gtkwave_save(rtt: &RTT, dump_file: &Path, save_file: &Path) -> std::io::Result<()> {
+    let (save, filters) = gtkwave_save(rtt, dump_file, save_file);
+    for (path, contents) in filters {
+        std::fs::write(path, contents)?;
+    }
+    std::fs::write(save_file, save)
+}
+
+#[cfg(test)]
+mod tests {
+    use rhdl_trace_type::{
+        make_discriminant_layout, make_enum, make_field, make_sfixed, make_signal, make_struct,
+        make_ufixed, make_variant, DiscriminantType,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_gtkwave_save() {
+        let state = make_enum(
+            "fsm::State",
+            vec![
+                make_variant("Idle", TraceType::Empty, 0),
+                make_variant("Busy", TraceType::Bits(3), -1),
+            ],
+            make_discriminant_layout(2, DiscriminantAlignment::Msb, DiscriminantType::Signed),
+        );
+        let out = make_struct(
+            "Out",
+            vec![
+                make_field("valid", TraceType::Bits(1)),
+                make_field("state", state),
+            ],
+        );
+        let rtt = RTT::TraceInfo(
+            [
+                ("top::o".to_string(), make_signal(out, Color::Red)),
+                ("top.clock".to_string(), TraceType::Clock),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let (save, filters) = gtkwave_save(&rtt, Path::new("out.vcd"), Path::new("dir/out.gtkw"));
+        assert_eq!(
+            filters,
+            [(
+                PathBuf::from("dir/out_1_fsm__State.txt"),
+                "00 Idle\n11 Busy\n".to_string()
+            )]
+        );
+        let expect = "\
+[dumpfile] \"out.vcd\"
+[timestart] 0
+@28
+top.clock
+@800200
+-top__o
+[color] 1
+@28
+top__o[0]
+@800200
+-top__o.state
+^1 dir/out_1_fsm__State.txt
+[color] 1
+@2028
+#{top__o.state#[5:4]} top__o[5] top__o[4]
+[color] 1
+@22
+#{top__o.state#Busy[3:1]} top__o[3] top__o[2] top__o[1]
+@1000200
+-top__o.state
+@1000200
+-top__o
+";
+        assert_eq!(save, expect);
+    }
+
+    #[test]
+    fn test_gtkwave_save_fixed_point() {
+        let out = make_struct(
+            "Out",
+            vec![
+                make_field("gain", make_ufixed(2, 3)),
+                make_field("offset", make_sfixed(3, 1)),
+            ],
+        );
+        let rtt = RTT::TraceInfo([("top.o".to_string(), out)].into_iter().collect());
+        let (save, _) = gtkwave_save(&rtt, Path::new("out.vcd"), Path::new("out.gtkw"));
+        let expect = "\
+[dumpfile] \"out.vcd\"
+[timestart] 0
+@800200
+-top.o
+[fpshift_count] 3
+@40000024
+#{top.o.gain[4:0]} top.o[4] top.o[3] top.o[2] top.o[1] top.o[0]
+[fpshift_count] 1
+@40000424
+#{top.o.offset[8:5]} top.o[8] top.o[7] top.o[6] top.o[5]
+@1000200
+-top.o
+";
+        assert_eq!(save, expect);
+    }
+}
@@ -1,5 +1,11 @@
 pub mod bit;
+pub mod compare;
 pub mod db;
+pub mod filter;
+pub mod fst;
+pub mod gtkwave;
 pub mod key;
+pub mod reader;
 pub mod rtt;
+pub mod toggle;
 pub mod vcd;
@@ -0,0 +1,324 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufReader, Read, Seek},
+    path::Path,
+};
+
+use rhdl_trace_type::{TraceType, RTT};
+
+use crate::{Digital, TraceDB, TypedBits};
+
+use super::{bit::TraceBit, fst::is_fst_path, rtt::trace_to_kind};
+
+// A signal read back from a waveform file.
+#[derive(Clone, Debug)]
+pub struct WaveformSignal {
+    pub width: usize,
+    // The type of the signal, if the file (or the caller) provided it.
+    pub trace_type: Option<TraceType>,
+    // The value of the signal after each change, least significant bit
+    // first (the same order as `Digital::trace`).
+    pub changes: Vec<(u64, Vec<TraceBit>)>,
+}
+
+// The signals of a VCD or FST file, by their full hierarchical name
+// (e.g. `top.fifo.write_enable`).  Files written by RHDL embed the
+// trace types of their signals, so that the values can be turned back
+// into `TypedBits` or concrete `Digital` values.  For files written by
+// other tools (like the iverilog test bench), the types can be supplied
+// with `with_rtt` or `with_trace_type`.
+#[derive(Clone, Debug, Default)]
+pub struct Waveform {
+    signals: BTreeMap<String, WaveformSignal>,
+}
+
+fn invalid_data(err: impl std::fmt::Display) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, err.to_string())
+}
+
+// Convert VCD style characters (most significant bit first) into trace
+// bits.  A value shorter than the signal is extended to the left with
+// zeros, unless it starts with an x or z, which is extended instead.
+fn trace_bits(chars: impl DoubleEndedIterator<Item = u8>, width: usize) -> Vec<TraceBit> {
+    let mut bits: Vec<TraceBit> = chars
+        .rev()
+        .map(|c| match c {
+            b'0' => TraceBit::Zero,
+            b'1' => TraceBit::One,
+            b'z' | b'Z' => TraceBit::Z,
+            _ => TraceBit::X,
+        })
+        .collect();
+    let fill = match bits.last() {
+        Some(TraceBit::X) => TraceBit::X,
+        Some(TraceBit::Z) => TraceBit::Z,
+        _ => TraceBit::Zero,
+    };
+    if bits.len() < width {
+        bits.resize(width, fill);
+    }
+    bits
+}
+
+impl Waveform {
+    fn declare(&mut self, name: String, width: usize) {
+        self.signals.insert(
+            name,
+            WaveformSignal {
+                width,
+                trace_type: None,
+                changes: vec![],
+            },
+        );
+    }
+    fn change(&mut self, name: &str, time: u64, chars: &[u8]) {
+        let Some(signal) = self.signals.get_mut(name) else {
+            return;
+        };
+        let value = trace_bits(chars.iter().copied(), signal.width);
+        match signal.changes.last_mut() {
+            Some((last, last_value)) if *last == time => *last_value = value,
+            _ => signal.changes.push((time, value)),
+        }
+    }
+    // Apply the trace types in a comment, if it holds RHDL trace information.
+    fn comment(&mut self, text: &str) {
+        if let Ok(rtt) = ron::from_str::<RTT>(text) {
+            self.apply_rtt(&rtt);
+        }
+    }
+    fn apply_rtt(&mut self, rtt: &RTT) {
+        let RTT::TraceInfo(info) = rtt;
+        for (name, ty) in info {
+            // Match the sanitizing of names done when the waveform is written.
+            if let Some(signal) = self.signals.get_mut(&name.replace("::", "__")) {
+                signal.trace_type = Some(ty.clone());
+            }
+        }
+    }
+    pub fn read_vcd<R: Read>(reader: R) -> std::io::Result<Self> {
+        let mut parser = vcd::Parser::new(BufReader::new(reader));
+        let header = parser.parse_header()?;
+        let mut waveform = Waveform::default();
+        let mut codes = BTreeMap::<vcd::IdCode, Vec<String>>::new();
+        let mut comments = vec![];
+        let mut items: Vec<(String, &vcd::ScopeItem)> = header
+            .items
+            .iter()
+            .rev()
+            .map(|x| (String::new(), x))
+            .collect();
+        while let Some((scope, item)) = items.pop() {
+            match item {
+                vcd::ScopeItem::Scope(child) => {
+                    let scope = if scope.is_empty() {
+                        child.identifier.clone()
+                    } else {
+                        format!("{scope}.{}", child.identifier)
+                    };
+                    items.extend(child.items.iter().rev().map(|x| (scope.clone(), x)));
+                }
+                vcd::ScopeItem::Var(var) => {
+                    let name = format!("{scope}.{}", var.reference);
+                    codes.entry(var.code).or_default().push(name.clone());
+                    waveform.declare(name, var.size as usize);
+                }
+                vcd::ScopeItem::Comment(text) => comments.push(text.clone()),
+                _ => {}
+            }
+        }
+        comments.iter().for_each(|text| waveform.comment(text));
+        let mut time = 0;
+        for command in parser {
+            let (code, chars) = match command? {
+                vcd::Command::Timestamp(t) => {
+                    time = t;
+                    continue;
+                }
+                vcd::Command::ChangeScalar(code, value) => (code, value.to_string()),
+                vcd::Command::ChangeVector(code, value) => (code, value.to_string()),
+                _ => continue,
+            };
+            for name in codes.get(&code).into_iter().flatten() {
+                waveform.change(name, time, chars.as_bytes());
+            }
+        }
+        Ok(waveform)
+    }
+    pub fn read_fst<R: Read + Seek>(reader: R) -> std::io::Result<Self> {
+        let mut reader =
+            fst_reader::FstReader::open(BufReader::new(reader)).map_err(invalid_data)?;
+        let mut waveform = Waveform::default();
+        let mut handles = BTreeMap::<usize, Vec<String>>::new();
+        let mut scope = vec![];
+        let mut comments = vec![];
+        reader
+            .read_hierarchy(|entry| match entry {
+                fst_reader::FstHierarchyEntry::Scope { name, .. } => scope.push(name),
+                fst_reader::FstHierarchyEntry::UpScope => {
+                    scope.pop();
+                }
+                fst_reader::FstHierarchyEntry::Var {
+                    name,
+                    length,
+                    handle,
+                    ..
+                } => {
+                    let name = format!("{}.{name}", scope.join("."));
+                    handles
+                        .entry(handle.get_index())
+                        .or_default()
+                        .push(name.clone());
+                    waveform.declare(name, length as usize);
+                }
+                fst_reader::FstHierarchyEntry::Comment { string } => comments.push(string),
+                _ => {}
+            })
+            .map_err(invalid_data)?;
+        comments.iter().for_each(|text| waveform.comment(text));
+        reader
+            .read_signals(&fst_reader::FstFilter::all(), |time, handle, value| {
+                let fst_reader::FstSignalValue::String(chars) = value else {
+                    return;
+                };
+                for name in handles.get(&handle.get_index()).into_iter().flatten() {
+                    waveform.change(name, time, chars);
+                }
+            })
+            .map_err(invalid_data)?;
+        Ok(waveform)
+    }
+    // The waveform of a trace database, e.g. from a Rust simulation.
+    pub fn from_trace_db(db: &TraceDB) -> std::io::Result<Self> {
+        Ok(Waveform {
+            signals: db.waveform_signals()?,
+        })
+    }
+    // Read a waveform file, as FST if the path ends in `.fst`, and as VCD
+    // otherwise.
+    pub fn read_file(path: &Path) -> std::io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        if is_fst_path(path) {
+            Self::read_fst(file)
+        } else {
+            Self::read_vcd(file)
+        }
+    }
+    // Supply the trace types of signals, using the names of the trace
+    // database (e.g. from `TraceDB::collect_rtt_info`).
+    pub fn with_rtt(mut self, rtt: &RTT) -> Self {
+        self.apply_rtt(rtt);
+        self
+    }
+    pub fn with_trace_type(mut self, name: &str, trace_type: TraceType) -> Self {
+        if let Some(signal) = self.signals.get_mut(name) {
+            signal.trace_type = Some(trace_type);
+        }
+        self
+    }
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.signals.keys().map(String::as_str)
+    }
+    pub fn signal(&self, name: &str) -> Option<&WaveformSignal> {
+        self.signals.get(name)
+    }
+    // The value of the signal at the given time, i.e., the value of the
+    // last change at or before it.
+    pub fn value_at(&self, name: &str, time: u64) -> Option<&[TraceBit]> {
+        let changes = &self.signals.get(name)?.changes;
+        let ndx = changes
+            .partition_point(|(t, _)| *t <= time)
+            .checked_sub(1)?;
+        Some(&changes[ndx].1)
+    }
+    // The changes of the signal as `TypedBits`, using its trace type.  Any
+    // x or z bits are returned as `BitX::X`.
+    pub fn typed_bits(&self, name: &str) -> Option<Vec<(u64, TypedBits)>> {
+        let signal = self.signals.get(name)?;
+        let kind = trace_to_kind(signal.trace_type.as_ref()?);
+        if kind.bits() != signal.width {
+            return None;
+        }
+        Some(
+            signal
+                .changes
+                .iter()
+                .map(|(time, value)| {
+                    let bits = value.iter().map(|b| (*b).into()).collect();
+                    (*time, TypedBits { bits, kind })
+                })
+                .collect(),
+        )
+    }
+    // The changes of the signal as values of type `T`.  A value that is
+    // not fully known (or not a valid `T`) is returned as `None`.
+    pub fn values<T: Digital>(&self, name: &str) -> Option<Vec<(u64, Option<T>)>> {
+        let signal = self.signals.get(name)?;
+        if T::TRACE_BITS != T::BITS || signal.width != T::BITS {
+            return None;
+        }
+        Some(
+            signal
+                .changes
+                .iter()
+                .map(|(time, value)| {
+                    let bits: Vec<_> = value.iter().map(|b| (*b).into()).collect();
+                    (*time, T::from_bin(&bits))
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rhdl_bits::alias::*;
+
+    use super::*;
+
+    #[test]
+    fn test_read_foreign_vcd() {
+        let vcd = b"$timescale 1ps $end
+$scope module testbench $end
+$scope module dut $end
+$var wire 8 ! o [7:0] $end
+$var wire 1 \" clk $end
+$upscope $end
+$var wire 8 ! dut_o [7:0] $end
+$upscope $end
+$enddefinitions $end
+#0
+$dumpvars
+bx !
+0\"
+$end
+#10
+b101 !
+1\"
+#20
+bz1 !
+";
+        let waveform = Waveform::read_vcd(&vcd[..])
+            .unwrap()
+            .with_trace_type("testbench.dut.o", rhdl_trace_type::TraceType::Bits(8));
+        assert_eq!(
+            waveform.names().collect::<Vec<_>>(),
+            ["testbench.dut.clk", "testbench.dut.o", "testbench.dut_o"]
+        );
+        assert_eq!(
+            waveform.values::<b8>("testbench.dut.o").unwrap(),
+            [(0, None), (10, Some(b8(5))), (20, None)]
+        );
+        assert_eq!(
+            waveform.value_at("testbench.dut_o", 25).unwrap()[..2],
+            [TraceBit::One, TraceBit::Z]
+        );
+        assert_eq!(
+            waveform.value_at("testbench.dut.clk", 15),
+            Some(&[TraceBit::One][..])
+        );
+        assert!(waveform.value_at("testbench.dut.clk", 0).is_some());
+        assert!(waveform.typed_bits("testbench.dut_o").is_none());
+        assert_eq!(waveform.typed_bits("testbench.dut.o").unwrap().len(), 3);
+    }
+}
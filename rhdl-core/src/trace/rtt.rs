@@ -46,6 +46,80 @@ impl From<kind::DiscriminantLayout> for rtt::DiscriminantLayout {
     }
 }
 
+// The reverse direction, used when reading a trace back in.  The trace
+// type carries more information than the Kind (fixed point, clocks and
+// resets), which is dropped here.
+impl From<rtt::Color> for crate::Color {
+    fn from(color: rtt::Color) -> Self {
+        match color {
+            rtt::Color::Red => crate::Color::Red,
+            rtt::Color::Orange => crate::Color::Orange,
+            rtt::Color::Yellow => crate::Color::Yellow,
+            rtt::Color::Green => crate::Color::Green,
+            rtt::Color::Blue => crate::Color::Blue,
+            rtt::Color::Indigo => crate::Color::Indigo,
+            _ => crate::Color::Violet,
+        }
+    }
+}
+
+impl From<rtt::DiscriminantLayout> for kind::DiscriminantLayout {
+    fn from(dl: rtt::DiscriminantLayout) -> Self {
+        kind::DiscriminantLayout {
+            width: dl.width,
+            alignment: match dl.alignment {
+                rtt::DiscriminantAlignment::Msb => kind::DiscriminantAlignment::Msb,
+                rtt::DiscriminantAlignment::Lsb => kind::DiscriminantAlignment::Lsb,
+            },
+            ty: match dl.ty {
+                rtt::DiscriminantType::Unsigned => kind::DiscriminantType::Unsigned,
+                rtt::DiscriminantType::Signed => kind::DiscriminantType::Signed,
+            },
+        }
+    }
+}
+
+pub fn trace_to_kind(ty: &rtt::TraceType) -> kind::Kind {
+    use kind::Kind;
+    use rtt::TraceType;
+    match ty {
+        TraceType::Array(array) => Kind::make_array(trace_to_kind(&array.base), array.size),
+        TraceType::Tuple(tuple) => {
+            Kind::make_tuple(tuple.elements.iter().map(trace_to_kind).collect())
+        }
+        TraceType::Struct(strukt) => Kind::make_struct(
+            &strukt.name,
+            strukt
+                .fields
+                .iter()
+                .map(|field| Kind::make_field(&field.name, trace_to_kind(&field.ty)))
+                .collect(),
+        ),
+        TraceType::Enum(enumerate) => Kind::make_enum(
+            &enumerate.name,
+            enumerate
+                .variants
+                .iter()
+                .map(|variant| {
+                    Kind::make_variant(
+                        &variant.name,
+                        trace_to_kind(&variant.ty),
+                        variant.discriminant,
+                    )
+                })
+                .collect(),
+            enumerate.discriminant_layout.into(),
+        ),
+        TraceType::Bits(len) => Kind::make_bits(*len),
+        TraceType::Signed(len) => Kind::make_signed(*len),
+        TraceType::UFixed(fixed) => Kind::make_bits(fixed.integer + fixed.fraction),
+        TraceType::SFixed(fixed) => Kind::make_signed(fixed.integer + fixed.fraction),
+        TraceType::Signal(ty, color) => Kind::make_signal(trace_to_kind(ty), (*color).into()),
+        TraceType::Clock | TraceType::Reset => Kind::make_bool(),
+        _ => Kind::Empty,
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod test {
     use super::*;
@@ -0,0 +1,282 @@
+// Toggle coverage of the traced signals.  For each bit of each signal, we
+// count how often it rose (0 to 1) and fell (1 to 0).  Changes to or from
+// an unknown (x or z) value are not counted.  A bit that never took on
+// one of the values is stuck, e.g., the upper address bits of a bus that
+// only ever addresses a small memory.
+use std::{collections::BTreeMap, ops::Range};
+
+use rhdl_trace_type::TraceType;
+
+use crate::{types::path::Path, DiscriminantAlignment, Kind};
+
+use super::{bit::TraceBit, rtt::trace_to_kind};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct BitToggles {
+    pub rises: u64,
+    pub falls: u64,
+    pub seen_zero: bool,
+    pub seen_one: bool,
+}
+
+impl BitToggles {
+    pub fn toggled(&self) -> bool {
+        self.rises > 0 && self.falls > 0
+    }
+    // The value the bit is stuck at, if it only ever held one value.  A
+    // bit that never held a known value is stuck at x.
+    pub fn stuck_at(&self) -> Option<TraceBit> {
+        match (self.seen_zero, self.seen_one) {
+            (true, true) => None,
+            (true, false) => Some(TraceBit::Zero),
+            (false, true) => Some(TraceBit::One),
+            (false, false) => Some(TraceBit::X),
+        }
+    }
+    fn merge(&mut self, other: &BitToggles) {
+        self.rises += other.rises;
+        self.falls += other.falls;
+        self.seen_zero |= other.seen_zero;
+        self.seen_one |= other.seen_one;
+    }
+}
+
+// The toggles of the bits of one signal, least significant bit first
+// (the same order as `Digital::trace`).
+#[derive(Clone, Debug)]
+pub struct SignalToggles {
+    pub trace_type: TraceType,
+    pub bits: Vec<BitToggles>,
+}
+
+// The toggles of the bits of one part of a signal.
+#[derive(Clone, Debug)]
+pub struct LeafToggles {
+    // The part of the signal, e.g. `.axi.addr`.
+    pub path: String,
+    pub bits: Vec<BitToggles>,
+}
+
+impl SignalToggles {
+    pub(crate) fn new(trace_type: TraceType) -> Self {
+        SignalToggles {
+            trace_type,
+            bits: vec![],
+        }
+    }
+    // Account for the change of the signal from `last` (which is empty
+    // for the first value) to `value`.
+    pub(crate) fn observe(&mut self, last: &[TraceBit], value: &[TraceBit]) {
+        if self.bits.len() < value.len() {
+            self.bits.resize(value.len(), BitToggles::default());
+        }
+        for (ndx, (bit, toggles)) in value.iter().zip(self.bits.iter_mut()).enumerate() {
+            match bit {
+                TraceBit::Zero => toggles.seen_zero = true,
+                TraceBit::One => toggles.seen_one = true,
+                _ => {}
+            }
+            match (last.get(ndx), bit) {
+                (Some(TraceBit::Zero), TraceBit::One) => toggles.rises += 1,
+                (Some(TraceBit::One), TraceBit::Zero) => toggles.falls += 1,
+                _ => {}
+            }
+        }
+    }
+    pub fn toggled(&self) -> usize {
+        self.bits.iter().filter(|x| x.toggled()).count()
+    }
+    // Split the bits of the signal into the leaves of its type.  The
+    // payloads of the variants of an enum share their bits, so those bits
+    // are listed under each variant.
+    pub fn leaves(&self) -> Vec<LeafToggles> {
+        let kind = trace_to_kind(&self.trace_type);
+        let mut leaves = vec![];
+        if kind.bits() == self.bits.len() {
+            leaf_ranges(&kind, Path::default(), 0, &mut leaves);
+        } else {
+            leaves.push((Path::default(), 0..self.bits.len()));
+        }
+        leaves
+            .into_iter()
+            .filter(|(_, range)| !range.is_empty())
+            .map(|(path, range)| LeafToggles {
+                path: format!("{path:?}"),
+                bits: self.bits[range].to_vec(),
+            })
+            .collect()
+    }
+}
+
+fn leaf_ranges(kind: &Kind, path: Path, offset: usize, leaves: &mut Vec<(Path, Range<usize>)>) {
+    match kind {
+        Kind::Array(array) => {
+            let width = array.base.bits();
+            for ndx in 0..array.size {
+                leaf_ranges(
+                    &array.base,
+                    path.clone().index(ndx),
+                    offset + ndx * width,
+                    leaves,
+                );
+            }
+        }
+        Kind::Tuple(tuple) => {
+            let mut offset = offset;
+            for (ndx, element) in tuple.elements.iter().enumerate() {
+                leaf_ranges(element, path.clone().tuple_index(ndx), offset, leaves);
+                offset += element.bits();
+            }
+        }
+        Kind::Struct(strukt) => {
+            let mut offset = offset;
+            for field in strukt.fields.iter() {
+                leaf_ranges(&field.kind, path.clone().field(&field.name), offset, leaves);
+                offset += field.kind.bits();
+            }
+        }
+        Kind::Signal(root, _) => leaf_ranges(root, path, offset, leaves),
+        Kind::Enum(enumerate) => {
+            let layout = enumerate.discriminant_layout;
+            let (discriminant, payload) = match layout.alignment {
+                DiscriminantAlignment::Lsb => (offset, offset + layout.width),
+                DiscriminantAlignment::Msb => (offset + kind.bits() - layout.width, offset),
+            };
+            leaves.push((
+                path.clone().discriminant(),
+                discriminant..discriminant + layout.width,
+            ));
+            for variant in &enumerate.variants {
+                leaf_ranges(
+                    &variant.kind,
+                    path.clone().payload(&variant.name),
+                    payload,
+                    leaves,
+                );
+            }
+        }
+        Kind::Bits(_) | Kind::Signed(_) | Kind::Empty => {
+            leaves.push((path, offset..offset + kind.bits()))
+        }
+    }
+}
+
+// Format bit indices as ranges, most significant first, e.g. `[31:28] [3]`.
+fn bit_ranges(bits: &[usize]) -> String {
+    let mut ranges: Vec<Range<usize>> = vec![];
+    for &bit in bits {
+        match ranges.last_mut() {
+            Some(range) if range.end == bit => range.end += 1,
+            _ => ranges.push(bit..bit + 1),
+        }
+    }
+    ranges
+        .iter()
+        .rev()
+        .map(|range| match range.len() {
+            1 => format!("[{}]", range.start),
+            _ => format!("[{}:{}]", range.end - 1, range.start),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// The toggle coverage of the signals of a trace database, by their full
+// hierarchical name (e.g. `top.fifo.write_enable`).  The coverage of
+// several runs (e.g., the tests of a regression) can be combined with
+// `merge`.
+#[derive(Clone, Debug, Default)]
+pub struct ToggleCoverage {
+    pub signals: BTreeMap<String, SignalToggles>,
+}
+
+impl ToggleCoverage {
+    pub fn merge(&mut self, other: &ToggleCoverage) {
+        for (name, signal) in &other.signals {
+            let Some(mine) = self.signals.get_mut(name) else {
+                self.signals.insert(name.clone(), signal.clone());
+                continue;
+            };
+            if mine.bits.len() < signal.bits.len() {
+                mine.bits.resize(signal.bits.len(), BitToggles::default());
+            }
+            for (bit, other) in mine.bits.iter_mut().zip(&signal.bits) {
+                bit.merge(other);
+            }
+        }
+    }
+    // The number of bits that toggled, and the total number of bits.
+    pub fn summary(&self) -> (usize, usize) {
+        self.signals
+            .values()
+            .fold((0, 0), |(toggled, total), signal| {
+                (toggled + signal.toggled(), total + signal.bits.len())
+            })
+    }
+    // A report of the toggle coverage of each signal, and of each part of
+    // it.  Parts with bits that did not toggle are marked with a `!`, and
+    // list the bits that are stuck, and those that only toggled one way.
+    pub fn report(&self) -> String {
+        let (toggled, total) = self.summary();
+        let mut report = format!("{toggled} of {total} bits toggled\n");
+        for (name, signal) in &self.signals {
+            report += &format!(
+                "{name}: {} of {} bits toggled\n",
+                signal.toggled(),
+                signal.bits.len()
+            );
+            for leaf in signal.leaves() {
+                let toggled = leaf.bits.iter().filter(|x| x.toggled()).count();
+                let mark = if toggled < leaf.bits.len() { '!' } else { ' ' };
+                report += &format!(
+                    "{mark} {name}{}: {toggled} of {} bits toggled",
+                    leaf.path,
+                    leaf.bits.len()
+                );
+                let select = |f: &dyn Fn(&BitToggles) -> bool| {
+                    leaf.bits
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, x)| f(x))
+                        .map(|(ndx, _)| ndx)
+                        .collect::<Vec<_>>()
+                };
+                for (label, bits) in [
+                    (
+                        "stuck at 0",
+                        select(&|x| x.stuck_at() == Some(TraceBit::Zero)),
+                    ),
+                    (
+                        "stuck at 1",
+                        select(&|x| x.stuck_at() == Some(TraceBit::One)),
+                    ),
+                    (
+                        "never known",
+                        select(&|x| x.stuck_at() == Some(TraceBit::X)),
+                    ),
+                    (
+                        "toggled one way",
+                        select(&|x| x.stuck_at().is_none() && !x.toggled()),
+                    ),
+                ] {
+                    if !bits.is_empty() {
+                        report += &format!(", {label} {}", bit_ranges(&bits));
+                    }
+                }
+                report.push('\n');
+            }
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bit_ranges() {
+        assert_eq!(bit_ranges(&[0, 1, 2, 5, 7, 8]), "[8:7] [5] [2:0]");
+        assert_eq!(bit_ranges(&[]), "");
+    }
+}
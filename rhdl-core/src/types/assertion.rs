@@ -0,0 +1,81 @@
+// Support for `assert!` and `cover!` in kernels.  When the Rust version
+// of a kernel runs (e.g., during simulation), a failing `assert!` calls
+// `kernel_assertion_failed`, which reports the assertion against the
+// source of the kernel, like the other RHDL diagnostics.
+use miette::{Diagnostic, SourceSpan};
+use thiserror::Error;
+
+use crate::{
+    ast::{
+        ast_impl::{AssertKind, Expr, ExprKind, NodeId},
+        source::builder::build_spanned_source_for_kernel,
+        visit::Visitor,
+    },
+    KernelFnKind, RHDLError, SourcePool,
+};
+
+#[derive(Debug, Error)]
+#[error("RHDL Assertion Failed")]
+pub struct RHDLAssertionFailure {
+    pub message: String,
+    pub src: SourcePool,
+    pub err_span: SourceSpan,
+}
+
+impl Diagnostic for RHDLAssertionFailure {
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.src)
+    }
+    fn labels<'a>(&'a self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + 'a>> {
+        Some(Box::new(std::iter::once(
+            miette::LabeledSpan::new_primary_with_span(Some(self.message.clone()), self.err_span),
+        )))
+    }
+}
+
+// Collect the assertions in a kernel, in the order they appear.
+#[derive(Default)]
+pub(crate) struct AssertionCollector {
+    pub(crate) assertions: Vec<(NodeId, AssertKind, &'static str)>,
+}
+
+impl Visitor for AssertionCollector {
+    fn visit_expr(&mut self, node: &Expr) -> Result<(), RHDLError> {
+        if let ExprKind::Assert(assert) = &node.kind {
+            self.assertions.push((node.id, assert.kind, assert.message));
+        }
+        crate::ast::visit::visit_expr(self, node)
+    }
+}
+
+/// Report the failure of the `ordinal`-th assertion (counting both
+/// `assert!` and `cover!`) in the given kernel.  This is called from the
+/// code generated by the `#[kernel]` macro, and does not return.
+pub fn kernel_assertion_failed(ordinal: usize, kernel: Option<KernelFnKind>) -> ! {
+    let Some(KernelFnKind::Kernel(kernel)) = kernel else {
+        panic!("Assertion failed in kernel");
+    };
+    let kernel = kernel.inner();
+    let mut collector = AssertionCollector::default();
+    let assertion = collector
+        .visit_block(&kernel.body)
+        .ok()
+        .and_then(|_| collector.assertions.get(ordinal).copied());
+    let Some((id, _, message)) = assertion else {
+        panic!("Assertion failed in kernel {}", kernel.name);
+    };
+    let message = if message.is_empty() {
+        format!("Assertion failed in kernel {}", kernel.name)
+    } else {
+        message.to_string()
+    };
+    let Ok(source) = build_spanned_source_for_kernel(kernel) else {
+        panic!("{message}");
+    };
+    let err = RHDLAssertionFailure {
+        message,
+        err_span: source.span(id).into(),
+        src: source.source(),
+    };
+    panic!("{:?}", miette::Report::new(err));
+}
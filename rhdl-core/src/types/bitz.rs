@@ -54,4 +54,8 @@ impl<N: BitWidth> Digital for BitZ<N> {
             mask: Bits::dont_care(),
         }
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        let (value, mask) = <(Bits<N>, Bits<N>)>::from_bin(bits)?;
+        Some(Self { value, mask })
+    }
 }
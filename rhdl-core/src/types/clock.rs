@@ -27,4 +27,7 @@ impl Digital for Clock {
     fn dont_care() -> Self {
         Clock(false)
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        bool::from_bin(bits).map(Clock)
+    }
 }
@@ -53,4 +53,8 @@ impl Digital for ClockReset {
             reset: Reset::dont_care(),
         }
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        let (clock, reset) = <(Clock, Reset)>::from_bin(bits)?;
+        Some(Self { clock, reset })
+    }
 }
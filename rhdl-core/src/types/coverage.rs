@@ -0,0 +1,345 @@
+// Branch coverage of kernels.  While a coverage session is active (see
+// `coverage_init`), kernels record which of their branches are taken.
+// A branch is the `then` or `else` of an `if` (or `if let`), an arm of a
+// `match`, or an early `return`.  The Rust version of a kernel (e.g., in
+// a behavioral simulation) records its branches through the code added
+// by `#[kernel(coverage)]`, and kernels compiled during the session are
+// instrumented so that `rhif::vm::execute` records them as well.  Both
+// number the branches in the order in which `BranchCollector` finds them.
+use std::{cell::RefCell, collections::BTreeMap};
+
+use miette::{Diagnostic, SourceSpan};
+use thiserror::Error;
+
+use crate::{
+    ast::{
+        ast_impl::{Expr, ExprKind, FunctionId, NodeId},
+        source::{
+            builder::build_spanned_source_for_kernel, source_pool::SourcePool,
+            spanned_source::SpannedSource,
+        },
+        visit::Visitor,
+    },
+    rhif::{spec::Slot, Object},
+    KernelFnKind, RHDLError, TypedBits,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BranchKind {
+    Then,
+    Else,
+    // The else branch of an `if` that has no `else`.
+    ImplicitElse,
+    Arm,
+    Return,
+}
+
+impl std::fmt::Display for BranchKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BranchKind::Then => write!(f, "then branch"),
+            BranchKind::Else => write!(f, "else branch"),
+            BranchKind::ImplicitElse => write!(f, "implicit else branch"),
+            BranchKind::Arm => write!(f, "match arm"),
+            BranchKind::Return => write!(f, "return"),
+        }
+    }
+}
+
+// Collect the branches of a kernel, in the order they appear.  Each
+// branch is identified by the node it starts at (and its kind).  An `if`
+// without an `else` still has an (implicit) else branch, which is
+// identified by the `if` itself.  The conditions of assertions are not
+// visited, as the Rust version of the kernel does not see them as code.
+#[derive(Default)]
+pub(crate) struct BranchCollector {
+    pub(crate) branches: Vec<(NodeId, BranchKind)>,
+}
+
+impl Visitor for BranchCollector {
+    fn visit_expr(&mut self, node: &Expr) -> Result<(), RHDLError> {
+        match &node.kind {
+            ExprKind::If(expr) => {
+                self.branches.push((expr.then_branch.id, BranchKind::Then));
+                self.branches.push(match &expr.else_branch {
+                    Some(else_branch) => (else_branch.id, BranchKind::Else),
+                    None => (node.id, BranchKind::ImplicitElse),
+                });
+            }
+            ExprKind::IfLet(expr) => {
+                self.branches.push((expr.then_block.id, BranchKind::Then));
+                self.branches.push(match &expr.else_branch {
+                    Some(else_branch) => (else_branch.id, BranchKind::Else),
+                    None => (node.id, BranchKind::ImplicitElse),
+                });
+                // The generic visitor does not descend into an `if let`
+                self.visit_expr(&expr.test)?;
+                self.visit_block(&expr.then_block)?;
+                if let Some(else_branch) = &expr.else_branch {
+                    self.visit_expr(else_branch)?;
+                }
+                return Ok(());
+            }
+            ExprKind::Match(expr) => {
+                self.branches
+                    .extend(expr.arms.iter().map(|arm| (arm.id, BranchKind::Arm)));
+            }
+            ExprKind::Ret(_) => self.branches.push((node.id, BranchKind::Return)),
+            ExprKind::Try(expr) => return self.visit_expr(&expr.expr),
+            ExprKind::Cast(expr) => return self.visit_expr(&expr.expr),
+            ExprKind::Assert(_) => return Ok(()),
+            _ => {}
+        }
+        crate::ast::visit::visit_expr(self, node)
+    }
+}
+
+// The accessor of a kernel, as passed by the code generated by the
+// `#[kernel]` macro.
+type KernelAccessor = fn() -> Option<KernelFnKind>;
+
+#[derive(Default)]
+struct CoverageDB {
+    // Hits recorded by the Rust versions of kernels, by accessor.  The
+    // kernels are only built when the coverage is collected.
+    native: BTreeMap<usize, (KernelAccessor, Vec<u64>)>,
+    // Hits recorded by the RHIF VM, by function.
+    vm: BTreeMap<FunctionId, KernelCoverage>,
+}
+
+thread_local! {
+    static COVERAGE: RefCell<Option<CoverageDB>> = const { RefCell::new(None) };
+}
+
+pub struct CoverageGuard;
+
+impl CoverageGuard {
+    pub fn take(self) -> Coverage {
+        let db = COVERAGE
+            .with(|db| db.borrow_mut().take())
+            .unwrap_or_default();
+        let mut kernels = db.vm;
+        for (kernel, hits) in db.native.into_values() {
+            let Some(KernelFnKind::Kernel(kernel)) = kernel() else {
+                continue;
+            };
+            let kernel = kernel.inner();
+            let mut collector = BranchCollector::default();
+            let Ok(source) = collector
+                .visit_block(&kernel.body)
+                .map_err(|_| ())
+                .and_then(|_| build_spanned_source_for_kernel(kernel).map_err(|_| ()))
+            else {
+                continue;
+            };
+            let coverage = kernels
+                .entry(kernel.fn_id)
+                .or_insert_with(|| KernelCoverage {
+                    source,
+                    branches: collector
+                        .branches
+                        .iter()
+                        .map(|(node, kind)| BranchCoverage {
+                            node: *node,
+                            kind: *kind,
+                            hits: 0,
+                        })
+                        .collect(),
+                });
+            for (branch, hits) in coverage.branches.iter_mut().zip(hits) {
+                branch.hits += hits;
+            }
+        }
+        Coverage { kernels }
+    }
+}
+
+impl Drop for CoverageGuard {
+    fn drop(&mut self) {
+        COVERAGE.with(|db| db.borrow_mut().take());
+    }
+}
+
+// Start a coverage session, which lasts until the guard is taken (or
+// dropped).
+pub fn coverage_init() -> CoverageGuard {
+    COVERAGE.with(|db| *db.borrow_mut() = Some(CoverageDB::default()));
+    CoverageGuard
+}
+
+pub(crate) fn is_coverage_enabled() -> bool {
+    COVERAGE.with(|db| db.borrow().is_some())
+}
+
+/// Record that the `ordinal`-th branch of a kernel was taken.  This is
+/// called from the code generated by `#[kernel(coverage)]`, and does
+/// nothing unless a coverage session is active.
+pub fn kernel_branch_taken(ordinal: usize, kernel: fn() -> Option<KernelFnKind>) {
+    COVERAGE.with(|db| {
+        let mut db = db.borrow_mut();
+        let Some(db) = db.as_mut() else {
+            return;
+        };
+        let (_, hits) = db
+            .native
+            .entry(kernel as usize)
+            .or_insert_with(|| (kernel, vec![]));
+        if hits.len() <= ordinal {
+            hits.resize(ordinal + 1, 0);
+        }
+        hits[ordinal] += 1;
+    })
+}
+
+// Record the branches taken by one execution of an instrumented object,
+// given the registers at the end of the execution.
+pub(crate) fn record_vm_branches(obj: &Object, regs: &[Option<TypedBits>]) {
+    if obj.branches.is_empty() {
+        return;
+    }
+    COVERAGE.with(|db| {
+        let mut db = db.borrow_mut();
+        let Some(db) = db.as_mut() else {
+            return;
+        };
+        let Some(source) = obj.symbols.source_set.sources.get(&obj.fn_id) else {
+            return;
+        };
+        let coverage = db.vm.entry(obj.fn_id).or_insert_with(|| KernelCoverage {
+            source: source.clone(),
+            branches: obj
+                .branches
+                .iter()
+                .map(|branch| BranchCoverage {
+                    node: branch.loc.node,
+                    kind: branch.kind,
+                    hits: 0,
+                })
+                .collect(),
+        });
+        for (branch, count) in obj.branches.iter().zip(coverage.branches.iter_mut()) {
+            let taken = match branch.cond {
+                Slot::Register(r) => regs[r.0].as_ref(),
+                Slot::Literal(ndx) => obj.literals.get(&ndx),
+                Slot::Empty => None,
+            };
+            if taken.is_some_and(|x| x.as_bool().unwrap_or(false)) {
+                count.hits += 1;
+            }
+        }
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct BranchCoverage {
+    pub node: NodeId,
+    pub kind: BranchKind,
+    // The number of times the branch was taken.  The VM counts a branch
+    // once per execution of the kernel, even if it is inside a loop.
+    pub hits: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct KernelCoverage {
+    pub source: SpannedSource,
+    pub branches: Vec<BranchCoverage>,
+}
+
+impl KernelCoverage {
+    pub fn name(&self) -> &str {
+        &self.source.name
+    }
+    pub fn covered(&self) -> usize {
+        self.branches.iter().filter(|x| x.hits > 0).count()
+    }
+    pub fn uncovered(&self) -> impl Iterator<Item = &BranchCoverage> {
+        self.branches.iter().filter(|x| x.hits == 0)
+    }
+    // The line and column (both starting at 1) of a branch in the
+    // source of the kernel.
+    fn line_col(&self, branch: &BranchCoverage) -> (usize, usize) {
+        let start = self.source.span(branch.node).start;
+        let before = &self.source.source[..start];
+        let line = before.matches('\n').count() + 1;
+        let col = start - before.rfind('\n').map_or(0, |x| x + 1) + 1;
+        (line, col)
+    }
+    // A diagnostic that highlights the branches that were never taken, or
+    // `None` if all of them were.
+    pub fn diagnostic(&self) -> Option<UncoveredBranches> {
+        let spans = self
+            .uncovered()
+            .map(|branch| {
+                (
+                    format!("this {} is never taken", branch.kind),
+                    self.source.span(branch.node).into(),
+                )
+            })
+            .collect::<Vec<_>>();
+        (!spans.is_empty()).then(|| UncoveredBranches {
+            name: self.name().to_string(),
+            total: self.branches.len(),
+            src: self.source.source(),
+            spans,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("{} of the {total} branches of kernel {name} are never taken", spans.len())]
+pub struct UncoveredBranches {
+    pub name: String,
+    pub total: usize,
+    pub src: SourcePool,
+    pub spans: Vec<(String, SourceSpan)>,
+}
+
+impl Diagnostic for UncoveredBranches {
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.src)
+    }
+    fn labels<'a>(&'a self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + 'a>> {
+        Some(Box::new(self.spans.iter().map(|(label, span)| {
+            miette::LabeledSpan::new_with_span(Some(label.clone()), *span)
+        })))
+    }
+}
+
+// The branch coverage collected in a coverage session, by kernel.
+#[derive(Clone, Debug, Default)]
+pub struct Coverage {
+    pub kernels: BTreeMap<FunctionId, KernelCoverage>,
+}
+
+impl Coverage {
+    pub fn kernel(&self, name: &str) -> Option<&KernelCoverage> {
+        self.kernels.values().find(|x| x.name() == name)
+    }
+    // A summary of the coverage of each kernel, listing the branches that
+    // were never taken (by line and column in the source of the kernel),
+    // followed by the highlighted source of those branches.
+    pub fn report(&self) -> String {
+        let mut kernels = self.kernels.values().collect::<Vec<_>>();
+        kernels.sort_by(|a, b| a.name().cmp(b.name()));
+        let mut report = String::new();
+        for kernel in &kernels {
+            report += &format!(
+                "{} ({}): {} of {} branches taken\n",
+                kernel.name(),
+                kernel.source.filename,
+                kernel.covered(),
+                kernel.branches.len()
+            );
+            for branch in kernel.uncovered() {
+                let (line, col) = kernel.line_col(branch);
+                let text = kernel.source.text(branch.node);
+                let text = text.lines().next().unwrap_or_default().trim();
+                report += &format!("  {line}:{col} {} never taken: {text}\n", branch.kind);
+            }
+        }
+        for diagnostic in kernels.iter().filter_map(|x| x.diagnostic()) {
+            report += &format!("{:?}\n", miette::Report::new(diagnostic));
+        }
+        report
+    }
+}
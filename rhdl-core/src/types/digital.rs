@@ -1,4 +1,4 @@
-use rhdl_bits::{Bits, SignedBits};
+use rhdl_bits::{Bits, SFixed, SignedBits, UFixed, WideBits, WideSignedBits};
 
 use crate::{
     bitx::{bitx_vec, BitX},
@@ -92,6 +92,65 @@ pub trait Digital: Copy + PartialEq + Sized + Clone + 'static {
             .collect()
     }
     fn dont_care() -> Self;
+    /// Decode a value from its binary representation (as returned by
+    /// [Digital::bin]).  Returns `None` if the bits do not hold a value
+    /// of this type (e.g., they contain unknown bits or an undefined
+    /// discriminant), or if the type does not support decoding.
+    fn from_bin(_bits: &[BitX]) -> Option<Self> {
+        None
+    }
+}
+
+/// Split the binary representation of a `T` off the front of `bits`
+/// and decode it.  Composite values are decoded by taking each of
+/// their fields in turn.
+pub fn take_bin<T: Digital>(bits: &mut &[BitX]) -> Option<T> {
+    if bits.len() < T::BITS {
+        return None;
+    }
+    let (head, tail) = bits.split_at(T::BITS);
+    *bits = tail;
+    T::from_bin(head)
+}
+
+// The bits of a value of the given width, provided they are all known.
+fn known_bits(bits: &[BitX], width: usize) -> Option<Vec<bool>> {
+    if bits.len() != width {
+        return None;
+    }
+    bits.iter().map(|b| b.to_bool()).collect()
+}
+
+/// Split the binary representation of an enum into its discriminant and
+/// the (padded) payload bits.  This undoes the alignment of the
+/// discriminant applied by [Kind::pad].
+pub fn enum_from_bin(kind: &Kind, bits: &[BitX]) -> Option<(i64, Vec<BitX>)> {
+    let Kind::Enum(enumerate) = kind else {
+        return None;
+    };
+    if bits.len() != kind.bits() {
+        return None;
+    }
+    let layout = enumerate.discriminant_layout;
+    let (discriminant, payload) = match layout.alignment {
+        DiscriminantAlignment::Lsb => bits.split_at(layout.width),
+        DiscriminantAlignment::Msb => {
+            let (payload, discriminant) = bits.split_at(bits.len() - layout.width);
+            (discriminant, payload)
+        }
+    };
+    let discriminant = known_bits(discriminant, layout.width)?;
+    let mut value = discriminant
+        .iter()
+        .rev()
+        .fold(0_i64, |acc, b| (acc << 1) | (*b as i64));
+    if layout.ty == DiscriminantType::Signed
+        && layout.width < 64
+        && discriminant.last() == Some(&true)
+    {
+        value -= 1 << layout.width;
+    }
+    Some((value, payload.to_vec()))
 }
 
 impl<T: Digital> Digital for Option<T> {
@@ -149,6 +208,14 @@ impl<T: Digital> Digital for Option<T> {
     fn dont_care() -> Self {
         Self::None
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        let (discriminant, payload) = enum_from_bin(&Self::static_kind(), bits)?;
+        match discriminant {
+            0 => Some(Self::None),
+            1 => Some(Self::Some(take_bin(&mut payload.as_slice())?)),
+            _ => None,
+        }
+    }
 }
 
 impl<O: Digital, E: Digital> Digital for Result<O, E> {
@@ -218,6 +285,14 @@ impl<O: Digital, E: Digital> Digital for Result<O, E> {
     fn dont_care() -> Self {
         Self::Err(E::dont_care())
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        let (discriminant, payload) = enum_from_bin(&Self::static_kind(), bits)?;
+        match discriminant {
+            0 => Some(Self::Err(take_bin(&mut payload.as_slice())?)),
+            1 => Some(Self::Ok(take_bin(&mut payload.as_slice())?)),
+            _ => None,
+        }
+    }
 }
 
 impl Digital for () {
@@ -232,6 +307,9 @@ impl Digital for () {
         Vec::new()
     }
     fn dont_care() -> Self {}
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        bits.is_empty().then_some(())
+    }
 }
 
 impl Digital for bool {
@@ -248,6 +326,9 @@ impl Digital for bool {
     fn dont_care() -> Self {
         Self::default()
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        Some(known_bits(bits, 1)?[0])
+    }
 }
 /*
 
@@ -372,6 +453,9 @@ impl Digital for u128 {
     fn dont_care() -> Self {
         Self::default()
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        Some(Bits::<W128>::from_bools(&known_bits(bits, <Self as Digital>::BITS)?).raw())
+    }
 }
 
 impl Digital for i128 {
@@ -388,6 +472,9 @@ impl Digital for i128 {
     fn dont_care() -> Self {
         Self::default()
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        Some(SignedBits::<W128>::from_bools(&known_bits(bits, <Self as Digital>::BITS)?).raw())
+    }
 }
 
 impl Digital for usize {
@@ -408,6 +495,9 @@ impl Digital for usize {
     fn dont_care() -> Self {
         Self::default()
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        Some(Bits::<W128>::from_bools(&known_bits(bits, <Self as Digital>::BITS)?).raw() as usize)
+    }
 }
 
 impl<N: BitWidth> Digital for Bits<N> {
@@ -424,6 +514,9 @@ impl<N: BitWidth> Digital for Bits<N> {
     fn dont_care() -> Self {
         Self::default()
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        Some(Self::from_bools(&known_bits(bits, Self::BITS)?))
+    }
 }
 
 impl<N: BitWidth> Digital for SignedBits<N> {
@@ -440,6 +533,88 @@ impl<N: BitWidth> Digital for SignedBits<N> {
     fn dont_care() -> Self {
         Self::default()
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        Some(Self::from_bools(&known_bits(bits, Self::BITS)?))
+    }
+}
+
+impl<N: BitWidth> Digital for WideBits<N> {
+    const BITS: usize = N::BITS;
+    fn static_kind() -> Kind {
+        Kind::make_bits(N::BITS)
+    }
+    fn static_trace_type() -> rhdl_trace_type::TraceType {
+        rtt::TraceType::Bits(N::BITS)
+    }
+    fn bin(self) -> Vec<BitX> {
+        bitx_vec(&self.to_bools())
+    }
+    fn dont_care() -> Self {
+        Self::default()
+    }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        Some(Self::from_bools(&known_bits(bits, Self::BITS)?))
+    }
+}
+
+impl<N: BitWidth> Digital for WideSignedBits<N> {
+    const BITS: usize = N::BITS;
+    fn static_kind() -> Kind {
+        Kind::make_signed(N::BITS)
+    }
+    fn static_trace_type() -> rhdl_trace_type::TraceType {
+        rtt::TraceType::Signed(N::BITS)
+    }
+    fn bin(self) -> Vec<BitX> {
+        bitx_vec(&self.to_bools())
+    }
+    fn dont_care() -> Self {
+        Self::default()
+    }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        Some(Self::from_bools(&known_bits(bits, Self::BITS)?))
+    }
+}
+
+// Fixed point values look like plain bits to the compiler, so that
+// kernels operate on the raw (scaled) values.  Only the trace type
+// records the position of the binary point.
+impl<I: BitWidth, F: BitWidth> Digital for UFixed<I, F> {
+    const BITS: usize = I::BITS + F::BITS;
+    fn static_kind() -> Kind {
+        Kind::make_bits(Self::BITS)
+    }
+    fn static_trace_type() -> rhdl_trace_type::TraceType {
+        rtt::make_ufixed(I::BITS, F::BITS)
+    }
+    fn bin(self) -> Vec<BitX> {
+        bitx_vec(&self.to_bools())
+    }
+    fn dont_care() -> Self {
+        Self::default()
+    }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        Some(Self::from_bools(&known_bits(bits, Self::BITS)?))
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> Digital for SFixed<I, F> {
+    const BITS: usize = I::BITS + F::BITS;
+    fn static_kind() -> Kind {
+        Kind::make_signed(Self::BITS)
+    }
+    fn static_trace_type() -> rhdl_trace_type::TraceType {
+        rtt::make_sfixed(I::BITS, F::BITS)
+    }
+    fn bin(self) -> Vec<BitX> {
+        bitx_vec(&self.to_bools())
+    }
+    fn dont_care() -> Self {
+        Self::default()
+    }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        Some(Self::from_bools(&known_bits(bits, Self::BITS)?))
+    }
 }
 
 // Add blanket implementation for tuples up to size 4.
@@ -457,6 +632,9 @@ impl<T0: Digital> Digital for (T0,) {
     fn dont_care() -> Self {
         (T0::dont_care(),)
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        Some((T0::from_bin(bits)?,))
+    }
 }
 
 impl<T0: Digital, T1: Digital> Digital for (T0, T1) {
@@ -476,6 +654,12 @@ impl<T0: Digital, T1: Digital> Digital for (T0, T1) {
     fn dont_care() -> Self {
         (T0::dont_care(), T1::dont_care())
     }
+    fn from_bin(mut bits: &[BitX]) -> Option<Self> {
+        if bits.len() != Self::BITS {
+            return None;
+        }
+        Some((take_bin(&mut bits)?, take_bin(&mut bits)?))
+    }
 }
 
 impl<T0: Digital, T1: Digital, T2: Digital> Digital for (T0, T1, T2) {
@@ -504,6 +688,16 @@ impl<T0: Digital, T1: Digital, T2: Digital> Digital for (T0, T1, T2) {
     fn dont_care() -> Self {
         (T0::dont_care(), T1::dont_care(), T2::dont_care())
     }
+    fn from_bin(mut bits: &[BitX]) -> Option<Self> {
+        if bits.len() != Self::BITS {
+            return None;
+        }
+        Some((
+            take_bin(&mut bits)?,
+            take_bin(&mut bits)?,
+            take_bin(&mut bits)?,
+        ))
+    }
 }
 
 impl<T0: Digital, T1: Digital, T2: Digital, T3: Digital> Digital for (T0, T1, T2, T3) {
@@ -540,6 +734,17 @@ impl<T0: Digital, T1: Digital, T2: Digital, T3: Digital> Digital for (T0, T1, T2
             T3::dont_care(),
         )
     }
+    fn from_bin(mut bits: &[BitX]) -> Option<Self> {
+        if bits.len() != Self::BITS {
+            return None;
+        }
+        Some((
+            take_bin(&mut bits)?,
+            take_bin(&mut bits)?,
+            take_bin(&mut bits)?,
+            take_bin(&mut bits)?,
+        ))
+    }
 }
 
 // macro to add digital trait for array of size N
@@ -594,6 +799,12 @@ impl<T: Digital, const N: usize> Digital for [T; N] {
     fn dont_care() -> Self {
         [T::dont_care(); N]
     }
+    fn from_bin(mut bits: &[BitX]) -> Option<Self> {
+        if bits.len() != Self::BITS {
+            return None;
+        }
+        array_init::try_array_init(|_| take_bin(&mut bits).ok_or(())).ok()
+    }
 }
 
 #[cfg(test)]
@@ -890,4 +1101,16 @@ mod test {
         let x: Option<b8> = None;
         assert_eq!(x.discriminant().bits, vec![BitX::Zero]);
     }
+
+    #[test]
+    fn test_from_bin_round_trip() {
+        let x: (b4, [s3; 2], Option<bool>, Result<b2, s6>) =
+            (b4(9), [s3(-4), s3(3)], Some(true), Err(s6(-20)));
+        assert_eq!(Digital::from_bin(&x.bin()), Some(x));
+        let y: (u128, i128, b256, UFixed<W4, W4>) =
+            (u128::MAX, -5, b256(1) << 200, UFixed::from_f64(1.5));
+        assert_eq!(Digital::from_bin(&y.bin()), Some(y));
+        assert_eq!(<(b4, b4)>::from_bin(&b8(0).bin()[1..]), None);
+        assert_eq!(bool::from_bin(&[BitX::X]), None);
+    }
 }
@@ -166,6 +166,28 @@ where
     F::describe()
 }
 
+// Kernels defined as methods do not get a marker struct, so we
+// identify them by the (unique) type of the function item instead.
+pub fn fn_type_id<F: 'static>(_f: F) -> std::any::TypeId {
+    std::any::TypeId::of::<F>()
+}
+
+// A fixed point value is just its raw bits to the compiler, so the
+// `from_raw` constructors of the fixed point types are called in kernels
+// through this accessor, as if they were kernel methods.  The type must
+// be spelled so that the macro can tell it is one, e.g.,
+// `UFixed::<W4, W4>::from_raw(x)` or `<UQ4_4>::from_raw(x)`.
+pub trait FixedKernel {
+    #[doc(hidden)]
+    fn __rhdl_kernel_from_raw() -> Option<KernelFnKind> {
+        Some(KernelFnKind::FixedConstructor)
+    }
+}
+
+impl<I: BitWidth, F: BitWidth> FixedKernel for rhdl_bits::UFixed<I, F> {}
+
+impl<I: BitWidth, F: BitWidth> FixedKernel for rhdl_bits::SFixed<I, F> {}
+
 impl<N> DigitalFn for rhdl_bits::Bits<N>
 where
     N: BitWidth,
@@ -201,3 +223,39 @@ where
         Some(KernelFnKind::SignedBitsConstructor(N::BITS))
     }
 }
+
+impl<N> DigitalFn for rhdl_bits::WideBits<N>
+where
+    N: BitWidth,
+{
+    fn kernel_fn() -> Option<KernelFnKind> {
+        Some(KernelFnKind::BitConstructor(N::BITS))
+    }
+}
+
+impl<N> DigitalFn for rhdl_bits::WideSignedBits<N>
+where
+    N: BitWidth,
+{
+    fn kernel_fn() -> Option<KernelFnKind> {
+        Some(KernelFnKind::SignedBitsConstructor(N::BITS))
+    }
+}
+
+impl<N> DigitalFn for rhdl_bits::wide_bits<N>
+where
+    N: BitWidth,
+{
+    fn kernel_fn() -> Option<KernelFnKind> {
+        Some(KernelFnKind::BitConstructor(N::BITS))
+    }
+}
+
+impl<N> DigitalFn for rhdl_bits::wide_signed<N>
+where
+    N: BitWidth,
+{
+    fn kernel_fn() -> Option<KernelFnKind> {
+        Some(KernelFnKind::SignedBitsConstructor(N::BITS))
+    }
+}
@@ -69,6 +69,8 @@ pub enum DynamicTypeError {
     CannotApplyBinaryOperationToComposite { value: TypedBits },
     #[error("Cannot negate unsigned value {value:?}")]
     CannotNegateUnsigned { value: TypedBits },
+    #[error("Bit manipulation operations require an unsigned value, not {value:?}")]
+    CannotApplyBitManipToNonUnsigned { value: TypedBits },
     #[error("Cannot apply shift operator to composite value {value:?}")]
     CannotApplyShiftOperationToComposite { value: TypedBits },
     #[error("Shift amount {value:?} must be unsigned")]
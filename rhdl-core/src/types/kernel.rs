@@ -32,6 +32,7 @@ pub enum KernelFnKind {
     BitCast(usize),
     SignedCast(usize),
     Wrap(WrapOp),
+    FixedConstructor,
 }
 
 impl std::fmt::Debug for KernelFnKind {
@@ -61,6 +62,7 @@ impl std::fmt::Debug for KernelFnKind {
             KernelFnKind::BitCast(width) => write!(f, "bit cast {}", width),
             KernelFnKind::SignedCast(width) => write!(f, "signed cast {}", width),
             KernelFnKind::Wrap(op) => write!(f, "wrap {:?}", op),
+            KernelFnKind::FixedConstructor => write!(f, "fixed constructor"),
         }
     }
 }
@@ -1,7 +1,9 @@
+pub mod assertion;
 pub mod bit_string;
 pub mod bitz;
 pub mod clock;
 pub mod clock_reset;
+pub mod coverage;
 pub mod digital;
 pub mod digital_fn;
 pub mod domain;
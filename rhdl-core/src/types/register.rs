@@ -1,4 +1,4 @@
-use rhdl_bits::{Bits, SignedBits};
+use rhdl_bits::{Bits, SignedBits, WideBits, WideSignedBits};
 use rhdl_typenum::*;
 
 use crate::{Digital, Kind};
@@ -13,6 +13,10 @@ impl<N: BitWidth> Register for Bits<N> {}
 
 impl<N: BitWidth> Register for SignedBits<N> {}
 
+impl<N: BitWidth> Register for WideBits<N> {}
+
+impl<N: BitWidth> Register for WideSignedBits<N> {}
+
 pub trait SignedRegister: Digital {
     fn static_kind() -> Kind {
         <Self as Digital>::static_kind()
@@ -20,3 +24,5 @@ pub trait SignedRegister: Digital {
 }
 
 impl<N: BitWidth> SignedRegister for SignedBits<N> {}
+
+impl<N: BitWidth> SignedRegister for WideSignedBits<N> {}
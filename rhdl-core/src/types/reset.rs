@@ -33,4 +33,7 @@ impl Digital for Reset {
     fn dont_care() -> Self {
         Reset(false)
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        bool::from_bin(bits).map(Reset)
+    }
 }
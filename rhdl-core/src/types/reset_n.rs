@@ -34,4 +34,7 @@ impl Digital for ResetN {
     fn dont_care() -> Self {
         ResetN(true)
     }
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        bool::from_bin(bits).map(ResetN)
+    }
 }
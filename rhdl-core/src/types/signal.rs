@@ -47,6 +47,10 @@ impl<T: Digital, C: Domain> Digital for Signal<T, C> {
             domain: std::marker::PhantomData,
         }
     }
+
+    fn from_bin(bits: &[BitX]) -> Option<Self> {
+        T::from_bin(bits).map(signal)
+    }
 }
 
 /* macro_rules! impl_index {
@@ -6,6 +6,7 @@ use crate::ast::ast_impl::WrapOp;
 use crate::bitx::dyn_bit_manip::bits_shr_signed;
 use crate::bitx::dyn_bit_manip::{
     bit_neg, bit_not, bits_and, bits_or, bits_shl, bits_shr, bits_xor, full_add, full_sub,
+    to_bigint, to_biguint,
 };
 use crate::bitx::{bitx_string, BitX};
 use crate::error::{rhdl_error, RHDLError};
@@ -692,6 +693,15 @@ impl std::cmp::PartialOrd for TypedBits {
         } else {
             self.kind.is_unsigned()
         };
+        // Values wider than 128 bits are compared as big integers
+        // (which fails if either has unknown bits).
+        if self.bits.len() > 128 {
+            return if is_unsigned {
+                to_biguint(&self.bits)?.partial_cmp(&to_biguint(&other.bits)?)
+            } else {
+                to_bigint(&self.bits)?.partial_cmp(&to_bigint(&other.bits)?)
+            };
+        }
         if is_unsigned {
             let mut a_as_u128 = 0;
             let mut b_as_u128 = 0;
@@ -836,6 +846,12 @@ fn write_bits(bits: &[BitX], f: &mut std::fmt::Formatter<'_>) -> std::fmt::Resul
             }
         );
     }
+    if bits.len() > 128 {
+        return match to_biguint(bits) {
+            Some(val) => write!(f, "{:x}_b{}", val, bits.len()),
+            None => write!(f, "{}", bitx_string(bits)),
+        };
+    }
     // We know that the bits array will fit into a u128.
     let val = bits
         .iter()
@@ -856,6 +872,12 @@ fn write_signed(bits: &[BitX], f: &mut std::fmt::Formatter<'_>) -> std::fmt::Res
             }
         );
     }
+    if bits.len() > 128 {
+        return match to_bigint(bits) {
+            Some(val) => write!(f, "{}_s{}", val, bits.len()),
+            None => write!(f, "{}", bitx_string(bits)),
+        };
+    }
     // We know that the bits array will fit into a i128.
     let bit_len = bits.len();
     let sign_bit = bits.last().cloned().unwrap_or(BitX::Zero);
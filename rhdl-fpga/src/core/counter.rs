@@ -94,4 +94,42 @@ mod tests {
         tm.run_iverilog()?;
         Ok(())
     }
+
+    #[kernel]
+    pub fn below_five(_enable: bool, count: Bits<W4>) -> bool {
+        count < bits(5)
+    }
+
+    #[test]
+    fn test_counter_bounded_model_check() -> miette::Result<()> {
+        let uut: U<W4> = U::default();
+        // The counter cannot reach five within five cycles
+        assert!(bounded_model_check::<_, below_five>(&uut, 5)?.is_none());
+        let counterexample = bounded_model_check::<_, below_five>(&uut, 10)?.unwrap();
+        // The shortest counterexample enables the counter in each of the
+        // five cycles before the count is checked
+        let inputs = counterexample
+            .iter()
+            .filter(|x| !x.value.0.clock.raw() && !x.value.0.reset.any())
+            .map(|x| x.value.1)
+            .collect::<Vec<_>>();
+        assert_eq!(inputs.len(), 6);
+        assert_eq!(inputs[..5], [true; 5]);
+        let last = counterexample
+            .iter()
+            .rev()
+            .find(|x| !x.value.0.clock.raw())
+            .unwrap();
+        assert_eq!(last.value.2, bits(5));
+        // The counterexample can be replayed into a VCD
+        let vcd: Vcd = uut
+            .run(counterexample.iter().map(|x| x.map(|(cr, i, _)| (cr, i))))?
+            .collect();
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("vcd")
+            .join("counter");
+        std::fs::create_dir_all(&root).unwrap();
+        vcd.dump_to_file(&root.join("counter_bmc.vcd")).unwrap();
+        Ok(())
+    }
 }
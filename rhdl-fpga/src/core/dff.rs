@@ -92,7 +92,8 @@ impl<T: Digital> Synchronous for U<T> {
     fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError> {
         let mut flow_graph = FlowGraph::default();
         let module = self.hdl(name)?;
-        let (clock_reset, d, q) = flow_graph.synchronous_black_box::<Self>(module);
+        let (clock_reset, d, q) =
+            flow_graph.dff_black_box::<Self>(module, self.reset.typed_bits().into());
         flow_graph.inputs = vec![clock_reset, d];
         flow_graph.output = q;
         Ok(CircuitDescriptor {
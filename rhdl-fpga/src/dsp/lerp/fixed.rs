@@ -1,9 +1,7 @@
-use std::ops::Add;
+use std::ops::{Add, Sub};
 
 use rhdl::prelude::*;
 
-use crate::cdc::synchronizer::S;
-
 /// A Linear Interpolation unit.  This unit takes a pair of values that and an
 /// interpolation factor, and then produces the linear interpolation of the
 /// two values.  The interpolation factor is a fixed-point number, with a
@@ -14,53 +12,41 @@ use crate::cdc::synchronizer::S;
 ///     A * (1 - x) + B * x
 /// Or equivalently:
 ///    A + (B - A) * x
-/// Because x is represented as a fixed point number it really represents
-///    f * 2^N
-/// So we need to calculate
-///    A + (B - A) * f * 2^N
-
-/*#[derive(Debug, Clone, Synchronous)]
-pub struct U {}
-*/
-
-
-/*
-
-#[derive(PartialEq, Debug, Digital, Default)]
-pub struct I<N: BitWidth, const M: usize> {
-    /// The value taken when the interpolant is 0
-    pub a: SignedBits<N>,
-    /// The value taken when the interpolant is maximal
-    pub b: SignedBits<N>,
-    /// The interpolation value (0 <= x < 1)
-    pub x: Bits<M>,
-}
-
-#[derive(PartialEq, Debug, Digital, Default)]
-pub struct O<N: BitWidth> {
-    /// The interpolated value
-    pub y: SignedBits<N>,
-}
-
-//  Where A = M + 1, B = N + M + 1
-//#[kernel]
-pub fn lerp_kernel<N: BitWidth, const M: usize, const A: usize>(_cr: ClockReset, i: I<N, M>) -> O<N>
+///
+/// Here A and B are signed fixed point values with I integer bits and
+/// F fractional bits, and x is the M bit fraction of a [UFixed<W1, M>].
+/// The product (B - A) * x has F + M fractional bits, and is rounded
+/// back to F fractional bits before it is added to A.  Because the
+/// rounded product never exceeds B - A in magnitude, the result always
+/// lies between A and B, and so fits in the width of the inputs.
+#[kernel]
+pub fn lerp<I, F, M>(a: SFixed<I, F>, b: SFixed<I, F>, x: Bits<M>) -> SFixed<I, F>
 where
-    SignedBits<A>: std::ops::Mul<SignedBits<N>, Output = SignedBits<M>>,
-    SignedBits<N>: Pad,
+    // B - A needs an extra integer bit
+    I: BitWidth + Add<W1>,
+    Sum<I, W1>: BitWidth + Add<W2>,
+    // The signed factor has 2 integer bits, so the product has I + 3
+    Sum<Sum<I, W1>, W2>: BitWidth + Max<I>,
+    // The product has F + M fractional bits, and rounding removes M of them
+    F: BitWidth + Add<M>,
+    Sum<F, M>: BitWidth + Sub<M, Output = F>,
+    M: BitWidth,
+    W1: Add<M>,
+    Sum<W1, M>: BitWidth,
+    // Adding the rounded product to A
+    Maximum<Sum<Sum<I, W1>, W2>, I>: Add<W1>,
+    Sum<Maximum<Sum<Sum<I, W1>, W2>, I>, W1>: BitWidth,
 {
-    let a: SignedBits<N> = i.a.resize();
-    let ax = a.pad();
-    let b: SignedBits<N> = i.b.resize();
-    let delt = b - a;
-    let _a = a << (A as u128);
-    let x: Bits<A> = i.x.resize();
-    let x: SignedBits<A> = x.as_signed();
-    let z = x * delt;
-    //    let y = (a + delt * x) >> (A as u128);
-    O::<N> { y: z.resize() }
+    // The interpolation factor as an unsigned fraction in [0, 1)
+    let x: UFixed<W1, M> = UFixed::<W1, M>::from_raw(x.resize());
+    // Compute B - A.  This is signed with I + 1 integer bits
+    let delta = b.xsub(a);
+    // Compute (B - A) * x, with F + M fractional bits
+    let step = delta.xmul(x.xsgn());
+    // Round the step back to F fractional bits, and add it to A
+    let y = step.xround::<M>().xadd(a);
+    y.resize_fixed()
 }
-*/
 
 /// Linear interpolation as a function - for unsigned values
 ///
@@ -125,21 +111,69 @@ where
     // Convert it to a signed value so we can add the correction (requires an additional bit)
     let lower_value = lower_value.xsgn().xext::<W1>();
     // Compute the correction - we do not need overflow on this, so a regular add (wrapping) is fine
-    let y = lower_value + correction;
+    let y = lower_value + correction.resize();
+    // Shift right by M bits to retrieve Y, which is positive and fits in N bits
+    (y >> M::BITS as u128).resize::<N>().as_unsigned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lerp_matches_float() {
+        type Q = SFixed<W4, W4>;
+        for a in (-128..128).step_by(7) {
+            for b in (-128..128).step_by(5) {
+                for x in 0..16 {
+                    let a = Q::from_raw(signed(a));
+                    let b = Q::from_raw(signed(b));
+                    let y = lerp::<W4, W4, W4>(a, b, bits(x));
+                    let step = (b.to_f64() - a.to_f64()) * x as f64 / 16.0;
+                    let expected = a.to_f64() + (step * 16.0 + 0.5).floor() / 16.0;
+                    assert_eq!(y.to_f64(), expected);
+                }
+            }
+        }
+    }
 
-    /*
-       // Calculate the product.  This will be signed of width N+M+2
-       let product = signed_factor.xmul(diff);
+    #[test]
+    fn test_lerp_stays_between_endpoints() {
+        type Q = SFixed<W2, W6>;
+        let a = Q::MIN;
+        let b = Q::MAX;
+        for x in 0..256 {
+            let y = lerp::<W2, W6, W8>(a, b, bits(x));
+            assert!(a <= y && y <= b);
+            let y = lerp::<W2, W6, W8>(b, a, bits(x));
+            assert!(a <= y && y <= b);
+        }
+    }
 
-       let b_frac = upper_value.xmul(factor);
-       let a_frac = lower_value.xmul(factor);
+    #[test]
+    fn test_lerp_unsigned() {
+        for a in (0..256).step_by(3) {
+            for b in (0..256).step_by(11) {
+                for x in 0..16 {
+                    let y = lerp_unsigned::<W8, W4>(bits(a), bits(b), bits(x));
+                    let expected = (a as i128 * 16 + (b as i128 - a as i128) * x as i128) >> 4;
+                    assert_eq!(y, bits(expected as u128));
+                }
+            }
+        }
+    }
 
-       // This is now N+1 bits wide and signed
-       let delta = upper_value.xsub(lower_value);
-       // We need the lower value to also be N+1 bits wide and signed
-       let lower_value = lower_value.xext::<W1>().as_signed();
-       // Next, we will multiply delta times the factor.  The result is M+N+1 bits wide
-       let delta_times_factor = delta.xmul(factor);
-    */
-    todo!()
+    #[test]
+    fn test_lerp_compiles() -> miette::Result<()> {
+        #[kernel]
+        fn do_stuff(
+            a: Signal<SFixed<W4, W4>, Red>,
+            b: Signal<SFixed<W4, W4>, Red>,
+            x: Signal<b6, Red>,
+        ) -> Signal<SFixed<W4, W4>, Red> {
+            signal(lerp::<W4, W4, W6>(a.val(), b.val(), x.val()))
+        }
+        compile_design::<do_stuff>(CompilationMode::Asynchronous)?;
+        Ok(())
+    }
 }
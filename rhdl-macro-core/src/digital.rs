@@ -109,6 +109,16 @@ fn derive_digital_tuple_struct(decl: DeriveInput) -> syn::Result<TokenStream> {
                             )*
                         )
                     }
+                    fn from_bin(mut bits: &[rhdl::core::BitX]) -> Option<Self> {
+                        if bits.len() != <Self as rhdl::core::Digital>::BITS {
+                            return None;
+                        }
+                        Some(Self(
+                            #(
+                                rhdl::core::types::digital::take_bin::<#field_types>(&mut bits)?,
+                            )*
+                        ))
+                    }
                 }
                 impl #impl_generics rhdl::core::DigitalFn for #struct_name #ty_generics #where_clause {
                     fn kernel_fn() -> Option<rhdl::core::KernelFnKind> {
@@ -193,6 +203,16 @@ fn derive_digital_named_struct(decl: DeriveInput) -> syn::Result<TokenStream> {
                             )*
                         }
                     }
+                    fn from_bin(mut bits: &[rhdl::core::BitX]) -> Option<Self> {
+                        if bits.len() != <Self as rhdl::core::Digital>::BITS {
+                            return None;
+                        }
+                        Some(Self {
+                            #(
+                                #fields: rhdl::core::types::digital::take_bin::<#field_types>(&mut bits)?,
+                            )*
+                        })
+                    }
                 }
             })
         }
@@ -216,7 +236,7 @@ mod test {
         );
         let output = derive_digital(decl).unwrap().to_string();
         let expected = expect![[r#"
-            "impl core :: marker :: Copy for NestedBits { } impl Clone for NestedBits { # [inline] fn clone (& self) -> Self { Self { nest_1 : self . nest_1 . clone () , nest_2 : self . nest_2 . clone () , nest_3 : self . nest_3 . clone () , } } } impl rhdl :: core :: Digital for NestedBits { const BITS : usize = < bool as rhdl :: core :: Digital > :: BITS + < u8 as rhdl :: core :: Digital > :: BITS + < TwoBits as rhdl :: core :: Digital > :: BITS ; const TRACE_BITS : usize = < bool as rhdl :: core :: Digital > :: TRACE_BITS + < u8 as rhdl :: core :: Digital > :: TRACE_BITS + < TwoBits as rhdl :: core :: Digital > :: TRACE_BITS ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_struct (concat ! (module_path ! () , \"::\" , stringify ! (NestedBits)) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (nest_1) , < bool as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (nest_2) , < u8 as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (nest_3) , < TwoBits as rhdl :: core :: Digital > :: static_kind ()) ,] ,) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_struct (concat ! (module_path ! () , \"::\" , stringify ! (NestedBits)) , vec ! [rhdl :: rtt :: make_field (stringify ! (nest_1) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (nest_2) , < u8 as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (nest_3) , < TwoBits as rhdl :: core :: Digital > :: static_trace_type ()) ,] ,) } fn bin (self) -> Vec < rhdl :: core :: BitX > { [self . nest_1 . bin () . as_slice () , self . nest_2 . bin () . as_slice () , self . nest_3 . bin () . as_slice () ,] . concat () } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { [self . nest_1 . trace () . as_slice () , self . nest_2 . trace () . as_slice () , self . nest_3 . trace () . as_slice () ,] . concat () } fn dont_care () -> Self { Self { nest_1 : < bool as rhdl :: core :: Digital > :: dont_care () , nest_2 : < u8 as rhdl :: core :: Digital > :: dont_care () , nest_3 : < TwoBits as rhdl :: core :: Digital > :: dont_care () , } } }"
+            "impl core :: marker :: Copy for NestedBits { } impl Clone for NestedBits { # [inline] fn clone (& self) -> Self { Self { nest_1 : self . nest_1 . clone () , nest_2 : self . nest_2 . clone () , nest_3 : self . nest_3 . clone () , } } } impl rhdl :: core :: Digital for NestedBits { const BITS : usize = < bool as rhdl :: core :: Digital > :: BITS + < u8 as rhdl :: core :: Digital > :: BITS + < TwoBits as rhdl :: core :: Digital > :: BITS ; const TRACE_BITS : usize = < bool as rhdl :: core :: Digital > :: TRACE_BITS + < u8 as rhdl :: core :: Digital > :: TRACE_BITS + < TwoBits as rhdl :: core :: Digital > :: TRACE_BITS ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_struct (concat ! (module_path ! () , \"::\" , stringify ! (NestedBits)) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (nest_1) , < bool as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (nest_2) , < u8 as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (nest_3) , < TwoBits as rhdl :: core :: Digital > :: static_kind ()) ,] ,) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_struct (concat ! (module_path ! () , \"::\" , stringify ! (NestedBits)) , vec ! [rhdl :: rtt :: make_field (stringify ! (nest_1) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (nest_2) , < u8 as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (nest_3) , < TwoBits as rhdl :: core :: Digital > :: static_trace_type ()) ,] ,) } fn bin (self) -> Vec < rhdl :: core :: BitX > { [self . nest_1 . bin () . as_slice () , self . nest_2 . bin () . as_slice () , self . nest_3 . bin () . as_slice () ,] . concat () } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { [self . nest_1 . trace () . as_slice () , self . nest_2 . trace () . as_slice () , self . nest_3 . trace () . as_slice () ,] . concat () } fn dont_care () -> Self { Self { nest_1 : < bool as rhdl :: core :: Digital > :: dont_care () , nest_2 : < u8 as rhdl :: core :: Digital > :: dont_care () , nest_3 : < TwoBits as rhdl :: core :: Digital > :: dont_care () , } } fn from_bin (mut bits : & [rhdl :: core :: BitX]) -> Option < Self > { if bits . len () != < Self as rhdl :: core :: Digital > :: BITS { return None ; } Some (Self { nest_1 : rhdl :: core :: types :: digital :: take_bin :: < bool > (& mut bits) ? , nest_2 : rhdl :: core :: types :: digital :: take_bin :: < u8 > (& mut bits) ? , nest_3 : rhdl :: core :: types :: digital :: take_bin :: < TwoBits > (& mut bits) ? , }) } }"
         "#]];
         expected.assert_debug_eq(&output);
     }
@@ -232,7 +252,7 @@ mod test {
         );
         let output = derive_digital(decl).unwrap().to_string();
         let expected = expect![[
-            r#"impl core :: marker :: Copy for Inputs { } impl Clone for Inputs { # [inline] fn clone (& self) -> Self { Self { input : self . input . clone () , write : self . write . clone () , read : self . read . clone () , } } } impl rhdl :: core :: Digital for Inputs { const BITS : usize = < u32 as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS ; const TRACE_BITS : usize = < u32 as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (input) , < u32 as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (read) , < bool as rhdl :: core :: Digital > :: static_kind ()) ,] ,) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: rtt :: make_field (stringify ! (input) , < u32 as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (read) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) ,] ,) } fn bin (self) -> Vec < rhdl :: core :: BitX > { [self . input . bin () . as_slice () , self . write . bin () . as_slice () , self . read . bin () . as_slice () ,] . concat () } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { [self . input . trace () . as_slice () , self . write . trace () . as_slice () , self . read . trace () . as_slice () ,] . concat () } fn dont_care () -> Self { Self { input : < u32 as rhdl :: core :: Digital > :: dont_care () , write : < bool as rhdl :: core :: Digital > :: dont_care () , read : < bool as rhdl :: core :: Digital > :: dont_care () , } } }"#
+            r#"impl core :: marker :: Copy for Inputs { } impl Clone for Inputs { # [inline] fn clone (& self) -> Self { Self { input : self . input . clone () , write : self . write . clone () , read : self . read . clone () , } } } impl rhdl :: core :: Digital for Inputs { const BITS : usize = < u32 as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS ; const TRACE_BITS : usize = < u32 as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (input) , < u32 as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (read) , < bool as rhdl :: core :: Digital > :: static_kind ()) ,] ,) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: rtt :: make_field (stringify ! (input) , < u32 as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (read) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) ,] ,) } fn bin (self) -> Vec < rhdl :: core :: BitX > { [self . input . bin () . as_slice () , self . write . bin () . as_slice () , self . read . bin () . as_slice () ,] . concat () } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { [self . input . trace () . as_slice () , self . write . trace () . as_slice () , self . read . trace () . as_slice () ,] . concat () } fn dont_care () -> Self { Self { input : < u32 as rhdl :: core :: Digital > :: dont_care () , write : < bool as rhdl :: core :: Digital > :: dont_care () , read : < bool as rhdl :: core :: Digital > :: dont_care () , } } fn from_bin (mut bits : & [rhdl :: core :: BitX]) -> Option < Self > { if bits . len () != < Self as rhdl :: core :: Digital > :: BITS { return None ; } Some (Self { input : rhdl :: core :: types :: digital :: take_bin :: < u32 > (& mut bits) ? , write : rhdl :: core :: types :: digital :: take_bin :: < bool > (& mut bits) ? , read : rhdl :: core :: types :: digital :: take_bin :: < bool > (& mut bits) ? , }) } }"#
         ]];
         expected.assert_eq(&output);
     }
@@ -248,7 +268,7 @@ mod test {
         );
         let output = derive_digital(decl).unwrap().to_string();
         let expected = expect![[
-            r#"impl < T : Digital > core :: marker :: Copy for Inputs < T > { } impl < T : Digital > Clone for Inputs < T > { # [inline] fn clone (& self) -> Self { Self { input : self . input . clone () , write : self . write . clone () , read : self . read . clone () , } } } impl < T : Digital > rhdl :: core :: Digital for Inputs < T > { const BITS : usize = < T as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS ; const TRACE_BITS : usize = < T as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_struct (& vec ! [module_path ! () . to_string () , "::" . to_string () , stringify ! (Inputs) . to_string () , "<" . to_string () , std :: any :: type_name :: < T > () . to_string () , ">" . to_string ()] . join ("") , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (input) , < T as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (read) , < bool as rhdl :: core :: Digital > :: static_kind ()) ,] ,) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_struct (& vec ! [module_path ! () . to_string () , "::" . to_string () , stringify ! (Inputs) . to_string () , "<" . to_string () , std :: any :: type_name :: < T > () . to_string () , ">" . to_string ()] . join ("") , vec ! [rhdl :: rtt :: make_field (stringify ! (input) , < T as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (read) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) ,] ,) } fn bin (self) -> Vec < rhdl :: core :: BitX > { [self . input . bin () . as_slice () , self . write . bin () . as_slice () , self . read . bin () . as_slice () ,] . concat () } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { [self . input . trace () . as_slice () , self . write . trace () . as_slice () , self . read . trace () . as_slice () ,] . concat () } fn dont_care () -> Self { Self { input : < T as rhdl :: core :: Digital > :: dont_care () , write : < bool as rhdl :: core :: Digital > :: dont_care () , read : < bool as rhdl :: core :: Digital > :: dont_care () , } } }"#
+            r#"impl < T : Digital > core :: marker :: Copy for Inputs < T > { } impl < T : Digital > Clone for Inputs < T > { # [inline] fn clone (& self) -> Self { Self { input : self . input . clone () , write : self . write . clone () , read : self . read . clone () , } } } impl < T : Digital > rhdl :: core :: Digital for Inputs < T > { const BITS : usize = < T as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS ; const TRACE_BITS : usize = < T as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_struct (& vec ! [module_path ! () . to_string () , "::" . to_string () , stringify ! (Inputs) . to_string () , "<" . to_string () , std :: any :: type_name :: < T > () . to_string () , ">" . to_string ()] . join ("") , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (input) , < T as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (read) , < bool as rhdl :: core :: Digital > :: static_kind ()) ,] ,) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_struct (& vec ! [module_path ! () . to_string () , "::" . to_string () , stringify ! (Inputs) . to_string () , "<" . to_string () , std :: any :: type_name :: < T > () . to_string () , ">" . to_string ()] . join ("") , vec ! [rhdl :: rtt :: make_field (stringify ! (input) , < T as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (read) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) ,] ,) } fn bin (self) -> Vec < rhdl :: core :: BitX > { [self . input . bin () . as_slice () , self . write . bin () . as_slice () , self . read . bin () . as_slice () ,] . concat () } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { [self . input . trace () . as_slice () , self . write . trace () . as_slice () , self . read . trace () . as_slice () ,] . concat () } fn dont_care () -> Self { Self { input : < T as rhdl :: core :: Digital > :: dont_care () , write : < bool as rhdl :: core :: Digital > :: dont_care () , read : < bool as rhdl :: core :: Digital > :: dont_care () , } } fn from_bin (mut bits : & [rhdl :: core :: BitX]) -> Option < Self > { if bits . len () != < Self as rhdl :: core :: Digital > :: BITS { return None ; } Some (Self { input : rhdl :: core :: types :: digital :: take_bin :: < T > (& mut bits) ? , write : rhdl :: core :: types :: digital :: take_bin :: < bool > (& mut bits) ? , read : rhdl :: core :: types :: digital :: take_bin :: < bool > (& mut bits) ? , }) } }"#
         ]];
         expected.assert_eq(&output);
     }
@@ -264,7 +284,7 @@ mod test {
         );
         let output = derive_digital(decl).unwrap().to_string();
         let expected = expect![[
-            r#"impl core :: marker :: Copy for Inputs { } impl Clone for Inputs { # [inline] fn clone (& self) -> Self { Self { input : self . input . clone () , write : self . write . clone () , read : self . read . clone () , } } } impl rhdl :: core :: Digital for Inputs { const BITS : usize = < u32 as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS + < (bool , bool) as rhdl :: core :: Digital > :: BITS ; const TRACE_BITS : usize = < u32 as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS + < (bool , bool) as rhdl :: core :: Digital > :: TRACE_BITS ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (input) , < u32 as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (read) , < (bool , bool) as rhdl :: core :: Digital > :: static_kind ()) ,] ,) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: rtt :: make_field (stringify ! (input) , < u32 as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (read) , < (bool , bool) as rhdl :: core :: Digital > :: static_trace_type ()) ,] ,) } fn bin (self) -> Vec < rhdl :: core :: BitX > { [self . input . bin () . as_slice () , self . write . bin () . as_slice () , self . read . bin () . as_slice () ,] . concat () } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { [self . input . trace () . as_slice () , self . write . trace () . as_slice () , self . read . trace () . as_slice () ,] . concat () } fn dont_care () -> Self { Self { input : < u32 as rhdl :: core :: Digital > :: dont_care () , write : < bool as rhdl :: core :: Digital > :: dont_care () , read : < (bool , bool) as rhdl :: core :: Digital > :: dont_care () , } } }"#
+            r#"impl core :: marker :: Copy for Inputs { } impl Clone for Inputs { # [inline] fn clone (& self) -> Self { Self { input : self . input . clone () , write : self . write . clone () , read : self . read . clone () , } } } impl rhdl :: core :: Digital for Inputs { const BITS : usize = < u32 as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS + < (bool , bool) as rhdl :: core :: Digital > :: BITS ; const TRACE_BITS : usize = < u32 as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS + < (bool , bool) as rhdl :: core :: Digital > :: TRACE_BITS ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (input) , < u32 as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (read) , < (bool , bool) as rhdl :: core :: Digital > :: static_kind ()) ,] ,) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: rtt :: make_field (stringify ! (input) , < u32 as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (write) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (read) , < (bool , bool) as rhdl :: core :: Digital > :: static_trace_type ()) ,] ,) } fn bin (self) -> Vec < rhdl :: core :: BitX > { [self . input . bin () . as_slice () , self . write . bin () . as_slice () , self . read . bin () . as_slice () ,] . concat () } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { [self . input . trace () . as_slice () , self . write . trace () . as_slice () , self . read . trace () . as_slice () ,] . concat () } fn dont_care () -> Self { Self { input : < u32 as rhdl :: core :: Digital > :: dont_care () , write : < bool as rhdl :: core :: Digital > :: dont_care () , read : < (bool , bool) as rhdl :: core :: Digital > :: dont_care () , } } fn from_bin (mut bits : & [rhdl :: core :: BitX]) -> Option < Self > { if bits . len () != < Self as rhdl :: core :: Digital > :: BITS { return None ; } Some (Self { input : rhdl :: core :: types :: digital :: take_bin :: < u32 > (& mut bits) ? , write : rhdl :: core :: types :: digital :: take_bin :: < bool > (& mut bits) ? , read : rhdl :: core :: types :: digital :: take_bin :: < (bool , bool) > (& mut bits) ? , }) } }"#
         ]];
         expected.assert_eq(&output);
     }
@@ -276,7 +296,7 @@ mod test {
         );
         let output = derive_digital(decl).unwrap().to_string();
         let expected = expect![[
-            r#"impl core :: marker :: Copy for Inputs { } impl Clone for Inputs { # [inline] fn clone (& self) -> Self { Self (self . 0 . clone () , self . 1 . clone () , self . 2 . clone () ,) } } impl rhdl :: core :: Digital for Inputs { const BITS : usize = < u32 as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS ; const TRACE_BITS : usize = < u32 as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (0) , < u32 as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (1) , < bool as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (2) , < bool as rhdl :: core :: Digital > :: static_kind ()) ,]) } fn static_trace_type () -> rhdl :: rtt :: TraceType { rhdl :: rtt :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: rtt :: make_field (stringify ! (0) , < u32 as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (1) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (2) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) ,]) } fn bin (self) -> Vec < rhdl :: core :: BitX > { [self . 0 . bin () . as_slice () , self . 1 . bin () . as_slice () , self . 2 . bin () . as_slice () ,] . concat () } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { [self . 0 . trace () . as_slice () , self . 1 . trace () . as_slice () , self . 2 . trace () . as_slice () ,] . concat () } fn dont_care () -> Self { Self (< u32 as rhdl :: core :: Digital > :: dont_care () , < bool as rhdl :: core :: Digital > :: dont_care () , < bool as rhdl :: core :: Digital > :: dont_care () ,) } } impl rhdl :: core :: DigitalFn for Inputs { fn kernel_fn () -> Option < rhdl :: core :: KernelFnKind > { Some (rhdl :: core :: KernelFnKind :: TupleStructConstructor (< Self as rhdl :: core :: Digital > :: static_kind () . place_holder ())) } }"#
+            r#"impl core :: marker :: Copy for Inputs { } impl Clone for Inputs { # [inline] fn clone (& self) -> Self { Self (self . 0 . clone () , self . 1 . clone () , self . 2 . clone () ,) } } impl rhdl :: core :: Digital for Inputs { const BITS : usize = < u32 as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS + < bool as rhdl :: core :: Digital > :: BITS ; const TRACE_BITS : usize = < u32 as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS + < bool as rhdl :: core :: Digital > :: TRACE_BITS ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (0) , < u32 as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (1) , < bool as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (2) , < bool as rhdl :: core :: Digital > :: static_kind ()) ,]) } fn static_trace_type () -> rhdl :: rtt :: TraceType { rhdl :: rtt :: make_struct (concat ! (module_path ! () , "::" , stringify ! (Inputs)) , vec ! [rhdl :: rtt :: make_field (stringify ! (0) , < u32 as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (1) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (2) , < bool as rhdl :: core :: Digital > :: static_trace_type ()) ,]) } fn bin (self) -> Vec < rhdl :: core :: BitX > { [self . 0 . bin () . as_slice () , self . 1 . bin () . as_slice () , self . 2 . bin () . as_slice () ,] . concat () } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { [self . 0 . trace () . as_slice () , self . 1 . trace () . as_slice () , self . 2 . trace () . as_slice () ,] . concat () } fn dont_care () -> Self { Self (< u32 as rhdl :: core :: Digital > :: dont_care () , < bool as rhdl :: core :: Digital > :: dont_care () , < bool as rhdl :: core :: Digital > :: dont_care () ,) } fn from_bin (mut bits : & [rhdl :: core :: BitX]) -> Option < Self > { if bits . len () != < Self as rhdl :: core :: Digital > :: BITS { return None ; } Some (Self (rhdl :: core :: types :: digital :: take_bin :: < u32 > (& mut bits) ? , rhdl :: core :: types :: digital :: take_bin :: < bool > (& mut bits) ? , rhdl :: core :: types :: digital :: take_bin :: < bool > (& mut bits) ? ,)) } } impl rhdl :: core :: DigitalFn for Inputs { fn kernel_fn () -> Option < rhdl :: core :: KernelFnKind > { Some (rhdl :: core :: KernelFnKind :: TupleStructConstructor (< Self as rhdl :: core :: Digital > :: static_kind () . place_holder ())) } }"#
         ]];
         expected.assert_eq(&output);
     }
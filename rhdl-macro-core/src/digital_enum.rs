@@ -324,6 +324,39 @@ fn variant_payload_trace(
     }
 }
 
+// Decode the payload of a variant from the bits that follow the
+// discriminant.  The fields are taken in the order they are packed by
+// `variant_payload_bin`.
+fn variant_from_bin(variant: &Variant, discriminant: i64) -> TokenStream {
+    let variant_name = &variant.ident;
+    match &variant.fields {
+        syn::Fields::Unit => quote! {
+            (#discriminant, _) => Some(Self::#variant_name)
+        },
+        syn::Fields::Unnamed(fields) => {
+            let field_types = fields.unnamed.iter().map(|f| &f.ty);
+            quote! {
+                (#discriminant, mut payload) => Some(Self::#variant_name(
+                    #(
+                        rhdl::core::types::digital::take_bin::<#field_types>(&mut payload)?
+                    ),*
+                ))
+            }
+        }
+        syn::Fields::Named(fields) => {
+            let field_names = fields.named.iter().map(|f| &f.ident);
+            let field_types = fields.named.iter().map(|f| &f.ty);
+            quote! {
+                (#discriminant, mut payload) => Some(Self::#variant_name {
+                    #(
+                        #field_names: rhdl::core::types::digital::take_bin::<#field_types>(&mut payload)?
+                    ),*
+                })
+            }
+        }
+    }
+}
+
 fn variant_payload_bin(
     variant: &Variant,
     kind: DiscriminantType,
@@ -478,6 +511,11 @@ pub fn derive_digital_enum(decl: DeriveInput) -> syn::Result<TokenStream> {
         .iter()
         .zip(discriminants_values.iter())
         .map(|(variant, discriminant)| variant_payload_bin(variant, kind, *discriminant));
+    let from_bin_fns = e
+        .variants
+        .iter()
+        .zip(discriminants_values.iter())
+        .map(|(variant, discriminant)| variant_from_bin(variant, *discriminant));
     let trace_bin_fns = e
         .variants
         .iter()
@@ -569,6 +607,18 @@ pub fn derive_digital_enum(decl: DeriveInput) -> syn::Result<TokenStream> {
             fn dont_care() -> Self {
                 <Self as Default>::default()
             }
+            fn from_bin(bits: &[rhdl::core::BitX]) -> Option<Self> {
+                let (discriminant, payload) = rhdl::core::types::digital::enum_from_bin(
+                    &<Self as rhdl::core::Digital>::static_kind(),
+                    bits,
+                )?;
+                match (discriminant, payload.as_slice()) {
+                    #(
+                        #from_bin_fns,
+                    )*
+                    _ => None,
+                }
+            }
         }
     })
 }
@@ -37,7 +37,7 @@ fn test_enum_derive() {
         }
     };
     let output = derive_digital_enum(input).unwrap().to_string();
-    let expected = expect![[r#"impl core :: marker :: Copy for Test { } impl Clone for Test { # [inline] fn clone (& self) -> Self { match self { Test :: A => Test :: A , Test :: B (a ,) => Test :: B (a . clone () ,) , Test :: C { a , b , } => Test :: C { a : a . clone () , b : b . clone () , } , Test :: Unknown => Test :: Unknown , } } } impl rhdl :: core :: Digital for Test { const BITS : usize = 3usize + rhdl :: core :: const_max ! (0_usize , < Bits :: < 16 > as rhdl :: core :: Digital > :: BITS , < Bits :: < 32 > as rhdl :: core :: Digital > :: BITS + < Bits :: < 8 > as rhdl :: core :: Digital > :: BITS , 0_usize) ; const TRACE_BITS : usize = 3usize + rhdl :: core :: const_max ! (0_usize , < Bits :: < 16 > as rhdl :: core :: Digital > :: TRACE_BITS , < Bits :: < 32 > as rhdl :: core :: Digital > :: TRACE_BITS + < Bits :: < 8 > as rhdl :: core :: Digital > :: TRACE_BITS , 0_usize) ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: core :: Kind :: make_variant (stringify ! (A) , rhdl :: core :: Kind :: Empty , 1i64) , rhdl :: core :: Kind :: make_variant (stringify ! (B) , rhdl :: core :: Kind :: make_tuple (vec ! [< Bits :: < 16 > as rhdl :: core :: Digital > :: static_kind ()]) , 2i64) , rhdl :: core :: Kind :: make_variant (stringify ! (C) , rhdl :: core :: Kind :: make_struct (stringify ! (_Test__C) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (a) , < Bits :: < 32 > as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (b) , < Bits :: < 8 > as rhdl :: core :: Digital > :: static_kind ())]) , 3i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Unknown) , rhdl :: core :: Kind :: Empty , 4i64)] , rhdl :: core :: Kind :: make_discriminant_layout (3usize , rhdl :: core :: DiscriminantAlignment :: Msb , rhdl :: core :: DiscriminantType :: Unsigned)) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: rtt :: make_variant (stringify ! (A) , rhdl :: rtt :: TraceType :: Empty , 1i64) , rhdl :: rtt :: make_variant (stringify ! (B) , rhdl :: rtt :: make_tuple (vec ! [< Bits :: < 16 > as rhdl :: core :: Digital > :: static_trace_type ()]) , 2i64) , rhdl :: rtt :: make_variant (stringify ! (C) , rhdl :: rtt :: make_struct (stringify ! (_Test__C) , vec ! [rhdl :: rtt :: make_field (stringify ! (a) , < Bits :: < 32 > as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (b) , < Bits :: < 8 > as rhdl :: core :: Digital > :: static_trace_type ())]) , 3i64) , rhdl :: rtt :: make_variant (stringify ! (Unknown) , rhdl :: rtt :: TraceType :: Empty , 4i64)] , rhdl :: rtt :: make_discriminant_layout (3usize , rhdl :: core :: DiscriminantAlignment :: Msb . into () , rhdl :: core :: DiscriminantType :: Unsigned . into ())) } fn bin (self) -> Vec < rhdl :: core :: BitX > { let mut raw = match self { Self :: A => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (1i64 as u128) . to_bools ()) } Self :: B (_0) => { let mut v = rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (2i64 as u128) . to_bools ()) ; v . extend (_0 . bin ()) ; v } Self :: C { a , b } => { let mut v = rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (3i64 as u128) . to_bools ()) ; v . extend (a . bin ()) ; v . extend (b . bin ()) ; v } Self :: Unknown => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (4i64 as u128) . to_bools ()) } } ; raw . resize (Self :: BITS , rhdl :: core :: BitX :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 3usize) } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { let mut raw = match self { Self :: A => { rhdl :: bits :: bits :: < W3 > (1i64 as u128) . trace () } Self :: B (_0) => { let mut v = rhdl :: bits :: bits :: < W3 > (2i64 as u128) . trace () ; v . extend (_0 . trace ()) ; v } Self :: C { a , b } => { let mut v = rhdl :: bits :: bits :: < W3 > (3i64 as u128) . trace () ; v . extend (a . trace ()) ; v . extend (b . trace ()) ; v } Self :: Unknown => { rhdl :: bits :: bits :: < W3 > (4i64 as u128) . trace () } } ; raw . resize (Self :: TRACE_BITS , rhdl :: core :: TraceBit :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 3usize) } fn discriminant (self) -> rhdl :: core :: TypedBits { match self { Self :: A => { rhdl :: bits :: bits :: < W3 > (1i64 as u128) . typed_bits () } Self :: B (_0) => { rhdl :: bits :: bits :: < W3 > (2i64 as u128) . typed_bits () } Self :: C { a , b } => { rhdl :: bits :: bits :: < W3 > (3i64 as u128) . typed_bits () } Self :: Unknown => { rhdl :: bits :: bits :: < W3 > (4i64 as u128) . typed_bits () } } } fn variant_kind (self) -> rhdl :: core :: Kind { match self { Self :: A => { rhdl :: core :: Kind :: Empty } Self :: B (_0) => { rhdl :: core :: Kind :: make_tuple (vec ! [< Bits :: < 16 > as rhdl :: core :: Digital > :: static_kind ()]) } Self :: C { a , b } => { rhdl :: core :: Kind :: make_struct (stringify ! (_Test__C) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (a) , < Bits :: < 32 > as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (b) , < Bits :: < 8 > as rhdl :: core :: Digital > :: static_kind ())]) } Self :: Unknown => { rhdl :: core :: Kind :: Empty } } } fn dont_care () -> Self { < Self as Default > :: default () } }"#]];
+    let expected = expect![[r#"impl core :: marker :: Copy for Test { } impl Clone for Test { # [inline] fn clone (& self) -> Self { match self { Test :: A => Test :: A , Test :: B (a ,) => Test :: B (a . clone () ,) , Test :: C { a , b , } => Test :: C { a : a . clone () , b : b . clone () , } , Test :: Unknown => Test :: Unknown , } } } impl rhdl :: core :: Digital for Test { const BITS : usize = 3usize + rhdl :: core :: const_max ! (0_usize , < Bits :: < 16 > as rhdl :: core :: Digital > :: BITS , < Bits :: < 32 > as rhdl :: core :: Digital > :: BITS + < Bits :: < 8 > as rhdl :: core :: Digital > :: BITS , 0_usize) ; const TRACE_BITS : usize = 3usize + rhdl :: core :: const_max ! (0_usize , < Bits :: < 16 > as rhdl :: core :: Digital > :: TRACE_BITS , < Bits :: < 32 > as rhdl :: core :: Digital > :: TRACE_BITS + < Bits :: < 8 > as rhdl :: core :: Digital > :: TRACE_BITS , 0_usize) ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: core :: Kind :: make_variant (stringify ! (A) , rhdl :: core :: Kind :: Empty , 1i64) , rhdl :: core :: Kind :: make_variant (stringify ! (B) , rhdl :: core :: Kind :: make_tuple (vec ! [< Bits :: < 16 > as rhdl :: core :: Digital > :: static_kind ()]) , 2i64) , rhdl :: core :: Kind :: make_variant (stringify ! (C) , rhdl :: core :: Kind :: make_struct (stringify ! (_Test__C) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (a) , < Bits :: < 32 > as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (b) , < Bits :: < 8 > as rhdl :: core :: Digital > :: static_kind ())]) , 3i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Unknown) , rhdl :: core :: Kind :: Empty , 4i64)] , rhdl :: core :: Kind :: make_discriminant_layout (3usize , rhdl :: core :: DiscriminantAlignment :: Msb , rhdl :: core :: DiscriminantType :: Unsigned)) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: rtt :: make_variant (stringify ! (A) , rhdl :: rtt :: TraceType :: Empty , 1i64) , rhdl :: rtt :: make_variant (stringify ! (B) , rhdl :: rtt :: make_tuple (vec ! [< Bits :: < 16 > as rhdl :: core :: Digital > :: static_trace_type ()]) , 2i64) , rhdl :: rtt :: make_variant (stringify ! (C) , rhdl :: rtt :: make_struct (stringify ! (_Test__C) , vec ! [rhdl :: rtt :: make_field (stringify ! (a) , < Bits :: < 32 > as rhdl :: core :: Digital > :: static_trace_type ()) , rhdl :: rtt :: make_field (stringify ! (b) , < Bits :: < 8 > as rhdl :: core :: Digital > :: static_trace_type ())]) , 3i64) , rhdl :: rtt :: make_variant (stringify ! (Unknown) , rhdl :: rtt :: TraceType :: Empty , 4i64)] , rhdl :: rtt :: make_discriminant_layout (3usize , rhdl :: core :: DiscriminantAlignment :: Msb . into () , rhdl :: core :: DiscriminantType :: Unsigned . into ())) } fn bin (self) -> Vec < rhdl :: core :: BitX > { let mut raw = match self { Self :: A => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (1i64 as u128) . to_bools ()) } Self :: B (_0) => { let mut v = rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (2i64 as u128) . to_bools ()) ; v . extend (_0 . bin ()) ; v } Self :: C { a , b } => { let mut v = rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (3i64 as u128) . to_bools ()) ; v . extend (a . bin ()) ; v . extend (b . bin ()) ; v } Self :: Unknown => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (4i64 as u128) . to_bools ()) } } ; raw . resize (Self :: BITS , rhdl :: core :: BitX :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 3usize) } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { let mut raw = match self { Self :: A => { rhdl :: bits :: bits :: < W3 > (1i64 as u128) . trace () } Self :: B (_0) => { let mut v = rhdl :: bits :: bits :: < W3 > (2i64 as u128) . trace () ; v . extend (_0 . trace ()) ; v } Self :: C { a , b } => { let mut v = rhdl :: bits :: bits :: < W3 > (3i64 as u128) . trace () ; v . extend (a . trace ()) ; v . extend (b . trace ()) ; v } Self :: Unknown => { rhdl :: bits :: bits :: < W3 > (4i64 as u128) . trace () } } ; raw . resize (Self :: TRACE_BITS , rhdl :: core :: TraceBit :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 3usize) } fn discriminant (self) -> rhdl :: core :: TypedBits { match self { Self :: A => { rhdl :: bits :: bits :: < W3 > (1i64 as u128) . typed_bits () } Self :: B (_0) => { rhdl :: bits :: bits :: < W3 > (2i64 as u128) . typed_bits () } Self :: C { a , b } => { rhdl :: bits :: bits :: < W3 > (3i64 as u128) . typed_bits () } Self :: Unknown => { rhdl :: bits :: bits :: < W3 > (4i64 as u128) . typed_bits () } } } fn variant_kind (self) -> rhdl :: core :: Kind { match self { Self :: A => { rhdl :: core :: Kind :: Empty } Self :: B (_0) => { rhdl :: core :: Kind :: make_tuple (vec ! [< Bits :: < 16 > as rhdl :: core :: Digital > :: static_kind ()]) } Self :: C { a , b } => { rhdl :: core :: Kind :: make_struct (stringify ! (_Test__C) , vec ! [rhdl :: core :: Kind :: make_field (stringify ! (a) , < Bits :: < 32 > as rhdl :: core :: Digital > :: static_kind ()) , rhdl :: core :: Kind :: make_field (stringify ! (b) , < Bits :: < 8 > as rhdl :: core :: Digital > :: static_kind ())]) } Self :: Unknown => { rhdl :: core :: Kind :: Empty } } } fn dont_care () -> Self { < Self as Default > :: default () } fn from_bin (bits : & [rhdl :: core :: BitX]) -> Option < Self > { let (discriminant , payload) = rhdl :: core :: types :: digital :: enum_from_bin (& < Self as rhdl :: core :: Digital > :: static_kind () , bits ,) ? ; match (discriminant , payload . as_slice ()) { (1i64 , _) => Some (Self :: A) , (2i64 , mut payload) => Some (Self :: B (rhdl :: core :: types :: digital :: take_bin :: < Bits :: < 16 > > (& mut payload) ?)) , (3i64 , mut payload) => Some (Self :: C { a : rhdl :: core :: types :: digital :: take_bin :: < Bits :: < 32 > > (& mut payload) ? , b : rhdl :: core :: types :: digital :: take_bin :: < Bits :: < 8 > > (& mut payload) ? }) , (4i64 , _) => Some (Self :: Unknown) , _ => None , } } }"#]];
     expected.assert_eq(&output);
 }
 
@@ -57,7 +57,7 @@ fn test_enum_no_payloads() {
     let output = derive_digital_enum(syn::parse2(decl).unwrap())
         .unwrap()
         .to_string();
-    let expected = expect![[r#"impl core :: marker :: Copy for State { } impl Clone for State { # [inline] fn clone (& self) -> Self { match self { State :: Init => State :: Init , State :: Boot => State :: Boot , State :: Running => State :: Running , State :: Stop => State :: Stop , State :: Boom => State :: Boom , State :: Unknown => State :: Unknown , } } } impl rhdl :: core :: Digital for State { const BITS : usize = 3usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize , 0_usize , 0_usize) ; const TRACE_BITS : usize = 3usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize , 0_usize , 0_usize) ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_enum (concat ! (module_path ! () , "::" , stringify ! (State)) , vec ! [rhdl :: core :: Kind :: make_variant (stringify ! (Init) , rhdl :: core :: Kind :: Empty , 0i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Boot) , rhdl :: core :: Kind :: Empty , 1i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Running) , rhdl :: core :: Kind :: Empty , 2i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Stop) , rhdl :: core :: Kind :: Empty , 3i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Boom) , rhdl :: core :: Kind :: Empty , 4i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Unknown) , rhdl :: core :: Kind :: Empty , 5i64)] , rhdl :: core :: Kind :: make_discriminant_layout (3usize , rhdl :: core :: DiscriminantAlignment :: Msb , rhdl :: core :: DiscriminantType :: Unsigned)) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_enum (concat ! (module_path ! () , "::" , stringify ! (State)) , vec ! [rhdl :: rtt :: make_variant (stringify ! (Init) , rhdl :: rtt :: TraceType :: Empty , 0i64) , rhdl :: rtt :: make_variant (stringify ! (Boot) , rhdl :: rtt :: TraceType :: Empty , 1i64) , rhdl :: rtt :: make_variant (stringify ! (Running) , rhdl :: rtt :: TraceType :: Empty , 2i64) , rhdl :: rtt :: make_variant (stringify ! (Stop) , rhdl :: rtt :: TraceType :: Empty , 3i64) , rhdl :: rtt :: make_variant (stringify ! (Boom) , rhdl :: rtt :: TraceType :: Empty , 4i64) , rhdl :: rtt :: make_variant (stringify ! (Unknown) , rhdl :: rtt :: TraceType :: Empty , 5i64)] , rhdl :: rtt :: make_discriminant_layout (3usize , rhdl :: core :: DiscriminantAlignment :: Msb . into () , rhdl :: core :: DiscriminantType :: Unsigned . into ())) } fn bin (self) -> Vec < rhdl :: core :: BitX > { let mut raw = match self { Self :: Init => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (0i64 as u128) . to_bools ()) } Self :: Boot => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (1i64 as u128) . to_bools ()) } Self :: Running => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (2i64 as u128) . to_bools ()) } Self :: Stop => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (3i64 as u128) . to_bools ()) } Self :: Boom => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (4i64 as u128) . to_bools ()) } Self :: Unknown => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (5i64 as u128) . to_bools ()) } } ; raw . resize (Self :: BITS , rhdl :: core :: BitX :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 3usize) } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { let mut raw = match self { Self :: Init => { rhdl :: bits :: bits :: < W3 > (0i64 as u128) . trace () } Self :: Boot => { rhdl :: bits :: bits :: < W3 > (1i64 as u128) . trace () } Self :: Running => { rhdl :: bits :: bits :: < W3 > (2i64 as u128) . trace () } Self :: Stop => { rhdl :: bits :: bits :: < W3 > (3i64 as u128) . trace () } Self :: Boom => { rhdl :: bits :: bits :: < W3 > (4i64 as u128) . trace () } Self :: Unknown => { rhdl :: bits :: bits :: < W3 > (5i64 as u128) . trace () } } ; raw . resize (Self :: TRACE_BITS , rhdl :: core :: TraceBit :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 3usize) } fn discriminant (self) -> rhdl :: core :: TypedBits { match self { Self :: Init => { rhdl :: bits :: bits :: < W3 > (0i64 as u128) . typed_bits () } Self :: Boot => { rhdl :: bits :: bits :: < W3 > (1i64 as u128) . typed_bits () } Self :: Running => { rhdl :: bits :: bits :: < W3 > (2i64 as u128) . typed_bits () } Self :: Stop => { rhdl :: bits :: bits :: < W3 > (3i64 as u128) . typed_bits () } Self :: Boom => { rhdl :: bits :: bits :: < W3 > (4i64 as u128) . typed_bits () } Self :: Unknown => { rhdl :: bits :: bits :: < W3 > (5i64 as u128) . typed_bits () } } } fn variant_kind (self) -> rhdl :: core :: Kind { match self { Self :: Init => { rhdl :: core :: Kind :: Empty } Self :: Boot => { rhdl :: core :: Kind :: Empty } Self :: Running => { rhdl :: core :: Kind :: Empty } Self :: Stop => { rhdl :: core :: Kind :: Empty } Self :: Boom => { rhdl :: core :: Kind :: Empty } Self :: Unknown => { rhdl :: core :: Kind :: Empty } } } fn dont_care () -> Self { < Self as Default > :: default () } }"#]];
+    let expected = expect![[r#"impl core :: marker :: Copy for State { } impl Clone for State { # [inline] fn clone (& self) -> Self { match self { State :: Init => State :: Init , State :: Boot => State :: Boot , State :: Running => State :: Running , State :: Stop => State :: Stop , State :: Boom => State :: Boom , State :: Unknown => State :: Unknown , } } } impl rhdl :: core :: Digital for State { const BITS : usize = 3usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize , 0_usize , 0_usize) ; const TRACE_BITS : usize = 3usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize , 0_usize , 0_usize) ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_enum (concat ! (module_path ! () , "::" , stringify ! (State)) , vec ! [rhdl :: core :: Kind :: make_variant (stringify ! (Init) , rhdl :: core :: Kind :: Empty , 0i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Boot) , rhdl :: core :: Kind :: Empty , 1i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Running) , rhdl :: core :: Kind :: Empty , 2i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Stop) , rhdl :: core :: Kind :: Empty , 3i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Boom) , rhdl :: core :: Kind :: Empty , 4i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Unknown) , rhdl :: core :: Kind :: Empty , 5i64)] , rhdl :: core :: Kind :: make_discriminant_layout (3usize , rhdl :: core :: DiscriminantAlignment :: Msb , rhdl :: core :: DiscriminantType :: Unsigned)) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_enum (concat ! (module_path ! () , "::" , stringify ! (State)) , vec ! [rhdl :: rtt :: make_variant (stringify ! (Init) , rhdl :: rtt :: TraceType :: Empty , 0i64) , rhdl :: rtt :: make_variant (stringify ! (Boot) , rhdl :: rtt :: TraceType :: Empty , 1i64) , rhdl :: rtt :: make_variant (stringify ! (Running) , rhdl :: rtt :: TraceType :: Empty , 2i64) , rhdl :: rtt :: make_variant (stringify ! (Stop) , rhdl :: rtt :: TraceType :: Empty , 3i64) , rhdl :: rtt :: make_variant (stringify ! (Boom) , rhdl :: rtt :: TraceType :: Empty , 4i64) , rhdl :: rtt :: make_variant (stringify ! (Unknown) , rhdl :: rtt :: TraceType :: Empty , 5i64)] , rhdl :: rtt :: make_discriminant_layout (3usize , rhdl :: core :: DiscriminantAlignment :: Msb . into () , rhdl :: core :: DiscriminantType :: Unsigned . into ())) } fn bin (self) -> Vec < rhdl :: core :: BitX > { let mut raw = match self { Self :: Init => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (0i64 as u128) . to_bools ()) } Self :: Boot => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (1i64 as u128) . to_bools ()) } Self :: Running => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (2i64 as u128) . to_bools ()) } Self :: Stop => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (3i64 as u128) . to_bools ()) } Self :: Boom => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (4i64 as u128) . to_bools ()) } Self :: Unknown => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W3 > (5i64 as u128) . to_bools ()) } } ; raw . resize (Self :: BITS , rhdl :: core :: BitX :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 3usize) } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { let mut raw = match self { Self :: Init => { rhdl :: bits :: bits :: < W3 > (0i64 as u128) . trace () } Self :: Boot => { rhdl :: bits :: bits :: < W3 > (1i64 as u128) . trace () } Self :: Running => { rhdl :: bits :: bits :: < W3 > (2i64 as u128) . trace () } Self :: Stop => { rhdl :: bits :: bits :: < W3 > (3i64 as u128) . trace () } Self :: Boom => { rhdl :: bits :: bits :: < W3 > (4i64 as u128) . trace () } Self :: Unknown => { rhdl :: bits :: bits :: < W3 > (5i64 as u128) . trace () } } ; raw . resize (Self :: TRACE_BITS , rhdl :: core :: TraceBit :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 3usize) } fn discriminant (self) -> rhdl :: core :: TypedBits { match self { Self :: Init => { rhdl :: bits :: bits :: < W3 > (0i64 as u128) . typed_bits () } Self :: Boot => { rhdl :: bits :: bits :: < W3 > (1i64 as u128) . typed_bits () } Self :: Running => { rhdl :: bits :: bits :: < W3 > (2i64 as u128) . typed_bits () } Self :: Stop => { rhdl :: bits :: bits :: < W3 > (3i64 as u128) . typed_bits () } Self :: Boom => { rhdl :: bits :: bits :: < W3 > (4i64 as u128) . typed_bits () } Self :: Unknown => { rhdl :: bits :: bits :: < W3 > (5i64 as u128) . typed_bits () } } } fn variant_kind (self) -> rhdl :: core :: Kind { match self { Self :: Init => { rhdl :: core :: Kind :: Empty } Self :: Boot => { rhdl :: core :: Kind :: Empty } Self :: Running => { rhdl :: core :: Kind :: Empty } Self :: Stop => { rhdl :: core :: Kind :: Empty } Self :: Boom => { rhdl :: core :: Kind :: Empty } Self :: Unknown => { rhdl :: core :: Kind :: Empty } } } fn dont_care () -> Self { < Self as Default > :: default () } fn from_bin (bits : & [rhdl :: core :: BitX]) -> Option < Self > { let (discriminant , payload) = rhdl :: core :: types :: digital :: enum_from_bin (& < Self as rhdl :: core :: Digital > :: static_kind () , bits ,) ? ; match (discriminant , payload . as_slice ()) { (0i64 , _) => Some (Self :: Init) , (1i64 , _) => Some (Self :: Boot) , (2i64 , _) => Some (Self :: Running) , (3i64 , _) => Some (Self :: Stop) , (4i64 , _) => Some (Self :: Boom) , (5i64 , _) => Some (Self :: Unknown) , _ => None , } } }"#]];
     expected.assert_eq(&output);
 }
 
@@ -75,7 +75,7 @@ fn test_enum_with_signed_discriminants() {
     let output = derive_digital_enum(syn::parse2(decl).unwrap())
         .unwrap()
         .to_string();
-    let expected = expect![[r#"impl core :: marker :: Copy for Test { } impl Clone for Test { # [inline] fn clone (& self) -> Self { match self { Test :: A => Test :: A , Test :: B => Test :: B , Test :: C => Test :: C , Test :: Unknown => Test :: Unknown , } } } impl rhdl :: core :: Digital for Test { const BITS : usize = 5usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize) ; const TRACE_BITS : usize = 5usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize) ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: core :: Kind :: make_variant (stringify ! (A) , rhdl :: core :: Kind :: Empty , 1i64) , rhdl :: core :: Kind :: make_variant (stringify ! (B) , rhdl :: core :: Kind :: Empty , 9i64) , rhdl :: core :: Kind :: make_variant (stringify ! (C) , rhdl :: core :: Kind :: Empty , - 8i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Unknown) , rhdl :: core :: Kind :: Empty , - 7i64)] , rhdl :: core :: Kind :: make_discriminant_layout (5usize , rhdl :: core :: DiscriminantAlignment :: Msb , rhdl :: core :: DiscriminantType :: Signed)) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: rtt :: make_variant (stringify ! (A) , rhdl :: rtt :: TraceType :: Empty , 1i64) , rhdl :: rtt :: make_variant (stringify ! (B) , rhdl :: rtt :: TraceType :: Empty , 9i64) , rhdl :: rtt :: make_variant (stringify ! (C) , rhdl :: rtt :: TraceType :: Empty , - 8i64) , rhdl :: rtt :: make_variant (stringify ! (Unknown) , rhdl :: rtt :: TraceType :: Empty , - 7i64)] , rhdl :: rtt :: make_discriminant_layout (5usize , rhdl :: core :: DiscriminantAlignment :: Msb . into () , rhdl :: core :: DiscriminantType :: Signed . into ())) } fn bin (self) -> Vec < rhdl :: core :: BitX > { let mut raw = match self { Self :: A => { rhdl :: core :: bitx_vec (& rhdl :: bits :: signed :: < W5 > (1i64 as i128) . to_bools ()) } Self :: B => { rhdl :: core :: bitx_vec (& rhdl :: bits :: signed :: < W5 > (9i64 as i128) . to_bools ()) } Self :: C => { rhdl :: core :: bitx_vec (& rhdl :: bits :: signed :: < W5 > (- 8i64 as i128) . to_bools ()) } Self :: Unknown => { rhdl :: core :: bitx_vec (& rhdl :: bits :: signed :: < W5 > (- 7i64 as i128) . to_bools ()) } } ; raw . resize (Self :: BITS , rhdl :: core :: BitX :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 5usize) } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { let mut raw = match self { Self :: A => { rhdl :: bits :: signed :: < W5 > (1i64 as i128) . trace () } Self :: B => { rhdl :: bits :: signed :: < W5 > (9i64 as i128) . trace () } Self :: C => { rhdl :: bits :: signed :: < W5 > (- 8i64 as i128) . trace () } Self :: Unknown => { rhdl :: bits :: signed :: < W5 > (- 7i64 as i128) . trace () } } ; raw . resize (Self :: TRACE_BITS , rhdl :: core :: TraceBit :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 5usize) } fn discriminant (self) -> rhdl :: core :: TypedBits { match self { Self :: A => { rhdl :: bits :: signed :: < W5 > (1i128) . typed_bits () } Self :: B => { rhdl :: bits :: signed :: < W5 > (9i128) . typed_bits () } Self :: C => { rhdl :: bits :: signed :: < W5 > (- 8i128) . typed_bits () } Self :: Unknown => { rhdl :: bits :: signed :: < W5 > (- 7i128) . typed_bits () } } } fn variant_kind (self) -> rhdl :: core :: Kind { match self { Self :: A => { rhdl :: core :: Kind :: Empty } Self :: B => { rhdl :: core :: Kind :: Empty } Self :: C => { rhdl :: core :: Kind :: Empty } Self :: Unknown => { rhdl :: core :: Kind :: Empty } } } fn dont_care () -> Self { < Self as Default > :: default () } }"#]];
+    let expected = expect![[r#"impl core :: marker :: Copy for Test { } impl Clone for Test { # [inline] fn clone (& self) -> Self { match self { Test :: A => Test :: A , Test :: B => Test :: B , Test :: C => Test :: C , Test :: Unknown => Test :: Unknown , } } } impl rhdl :: core :: Digital for Test { const BITS : usize = 5usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize) ; const TRACE_BITS : usize = 5usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize) ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: core :: Kind :: make_variant (stringify ! (A) , rhdl :: core :: Kind :: Empty , 1i64) , rhdl :: core :: Kind :: make_variant (stringify ! (B) , rhdl :: core :: Kind :: Empty , 9i64) , rhdl :: core :: Kind :: make_variant (stringify ! (C) , rhdl :: core :: Kind :: Empty , - 8i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Unknown) , rhdl :: core :: Kind :: Empty , - 7i64)] , rhdl :: core :: Kind :: make_discriminant_layout (5usize , rhdl :: core :: DiscriminantAlignment :: Msb , rhdl :: core :: DiscriminantType :: Signed)) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: rtt :: make_variant (stringify ! (A) , rhdl :: rtt :: TraceType :: Empty , 1i64) , rhdl :: rtt :: make_variant (stringify ! (B) , rhdl :: rtt :: TraceType :: Empty , 9i64) , rhdl :: rtt :: make_variant (stringify ! (C) , rhdl :: rtt :: TraceType :: Empty , - 8i64) , rhdl :: rtt :: make_variant (stringify ! (Unknown) , rhdl :: rtt :: TraceType :: Empty , - 7i64)] , rhdl :: rtt :: make_discriminant_layout (5usize , rhdl :: core :: DiscriminantAlignment :: Msb . into () , rhdl :: core :: DiscriminantType :: Signed . into ())) } fn bin (self) -> Vec < rhdl :: core :: BitX > { let mut raw = match self { Self :: A => { rhdl :: core :: bitx_vec (& rhdl :: bits :: signed :: < W5 > (1i64 as i128) . to_bools ()) } Self :: B => { rhdl :: core :: bitx_vec (& rhdl :: bits :: signed :: < W5 > (9i64 as i128) . to_bools ()) } Self :: C => { rhdl :: core :: bitx_vec (& rhdl :: bits :: signed :: < W5 > (- 8i64 as i128) . to_bools ()) } Self :: Unknown => { rhdl :: core :: bitx_vec (& rhdl :: bits :: signed :: < W5 > (- 7i64 as i128) . to_bools ()) } } ; raw . resize (Self :: BITS , rhdl :: core :: BitX :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 5usize) } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { let mut raw = match self { Self :: A => { rhdl :: bits :: signed :: < W5 > (1i64 as i128) . trace () } Self :: B => { rhdl :: bits :: signed :: < W5 > (9i64 as i128) . trace () } Self :: C => { rhdl :: bits :: signed :: < W5 > (- 8i64 as i128) . trace () } Self :: Unknown => { rhdl :: bits :: signed :: < W5 > (- 7i64 as i128) . trace () } } ; raw . resize (Self :: TRACE_BITS , rhdl :: core :: TraceBit :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 5usize) } fn discriminant (self) -> rhdl :: core :: TypedBits { match self { Self :: A => { rhdl :: bits :: signed :: < W5 > (1i128) . typed_bits () } Self :: B => { rhdl :: bits :: signed :: < W5 > (9i128) . typed_bits () } Self :: C => { rhdl :: bits :: signed :: < W5 > (- 8i128) . typed_bits () } Self :: Unknown => { rhdl :: bits :: signed :: < W5 > (- 7i128) . typed_bits () } } } fn variant_kind (self) -> rhdl :: core :: Kind { match self { Self :: A => { rhdl :: core :: Kind :: Empty } Self :: B => { rhdl :: core :: Kind :: Empty } Self :: C => { rhdl :: core :: Kind :: Empty } Self :: Unknown => { rhdl :: core :: Kind :: Empty } } } fn dont_care () -> Self { < Self as Default > :: default () } fn from_bin (bits : & [rhdl :: core :: BitX]) -> Option < Self > { let (discriminant , payload) = rhdl :: core :: types :: digital :: enum_from_bin (& < Self as rhdl :: core :: Digital > :: static_kind () , bits ,) ? ; match (discriminant , payload . as_slice ()) { (1i64 , _) => Some (Self :: A) , (9i64 , _) => Some (Self :: B) , (- 8i64 , _) => Some (Self :: C) , (- 7i64 , _) => Some (Self :: Unknown) , _ => None , } } }"#]];
     expected.assert_eq(&output);
 }
 
@@ -93,7 +93,7 @@ fn test_enum_with_discriminants() {
     let output = derive_digital_enum(syn::parse2(decl).unwrap())
         .unwrap()
         .to_string();
-    let expected = expect![[r#"impl core :: marker :: Copy for Test { } impl Clone for Test { # [inline] fn clone (& self) -> Self { match self { Test :: A => Test :: A , Test :: B => Test :: B , Test :: C => Test :: C , Test :: Unknown => Test :: Unknown , } } } impl rhdl :: core :: Digital for Test { const BITS : usize = 4usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize) ; const TRACE_BITS : usize = 4usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize) ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: core :: Kind :: make_variant (stringify ! (A) , rhdl :: core :: Kind :: Empty , 1i64) , rhdl :: core :: Kind :: make_variant (stringify ! (B) , rhdl :: core :: Kind :: Empty , 6i64) , rhdl :: core :: Kind :: make_variant (stringify ! (C) , rhdl :: core :: Kind :: Empty , 8i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Unknown) , rhdl :: core :: Kind :: Empty , 9i64)] , rhdl :: core :: Kind :: make_discriminant_layout (4usize , rhdl :: core :: DiscriminantAlignment :: Msb , rhdl :: core :: DiscriminantType :: Unsigned)) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: rtt :: make_variant (stringify ! (A) , rhdl :: rtt :: TraceType :: Empty , 1i64) , rhdl :: rtt :: make_variant (stringify ! (B) , rhdl :: rtt :: TraceType :: Empty , 6i64) , rhdl :: rtt :: make_variant (stringify ! (C) , rhdl :: rtt :: TraceType :: Empty , 8i64) , rhdl :: rtt :: make_variant (stringify ! (Unknown) , rhdl :: rtt :: TraceType :: Empty , 9i64)] , rhdl :: rtt :: make_discriminant_layout (4usize , rhdl :: core :: DiscriminantAlignment :: Msb . into () , rhdl :: core :: DiscriminantType :: Unsigned . into ())) } fn bin (self) -> Vec < rhdl :: core :: BitX > { let mut raw = match self { Self :: A => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W4 > (1i64 as u128) . to_bools ()) } Self :: B => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W4 > (6i64 as u128) . to_bools ()) } Self :: C => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W4 > (8i64 as u128) . to_bools ()) } Self :: Unknown => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W4 > (9i64 as u128) . to_bools ()) } } ; raw . resize (Self :: BITS , rhdl :: core :: BitX :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 4usize) } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { let mut raw = match self { Self :: A => { rhdl :: bits :: bits :: < W4 > (1i64 as u128) . trace () } Self :: B => { rhdl :: bits :: bits :: < W4 > (6i64 as u128) . trace () } Self :: C => { rhdl :: bits :: bits :: < W4 > (8i64 as u128) . trace () } Self :: Unknown => { rhdl :: bits :: bits :: < W4 > (9i64 as u128) . trace () } } ; raw . resize (Self :: TRACE_BITS , rhdl :: core :: TraceBit :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 4usize) } fn discriminant (self) -> rhdl :: core :: TypedBits { match self { Self :: A => { rhdl :: bits :: bits :: < W4 > (1i64 as u128) . typed_bits () } Self :: B => { rhdl :: bits :: bits :: < W4 > (6i64 as u128) . typed_bits () } Self :: C => { rhdl :: bits :: bits :: < W4 > (8i64 as u128) . typed_bits () } Self :: Unknown => { rhdl :: bits :: bits :: < W4 > (9i64 as u128) . typed_bits () } } } fn variant_kind (self) -> rhdl :: core :: Kind { match self { Self :: A => { rhdl :: core :: Kind :: Empty } Self :: B => { rhdl :: core :: Kind :: Empty } Self :: C => { rhdl :: core :: Kind :: Empty } Self :: Unknown => { rhdl :: core :: Kind :: Empty } } } fn dont_care () -> Self { < Self as Default > :: default () } }"#]];
+    let expected = expect![[r#"impl core :: marker :: Copy for Test { } impl Clone for Test { # [inline] fn clone (& self) -> Self { match self { Test :: A => Test :: A , Test :: B => Test :: B , Test :: C => Test :: C , Test :: Unknown => Test :: Unknown , } } } impl rhdl :: core :: Digital for Test { const BITS : usize = 4usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize) ; const TRACE_BITS : usize = 4usize + rhdl :: core :: const_max ! (0_usize , 0_usize , 0_usize , 0_usize) ; fn static_kind () -> rhdl :: core :: Kind { rhdl :: core :: Kind :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: core :: Kind :: make_variant (stringify ! (A) , rhdl :: core :: Kind :: Empty , 1i64) , rhdl :: core :: Kind :: make_variant (stringify ! (B) , rhdl :: core :: Kind :: Empty , 6i64) , rhdl :: core :: Kind :: make_variant (stringify ! (C) , rhdl :: core :: Kind :: Empty , 8i64) , rhdl :: core :: Kind :: make_variant (stringify ! (Unknown) , rhdl :: core :: Kind :: Empty , 9i64)] , rhdl :: core :: Kind :: make_discriminant_layout (4usize , rhdl :: core :: DiscriminantAlignment :: Msb , rhdl :: core :: DiscriminantType :: Unsigned)) } fn static_trace_type () -> rhdl :: core :: TraceType { rhdl :: rtt :: make_enum (concat ! (module_path ! () , "::" , stringify ! (Test)) , vec ! [rhdl :: rtt :: make_variant (stringify ! (A) , rhdl :: rtt :: TraceType :: Empty , 1i64) , rhdl :: rtt :: make_variant (stringify ! (B) , rhdl :: rtt :: TraceType :: Empty , 6i64) , rhdl :: rtt :: make_variant (stringify ! (C) , rhdl :: rtt :: TraceType :: Empty , 8i64) , rhdl :: rtt :: make_variant (stringify ! (Unknown) , rhdl :: rtt :: TraceType :: Empty , 9i64)] , rhdl :: rtt :: make_discriminant_layout (4usize , rhdl :: core :: DiscriminantAlignment :: Msb . into () , rhdl :: core :: DiscriminantType :: Unsigned . into ())) } fn bin (self) -> Vec < rhdl :: core :: BitX > { let mut raw = match self { Self :: A => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W4 > (1i64 as u128) . to_bools ()) } Self :: B => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W4 > (6i64 as u128) . to_bools ()) } Self :: C => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W4 > (8i64 as u128) . to_bools ()) } Self :: Unknown => { rhdl :: core :: bitx_vec (& rhdl :: bits :: bits :: < W4 > (9i64 as u128) . to_bools ()) } } ; raw . resize (Self :: BITS , rhdl :: core :: BitX :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 4usize) } fn trace (self) -> Vec < rhdl :: core :: TraceBit > { let mut raw = match self { Self :: A => { rhdl :: bits :: bits :: < W4 > (1i64 as u128) . trace () } Self :: B => { rhdl :: bits :: bits :: < W4 > (6i64 as u128) . trace () } Self :: C => { rhdl :: bits :: bits :: < W4 > (8i64 as u128) . trace () } Self :: Unknown => { rhdl :: bits :: bits :: < W4 > (9i64 as u128) . trace () } } ; raw . resize (Self :: TRACE_BITS , rhdl :: core :: TraceBit :: Zero) ; rhdl :: core :: move_nbits_to_msb (& raw , 4usize) } fn discriminant (self) -> rhdl :: core :: TypedBits { match self { Self :: A => { rhdl :: bits :: bits :: < W4 > (1i64 as u128) . typed_bits () } Self :: B => { rhdl :: bits :: bits :: < W4 > (6i64 as u128) . typed_bits () } Self :: C => { rhdl :: bits :: bits :: < W4 > (8i64 as u128) . typed_bits () } Self :: Unknown => { rhdl :: bits :: bits :: < W4 > (9i64 as u128) . typed_bits () } } } fn variant_kind (self) -> rhdl :: core :: Kind { match self { Self :: A => { rhdl :: core :: Kind :: Empty } Self :: B => { rhdl :: core :: Kind :: Empty } Self :: C => { rhdl :: core :: Kind :: Empty } Self :: Unknown => { rhdl :: core :: Kind :: Empty } } } fn dont_care () -> Self { < Self as Default > :: default () } fn from_bin (bits : & [rhdl :: core :: BitX]) -> Option < Self > { let (discriminant , payload) = rhdl :: core :: types :: digital :: enum_from_bin (& < Self as rhdl :: core :: Digital > :: static_kind () , bits ,) ? ; match (discriminant , payload . as_slice ()) { (1i64 , _) => Some (Self :: A) , (6i64 , _) => Some (Self :: B) , (8i64 , _) => Some (Self :: C) , (9i64 , _) => Some (Self :: Unknown) , _ => None , } } }"#]];
     expected.assert_eq(&output);
 }
 
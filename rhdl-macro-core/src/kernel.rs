@@ -1,5 +1,5 @@
 use core::num;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use inflections::Inflect;
 use quote::{format_ident, quote};
@@ -11,13 +11,15 @@ use syn::{
 // use crate::suffix::CustomSuffix;
 type TS = proc_macro2::TokenStream;
 type Result<T> = syn::Result<T>;
-// use syn::visit_mut::VisitMut;
+use syn::visit_mut::VisitMut;
 
 // We need the same kind of scope tracking that is used in `infer_types.rs`.
 // Basically, in any given scope, we need a list of the bindings that have
 // been defined thus far.  That way, if we encounter a path that is not
 // defined in the current scope, we can import it from the parent scope of the
-// function.
+// function.  Bindings with a declared type (like function arguments and
+// `let x: T = ...`) also record it, so that the `#[kernel]` methods called
+// on them can be resolved.
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 struct ScopeId(usize);
@@ -32,7 +34,7 @@ impl Default for ScopeId {
 
 #[derive(Default)]
 struct Scope {
-    bindings: HashSet<Ident>,
+    bindings: HashMap<Ident, Option<syn::Type>>,
     children: Vec<ScopeId>,
     parent: ScopeId,
 }
@@ -40,6 +42,11 @@ struct Scope {
 pub struct Context {
     scopes: Vec<Scope>,
     active_scope: ScopeId,
+    // Record the branches taken by the Rust version of the kernel
+    // (`#[kernel(coverage)]`).
+    coverage: bool,
+    // The generic type parameters of the kernel (and of its `impl` block).
+    type_params: HashSet<Ident>,
 }
 
 impl Default for Context {
@@ -47,6 +54,8 @@ impl Default for Context {
         Context {
             scopes: vec![Default::default()],
             active_scope: Default::default(),
+            coverage: false,
+            type_params: HashSet::new(),
         }
     }
 }
@@ -100,6 +109,7 @@ fn path_is_enum_tuple_struct_by_convention(path: &Path) -> bool {
         && ident_starts_with_capital_letter(&second_to_last.ident)
 }
 
+
 fn split_path_into_base_and_variant(path: &Path) -> Result<(Path, Ident)> {
     let base = path
         .segments
@@ -157,6 +167,45 @@ fn rewrite_pattern_as_typed_bits(pat: &syn::Pat) -> syn::Result<TS> {
     }
 }
 
+// The bounds of a range pattern are either integer literals or constants.
+fn range_bound_as_literal(expr: &syn::Expr) -> syn::Result<TS> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(i),
+            ..
+        }) => Ok(quote! {bob.expr_lit_int(stringify!(#i))}),
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) if matches!(
+            expr.as_ref(),
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(_),
+                ..
+            })
+        ) =>
+        {
+            let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(i),
+                ..
+            }) = expr.as_ref()
+            else {
+                unreachable!()
+            };
+            let value = format!("-{}", i.base10_digits());
+            Ok(quote! {bob.expr_lit_int(#value)})
+        }
+        syn::Expr::Path(path) => Ok(
+            quote! { bob.expr_lit_typed_bits(rhdl::core::Digital::typed_bits(#path), stringify!(#path)) },
+        ),
+        _ => Err(syn::Error::new(
+            expr.span(),
+            "Range patterns in rhdl kernel functions must use integer literals or constants as bounds",
+        )),
+    }
+}
+
 fn rewrite_pattern_to_use_defaults_for_bindings(pat: &syn::Pat) -> TS {
     match pat {
         Pat::Ident(_) => {
@@ -219,7 +268,7 @@ impl Context {
     fn new_scope(&mut self) -> ScopeId {
         let id = ScopeId(self.scopes.len());
         self.scopes.push(Scope {
-            bindings: HashSet::new(),
+            bindings: HashMap::new(),
             children: Vec::new(),
             parent: self.active_scope,
         });
@@ -230,22 +279,66 @@ impl Context {
     fn end_scope(&mut self) {
         self.active_scope = self.scopes[self.active_scope.0].parent;
     }
-    fn is_scoped_binding(&self, path: &Path) -> bool {
+    fn scoped_binding(&self, path: &Path) -> Option<&Option<syn::Type>> {
         if path.segments.len() != 1 {
-            return false;
+            return None;
         }
         let ident = &path.segments[0].ident;
         let mut scope = self.active_scope;
         loop {
-            if self.scopes[scope.0].bindings.contains(ident) {
-                return true;
+            if let Some(ty) = self.scopes[scope.0].bindings.get(ident) {
+                return Some(ty);
             }
             if scope == ROOT_SCOPE {
                 break;
             }
             scope = self.scopes[scope.0].parent;
         }
-        false
+        None
+    }
+    fn is_scoped_binding(&self, path: &Path) -> bool {
+        self.scoped_binding(path).is_some()
+    }
+    // The declared type of a binding used as the receiver of a method
+    // call, if it has one.  This covers `self`, the arguments of the kernel
+    // and `let x: T = ...`, which is all that the macro can know without
+    // inferring types.
+    fn receiver_type(&self, receiver: &syn::Expr) -> Option<&syn::Type> {
+        let syn::Expr::Path(path) = receiver else {
+            return None;
+        };
+        if path.qself.is_some() {
+            return None;
+        }
+        self.scoped_binding(&path.path)?.as_ref()
+    }
+    // Whether `path` (as in `path(args...)`) names an associated function
+    // of a type, rather than a function in a module.  Only then can it be
+    // a `#[kernel]` method, which is called through its hidden accessor.
+    // This is known if the type is `Self`, a generic parameter, or has
+    // generic arguments (e.g., `Self::method`, `T::method` or
+    // `UFixed::<W4, W4>::from_raw`).  A qualified path (`<Type>::method`)
+    // is always one, and is handled by the caller.
+    fn path_is_associated_fn(&self, path: &Path) -> bool {
+        if path.leading_colon.is_some() || path.segments.len() < 2 {
+            return false;
+        }
+        let prefix = &path.segments[path.segments.len() - 2];
+        if !prefix.arguments.is_none() {
+            return true;
+        }
+        path.segments.len() == 2
+            && (prefix.ident == "Self" || self.type_params.contains(&prefix.ident))
+    }
+    fn add_typed_binding(&mut self, pat: &PatType) -> Result<()> {
+        let Pat::Ident(ident) = pat.pat.as_ref() else {
+            return self.add_scoped_binding(&pat.pat);
+        };
+        self.add_scoped_binding(&pat.pat)?;
+        self.scopes[self.active_scope.0]
+            .bindings
+            .insert(ident.ident.clone(), Some(pat.ty.as_ref().clone()));
+        Ok(())
     }
     // Scoped bindings refer to something like:
     //  match x {
@@ -271,7 +364,7 @@ impl Context {
                 }
                 self.scopes[self.active_scope.0]
                     .bindings
-                    .insert(name.clone());
+                    .insert(name.clone(), None);
             }
             Pat::Tuple(tuple) => {
                 for pat in tuple.elems.iter() {
@@ -298,7 +391,7 @@ impl Context {
                     self.add_scoped_binding(pat)?;
                 }
             }
-            Pat::Type(pat) => self.add_scoped_binding(&pat.pat)?,
+            Pat::Type(pat) => self.add_typed_binding(pat)?,
             Pat::Wild(_) | Pat::Path(_) | Pat::Const(_) | Pat::Lit(_) => {}
             _ => {
                 return Err(syn::Error::new(
@@ -315,9 +408,189 @@ impl Context {
 }
 
 pub fn hdl_kernel(input: TS) -> Result<TS> {
-    let input = syn::parse::<syn::ItemFn>(input.into())?;
-    let mut context = Context::default();
-    context.function(input)
+    hdl_kernel_with_args(TS::new(), input)
+}
+
+// The arguments of `#[kernel(...)]`.  The only one is `coverage`, which
+// instruments the Rust version of the kernel for branch coverage.
+fn parse_coverage_arg(args: TS) -> Result<bool> {
+    if args.is_empty() {
+        return Ok(false);
+    }
+    let arg = syn::parse2::<Ident>(args)?;
+    if arg != "coverage" {
+        return Err(syn::Error::new(
+            arg.span(),
+            "Unknown #[kernel] argument, expected `coverage`",
+        ));
+    }
+    Ok(true)
+}
+
+pub fn hdl_kernel_with_args(args: TS, input: TS) -> Result<TS> {
+    let coverage = parse_coverage_arg(args)?;
+    match syn::parse::<syn::Item>(input.into())? {
+        syn::Item::Fn(function) => {
+            let mut context = Context {
+                coverage,
+                ..Default::default()
+            };
+            if let Some(FnArg::Receiver(_)) = function.sig.inputs.first() {
+                context.method(function)
+            } else {
+                context.function(function)
+            }
+        }
+        syn::Item::Impl(item) => hdl_kernel_impl(item, coverage),
+        syn::Item::Trait(item) => hdl_kernel_trait(item),
+        item => Err(syn::Error::new(
+            item.span(),
+            "#[kernel] can only be applied to functions, impl blocks and traits",
+        )),
+    }
+}
+
+fn type_params(generics: &syn::Generics) -> HashSet<Ident> {
+    generics.type_params().map(|x| x.ident.clone()).collect()
+}
+
+fn is_kernel_attribute(attr: &syn::Attribute) -> bool {
+    attr.path()
+        .segments
+        .last()
+        .map(|x| x.ident == "kernel")
+        .unwrap_or(false)
+}
+
+// Every function in an `impl` block marked with `#[kernel]` is a kernel
+// method.  Unlike a `#[kernel]` on a single method, this also covers
+// associated functions without a receiver, which is what trait
+// implementations (called as `T::combine(a, b)` in a generic kernel)
+// typically need.
+fn hdl_kernel_impl(mut item: syn::ItemImpl, coverage: bool) -> Result<TS> {
+    let mut items = vec![];
+    for impl_item in std::mem::take(&mut item.items) {
+        let syn::ImplItem::Fn(func) = impl_item else {
+            items.push(quote! {#impl_item});
+            continue;
+        };
+        let function = syn::ItemFn {
+            attrs: func
+                .attrs
+                .into_iter()
+                .filter(|x| !is_kernel_attribute(x))
+                .collect(),
+            vis: func.vis,
+            sig: func.sig,
+            block: Box::new(func.block),
+        };
+        items.push(
+            Context {
+                coverage,
+                type_params: type_params(&item.generics),
+                ..Default::default()
+            }
+            .method(function)?,
+        );
+    }
+    let attrs = &item.attrs;
+    let defaultness = &item.defaultness;
+    let unsafety = &item.unsafety;
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+    let trait_ = item
+        .trait_
+        .as_ref()
+        .map(|(bang, path, for_token)| quote!(#bang #path #for_token));
+    let self_ty = &item.self_ty;
+    Ok(quote! {
+        #(#attrs)*
+        #defaultness #unsafety impl #impl_generics #trait_ #self_ty #where_clause {
+            #(#items)*
+        }
+    })
+}
+
+// A trait whose functions are implemented by kernels.  For each function
+// we declare the hidden accessor for its kernel, so that generic kernels
+// can call through the trait bound, and the kernel is picked when the
+// caller is monomorphized.  Implementations must use `#[kernel] impl`.
+fn hdl_kernel_trait(mut item: syn::ItemTrait) -> Result<TS> {
+    let mut accessors = vec![];
+    for trait_item in item.items.iter() {
+        let syn::TraitItem::Fn(func) = trait_item else {
+            continue;
+        };
+        if let Some(body) = &func.default {
+            return Err(syn::Error::new(
+                body.span(),
+                "Default implementations are not supported for kernel trait functions",
+            ));
+        }
+        let kernel_name = method_kernel_name(&func.sig.ident);
+        let (impl_generics, _, where_clause) = func.sig.generics.split_for_impl();
+        accessors.push(syn::parse_quote! {
+            #[doc(hidden)]
+            fn #kernel_name #impl_generics () -> Option<rhdl::core::digital_fn::KernelFnKind> #where_clause;
+        });
+    }
+    item.items.extend(accessors);
+    // Kernels are identified by their `TypeId`, so any generic kernel
+    // parameterized by an implementor needs it to be `'static`.
+    item.supertraits.push(syn::parse_quote!('static));
+    if item.colon_token.is_none() {
+        item.colon_token = Some(Default::default());
+    }
+    Ok(quote! {#item})
+}
+
+// The name of the hidden associated function that returns the
+// kernel for a method `name`.
+fn method_kernel_name(name: &Ident) -> Ident {
+    format_ident!("__rhdl_kernel_{}", name)
+}
+
+// The methods that are built into the compiler.  Any other method called
+// in a kernel must be a `#[kernel]` method of the receiver.
+const KNOWN_METHODS: [&str; 35] = [
+    "any",
+    "all",
+    "xor",
+    "as_signed",
+    "as_unsigned",
+    "val",
+    "resize",
+    "raw",
+    "xadd",
+    "xsub",
+    "xmul",
+    "xneg",
+    "xext",
+    "xshl",
+    "xshr",
+    "xsgn",
+    "widen",
+    "narrow",
+    "saturating_add",
+    "saturating_sub",
+    "checked_add",
+    "checked_sub",
+    "overflowing_add",
+    "overflowing_sub",
+    "saturate",
+    "xfrac",
+    "xtrunc",
+    "xround",
+    "resize_fixed",
+    "count_ones",
+    "leading_zeros",
+    "trailing_zeros",
+    "reverse_bits",
+    "one_hot_to_binary",
+    "binary_to_one_hot",
+];
+
+fn is_known_method(expr: &syn::ExprMethodCall) -> bool {
+    KNOWN_METHODS.contains(&expr.method.to_string().as_str())
 }
 
 // Convert a pattern that would appear in a function argument into an expression.
@@ -435,6 +708,194 @@ fn impl_digital_fnk_trait(function: &syn::ItemFn) -> Result<TS> {
     })
 }
 
+// An `assert!` or `cover!` in a kernel.  The condition comes first,
+// followed by an optional message, which must be a string literal.
+struct Assertion {
+    cover: bool,
+    cond: syn::Expr,
+    message: Option<syn::LitStr>,
+}
+
+// Returns `None` if the macro is not an assertion at all.
+fn parse_assertion(mac: &syn::Macro) -> Option<Result<Assertion>> {
+    let cover = if mac.path.is_ident("assert") {
+        false
+    } else if mac.path.is_ident("cover") {
+        true
+    } else {
+        return None;
+    };
+    let args = match mac.parse_body_with(Punctuated::<syn::Expr, Comma>::parse_terminated) {
+        Ok(args) => args,
+        Err(err) => return Some(Err(err)),
+    };
+    let mut args = args.into_iter();
+    let Some(cond) = args.next() else {
+        return Some(Err(syn::Error::new(
+            mac.span(),
+            "Missing condition in assertion",
+        )));
+    };
+    let message =
+        match args.next() {
+            None => None,
+            Some(syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(message),
+                ..
+            })) => Some(message),
+            Some(arg) => return Some(Err(syn::Error::new(
+                arg.span(),
+                "The message of an assertion in an rhdl kernel function must be a string literal",
+            ))),
+        };
+    if let Some(arg) = args.next() {
+        return Some(Err(syn::Error::new(
+            arg.span(),
+            "Format arguments are not supported in assertions in rhdl kernel functions",
+        )));
+    }
+    Some(Ok(Assertion {
+        cover,
+        cond,
+        message,
+    }))
+}
+
+// Replace the assertions in the Rust version of a kernel.  A failing
+// `assert!` is reported against the source of the kernel (which is
+// found through the `kernel` accessor), and a `cover!` only evaluates
+// its condition.  Assertions are numbered in the order they appear,
+// which is the order in which the compiler collects them.
+struct AssertionRewriter {
+    kernel: TS,
+    ordinal: usize,
+}
+
+impl AssertionRewriter {
+    fn rewrite(&mut self, mac: &syn::Macro) -> Option<syn::Expr> {
+        let Assertion { cover, cond, .. } = parse_assertion(mac)?.ok()?;
+        let ordinal = self.ordinal;
+        self.ordinal += 1;
+        let kernel = &self.kernel;
+        Some(if cover {
+            syn::parse_quote! {
+                {
+                    let _: bool = #cond;
+                }
+            }
+        } else {
+            syn::parse_quote! {
+                if !(#cond) {
+                    rhdl::core::kernel_assertion_failed(#ordinal, #kernel)
+                }
+            }
+        })
+    }
+}
+
+impl VisitMut for AssertionRewriter {
+    fn visit_stmt_mut(&mut self, node: &mut syn::Stmt) {
+        if let syn::Stmt::Macro(stmt) = node {
+            if let Some(expr) = self.rewrite(&stmt.mac) {
+                *node = syn::Stmt::Expr(expr, stmt.semi_token);
+            }
+        }
+        syn::visit_mut::visit_stmt_mut(self, node);
+    }
+    fn visit_expr_mut(&mut self, node: &mut syn::Expr) {
+        if let syn::Expr::Macro(expr) = node {
+            if let Some(expr) = self.rewrite(&expr.mac) {
+                *node = expr;
+            }
+        }
+        syn::visit_mut::visit_expr_mut(self, node);
+    }
+}
+
+// Record the branches taken by the Rust version of a kernel, for
+// coverage.  This is only done for `#[kernel(coverage)]`, so that other
+// kernels do not pay for it in simulation.  Each branch (the `then` and
+// `else` of an `if`, the arms of a `match`, and each `return`) reports
+// its ordinal through the `kernel` accessor when it is taken.  Ordinals
+// are assigned to the branches of an expression before those nested
+// inside it, which is the order in which the compiler collects them.
+// Assertions are still macros at this point, so their conditions are not
+// visited.
+struct BranchRewriter {
+    kernel: TS,
+    ordinal: usize,
+}
+
+impl BranchRewriter {
+    fn hit(&mut self) -> syn::Stmt {
+        let ordinal = self.ordinal;
+        self.ordinal += 1;
+        let kernel = &self.kernel;
+        syn::parse_quote! {
+            rhdl::core::kernel_branch_taken(#ordinal, #kernel);
+        }
+    }
+}
+
+impl VisitMut for BranchRewriter {
+    fn visit_expr_mut(&mut self, node: &mut syn::Expr) {
+        match node {
+            syn::Expr::If(expr) => {
+                let hit = self.hit();
+                expr.then_branch.stmts.insert(0, hit);
+                let hit = self.hit();
+                match expr.else_branch.as_mut().map(|(_, x)| x.as_mut()) {
+                    Some(syn::Expr::Block(block)) => block.block.stmts.insert(0, hit),
+                    Some(else_if) => {
+                        *else_if = syn::parse_quote! {
+                            {
+                                #hit
+                                #else_if
+                            }
+                        }
+                    }
+                    None => {
+                        expr.else_branch = Some((
+                            Default::default(),
+                            Box::new(syn::parse_quote! {
+                                {
+                                    #hit
+                                }
+                            }),
+                        ))
+                    }
+                }
+            }
+            syn::Expr::Match(expr) => {
+                for arm in expr.arms.iter_mut() {
+                    let hit = self.hit();
+                    let body = &arm.body;
+                    arm.body = syn::parse_quote! {
+                        {
+                            #hit
+                            #body
+                        }
+                    };
+                }
+            }
+            syn::Expr::Return(expr) => {
+                let hit = self.hit();
+                syn::visit_mut::visit_expr_return_mut(self, expr);
+                let expr = expr.clone();
+                *node = syn::parse_quote! {
+                    {
+                        #hit
+                        #expr
+                    }
+                };
+                return;
+            }
+            _ => {}
+        }
+        syn::visit_mut::visit_expr_mut(self, node);
+    }
+}
+
 // put the original function inside a wrapper function with the name of the original.
 // Call the wrapped function 'inner'
 // Capture the return of the wrapped function
@@ -523,6 +984,7 @@ impl Context {
     fn function(&mut self, function: syn::ItemFn) -> Result<TS> {
         let orig_name = &function.sig.ident;
         let vis = &function.vis;
+        self.type_params.extend(type_params(&function.sig.generics));
         let (impl_generics, ty_generics, where_clause) = function.sig.generics.split_for_impl();
         let phantom_fields = function
             .sig
@@ -551,7 +1013,7 @@ impl Context {
                     ))
                 }
                 syn::FnArg::Typed(pat) => {
-                    self.add_scoped_binding(&pat.pat)?;
+                    self.add_typed_binding(pat)?;
                 }
             }
         }
@@ -581,7 +1043,20 @@ impl Context {
                 }
             })
             .collect::<Result<Vec<_>>>()?;
-        let wrapped_function = trace_wrap_function(&function)?;
+        let mut rust_function = function.clone();
+        if self.coverage {
+            BranchRewriter {
+                kernel: quote! {<#name #ty_generics as rhdl::core::digital_fn::DigitalFn>::kernel_fn},
+                ordinal: 0,
+            }
+            .visit_block_mut(&mut rust_function.block);
+        }
+        AssertionRewriter {
+            kernel: quote! {<#name #ty_generics as rhdl::core::digital_fn::DigitalFn>::kernel_fn()},
+            ordinal: 0,
+        }
+        .visit_block_mut(&mut rust_function.block);
+        let wrapped_function = trace_wrap_function(&rust_function)?;
         let digital_fnk_impl = impl_digital_fnk_trait(&function)?;
         let file = syn::File {
             shebang: None,
@@ -614,6 +1089,109 @@ impl Context {
         })
     }
 
+    // A kernel defined as a method (or associated function) inside
+    // an `impl` block.  We cannot emit a marker struct inside the `impl`,
+    // so instead the kernel is provided by a hidden associated function
+    // next to the method itself.  Callers resolve it through the type of
+    // the receiver.
+    fn method(&mut self, function: syn::ItemFn) -> Result<TS> {
+        let name = &function.sig.ident;
+        let vis = &function.vis;
+        let attrs = &function.attrs;
+        let (impl_generics, ty_generics, where_clause) = function.sig.generics.split_for_impl();
+        let ty_generics = ty_generics.as_turbofish();
+        let kernel_name = method_kernel_name(name);
+        self.type_params.extend(type_params(&function.sig.generics));
+        let mut args = vec![];
+        for arg in function.sig.inputs.iter() {
+            match arg {
+                syn::FnArg::Receiver(receiver) => {
+                    if receiver.reference.is_some() {
+                        return Err(syn::Error::new(
+                            receiver.span(),
+                            "Kernel methods must take `self` by value",
+                        ));
+                    }
+                    let this = Ident::new("self", receiver.self_token.span);
+                    self.scopes[self.active_scope.0]
+                        .bindings
+                        .insert(this, Some(syn::parse_quote!(Self)));
+                    let mutability = receiver.mutability.is_some();
+                    args.push(quote! {
+                        bob.type_pat(
+                            bob.ident_pat(stringify!(self), #mutability),
+                            <Self as rhdl::core::Digital>::static_kind()
+                        )
+                    });
+                }
+                syn::FnArg::Typed(pat) => {
+                    let ty = &pat.ty;
+                    let kind = quote! {<#ty as rhdl::core::Digital>::static_kind()};
+                    let arg_pat = self.pat(&pat.pat)?;
+                    self.add_typed_binding(pat)?;
+                    args.push(quote! { bob.type_pat(#arg_pat, #kind)});
+                }
+            }
+        }
+        let block = self.block_inner(&function.block)?;
+        let ret = match &function.sig.output {
+            syn::ReturnType::Default => quote! {rhdl::core::Kind::Empty},
+            syn::ReturnType::Type(_, ty) => {
+                quote! {
+                    <#ty as rhdl::core::Digital>::static_kind()
+                }
+            }
+        };
+        let inputs = &function.sig.inputs;
+        let output = &function.sig.output;
+        let mut body = function.block.clone();
+        if self.coverage {
+            BranchRewriter {
+                kernel: quote! {Self::#kernel_name #ty_generics},
+                ordinal: 0,
+            }
+            .visit_block_mut(&mut body);
+        }
+        AssertionRewriter {
+            kernel: quote! {Self::#kernel_name #ty_generics ()},
+            ordinal: 0,
+        }
+        .visit_block_mut(&mut body);
+        let file = syn::File {
+            shebang: None,
+            attrs: vec![],
+            items: vec![syn::Item::Fn(function.clone())],
+        };
+        let text = prettyplease::unparse(&file).to_string();
+        // The body is wrapped in a closure (rather than an inner function
+        // as for free kernels) so that it can still refer to `self`.
+        Ok(quote! {
+            #( #attrs )*
+            #[allow(clippy::redundant_closure_call)]
+            #vis fn #name #impl_generics (#inputs) #output #where_clause {
+                rhdl::core::trace_push_path(stringify!(#name));
+                let ret = (|| #output #body)();
+                rhdl::core::trace_pop_path();
+                ret
+            }
+
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            #vis fn #kernel_name #impl_generics () -> Option<rhdl::core::digital_fn::KernelFnKind> #where_clause {
+                let bob = rhdl::core::ast::builder::ASTBuilder::default();
+                Some(bob.kernel_fn(
+                    stringify!(#name),
+                    vec!{#(#args),*},
+                    #ret,
+                    #block,
+                    rhdl::core::digital_fn::fn_type_id(Self::#name #ty_generics),
+                    #text,
+                    concat!(file!(), ":", line!()),
+                ))
+            }
+        })
+    }
+
     fn block(&mut self, block: &syn::Block) -> Result<TS> {
         self.new_scope();
         let block = self.block_inner(block)?;
@@ -649,6 +1227,18 @@ impl Context {
                     })
                 }
             }
+            syn::Stmt::Macro(stmt) => {
+                let expr = self.assertion(&stmt.mac)?;
+                if stmt.semi_token.is_some() {
+                    Ok(quote! {
+                        bob.semi_stmt(#expr)
+                    })
+                } else {
+                    Ok(quote! {
+                        bob.expr_stmt(#expr)
+                    })
+                }
+            }
             _ => Err(syn::Error::new(
                 statement.span(),
                 "Unsupported statement type",
@@ -806,6 +1396,7 @@ impl Context {
             syn::Expr::Index(expr) => self.index(expr),
             syn::Expr::MethodCall(expr) => self.method_call(expr),
             syn::Expr::Cast(expr) => self.cast(expr),
+            syn::Expr::Macro(expr) => self.assertion(&expr.mac),
             _ => Err(syn::Error::new(
                 expr.span(),
                 format!(
@@ -816,6 +1407,30 @@ impl Context {
         }
     }
 
+    fn assertion(&mut self, mac: &syn::Macro) -> Result<TS> {
+        let Some(assertion) = parse_assertion(mac) else {
+            return Err(syn::Error::new(
+                mac.span(),
+                "Only the assert! and cover! macros are supported in rhdl kernel functions",
+            ));
+        };
+        let Assertion {
+            cover,
+            cond,
+            message,
+        } = assertion?;
+        let kind = if cover {
+            quote! {rhdl::core::ast::builder::AssertKind::Cover}
+        } else {
+            quote! {rhdl::core::ast::builder::AssertKind::Assert}
+        };
+        let cond = self.expr(&cond)?;
+        let message = message.map(|x| quote! {#x}).unwrap_or(quote! {""});
+        Ok(quote! {
+            bob.expr_assert(#kind, #cond, #message)
+        })
+    }
+
     fn cast(&mut self, expr: &syn::ExprCast) -> Result<TS> {
         let ty = &expr.ty;
         let len = quote! {<#ty as rhdl::core::Digital>::BITS};
@@ -826,25 +1441,20 @@ impl Context {
     }
 
     fn method_call(&mut self, expr: &syn::ExprMethodCall) -> Result<TS> {
-        const KNOWN_METHODS: [&str; 16] = [
-            "any",
-            "all",
-            "xor",
-            "as_signed",
-            "as_unsigned",
-            "val",
+        const TURBO_METHODS: [&str; 12] = [
             "resize",
-            "raw",
-            "xadd",
-            "xsub",
-            "xmul",
-            "xneg",
             "xext",
             "xshl",
             "xshr",
-            "xsgn",
+            "widen",
+            "narrow",
+            "saturate",
+            "xfrac",
+            "xtrunc",
+            "xround",
+            "one_hot_to_binary",
+            "binary_to_one_hot",
         ];
-        const TURBO_METHODS: [&str; 4] = ["resize", "xext", "xshl", "xshr"];
 
         let receiver = self.expr(&expr.receiver)?;
         let args = expr
@@ -853,14 +1463,8 @@ impl Context {
             .map(|x| self.expr(x))
             .collect::<Result<Vec<_>>>()?;
         let method = &expr.method;
-        if !KNOWN_METHODS.contains(&method.to_string().as_str()) {
-            return Err(syn::Error::new(
-                method.span(),
-                format!(
-                    "Unsupported method call {} in an rhdl kernel function",
-                    quote!(#expr)
-                ),
-            ));
+        if !is_known_method(expr) {
+            return self.user_method_call(expr, receiver, args);
         }
         let turbo = if let Some(x) = &expr.turbofish {
             if (x.args.len() != 1) || (TURBO_METHODS.iter().all(|x| method != x)) {
@@ -870,7 +1474,7 @@ impl Context {
                 ));
             }
             let x = x.args.iter().next().unwrap();
-            quote!(Some(<#x as rhdl_typenum::BitWidth>::BITS))
+            quote!(Some(<#x as rhdl::bits::BitWidth>::BITS))
         } else {
             quote!(None)
         };
@@ -879,6 +1483,40 @@ impl Context {
         })
     }
 
+    // A call to a method defined with `#[kernel]` in an `impl` block.  This
+    // is compiled as a call to the kernel with the receiver as the first
+    // argument, exactly as `<Type>::method(receiver, args...)` would be,
+    // where `Type` is the declared type of the receiver.
+    fn user_method_call(
+        &mut self,
+        expr: &syn::ExprMethodCall,
+        receiver: TS,
+        args: Vec<TS>,
+    ) -> Result<TS> {
+        let method = &expr.method;
+        let Some(ty) = self.receiver_type(&expr.receiver).cloned() else {
+            return Err(syn::Error::new(
+                expr.span(),
+                format!(
+                    "Unsupported method call {} in an rhdl kernel function.  Kernel methods can be called on `self`, on arguments and on `let` bindings with a declared type.  Otherwise, use `<Type>::{}(receiver, ...)`",
+                    quote!(#expr),
+                    method
+                ),
+            ));
+        };
+        let turbo = &expr.turbofish;
+        let kernel_name = method_kernel_name(method);
+        let path = self.path_inner(&Path::from(method.clone()))?;
+        Ok(quote! {
+            bob.call_expr(
+                #path,
+                vec![#receiver, #(#args),*],
+                rhdl::core::digital_fn::inspect_digital(<#ty>::#method #turbo),
+                <#ty>::#kernel_name #turbo ()
+            )
+        })
+    }
+
     fn index(&mut self, expr: &syn::ExprIndex) -> Result<TS> {
         let index = self.expr(&expr.index)?;
         let expr = self.expr(&expr.expr)?;
@@ -1011,7 +1649,7 @@ impl Context {
         }
         let code = if !path_is_enum_tuple_struct_by_convention(&func_path.path) {
             // This is a function call
-            self.get_code(&func_path.path)?
+            self.get_code(func_path)?
         } else {
             // This is an enum tuple struct... build one.
             // To do so, we split the path into the base and the variant, assuming that
@@ -1047,13 +1685,23 @@ impl Context {
         })
     }
 
-    fn get_code(&mut self, path: &Path) -> Result<TS> {
+    fn get_code(&mut self, func_path: &syn::ExprPath) -> Result<TS> {
+        let path = &func_path.path;
         if path.segments.is_empty() {
             return Err(syn::Error::new(
                 path.span(),
                 "Empty path in rhdl kernel function",
             ));
         }
+        if func_path.qself.is_some() || self.path_is_associated_fn(path) {
+            // A kernel method called as `Type::method(receiver, ...)`
+            let mut func_path = func_path.clone();
+            if let Some(mut last) = func_path.path.segments.pop().map(|x| x.into_value()) {
+                last.ident = method_kernel_name(&last.ident);
+                func_path.path.segments.push(last);
+            }
+            return Ok(quote!(#func_path()));
+        }
         Ok(quote!(<#path as rhdl::core::digital_fn::DigitalFn>::kernel_fn()))
     }
 
@@ -1161,41 +1809,99 @@ impl Context {
         let arms = expr
             .arms
             .iter()
-            .map(|x| self.arm(&x.pat, &x.body))
+            .map(|x| {
+                self.arm(
+                    &x.pat,
+                    x.guard.as_ref().map(|(_, guard)| guard.as_ref()),
+                    &x.body,
+                )
+            })
             .collect::<Result<Vec<_>>>()?;
         let expr = self.expr(&expr.expr)?;
         Ok(quote! {
             bob.match_expr(#expr, vec![#(#arms),*])
         })
     }
-    fn arm(&mut self, pat: &syn::Pat, body: &syn::Expr) -> Result<TS> {
+    fn arm(&mut self, pat: &syn::Pat, guard: Option<&syn::Expr>, body: &syn::Expr) -> Result<TS> {
         let option_or_result_discriminant = get_pattern_option_or_result_discriminant(pat);
         self.new_scope();
-        let arm = if !pattern_has_bindings(pat) && option_or_result_discriminant.is_none() {
-            let body = self.expr(body)?;
-            let kind = if let syn::Pat::Wild(_) = &pat {
-                quote! {bob.arm_kind_wild()}
-            } else if pat_is_none(pat) {
-                quote! {bob.arm_kind_none()}
-            } else {
-                let pat = rewrite_pattern_as_typed_bits(pat)?;
-                quote! {bob.arm_kind_constant(#pat)}
-            };
-            quote! {bob.arm(#kind, #body)}
+        let kind = if !pattern_has_bindings(pat) && option_or_result_discriminant.is_none() {
+            self.arm_kind(pat)?
+        } else if let Pat::Ident(ident) = pat {
+            // A bare binding (e.g., `x if x > 3 =>`) matches anything
+            if ident.subpat.is_some() {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    "Subpatterns in bindings are not supported in rhdl kernel functions",
+                ));
+            }
+            self.add_scoped_binding(pat)?;
+            let inner = self.pat(pat)?;
+            quote! {bob.arm_kind_bind(#inner)}
+        } else if let Pat::Or(or) = pat {
+            return Err(syn::Error::new(
+                or.span(),
+                "Or-patterns with bindings are not supported in rhdl kernel functions",
+            ));
         } else {
             self.add_scoped_binding(pat)?;
-            let body = self.expr(body)?;
             let mut discriminant = option_or_result_discriminant.map(|x| quote!(#x.typed_bits()));
             if discriminant.is_none() {
                 let pat_as_expr = rewrite_pattern_to_use_defaults_for_bindings(pat);
                 discriminant = Some(quote!(rhdl::core::Digital::discriminant(#pat_as_expr)));
             }
             let inner = self.pat(pat)?;
-            let kind = quote! {bob.arm_kind_enum(#inner, #discriminant)};
-            quote! {bob.arm(#kind, #body)}
+            quote! {bob.arm_kind_enum(#inner, #discriminant)}
         };
+        let guard = guard
+            .map(|x| self.expr(x))
+            .transpose()?
+            .map(|x| quote! {Some(#x)})
+            .unwrap_or_else(|| quote! {None});
+        let body = self.expr(body)?;
         self.end_scope();
-        Ok(arm)
+        Ok(quote! {bob.arm(#kind, #guard, #body)})
+    }
+    // The kind of an arm whose pattern does not bind any names.
+    fn arm_kind(&mut self, pat: &syn::Pat) -> Result<TS> {
+        Ok(match pat {
+            Pat::Wild(_) => quote! {bob.arm_kind_wild()},
+            Pat::Paren(paren) => self.arm_kind(&paren.pat)?,
+            Pat::Range(range) => {
+                let start = range
+                    .start
+                    .as_ref()
+                    .map(|x| range_bound_as_literal(x))
+                    .transpose()?
+                    .map(|x| quote! {Some(#x)})
+                    .unwrap_or_else(|| quote! {None});
+                let end = range
+                    .end
+                    .as_ref()
+                    .map(|x| range_bound_as_literal(x))
+                    .transpose()?
+                    .map(|x| quote! {Some(#x)})
+                    .unwrap_or_else(|| quote! {None});
+                let limits = match range.limits {
+                    syn::RangeLimits::HalfOpen(_) => quote!(bob.range_limits_half_open()),
+                    syn::RangeLimits::Closed(_) => quote!(bob.range_limits_closed()),
+                };
+                quote! {bob.arm_kind_range(#start, #limits, #end)}
+            }
+            Pat::Or(or) => {
+                let cases = or
+                    .cases
+                    .iter()
+                    .map(|x| self.arm_kind(x))
+                    .collect::<Result<Vec<_>>>()?;
+                quote! {bob.arm_kind_or(vec![#(#cases),*])}
+            }
+            _ if pat_is_none(pat) => quote! {bob.arm_kind_none()},
+            _ => {
+                let pat = rewrite_pattern_as_typed_bits(pat)?;
+                quote! {bob.arm_kind_constant(#pat)}
+            }
+        })
     }
 
     fn let_ex(&mut self, expr: &syn::ExprLet) -> Result<TS> {
@@ -1481,6 +2187,8 @@ impl Context {
             syn::BinOp::Add(_) => quote!(rhdl::core::ast::builder::BinOp::Add),
             syn::BinOp::Sub(_) => quote!(rhdl::core::ast::builder::BinOp::Sub),
             syn::BinOp::Mul(_) => quote!(rhdl::core::ast::builder::BinOp::Mul),
+            syn::BinOp::Div(_) => quote!(rhdl::core::ast::builder::BinOp::Div),
+            syn::BinOp::Rem(_) => quote!(rhdl::core::ast::builder::BinOp::Rem),
             syn::BinOp::And(_) => quote!(rhdl::core::ast::builder::BinOp::And),
             syn::BinOp::Or(_) => quote!(rhdl::core::ast::builder::BinOp::Or),
             syn::BinOp::BitXor(_) => quote!(rhdl::core::ast::builder::BinOp::BitXor),
@@ -1497,6 +2205,8 @@ impl Context {
             syn::BinOp::AddAssign(_) => quote!(rhdl::core::ast::builder::BinOp::AddAssign),
             syn::BinOp::SubAssign(_) => quote!(rhdl::core::ast::builder::BinOp::SubAssign),
             syn::BinOp::MulAssign(_) => quote!(rhdl::core::ast::builder::BinOp::MulAssign),
+            syn::BinOp::DivAssign(_) => quote!(rhdl::core::ast::builder::BinOp::DivAssign),
+            syn::BinOp::RemAssign(_) => quote!(rhdl::core::ast::builder::BinOp::RemAssign),
             syn::BinOp::BitXorAssign(_) => quote!(rhdl::core::ast::builder::BinOp::BitXorAssign),
             syn::BinOp::BitAndAssign(_) => quote!(rhdl::core::ast::builder::BinOp::BitAndAssign),
             syn::BinOp::BitOrAssign(_) => quote!(rhdl::core::ast::builder::BinOp::BitOrAssign),
@@ -2237,19 +2947,20 @@ mod test {
                                 .path_segment(stringify!(l), bob.path_arguments_none())],)), vec![bob
                                 .arm(bob.arm_kind_constant(bob
                                 .expr_lit_typed_bits(rhdl::core::Digital::typed_bits(State::Init),
-                                stringify!(State::Init))), bob.block_expr(bob.block(vec![],))), bob
-                                .arm(bob.arm_kind_enum(bob.tuple_struct_pat(bob.path(vec![bob
+                                stringify!(State::Init))), None, bob.block_expr(bob.block(vec![],))),
+                                bob.arm(bob.arm_kind_enum(bob.tuple_struct_pat(bob.path(vec![bob
                                 .path_segment(stringify!(State), bob.path_arguments_none()), bob
                                 .path_segment(stringify!(Run), bob.path_arguments_none())],),
                                 vec![bob.ident_pat(stringify!(a), false)]),
                                 rhdl::core::Digital::discriminant(State::Run(Default::default()))),
-                                bob.block_expr(bob.block(vec![bob.semi_stmt(bob.assign_expr(bob
+                                None, bob.block_expr(bob.block(vec![bob.semi_stmt(bob.assign_expr(bob
                                 .path_expr(bob.path(vec![bob.path_segment(stringify!(l), bob
                                 .path_arguments_none())],)), bob.path_expr(bob.path(vec![bob
                                 .path_segment(stringify!(a), bob.path_arguments_none())],))))],))),
                                 bob.arm(bob.arm_kind_constant(bob
                                 .expr_lit_typed_bits(rhdl::core::Digital::typed_bits(State::Boom),
-                                stringify!(State::Boom))), bob.block_expr(bob.block(vec![],)))]))
+                                stringify!(State::Boom))), None, bob.block_expr(bob
+                                .block(vec![],)))]))
                             ],
                         ),
                 )
@@ -2548,12 +3259,12 @@ mod test {
                                             bob.expr_stmt(bob.match_expr(bob.path_expr(bob.path(vec![bob
                                             .path_segment(stringify!(z), bob.path_arguments_none())],)),
                                             vec![bob.arm(bob.arm_kind_constant(bob
-                                            .expr_lit_int(stringify!(1_u4))), bob.block_expr(bob
+                                            .expr_lit_int(stringify!(1_u4))), None, bob.block_expr(bob
                                             .block(vec![],))), bob.arm(bob.arm_kind_constant(bob
-                                            .expr_lit_int(stringify!(2_u4))), bob.block_expr(bob
+                                            .expr_lit_int(stringify!(2_u4))), None, bob.block_expr(bob
                                             .block(vec![],))), bob.arm(bob.arm_kind_constant(bob
                                             .expr_lit_typed_bits(rhdl::core::Digital::typed_bits(CONST_VAL),
-                                            stringify!(CONST_VAL))), bob.block_expr(bob
+                                            stringify!(CONST_VAL))), None, bob.block_expr(bob
                                             .block(vec![],)))]))
                                         ],
                                     ),
@@ -2634,14 +3345,15 @@ mod test {
                                             .path_segment(stringify!(z), bob.path_arguments_none())],)),
                                             vec![bob.arm(bob.arm_kind_constant(bob
                                             .expr_lit_typed_bits(rhdl::core::Digital::typed_bits(Bar::A),
-                                            stringify!(Bar::A))), bob.block_expr(bob.block(vec![],))),
-                                            bob.arm(bob.arm_kind_enum(bob.tuple_struct_pat(bob
-                                            .path(vec![bob.path_segment(stringify!(Bar), bob
-                                            .path_arguments_none()), bob.path_segment(stringify!(B), bob
+                                            stringify!(Bar::A))), None, bob.block_expr(bob
+                                            .block(vec![],))), bob.arm(bob.arm_kind_enum(bob
+                                            .tuple_struct_pat(bob.path(vec![bob
+                                            .path_segment(stringify!(Bar), bob.path_arguments_none()),
+                                            bob.path_segment(stringify!(B), bob
                                             .path_arguments_none())],), vec![bob
                                             .ident_pat(stringify!(x), false)]),
                                             rhdl::core::Digital::discriminant(Bar::B(Default::default()))),
-                                            bob.block_expr(bob.block(vec![],))), bob.arm(bob
+                                            None, bob.block_expr(bob.block(vec![],))), bob.arm(bob
                                             .arm_kind_enum(bob.struct_pat(bob.path(vec![bob
                                             .path_segment(stringify!(Bar), bob.path_arguments_none()),
                                             bob.path_segment(stringify!(C), bob
@@ -2650,7 +3362,7 @@ mod test {
                                             false)), bob.field_pat(bob.member_named(stringify!(y)), bob
                                             .ident_pat(stringify!(y), false))], false),
                                             rhdl::core::Digital::discriminant(Bar::C { x :
-                                            Default::default(), y : Default::default() })), bob
+                                            Default::default(), y : Default::default() })), None, bob
                                             .block_expr(bob.block(vec![],)))]))
                                         ],
                                     ),
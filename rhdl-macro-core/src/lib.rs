@@ -4,6 +4,7 @@ pub use digital::derive_digital;
 mod digital_enum;
 mod kernel;
 pub use kernel::hdl_kernel;
+pub use kernel::hdl_kernel_with_args;
 mod circuit;
 mod suffix;
 pub use circuit::derive_circuit;
@@ -24,4 +25,6 @@ pub use typenum_traits::impl_log2_trait;
 pub use typenum_traits::impl_max_trait;
 pub use typenum_traits::impl_min_trait;
 pub use typenum_traits::impl_sub_trait;
+pub use typenum_traits::impl_wide_add_trait;
+pub use typenum_traits::impl_wide_sub_trait;
 mod partial_eq;
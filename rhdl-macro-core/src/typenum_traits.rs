@@ -24,6 +24,7 @@ struct ImplBinOp {
     trait_name: TokenStream,
     func_name: &'static str,
     op: fn(usize, usize) -> Option<usize>,
+    max_rhs: usize,
 }
 
 struct ImplUnaryOp {
@@ -53,7 +54,7 @@ fn impl_unary_op(params: ImplUnaryOp, input: TokenStream) -> syn::Result<TokenSt
 
 fn impl_bin_op(params: ImplBinOp, input: TokenStream) -> syn::Result<TokenStream> {
     let bits = get_usize_arg(input)?;
-    let impl_txts = (1..=128).filter_map(|arg| {
+    let impl_txts = (1..=params.max_rhs).filter_map(|arg| {
         if let Some(output) = (params.op)(bits, arg) {
             let target_type_num = tn_name(bits);
             let rhs_type_num = tn_name(arg);
@@ -88,6 +89,7 @@ pub fn impl_add_trait(input: TokenStream) -> syn::Result<TokenStream> {
             trait_name: quote!(std::ops::Add),
             func_name: "add",
             op: add,
+            max_rhs: 128,
         },
         input,
     )
@@ -103,6 +105,7 @@ pub fn impl_sub_trait(input: TokenStream) -> syn::Result<TokenStream> {
             trait_name: quote!(std::ops::Sub),
             func_name: "sub",
             op: sub,
+            max_rhs: 128,
         },
         input,
     )
@@ -122,6 +125,7 @@ pub fn impl_max_trait(input: TokenStream) -> syn::Result<TokenStream> {
             trait_name: quote!(crate::Max),
             func_name: "max",
             op: max,
+            max_rhs: 128,
         },
         input,
     )
@@ -141,6 +145,58 @@ pub fn impl_min_trait(input: TokenStream) -> syn::Result<TokenStream> {
             trait_name: quote!(crate::Min),
             func_name: "min",
             op: min,
+            max_rhs: 128,
+        },
+        input,
+    )
+}
+
+// The wide widths (129 to 1024 bits) do not get a complete table, as
+// that would mean hundreds of thousands of impls.  Instead, a wide
+// width can be combined with small widths (up to 8 bits, which covers
+// the carry bit of `xadd` and friends) and multiples of 32 bits (which
+// covers the usual bus and register widths).
+fn is_wide_operand(y: usize) -> bool {
+    y <= 8 || y.is_multiple_of(32)
+}
+
+// The additions that are not covered by `impl_add_trait`.  For a pair
+// of narrow widths (both at most 128 bits) these are the pairs where
+// the left hand side is not the larger one, and the pairs whose sum
+// crosses into the wide widths (so that `b128 + b1` gives a 129 bit
+// result).  A wide left hand side can be combined with any operand
+// that is no wider than itself and passes `is_wide_operand`.
+pub fn impl_wide_add_trait(input: TokenStream) -> syn::Result<TokenStream> {
+    fn add(x: usize, y: usize) -> Option<usize> {
+        if x <= 128 && y <= 128 {
+            ((x <= y) || (x + y > 128)).then(|| x + y)
+        } else {
+            ((x >= y) && (x + y <= 1024) && is_wide_operand(y)).then(|| x + y)
+        }
+    }
+
+    impl_bin_op(
+        ImplBinOp {
+            trait_name: quote!(std::ops::Add),
+            func_name: "add",
+            op: add,
+            max_rhs: 1024,
+        },
+        input,
+    )
+}
+
+pub fn impl_wide_sub_trait(input: TokenStream) -> syn::Result<TokenStream> {
+    fn sub(x: usize, y: usize) -> Option<usize> {
+        ((x > y) && is_wide_operand(y)).then(|| x - y)
+    }
+
+    impl_bin_op(
+        ImplBinOp {
+            trait_name: quote!(std::ops::Sub),
+            func_name: "sub",
+            op: sub,
+            max_rhs: 1024,
         },
         input,
     )
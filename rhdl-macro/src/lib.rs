@@ -17,8 +17,8 @@ pub fn timed(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_attribute]
-pub fn kernel(_attr: TokenStream, input: TokenStream) -> TokenStream {
-    match rhdl_macro_core::hdl_kernel(input.into()) {
+pub fn kernel(attr: TokenStream, input: TokenStream) -> TokenStream {
+    match rhdl_macro_core::hdl_kernel_with_args(attr.into(), input.into()) {
         Ok(output) => output.into(),
         Err(err) => err.to_compile_error().into(),
     }
@@ -104,6 +104,22 @@ pub fn min_impl(input: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro]
+pub fn wide_add_impl(input: TokenStream) -> TokenStream {
+    match rhdl_macro_core::impl_wide_add_trait(input.into()) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+#[proc_macro]
+pub fn wide_sub_impl(input: TokenStream) -> TokenStream {
+    match rhdl_macro_core::impl_wide_sub_trait(input.into()) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
 #[proc_macro]
 pub fn log2_impl(input: TokenStream) -> TokenStream {
     match rhdl_macro_core::impl_log2_trait(input.into()) {
@@ -16,6 +16,8 @@ pub enum TraceType {
     Enum(Enum),
     Bits(usize),
     Signed(usize),
+    UFixed(Fixed),
+    SFixed(Fixed),
     Signal(Box<TraceType>, Color),
     Clock,
     Reset,
@@ -28,6 +30,15 @@ pub struct Array {
     pub size: usize,
 }
 
+// The widths of the integer and fraction parts of a fixed point
+// value.  The value itself is traced as its raw bits, which are the
+// value scaled by 2^fraction.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug, Hash)]
+pub struct Fixed {
+    pub integer: usize,
+    pub fraction: usize,
+}
+
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, Hash)]
 pub struct Tuple {
     pub elements: Vec<TraceType>,
@@ -105,6 +116,14 @@ pub fn make_tuple(elements: Vec<TraceType>) -> TraceType {
     }
 }
 
+pub fn make_ufixed(integer: usize, fraction: usize) -> TraceType {
+    TraceType::UFixed(Fixed { integer, fraction })
+}
+
+pub fn make_sfixed(integer: usize, fraction: usize) -> TraceType {
+    TraceType::SFixed(Fixed { integer, fraction })
+}
+
 pub fn make_field(name: &str, ty: TraceType) -> Field {
     Field {
         name: name.to_string(),
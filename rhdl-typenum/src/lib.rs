@@ -1,6 +1,6 @@
 // Tell clippy to ignore this module
 #![allow(clippy::all)]
-use rhdl_macro::{add_impl, log2_impl, max_impl, min_impl, sub_impl};
+use rhdl_macro::{add_impl, log2_impl, max_impl, min_impl, sub_impl, wide_add_impl, wide_sub_impl};
 use seq_macro::seq;
 
 pub trait BitWidth: Copy + Clone + PartialEq + Eq + Default + 'static {
@@ -60,6 +60,7 @@ seq!(N in 1..=128 {
         }
 
         add_impl!(N);
+        wide_add_impl!(N);
         sub_impl!(N);
         max_impl!(N);
         min_impl!(N);
@@ -67,6 +68,26 @@ seq!(N in 1..=128 {
     )*
 });
 
+// The wide widths are used by the multi-limb bit types.  They only
+// get a partial table of relationships (see `wide_add_impl`), since
+// a full table would take far too long to compile.  The narrow widths
+// above also use `wide_add_impl` for the sums that `add_impl` leaves
+// out, including the ones that cross over into the wide widths.
+seq!(N in 129..=1024 {
+    #(
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+        pub struct W~N;
+
+        impl BitWidth for W~N {
+            const BITS: usize = N;
+        }
+
+        wide_add_impl!(N);
+        wide_sub_impl!(N);
+        log2_impl!(N);
+    )*
+});
+
 impl<W> Max<W> for W
 where
     W: BitWidth,
@@ -93,7 +114,7 @@ pub trait ToBitWidth {
 
 pub struct Const<const N: usize>;
 
-seq!(N in 1..=128 {
+seq!(N in 1..=1024 {
     #(
     impl ToBitWidth for Const<N> {
         type Output = W~N;
@@ -148,6 +169,24 @@ mod tests {
         assert_eq!(B::BITS, 4);
     }
 
+    #[test]
+    fn test_wide_widths() {
+        type A = Sum<W256, W1>;
+        assert_eq!(A::BITS, 257);
+        type B = Sum<W256, W256>;
+        assert_eq!(B::BITS, 512);
+        type C = Diff<W512, W384>;
+        assert_eq!(C::BITS, 128);
+        type D = Log2<W1024>;
+        assert_eq!(D::BITS, 10);
+        type E = WN<300>;
+        assert_eq!(E::BITS, 300);
+        type F = Sum<W128, W1>;
+        assert_eq!(F::BITS, 129);
+        type G = Sum<W64, W100>;
+        assert_eq!(G::BITS, 164);
+    }
+
     #[test]
     fn test_max_3_4() {
         type A = Maximum<W3, W4>;
@@ -1,9 +1,18 @@
 pub use rhdl_bits::alias::*;
 pub use rhdl_bits::bits;
 pub use rhdl_bits::signed;
+pub use rhdl_bits::wide_bits;
+pub use rhdl_bits::wide_signed;
 pub use rhdl_bits::Bits;
+pub use rhdl_bits::SFixed;
 pub use rhdl_bits::SignedBits;
+pub use rhdl_bits::UFixed;
+pub use rhdl_bits::WideBits;
+pub use rhdl_bits::WideSignedBits;
+pub use rhdl_core::bmc::bounded_model_check;
+pub use rhdl_core::bmc::equivalence::{flow_graph_equivalence, kernel_equivalence};
 pub use rhdl_core::circuit::adapter::Adapter;
+pub use rhdl_core::circuit::black_box::{AsyncBlackBox, BlackBox};
 pub use rhdl_core::circuit::circuit_descriptor::CircuitDescriptor;
 pub use rhdl_core::circuit::circuit_impl::Circuit;
 pub use rhdl_core::circuit::circuit_impl::CircuitIO;
@@ -15,12 +24,14 @@ pub use rhdl_core::circuit::synchronous::SynchronousIO;
 pub use rhdl_core::compile_design;
 pub use rhdl_core::compiler::driver::compile_design_stage1;
 pub use rhdl_core::const_max;
+pub use rhdl_core::coverage_init;
 pub use rhdl_core::error::RHDLError;
 pub use rhdl_core::flow_graph::build_rtl_flow_graph;
 pub use rhdl_core::flow_graph::component::Component;
 pub use rhdl_core::flow_graph::component::ComponentKind;
 pub use rhdl_core::flow_graph::dot::write_dot;
 pub use rhdl_core::flow_graph::flow_graph_impl::{FlowGraph, FlowIx};
+pub use rhdl_core::flow_graph::sim::FlowGraphSimulator;
 pub use rhdl_core::hdl::ast::{
     always, assign, bit_string, continuous_assignment, id, if_statement, initial, port,
 };
@@ -31,9 +42,16 @@ pub use rhdl_core::rtl::vm::execute;
 pub use rhdl_core::rtl::Object;
 pub use rhdl_core::sim::stream;
 pub use rhdl_core::sim::stream::stream;
+pub use rhdl_core::smt::{flow_graph::synchronous_smt, rtl::kernel_smt, rtl::rtl_smt};
 pub use rhdl_core::trace;
 pub use rhdl_core::trace::db::with_trace_db;
+pub use rhdl_core::trace::filter::TraceFilter;
+pub use rhdl_core::trace::reader::Waveform;
+pub use rhdl_core::trace::toggle::ToggleCoverage;
 pub use rhdl_core::trace_init_db;
+pub use rhdl_core::trace_init_db_with_filter;
+pub use rhdl_core::trace_init_streaming_db;
+pub use rhdl_core::trace_init_streaming_db_with_filter;
 pub use rhdl_core::trace_pop_path;
 pub use rhdl_core::trace_push_path;
 pub use rhdl_core::trace_time;
@@ -51,6 +69,7 @@ pub use rhdl_core::types::digital_fn::DigitalFn3;
 pub use rhdl_core::types::digital_fn::DigitalFn4;
 pub use rhdl_core::types::digital_fn::DigitalFn5;
 pub use rhdl_core::types::digital_fn::DigitalFn6;
+pub use rhdl_core::types::digital_fn::FixedKernel;
 pub use rhdl_core::types::digital_fn::NoKernel2;
 pub use rhdl_core::types::digital_fn::NoKernel3;
 pub use rhdl_core::types::domain::Domain;
@@ -101,6 +120,7 @@ pub use rhdl_core::sim::merge::MergeExt;
 pub use rhdl_core::sim::probe::ext::ProbeExt;
 pub use rhdl_core::sim::probe::ext::SynchronousProbeExt;
 pub use rhdl_core::sim::run::asynchronous::RunExt;
+pub use rhdl_core::sim::run::flow_graph::{RunFlowGraphExt, RunSynchronousFlowGraphExt};
 pub use rhdl_core::sim::run::sync_fn::RunSynchronousFeedbackExt;
 pub use rhdl_core::sim::run::synchronous::RunSynchronousExt;
 pub use rhdl_core::sim::stream::TimedStreamExt;
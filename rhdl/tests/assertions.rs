@@ -0,0 +1,113 @@
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl_core::sim::testbench::kernel::test_kernel_vm_and_verilog;
+
+#[test]
+fn test_assertions_that_hold() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        let b = a >> 1;
+        assert!(b <= a, "shifting right never increases the value");
+        cover!(a == b);
+        if a.any() {
+            assert!(b < a);
+        }
+        signal(b)
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+#[test]
+fn test_assertions_after_early_return() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        if !a.any() {
+            return signal(a);
+        }
+        // Not reached when `a` is zero
+        assert!(a.any(), "a is not zero here");
+        signal(a - 1)
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "a is never three")]
+fn test_failing_assertion_in_rust() {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        assert!(a != bits(3), "a is never three");
+        signal(a)
+    }
+    do_stuff(signal(bits(3)));
+}
+
+pub mod checker {
+    use super::*;
+
+    #[derive(Clone, Debug, Synchronous, Default)]
+    pub struct U {}
+
+    impl SynchronousIO for U {
+        type I = (b4, b4);
+        type O = b4;
+        type Kernel = checker;
+    }
+
+    impl SynchronousDQ for U {
+        type D = ();
+        type Q = ();
+    }
+
+    #[kernel]
+    pub fn checker(_cr: ClockReset, i: (b4, b4), _q: ()) -> (b4, ()) {
+        let (a, b) = i;
+        let c = a & b;
+        assert!(c <= a, "the and of two values is no larger than either");
+        cover!(c == a);
+        (c, ())
+    }
+}
+
+fn test_seq() -> impl Iterator<Item = TimedSample<(ClockReset, (b4, b4))>> {
+    exhaustive::<W4>()
+        .into_iter()
+        .flat_map(|a| exhaustive::<W4>().into_iter().map(move |b| (a, b)))
+        .stream_after_reset(1)
+        .clock_pos_edge(100)
+}
+
+#[test]
+fn test_assertions_in_system_verilog() -> miette::Result<()> {
+    let uut = checker::U::default();
+    let hdl = uut.hdl("top")?.as_module();
+    let sv = hdl.as_system_verilog();
+    assert!(sv.contains(
+        "assert property (@(posedge clock_reset[0]) disable iff (clock_reset[1]) assertions[0]) else $error(\"the and of two values is no larger than either\");"
+    ));
+    assert!(sv.contains(
+        "cover property (@(posedge clock_reset[0]) disable iff (clock_reset[1]) assertions[1]);"
+    ));
+    // Only the SystemVerilog backend emits the assertions
+    assert!(!hdl.as_verilog().contains("assert property"));
+    Ok(())
+}
+
+#[test]
+fn test_assertions_in_synchronous_sim() -> miette::Result<()> {
+    let uut = checker::U::default();
+    let test_bench = uut.run(test_seq())?.collect::<SynchronousTestBench<_, _>>();
+    let tm = test_bench.rtl(&uut, &TestBenchOptions::default())?;
+    tm.run_iverilog()?;
+    Ok(())
+}
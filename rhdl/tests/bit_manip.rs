@@ -0,0 +1,122 @@
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl_core::sim::testbench::kernel::{test_kernel_compiled, test_kernel_vm_and_verilog};
+
+#[test]
+fn test_bit_counts() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<(b8, b8, b8, b8), Red> {
+        let a = a.val();
+        signal((
+            a.count_ones(),
+            a.leading_zeros(),
+            a.trailing_zeros(),
+            a.reverse_bits(),
+        ))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+#[test]
+fn test_one_hot_conversions() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<(b3, b8, b16), Red> {
+        let a = a.val();
+        let ndx = a.one_hot_to_binary::<W3>();
+        let b: b4 = a.resize();
+        signal((
+            ndx,
+            ndx.binary_to_one_hot::<W8>(),
+            b.binary_to_one_hot::<W16>(),
+        ))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+#[test]
+fn test_priority_encoder() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<(bool, b3), Red> {
+        let a = a.val();
+        // Isolate the lowest set bit, and encode it
+        let lowest = a & (!a + 1);
+        signal((a.any(), lowest.one_hot_to_binary::<W3>()))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+pub mod counter {
+    use super::*;
+
+    #[derive(Clone, Debug, Synchronous, Default)]
+    pub struct U {}
+
+    impl SynchronousIO for U {
+        type I = (b8, b8);
+        type O = (b8, b8, b3);
+        type Kernel = counter;
+    }
+
+    impl SynchronousDQ for U {
+        type D = ();
+        type Q = ();
+    }
+
+    #[kernel]
+    pub fn counter(_cr: ClockReset, i: (b8, b8), _q: ()) -> ((b8, b8, b3), ()) {
+        let (a, b) = i;
+        (
+            (a.count_ones(), b.count_ones(), a.one_hot_to_binary::<W3>()),
+            (),
+        )
+    }
+}
+
+#[test]
+fn test_bit_manip_hdl_helpers() -> miette::Result<()> {
+    let uut = counter::U::default();
+    let hdl = uut.hdl("top")?;
+    let verilog = hdl.as_module().to_string();
+    // The helper function is defined once for each pair of widths
+    assert_eq!(
+        verilog
+            .matches("function [7:0] rhdl_count_ones_8_8(input [7:0] a);")
+            .count(),
+        1
+    );
+    assert!(verilog.contains("function [2:0] rhdl_one_hot_to_binary_8_3(input [7:0] a);"));
+    let vhdl = hdl.as_vhdl();
+    assert!(vhdl.contains("rhdl_one_hot_to_binary("));
+    Ok(())
+}
+
+type Counts = (b8, b8, b8, b8);
+
+#[test]
+fn test_compiled_bit_manip() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<(Counts, b3, b8), Red> {
+        let a = a.val();
+        let ndx = a.one_hot_to_binary::<W3>();
+        signal((
+            (
+                a.count_ones(),
+                a.leading_zeros(),
+                a.trailing_zeros(),
+                a.reverse_bits(),
+            ),
+            ndx,
+            ndx.binary_to_one_hot::<W8>(),
+        ))
+    }
+    test_kernel_compiled::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
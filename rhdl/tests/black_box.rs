@@ -0,0 +1,125 @@
+use rhdl::prelude::*;
+
+pub mod common;
+
+const ADDER_V: &str = "
+module adder_core(input wire clk, input wire [3:0] a, input wire [3:0] b, output wire [3:0] sum);
+   assign sum = a + b;
+endmodule
+";
+
+fn adder() -> BlackBox<(b4, b4), b4, ()> {
+    BlackBox::new("adder_core", ADDER_V, (), |_cr, i: (b4, b4), _s| i.0 + i.1)
+        .clock("clk")
+        .input("a", Path::default().tuple_index(0))
+        .input("b", Path::default().tuple_index(1))
+        .output("sum", Path::default())
+}
+
+fn test_seq() -> impl Iterator<Item = TimedSample<(ClockReset, (b4, b4))>> {
+    common::exhaustive::<W4>()
+        .into_iter()
+        .flat_map(|a| common::exhaustive::<W4>().into_iter().map(move |b| (a, b)))
+        .stream_after_reset(1)
+        .clock_pos_edge(100)
+}
+
+#[test]
+fn test_black_box_sim_uses_rust_model() -> miette::Result<()> {
+    let uut = adder();
+    let output = uut
+        .run(test_seq())?
+        .synchronous_sample()
+        .skip(1)
+        .all(|x| x.value.2 == x.value.1 .0 + x.value.1 .1);
+    assert!(output);
+    Ok(())
+}
+
+#[test]
+fn test_black_box_hdl_instantiates_core() -> miette::Result<()> {
+    let hdl = adder().hdl("top")?.as_module().to_string();
+    assert!(
+        hdl.contains("adder_core core (.clk(clock_reset[0]),.a(i[3:0]),.b(i[7:4]),.sum(o[3:0]));")
+    );
+    assert_eq!(hdl.matches("module adder_core").count(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_black_box_source_is_emitted_once() -> miette::Result<()> {
+    mod parent {
+        use super::*;
+
+        #[derive(Clone, Synchronous, SynchronousDQ)]
+        pub struct Parent {
+            left: BlackBox<(b4, b4), b4, ()>,
+            right: BlackBox<(b4, b4), b4, ()>,
+        }
+
+        impl Default for Parent {
+            fn default() -> Self {
+                Self {
+                    left: adder(),
+                    right: adder(),
+                }
+            }
+        }
+
+        impl SynchronousIO for Parent {
+            type I = (b4, b4);
+            type O = b4;
+            type Kernel = parent;
+        }
+
+        #[kernel]
+        pub fn parent(_cr: ClockReset, i: (b4, b4), q: Q) -> (b4, D) {
+            let mut d = D::dont_care();
+            d.left = i;
+            d.right = (q.left, i.1);
+            (q.right, d)
+        }
+    }
+
+    let uut = parent::Parent::default();
+    let hdl = uut.hdl("top")?.as_module().to_string();
+    assert_eq!(hdl.matches("module adder_core").count(), 1);
+    let fg = uut.flow_graph("top")?.hdl("dut")?.to_string();
+    assert_eq!(fg.matches("module adder_core").count(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_black_box_unbound_output_is_an_error() {
+    let uut: BlackBox<(b4, b4), b4, ()> =
+        BlackBox::new("adder_core", ADDER_V, (), |_cr, i: (b4, b4), _s| i.0 + i.1)
+            .input("a", Path::default().tuple_index(0))
+            .input("b", Path::default().tuple_index(1));
+    assert!(matches!(
+        uut.hdl("top"),
+        Err(RHDLError::OutputsNotCovered(_))
+    ));
+}
+
+#[test]
+fn test_black_box_unbound_input_is_an_error() {
+    let uut: BlackBox<(b4, b4), b4, ()> =
+        BlackBox::new("adder_core", ADDER_V, (), |_cr, i: (b4, b4), _s| i.0 + i.1)
+            .input("a", Path::default().tuple_index(0))
+            .output("sum", Path::default());
+    assert!(matches!(
+        uut.hdl("top"),
+        Err(RHDLError::InputsNotCovered(_))
+    ));
+}
+
+#[test]
+fn test_black_box_hdl_matches_rust_model() -> miette::Result<()> {
+    let uut = adder();
+    let test_bench = uut.run(test_seq())?.collect::<SynchronousTestBench<_, _>>();
+    let tm = test_bench.rtl(&uut, &TestBenchOptions::default())?;
+    tm.run_iverilog()?;
+    let tm = test_bench.flow_graph(&uut, &TestBenchOptions::default())?;
+    tm.run_iverilog()?;
+    Ok(())
+}
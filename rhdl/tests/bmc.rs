@@ -0,0 +1,65 @@
+use rhdl::prelude::*;
+
+pub mod arith {
+    use super::*;
+
+    #[derive(Clone, Debug, Synchronous, Default)]
+    pub struct U {}
+
+    impl SynchronousIO for U {
+        type I = (b4, b4);
+        type O = (b4, b4, b4, b4);
+        type Kernel = arith;
+    }
+
+    impl SynchronousDQ for U {
+        type D = ();
+        type Q = ();
+    }
+
+    #[kernel]
+    pub fn arith(_cr: ClockReset, i: (b4, b4), _q: ()) -> ((b4, b4, b4, b4), ()) {
+        let (a, b) = i;
+        ((a * b, a / b, a % b, a + b), ())
+    }
+}
+
+#[kernel]
+pub fn division_identity(i: (b4, b4), o: (b4, b4, b4, b4)) -> bool {
+    let (a, b) = i;
+    let (product, quotient, remainder, _sum) = o;
+    if b == bits(0) {
+        quotient == bits(15) && remainder == a
+    } else {
+        product == b * a && quotient * b + remainder == a && remainder < b
+    }
+}
+
+#[kernel]
+pub fn sum_does_not_wrap(i: (b4, b4), o: (b4, b4, b4, b4)) -> bool {
+    let (a, _b) = i;
+    let (_product, _quotient, _remainder, sum) = o;
+    sum >= a
+}
+
+#[test]
+fn test_bmc_proves_property() -> miette::Result<()> {
+    let uut = arith::U::default();
+    assert!(bounded_model_check::<_, division_identity>(&uut, 2)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_bmc_finds_counterexample() -> miette::Result<()> {
+    let uut = arith::U::default();
+    let counterexample = bounded_model_check::<_, sum_does_not_wrap>(&uut, 2)?.unwrap();
+    let last = counterexample
+        .iter()
+        .rev()
+        .find(|x| !x.value.0.clock.raw())
+        .unwrap();
+    let ((a, b), o) = (last.value.1, last.value.2);
+    assert!(a.raw() + b.raw() > 15);
+    assert!(!sum_does_not_wrap((a, b), o));
+    Ok(())
+}
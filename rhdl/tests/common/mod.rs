@@ -2,6 +2,8 @@
 use rhdl::prelude::*;
 use rhdl_core::digital_fn::BitWidth;
 
+pub mod smt;
+
 pub fn exhaustive<N: BitWidth>() -> Vec<Bits<N>> {
     (0..(1 << N::BITS)).map(bits).collect()
 }
@@ -0,0 +1,276 @@
+// A small evaluator for the SMT-LIB2 models exported by RHDL, so that
+// the tests do not need an external solver.  It only knows the subset
+// of SMT-LIB2 that the exporter produces, and words of up to 128 bits.
+use std::collections::HashMap;
+
+use rhdl::prelude::*;
+
+// The values of the declared functions in one step.  Missing values
+// (e.g., unknowns) are zero.
+pub type Step = HashMap<String, u128>;
+
+#[derive(Clone, Debug)]
+enum Sexp {
+    Atom(String),
+    List(Vec<Sexp>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Value {
+    Bool(bool),
+    Bv(u128, usize),
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ';' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '(' | ')' => tokens.push(c.to_string()),
+            '|' => {
+                let symbol = chars.by_ref().take_while(|c| *c != '|').collect();
+                tokens.push(symbol);
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut atom = c.to_string();
+                while let Some(c) = chars.peek() {
+                    if c.is_whitespace() || *c == '(' || *c == ')' {
+                        break;
+                    }
+                    atom.push(chars.next().unwrap());
+                }
+                tokens.push(atom);
+            }
+        }
+    }
+    tokens
+}
+
+fn parse(tokens: &mut std::iter::Peekable<std::vec::IntoIter<String>>) -> Sexp {
+    let token = tokens.next().unwrap();
+    if token != "(" {
+        return Sexp::Atom(token);
+    }
+    let mut list = vec![];
+    while tokens.peek().map(|t| t.as_str()) != Some(")") {
+        list.push(parse(tokens));
+    }
+    tokens.next();
+    Sexp::List(list)
+}
+
+fn atom(sexp: &Sexp) -> &str {
+    match sexp {
+        Sexp::Atom(atom) => atom,
+        Sexp::List(_) => panic!("expected an atom, got {sexp:?}"),
+    }
+}
+
+fn mask(width: usize) -> u128 {
+    if width >= 128 {
+        u128::MAX
+    } else {
+        (1 << width) - 1
+    }
+}
+
+fn signed(value: u128, width: usize) -> i128 {
+    let shift = 128 - width;
+    ((value << shift) as i128) >> shift
+}
+
+fn bv(value: u128, width: usize) -> Value {
+    Value::Bv(value & mask(width), width)
+}
+
+fn bits(value: Value) -> (u128, usize) {
+    match value {
+        Value::Bv(value, width) => (value, width),
+        Value::Bool(_) => panic!("expected a bit vector"),
+    }
+}
+
+fn boolean(value: Value) -> bool {
+    match value {
+        Value::Bool(value) => value,
+        Value::Bv(..) => panic!("expected a Bool"),
+    }
+}
+
+// The bits of a value, LSB first, as a number.
+pub fn word(bits: &[BitX]) -> u128 {
+    bits.iter()
+        .rev()
+        .fold(0, |acc, bit| (acc << 1) | (*bit == BitX::One) as u128)
+}
+
+// The values of the definitions evaluated so far, by the steps they
+// were evaluated for.
+type Cache = HashMap<(String, Vec<*const Step>), Value>;
+
+pub struct Model {
+    declared: HashMap<String, usize>,
+    defined: HashMap<String, (Vec<String>, Sexp)>,
+}
+
+impl Model {
+    pub fn parse(text: &str) -> Self {
+        let mut tokens = tokenize(text).into_iter().peekable();
+        let mut model = Model {
+            declared: HashMap::new(),
+            defined: HashMap::new(),
+        };
+        while tokens.peek().is_some() {
+            let Sexp::List(command) = parse(&mut tokens) else {
+                panic!("expected a command");
+            };
+            match atom(&command[0]) {
+                "declare-sort" => {}
+                "declare-fun" => {
+                    let Sexp::List(sort) = &command[3] else {
+                        panic!("expected a bit vector sort");
+                    };
+                    let width = atom(&sort[2]).parse().unwrap();
+                    model.declared.insert(atom(&command[1]).into(), width);
+                }
+                "define-fun" => {
+                    let Sexp::List(params) = &command[2] else {
+                        panic!("expected parameters");
+                    };
+                    let params = params
+                        .iter()
+                        .map(|param| match param {
+                            Sexp::List(param) => atom(&param[0]).to_string(),
+                            _ => panic!("expected a parameter"),
+                        })
+                        .collect();
+                    model
+                        .defined
+                        .insert(atom(&command[1]).into(), (params, command[4].clone()));
+                }
+                other => panic!("unexpected command {other}"),
+            }
+        }
+        model
+    }
+    pub fn has(&self, name: &str) -> bool {
+        self.declared.contains_key(name) || self.defined.contains_key(name)
+    }
+    // Evaluate a defined bit vector for the given steps.
+    pub fn eval(&self, name: &str, steps: &[&Step]) -> u128 {
+        bits(self.call(name, steps, &mut Cache::new())).0
+    }
+    // Evaluate a defined predicate for the given steps.
+    pub fn holds(&self, name: &str, steps: &[&Step]) -> bool {
+        boolean(self.call(name, steps, &mut Cache::new()))
+    }
+    fn call(&self, name: &str, steps: &[&Step], cache: &mut Cache) -> Value {
+        if let Some(width) = self.declared.get(name) {
+            return bv(steps[0].get(name).copied().unwrap_or_default(), *width);
+        }
+        let key = (
+            name.to_string(),
+            steps.iter().map(|step| *step as *const Step).collect(),
+        );
+        if let Some(value) = cache.get(&key) {
+            return *value;
+        }
+        let (params, body) = &self.defined[name];
+        let env = params
+            .iter()
+            .cloned()
+            .zip(steps.iter().copied())
+            .collect::<HashMap<_, _>>();
+        let value = self.term(body, &env, cache);
+        cache.insert(key, value);
+        value
+    }
+    fn term(&self, sexp: &Sexp, env: &HashMap<String, &Step>, cache: &mut Cache) -> Value {
+        let list = match sexp {
+            Sexp::Atom(atom) if atom == "true" => return Value::Bool(true),
+            Sexp::Atom(atom) if atom == "false" => return Value::Bool(false),
+            Sexp::Atom(atom) => {
+                let digits = atom.strip_prefix("#b").expect("expected a literal");
+                return bv(u128::from_str_radix(digits, 2).unwrap(), digits.len());
+            }
+            Sexp::List(list) => list,
+        };
+        let args = &list[1..];
+        if let Sexp::List(indexed) = &list[0] {
+            let (value, width) = bits(self.term(&args[0], env, cache));
+            let index = |ndx: usize| atom(&indexed[ndx]).parse::<usize>().unwrap();
+            return match atom(&indexed[1]) {
+                "extract" => bv(value >> index(3), index(2) - index(3) + 1),
+                "zero_extend" => bv(value, width + index(2)),
+                "sign_extend" => bv(signed(value, width) as u128, width + index(2)),
+                other => panic!("unexpected indexed operator {other}"),
+            };
+        }
+        let head = atom(&list[0]);
+        if self.has(head) {
+            let steps = args.iter().map(|arg| env[atom(arg)]).collect::<Vec<_>>();
+            return self.call(head, &steps, cache);
+        }
+        let values = args
+            .iter()
+            .map(|arg| self.term(arg, env, cache))
+            .collect::<Vec<_>>();
+        match head {
+            "ite" => {
+                if boolean(values[0]) {
+                    values[1]
+                } else {
+                    values[2]
+                }
+            }
+            "=" => Value::Bool(values[0] == values[1]),
+            "distinct" => Value::Bool(values[0] != values[1]),
+            "not" => Value::Bool(!boolean(values[0])),
+            "and" => Value::Bool(values.iter().all(|v| boolean(*v))),
+            "bvnot" => {
+                let (a, w) = bits(values[0]);
+                bv(!a, w)
+            }
+            "bvneg" => {
+                let (a, w) = bits(values[0]);
+                bv(a.wrapping_neg(), w)
+            }
+            _ => {
+                let (a, w) = bits(values[0]);
+                let (b, v) = bits(values[1]);
+                let (sa, sb) = (signed(a, w), signed(b, v));
+                match head {
+                    "concat" => bv((a << v) | b, w + v),
+                    "bvadd" => bv(a.wrapping_add(b), w),
+                    "bvsub" => bv(a.wrapping_sub(b), w),
+                    "bvmul" => bv(a.wrapping_mul(b), w),
+                    "bvand" => bv(a & b, w),
+                    "bvor" => bv(a | b, w),
+                    "bvxor" => bv(a ^ b, w),
+                    "bvudiv" => bv(a.checked_div(b).unwrap_or(u128::MAX), w),
+                    "bvurem" => bv(a.checked_rem(b).unwrap_or(a), w),
+                    "bvsdiv" if b == 0 => bv(if sa < 0 { 1 } else { u128::MAX }, w),
+                    "bvsdiv" => bv(sa.wrapping_div(sb) as u128, w),
+                    "bvsrem" if b == 0 => bv(a, w),
+                    "bvsrem" => bv(sa.wrapping_rem(sb) as u128, w),
+                    "bvshl" if b >= w as u128 => bv(0, w),
+                    "bvshl" => bv(a << b, w),
+                    "bvlshr" if b >= w as u128 => bv(0, w),
+                    "bvlshr" => bv(a >> b, w),
+                    "bvashr" => bv((sa >> b.min(w as u128 - 1)) as u128, w),
+                    "bvult" => Value::Bool(a < b),
+                    "bvslt" => Value::Bool(sa < sb),
+                    other => panic!("unexpected operator {other}"),
+                }
+            }
+        }
+    }
+}
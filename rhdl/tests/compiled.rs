@@ -0,0 +1,172 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+use rhdl::prelude::*;
+use rhdl_core::{
+    compiler::driver::{compile_design_stage1, compile_design_stage2},
+    rtl::codegen::generate_rust,
+    sim::testbench::kernel::test_kernel_compiled,
+};
+
+mod common;
+use common::*;
+
+type Arithmetic<T> = ((T, T, T), (bool, bool));
+
+#[test]
+fn test_compiled_unsigned_arithmetic() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<Arithmetic<b8>, Red> {
+        let a = a.val();
+        let b = b.val();
+        let shift = b.resize::<W3>();
+        signal((
+            (a + b, a * b - a, (a << shift) ^ (b >> shift)),
+            (a > b, a.any()),
+        ))
+    }
+    test_kernel_compiled::<foo, _, _, _>(foo, tuple_pair_b8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_compiled_signed_arithmetic() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: Signal<s8, Red>, b: Signal<s8, Red>) -> Signal<Arithmetic<s8>, Red> {
+        let a = a.val();
+        let b = b.val();
+        let shift = b.as_unsigned().resize::<W3>();
+        signal(((a - b, !a, a >> shift), (a <= b, a >= b)))
+    }
+    test_kernel_compiled::<foo, _, _, _>(foo, tuple_pair_s8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_compiled_casts() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: Signal<s8, Red>) -> Signal<(s16, b16, s4, b4), Red> {
+        let a = a.val();
+        signal((
+            a.resize(),
+            a.as_unsigned().resize(),
+            a.resize(),
+            a.as_unsigned().resize(),
+        ))
+    }
+    test_kernel_compiled::<foo, _, _, _>(foo, s8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_compiled_match() -> miette::Result<()> {
+    const ONE: s8 = s8(1);
+    const TWO: s8 = s8(2);
+
+    #[kernel]
+    fn foo(a: Signal<s8, Red>, b: Signal<s8, Red>) -> Signal<s8, Red> {
+        match a.val() {
+            ONE => b,
+            TWO => a,
+            _ => signal(s8(3)),
+        }
+    }
+    test_kernel_compiled::<foo, _, _, _>(foo, tuple_pair_s8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_compiled_enum() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital, Default)]
+    enum Op {
+        #[default]
+        Nop,
+        Add(b4),
+        Mask {
+            mask: b4,
+            invert: bool,
+        },
+    }
+
+    #[kernel]
+    fn foo(op: Signal<Op, Red>, a: Signal<b4, Red>) -> Signal<b4, Red> {
+        let a = a.val();
+        signal(match op.val() {
+            Op::Nop => a,
+            Op::Add(x) => a + x,
+            Op::Mask { mask, invert } => {
+                if invert {
+                    !(a & mask)
+                } else {
+                    a & mask
+                }
+            }
+        })
+    }
+    let ops = exhaustive::<W4>()
+        .into_iter()
+        .flat_map(|x| {
+            [
+                Op::Nop,
+                Op::Add(x),
+                Op::Mask {
+                    mask: x,
+                    invert: true,
+                },
+            ]
+        })
+        .chain([Op::Mask {
+            mask: b4(5),
+            invert: false,
+        }])
+        .collect::<Vec<_>>();
+    let inputs = ops.into_iter().flat_map(|op| {
+        exhaustive::<W4>()
+            .into_iter()
+            .map(move |a| (red(op), red(a)))
+    });
+    test_kernel_compiled::<foo, _, _, _>(foo, inputs)?;
+    Ok(())
+}
+
+#[test]
+fn test_compiled_dynamic_indexing() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: Signal<[b4; 4], Red>, ndx: Signal<b2, Red>) -> Signal<([b4; 4], b4), Red> {
+        let mut a = a.val();
+        let ndx = ndx.val();
+        let b = a[ndx];
+        a[ndx] = b + 1;
+        a[0] = a[3];
+        signal((a, b))
+    }
+    let inputs = exhaustive::<W8>().into_iter().flat_map(|x| {
+        exhaustive::<W2>().into_iter().map(move |ndx| {
+            let x = x.resize::<W16>();
+            let a = [
+                (x & 0xF).resize(),
+                ((x >> 4) & 0xF).resize(),
+                ((x >> 2) & 0xF).resize(),
+                ((x >> 5) & 0xF).resize(),
+            ];
+            (red(a), red(ndx))
+        })
+    });
+    test_kernel_compiled::<foo, _, _, _>(foo, inputs)?;
+    Ok(())
+}
+
+#[test]
+fn test_compiled_rejects_wide_registers() -> miette::Result<()> {
+    #[kernel]
+    fn foo(a: Signal<(b128, b128), Red>) -> Signal<b128, Red> {
+        let (x, y) = a.val();
+        signal(x ^ y)
+    }
+    let design = compile_design_stage1::<foo>(CompilationMode::Asynchronous)?;
+    let rtl = compile_design_stage2(&design)?;
+    assert!(matches!(
+        generate_rust(&rtl),
+        Err(RHDLError::RustCodegenError(_))
+    ));
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use rhdl::prelude::*;
+
+#[derive(PartialEq, Debug, Digital, Default)]
+pub enum State {
+    #[default]
+    Idle,
+    Run(b4),
+    Done,
+}
+
+#[kernel(coverage)]
+fn step(state: State, go: bool) -> State {
+    if !go {
+        return state;
+    }
+    match state {
+        State::Idle => State::Run(bits(0)),
+        State::Run(n) => {
+            if n == bits(15) {
+                State::Done
+            } else {
+                State::Run(n + 1)
+            }
+        }
+        State::Done => State::Idle,
+    }
+}
+
+fn uncovered(coverage: &rhdl_core::Coverage) -> Vec<String> {
+    let kernel = coverage.kernel("step").unwrap();
+    assert_eq!(kernel.branches.len(), 8);
+    kernel
+        .uncovered()
+        .map(|branch| format!("{} {}", branch.kind, kernel.source.text(branch.node)))
+        .collect()
+}
+
+#[test]
+fn test_coverage_of_rust_kernel() {
+    let guard = coverage_init();
+    step(State::Idle, true);
+    step(State::Run(bits(3)), true);
+    step(State::Idle, false);
+    let coverage = guard.take();
+    assert_eq!(
+        uncovered(&coverage),
+        [
+            "match arm State::Done => State::Idle,",
+            "then branch { State::Done }"
+        ]
+    );
+    let report = coverage.report();
+    assert!(report.contains("step"));
+    assert!(report.contains("6 of 8 branches taken"));
+    // Without a coverage session, nothing is recorded
+    step(State::Done, true);
+    assert!(coverage_init().take().kernels.is_empty());
+}
+
+#[test]
+fn test_coverage_of_vm_kernel() -> miette::Result<()> {
+    let guard = coverage_init();
+    let design = compile_design_stage1::<step>(CompilationMode::Asynchronous)?;
+    for (state, go) in [(State::Done, true), (State::Run(bits(15)), true)] {
+        rhdl_core::rhif::vm::execute(&design, vec![state.typed_bits(), go.typed_bits()])?;
+    }
+    let coverage = guard.take();
+    assert_eq!(
+        uncovered(&coverage),
+        [
+            "then branch {\n        return state;\n    }",
+            "return return state",
+            "match arm State::Idle => State::Run(bits(0)),",
+            "else branch { State::Run(n + 1) }"
+        ]
+    );
+    Ok(())
+}
@@ -189,3 +189,61 @@ fn test_derive_enum_alignment_lsb() {
     assert_eq!(range, 0..2);
     assert_eq!(kind, Kind::make_bits(2));
 }
+
+#[test]
+fn test_derive_from_bin_round_trip() {
+    #[derive(PartialEq, Debug, Default, Digital)]
+    struct Pair(b4, s4);
+
+    #[derive(PartialEq, Debug, Default, Digital)]
+    struct Point {
+        x: b8,
+        y: Pair,
+        z: [bool; 3],
+    }
+
+    #[derive(PartialEq, Debug, Default, Digital)]
+    #[rhdl(discriminant_align = "lsb")]
+    enum Lsb {
+        #[default]
+        A,
+        B(b4),
+        C {
+            a: s4,
+            b: bool,
+        },
+    }
+
+    #[derive(PartialEq, Debug, Default, Digital)]
+    #[repr(i8)]
+    enum Signed {
+        Neg = -2,
+        #[default]
+        Zero = 0,
+        Pos(Option<b3>, Lsb) = 1,
+    }
+
+    fn round_trip<T: Digital + std::fmt::Debug>(value: T) {
+        assert_eq!(T::from_bin(&value.bin()), Some(value));
+    }
+
+    round_trip(Point {
+        x: b8(0xA5),
+        y: Pair(b4(9), s4(-3)),
+        z: [true, false, true],
+    });
+    round_trip(Lsb::A);
+    round_trip(Lsb::B(b4(12)));
+    round_trip(Lsb::C { a: s4(-8), b: true });
+    round_trip(Signed::Neg);
+    round_trip(Signed::Zero);
+    round_trip(Signed::Pos(Some(b3(5)), Lsb::C { a: s4(7), b: false }));
+    round_trip(Signed::Pos(None, Lsb::B(b4(1))));
+    // Unknown bits and undefined discriminants do not decode
+    assert_eq!(b8::from_bin(&[BitX::X; 8]), None);
+    assert_eq!(Signed::from_bin(&Signed::Zero.bin()[1..]), None);
+    let mut undefined = Signed::Zero.bin();
+    let len = undefined.len();
+    undefined[len - 2..].copy_from_slice(&[BitX::One, BitX::One]);
+    assert_eq!(Signed::from_bin(&undefined), None);
+}
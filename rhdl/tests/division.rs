@@ -0,0 +1,150 @@
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl_core::sim::testbench::kernel::{test_kernel_compiled, test_kernel_vm_and_verilog};
+
+#[test]
+fn test_unsigned_division() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<(b8, b8), Red> {
+        let a = a.val();
+        let b = b.val();
+        signal((a / b, a % b))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_pair_b8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_signed_division() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>, b: Signal<s8, Red>) -> Signal<(s8, s8), Red> {
+        let a = a.val();
+        let b = b.val();
+        signal((a / b, a % b))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_pair_s8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_unsigned_division_by_constants() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<[b8; 10], Red> {
+        let a = a.val();
+        signal([
+            a / 1,
+            a / 2,
+            a / 3,
+            a / 7,
+            a / 10,
+            a / 16,
+            a / 255,
+            a % 10,
+            a % 32,
+            a % 3,
+        ])
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+#[test]
+// `a % -1` is always zero, but it exercises the lowering of a remainder
+// by a constant divisor.
+#[allow(clippy::modulo_one)]
+fn test_signed_division_by_constants() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>) -> Signal<[s8; 12], Red> {
+        let a = a.val();
+        signal([
+            a / 1,
+            a / -1,
+            a / 2,
+            a / -4,
+            a / 3,
+            a / -7,
+            a / 10,
+            a / s8(-128),
+            a % 3,
+            a % -8,
+            a % -1,
+            a % 10,
+        ])
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, s8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_division_by_constant_through_call() -> miette::Result<()> {
+    #[kernel]
+    fn scale(a: b12, b: b12) -> b12 {
+        (a * b12(10)) / b
+    }
+
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<b12, Red> {
+        signal(scale(a.val().resize(), bits(15)))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+#[test]
+fn test_division_assign() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<b8, Red> {
+        let mut a = a.val();
+        let b = b.val();
+        a /= 3;
+        a %= b;
+        signal(a)
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_pair_b8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_compiled_division() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>, b: Signal<s8, Red>) -> Signal<(s8, s8, b8, s8), Red> {
+        let a = a.val();
+        let b = b.val();
+        signal((a / b, a % b, a.as_unsigned() / b.as_unsigned(), a / 5))
+    }
+    test_kernel_compiled::<do_stuff, _, _, _>(do_stuff, tuple_pair_s8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_wide_division_by_constant_is_accepted() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b64, Red>) -> Signal<(b64, b64), Red> {
+        let a = a.val();
+        signal((a / 1000, a % 1024))
+    }
+    let args = [0, 1, 999, 1000, 123_456_789, u64::MAX as u128]
+        .into_iter()
+        .map(|x| (red(b64(x)),));
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_wide_combinational_divider_is_rejected() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b64, Red>, b: Signal<b64, Red>) -> Signal<b64, Red> {
+        signal(a.val() / b.val())
+    }
+    let Err(RHDLError::RHDLSyntaxError(_)) =
+        compile_design::<do_stuff>(CompilationMode::Asynchronous)
+    else {
+        panic!("Expected syntax error");
+    };
+    Ok(())
+}
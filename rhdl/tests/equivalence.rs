@@ -0,0 +1,75 @@
+use rhdl::{core::flow_graph::optimization::optimize_flow_graph, prelude::*};
+
+#[kernel]
+fn double_by_add(a: b8) -> b8 {
+    a + a
+}
+
+#[kernel]
+fn double_by_shift(a: b8) -> b8 {
+    a << 1
+}
+
+#[kernel]
+fn factored(a: b8, b: b8, c: b8) -> b8 {
+    a & (b | c)
+}
+
+#[kernel]
+fn distributed(a: b8, b: b8, c: b8) -> b8 {
+    (a & b) | (a & c)
+}
+
+#[kernel]
+fn sum(a: b4, b: b4) -> b4 {
+    a + b
+}
+
+#[kernel]
+fn bitwise_or(a: b4, b: b4) -> b4 {
+    a | b
+}
+
+#[kernel]
+fn narrow_sum(a: b4, b: b4) -> b4 {
+    a + b
+}
+
+#[kernel]
+fn wide_sum(a: b8, b: b8) -> b8 {
+    a + b
+}
+
+#[test]
+fn test_equivalent_kernels() -> miette::Result<()> {
+    assert!(kernel_equivalence::<double_by_add, double_by_shift>()?.is_none());
+    assert!(kernel_equivalence::<factored, distributed>()?.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_distinguishing_input() -> miette::Result<()> {
+    let inputs = kernel_equivalence::<sum, bitwise_or>()?.unwrap();
+    assert_eq!(inputs.len(), 2);
+    assert!(inputs.iter().all(|x| x.kind == b4::static_kind()));
+    let a = b4::from_bin(&inputs[0].bits).unwrap();
+    let b = b4::from_bin(&inputs[1].bits).unwrap();
+    assert_ne!(sum(a, b), bitwise_or(a, b));
+    Ok(())
+}
+
+#[test]
+fn test_signatures_must_match() {
+    assert!(kernel_equivalence::<narrow_sum, wide_sum>().is_err());
+}
+
+#[test]
+fn test_flow_graph_optimization_is_equivalent() -> miette::Result<()> {
+    let rtl = compile_design::<distributed>(CompilationMode::Synchronous)?;
+    let flow_graph = build_rtl_flow_graph(&rtl);
+    let optimized = optimize_flow_graph(flow_graph.clone())?;
+    assert!(flow_graph_equivalence(&flow_graph, &optimized)?.is_none());
+    let other = build_rtl_flow_graph(&compile_design::<factored>(CompilationMode::Synchronous)?);
+    assert!(flow_graph_equivalence(&optimized, &other)?.is_none());
+    Ok(())
+}
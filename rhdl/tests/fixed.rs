@@ -0,0 +1,141 @@
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl_core::sim::testbench::kernel::{test_kernel_compiled, test_kernel_vm_and_verilog};
+
+type UQ4_4 = UFixed<W4, W4>;
+type SQ4_4 = SFixed<W4, W4>;
+type UnsignedOut = ((UQ4_4, UQ4_4), UFixed<W5, W4>, UFixed<W8, W8>, bool);
+type SignedOut = (
+    (SQ4_4, SFixed<W5, W4>),
+    SFixed<W8, W8>,
+    SFixed<W5, W4>,
+    bool,
+);
+type ScaledOut = (
+    (SFixed<W4, W1>, SFixed<W4, W3>),
+    SFixed<W4, W1>,
+    SFixed<W6, W6>,
+    SFixed<W2, W4>,
+);
+type CompiledOut = (SFixed<W5, W4>, SFixed<W8, W6>);
+
+fn unsigned_pairs() -> impl Iterator<Item = (Signal<UQ4_4, Red>, Signal<UQ4_4, Red>)> + Clone {
+    tuple_pair_b8_red().map(|(a, b)| (red(UQ4_4::from_raw(a.val())), red(UQ4_4::from_raw(b.val()))))
+}
+
+fn signed_pairs() -> impl Iterator<Item = (Signal<SQ4_4, Red>, Signal<SQ4_4, Red>)> + Clone {
+    tuple_pair_s8_red().map(|(a, b)| (red(SQ4_4::from_raw(a.val())), red(SQ4_4::from_raw(b.val()))))
+}
+
+#[test]
+fn test_unsigned_fixed_arithmetic() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<UQ4_4, Red>, b: Signal<UQ4_4, Red>) -> Signal<UnsignedOut, Red> {
+        let a = a.val();
+        let b = b.val();
+        signal(((a + b, a - b), a.xadd(b), a.xmul(b), a < b))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, unsigned_pairs())?;
+    Ok(())
+}
+
+#[test]
+fn test_signed_fixed_arithmetic() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<SQ4_4, Red>, b: Signal<SQ4_4, Red>) -> Signal<SignedOut, Red> {
+        let a = a.val();
+        let b = b.val();
+        signal(((-a + b, a.xsub(b)), a.xmul(b), b.xneg(), a >= b))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, signed_pairs())?;
+    Ok(())
+}
+
+#[test]
+fn test_fixed_scaling() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<SQ4_4, Red>) -> Signal<ScaledOut, Red> {
+        let a = a.val();
+        let b: SFixed<W2, W4> = a.resize_fixed();
+        signal((
+            (a.xround::<W3>(), a.xround::<W1>()),
+            a.xtrunc::<W3>(),
+            a.xext::<W2>().xfrac::<W2>(),
+            b,
+        ))
+    }
+    let args = s8_red().map(|(a,)| (red(SQ4_4::from_raw(a.val())),));
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_fixed_raw_and_constants() -> miette::Result<()> {
+    const HALF: UQ4_4 = UQ4_4::from_raw(b8(0x08));
+
+    #[kernel]
+    fn scale(x: b8) -> UFixed<W4, W6> {
+        let x = UFixed::<W4, W4>::from_raw(x);
+        let y = x.xadd(HALF).xround::<W2>();
+        UFixed::<W4, W6>::from_raw(y.raw().xshl::<W5>().resize())
+    }
+
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<(UFixed<W4, W6>, b8), Red> {
+        let a = a.val();
+        let x = UFixed::<W4, W4>::from_raw(a);
+        signal((scale(a), x.raw()))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+#[test]
+fn test_fixed_matches_float() -> miette::Result<()> {
+    // Check the kernel against floating point arithmetic, rather
+    // than against the fixed point types.
+    #[kernel]
+    fn do_stuff(a: Signal<SQ4_4, Red>, b: Signal<SQ4_4, Red>) -> Signal<SFixed<W9, W4>, Red> {
+        let a = a.val();
+        let b = b.val();
+        signal(a.xmul(b).xround::<W4>().resize_fixed())
+    }
+    for (a, b) in signed_pairs().step_by(97) {
+        let expected = (a.val().to_f64() * b.val().to_f64() * 16.0 + 0.5).floor() / 16.0;
+        assert_eq!(do_stuff(a, b).val().to_f64(), expected);
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, signed_pairs())?;
+    Ok(())
+}
+
+#[test]
+fn test_compiled_fixed() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<SQ4_4, Red>, b: Signal<SQ4_4, Red>) -> Signal<CompiledOut, Red> {
+        let a = a.val();
+        let b = b.val();
+        signal((a.xadd(b), a.xmul(b).xround::<W2>()))
+    }
+    test_kernel_compiled::<do_stuff, _, _, _>(do_stuff, signed_pairs())?;
+    Ok(())
+}
+
+#[test]
+fn test_fixed_trace_type() {
+    let guard = trace_init_db();
+    trace_time(0);
+    trace("x", &SQ4_4::from_f64(-1.25));
+    trace_time(1_000);
+    trace("x", &SQ4_4::from_f64(2.5));
+    let mut vcd = vec![];
+    guard.take().dump_vcd(&mut vcd, None).unwrap();
+    let vcd = String::from_utf8(vcd).unwrap();
+    assert!(vcd.contains("SFixed((integer:4,fraction:4))"));
+    assert!(vcd.contains("b11101100"));
+    assert!(vcd.contains("b00101000"));
+}
@@ -132,6 +132,7 @@ where
     I: Iterator<Item = TimedSample<(ClockReset, T::I)>>,
 {
     let test_bench = uut.run(inputs)?.collect::<SynchronousTestBench<_, _>>();
+    test_bench.run_flow_graph(uut, &TestBenchOptions::default())?;
     let tm_rtl = test_bench.rtl(uut, &TestBenchOptions::default())?;
     tm_rtl.run_iverilog()?;
     let tm_fg = test_bench.flow_graph(uut, &TestBenchOptions::default())?;
@@ -145,6 +146,7 @@ where
     I: Iterator<Item = TimedSample<T::I>>,
 {
     let test_bench = uut.run(inputs)?.collect::<TestBench<_, _>>();
+    test_bench.run_flow_graph(uut, &TestBenchOptions::default())?;
     let tm_rtl = test_bench.rtl(uut, &TestBenchOptions::default())?;
     tm_rtl.run_iverilog()?;
     let tm_fg = test_bench.flow_graph(uut, &TestBenchOptions::default())?;
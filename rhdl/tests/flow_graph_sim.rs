@@ -0,0 +1,283 @@
+use common::{
+    exhaustive,
+    smt::{Model, Step},
+};
+use rhdl::{
+    core::{
+        hdl::ast::{index_bit, Declaration},
+        types::bit_string::BitString,
+    },
+    prelude::*,
+};
+
+pub mod common;
+
+// A positive edge triggered flip flop, with a model so that the flow graph
+// simulator can clock it.
+pub mod dff {
+    use super::*;
+
+    #[derive(PartialEq, Debug, Clone, Default)]
+    pub struct U<T: Digital> {
+        reset: T,
+    }
+
+    impl<T: Digital> SynchronousIO for U<T> {
+        type I = T;
+        type O = T;
+        type Kernel = NoKernel3<ClockReset, T, (), (T, ())>;
+    }
+
+    impl<T: Digital> SynchronousDQ for U<T> {
+        type D = ();
+        type Q = ();
+    }
+
+    #[derive(PartialEq, Debug, Digital)]
+    pub struct S<T: Digital> {
+        cr: ClockReset,
+        reset: Reset,
+        current: T,
+        next: T,
+    }
+
+    impl<T: Digital> Synchronous for U<T> {
+        type S = S<T>;
+
+        fn init(&self) -> Self::S {
+            Self::S::dont_care()
+        }
+
+        fn sim(&self, clock_reset: ClockReset, input: Self::I, state: &mut Self::S) -> Self::O {
+            if !clock_reset.clock.raw() {
+                state.next = input;
+                state.reset = clock_reset.reset;
+            }
+            if clock_reset.clock.raw() && !state.cr.clock.raw() {
+                state.current = if state.reset.raw() {
+                    self.reset
+                } else {
+                    state.next
+                };
+            }
+            state.cr = clock_reset;
+            state.current
+        }
+
+        fn description(&self) -> String {
+            "Test DFF".into()
+        }
+
+        fn hdl(&self, name: &str) -> Result<HDLDescriptor, RHDLError> {
+            let init: BitString = self.reset.typed_bits().into();
+            let width = unsigned_width(T::bits());
+            let module = Module {
+                name: name.into(),
+                ports: vec![
+                    port(
+                        "clock_reset",
+                        Direction::Input,
+                        HDLKind::Wire,
+                        unsigned_width(2),
+                    ),
+                    port("i", Direction::Input, HDLKind::Wire, width),
+                    port("o", Direction::Output, HDLKind::Reg, width),
+                ],
+                declarations: vec![Declaration {
+                    kind: HDLKind::Wire,
+                    name: "clock".into(),
+                    width: unsigned_width(1),
+                    alias: None,
+                }],
+                statements: vec![
+                    initial(vec![assign("o", bit_string(&init))]),
+                    continuous_assignment("clock", index_bit("clock_reset", 0)),
+                    always(
+                        vec![Events::Posedge("clock".into())],
+                        vec![if_statement(
+                            index_bit("clock_reset", 1),
+                            vec![non_blocking_assignment("o", bit_string(&init))],
+                            vec![non_blocking_assignment("o", id("i"))],
+                        )],
+                    ),
+                ],
+                ..Default::default()
+            };
+            Ok(HDLDescriptor {
+                name: name.into(),
+                body: module,
+                children: Default::default(),
+            })
+        }
+
+        fn descriptor(&self, name: &str) -> Result<CircuitDescriptor, RHDLError> {
+            let mut flow_graph = FlowGraph::default();
+            let (clock_reset, d, q) =
+                flow_graph.dff_black_box::<Self>(self.hdl(name)?, self.reset.typed_bits().into());
+            flow_graph.inputs = vec![clock_reset, d];
+            flow_graph.output = q;
+            Ok(CircuitDescriptor {
+                unique_name: name.to_string(),
+                input_kind: T::static_kind(),
+                output_kind: T::static_kind(),
+                d_kind: Kind::Empty,
+                q_kind: Kind::Empty,
+                children: Default::default(),
+                flow_graph,
+                rtl: None,
+            })
+        }
+    }
+}
+
+pub mod counter {
+    use super::*;
+
+    #[derive(Clone, Debug, Synchronous, SynchronousDQ, Default)]
+    pub struct U {
+        count: dff::U<b4>,
+    }
+
+    impl SynchronousIO for U {
+        type I = bool;
+        type O = b4;
+        type Kernel = counter;
+    }
+
+    #[kernel]
+    pub fn counter(_cr: ClockReset, enable: bool, q: Q) -> (b4, D) {
+        let mut d = D::dont_care();
+        d.count = q.count + if enable { bits(1) } else { bits(0) };
+        (q.count, d)
+    }
+}
+
+pub mod masker {
+    use super::*;
+
+    #[derive(Clone, Debug, Synchronous, Default)]
+    pub struct U {}
+
+    impl SynchronousIO for U {
+        type I = (b4, b4);
+        type O = (b4, bool);
+        type Kernel = masker;
+    }
+
+    impl SynchronousDQ for U {
+        type D = ();
+        type Q = ();
+    }
+
+    #[kernel]
+    pub fn masker(_cr: ClockReset, i: (b4, b4), _q: ()) -> ((b4, bool), ()) {
+        let (a, b) = i;
+        ((a & b, a > b), ())
+    }
+}
+
+fn enable_seq() -> impl Iterator<Item = TimedSample<(ClockReset, bool)>> {
+    [true, true, false, true, false, false, true, true, true]
+        .into_iter()
+        .cycle()
+        .take(50)
+        .stream_after_reset(2)
+        .clock_pos_edge(100)
+}
+
+#[test]
+fn test_flow_graph_sim_matches_combinational_kernel() -> miette::Result<()> {
+    let uut = masker::U::default();
+    let inputs = exhaustive::<W4>()
+        .into_iter()
+        .flat_map(|x| exhaustive::<W4>().into_iter().map(move |y| (x, y)))
+        .stream_after_reset(1)
+        .clock_pos_edge(100);
+    let test_bench = uut.run(inputs)?.collect::<SynchronousTestBench<_, _>>();
+    test_bench.run_flow_graph(&uut, &TestBenchOptions::default())?;
+    Ok(())
+}
+
+#[test]
+fn test_flow_graph_sim_clocks_dff_models() -> miette::Result<()> {
+    let uut = counter::U::default();
+    let test_bench = uut
+        .run(enable_seq())?
+        .collect::<SynchronousTestBench<_, _>>();
+    test_bench.run_flow_graph(&uut, &TestBenchOptions::default().skip(2))?;
+    Ok(())
+}
+
+#[test]
+fn test_flow_graph_sim_iterator_matches_run() -> miette::Result<()> {
+    let uut = counter::U::default();
+    let expected = uut
+        .run(enable_seq())?
+        .skip(2)
+        .map(|x| x.value.2.typed_bits());
+    let actual = uut
+        .run_flow_graph(enable_seq())?
+        .skip(2)
+        .map(|x| x.map(|(_, o)| o))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(actual.into_iter().eq(expected));
+    Ok(())
+}
+
+#[test]
+fn test_flow_graph_smt_transition_counts() -> miette::Result<()> {
+    let uut = counter::U::default();
+    let model = Model::parse(&synchronous_smt(&uut, "top")?);
+    let mut state = Step::from([("top.q0".to_string(), 0)]);
+    assert!(model.holds("top_init", &[&state]));
+    let mut count = 0;
+    for enable in [true, true, false, true, false, true] {
+        state.insert("top.i".into(), enable as u128);
+        assert_eq!(model.eval("top.o", &[&state]), count);
+        let next = Step::from([("top.q0".to_string(), model.eval("top.d0", &[&state]))]);
+        assert!(model.holds("top_trans", &[&state, &next]));
+        count += enable as u128;
+        state = next;
+    }
+    // Holding the counter in reset takes it back to its reset value
+    state.insert("top.clock_reset".into(), 0b10);
+    let next = Step::from([("top.q0".to_string(), 0)]);
+    assert!(model.holds("top_trans", &[&state, &next]));
+    assert!(!model.holds("top_init", &[&state]));
+    Ok(())
+}
+
+#[test]
+fn test_flow_graph_sim_propagates_x() -> miette::Result<()> {
+    let uut = masker::U::default();
+    let mut sim = uut.flow_graph("top")?.simulator()?;
+    let clock_reset = vec![BitX::Zero, BitX::Zero];
+    // a = 0bXX01, b = 0b0X11
+    let a = vec![BitX::One, BitX::Zero, BitX::X, BitX::X];
+    let b = vec![BitX::One, BitX::One, BitX::X, BitX::Zero];
+    let o = sim.step(&[clock_reset, [a, b].concat()])?;
+    // The mask only knows the bits where either side is known to be 0,
+    // or both are 1.  The comparison depends on the unknown bits.
+    assert_eq!(o, vec![BitX::One, BitX::Zero, BitX::X, BitX::Zero, BitX::X]);
+    Ok(())
+}
+
+#[test]
+fn test_flow_graph_sim_opaque_black_box_is_unknown() -> miette::Result<()> {
+    const ADDER_V: &str = "
+module adder_core(input wire [3:0] a, input wire [3:0] b, output wire [3:0] sum);
+   assign sum = a + b;
+endmodule
+";
+    let uut: BlackBox<(b4, b4), b4, ()> =
+        BlackBox::new("adder_core", ADDER_V, (), |_cr, i: (b4, b4), _s| i.0 + i.1)
+            .input("a", Path::default().tuple_index(0))
+            .input("b", Path::default().tuple_index(1))
+            .output("sum", Path::default());
+    let inputs = [(b4(1), b4(2))].into_iter().stream().clock_pos_edge(100);
+    for sample in uut.run_flow_graph(inputs)? {
+        let (_, o) = sample?;
+        assert!(o.bits.iter().all(|x| *x == BitX::X));
+    }
+    Ok(())
+}
@@ -0,0 +1,193 @@
+#![allow(dead_code)]
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl_core::sim::testbench::kernel::test_kernel_vm_and_verilog_synchronous;
+
+#[kernel]
+pub fn parity(x: b4) -> bool {
+    x.xor()
+}
+
+#[derive(PartialEq, Debug, Digital, Default)]
+pub struct Packet {
+    header: b4,
+    payload: [b4; 2],
+}
+
+impl Packet {
+    #[kernel]
+    pub fn checksum(self) -> b4 {
+        self.header ^ self.payload[0] ^ self.payload[1]
+    }
+
+    #[kernel]
+    pub fn is_valid(self, expected: b4) -> bool {
+        self.checksum() == expected
+    }
+
+    #[kernel]
+    pub fn with_header(mut self, header: b4) -> Packet {
+        self.header = header;
+        self
+    }
+
+    #[kernel]
+    pub fn header_parity(self) -> bool {
+        self::parity(self.header)
+    }
+}
+
+fn packets() -> impl Iterator<Item = (Packet, b4)> + Clone {
+    exhaustive::<W8>().into_iter().flat_map(|x| {
+        exhaustive::<W4>().into_iter().map(move |y| {
+            let packet = Packet {
+                header: y,
+                payload: [x.resize(), (x >> 4).resize()],
+            };
+            (packet, y)
+        })
+    })
+}
+
+#[test]
+fn test_method_call_on_argument() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(pkt: Packet, b: b4) -> (b4, bool) {
+        (pkt.checksum(), pkt.is_valid(b))
+    }
+    test_kernel_vm_and_verilog_synchronous::<do_stuff, _, _, _>(do_stuff, packets())?;
+    Ok(())
+}
+
+#[test]
+fn test_method_call_on_annotated_binding() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(pkt: Packet, b: b4) -> b4 {
+        let p: Packet = pkt.with_header(b);
+        p.checksum()
+    }
+    test_kernel_vm_and_verilog_synchronous::<do_stuff, _, _, _>(do_stuff, packets())?;
+    Ok(())
+}
+
+#[test]
+fn test_method_call_on_unannotated_binding() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(pkt: Packet, b: b4) -> b4 {
+        let p = pkt.with_header(b);
+        <Packet>::checksum(p)
+    }
+    test_kernel_vm_and_verilog_synchronous::<do_stuff, _, _, _>(do_stuff, packets())?;
+    Ok(())
+}
+
+#[test]
+fn test_method_call_on_field() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital, Default)]
+    pub struct Framed {
+        packet: Packet,
+        tag: b4,
+    }
+
+    #[kernel]
+    fn do_stuff(pkt: Packet, b: b4) -> bool {
+        let framed = Framed {
+            packet: pkt,
+            tag: b,
+        };
+        let packet: Packet = framed.packet;
+        packet.is_valid(framed.tag)
+    }
+    test_kernel_vm_and_verilog_synchronous::<do_stuff, _, _, _>(do_stuff, packets())?;
+    Ok(())
+}
+
+#[test]
+fn test_method_call_on_call_result() -> miette::Result<()> {
+    #[kernel]
+    fn reframe(pkt: Packet, b: b4) -> Packet {
+        Packet {
+            header: b,
+            payload: pkt.payload,
+        }
+    }
+
+    #[kernel]
+    fn do_stuff(pkt: Packet, b: b4) -> b4 {
+        let p: Packet = reframe(pkt, b);
+        <Packet>::checksum(p.with_header(b))
+    }
+    test_kernel_vm_and_verilog_synchronous::<do_stuff, _, _, _>(do_stuff, packets())?;
+    Ok(())
+}
+
+#[test]
+fn test_method_call_with_path_syntax() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(pkt: Packet, b: b4) -> bool {
+        let p = <Packet>::with_header(pkt, b);
+        <Packet>::is_valid(p, b)
+    }
+    test_kernel_vm_and_verilog_synchronous::<do_stuff, _, _, _>(do_stuff, packets())?;
+    Ok(())
+}
+
+#[test]
+fn test_method_calling_a_kernel_through_self_path() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(pkt: Packet, b: b4) -> (bool, bool) {
+        let p: Packet = pkt.with_header(b);
+        (pkt.header_parity(), p.header_parity())
+    }
+    test_kernel_vm_and_verilog_synchronous::<do_stuff, _, _, _>(do_stuff, packets())?;
+    Ok(())
+}
+
+#[test]
+fn test_method_call_on_generic_struct() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital, Default)]
+    pub struct Counter<T: Digital + Default> {
+        count: b4,
+        tag: T,
+    }
+
+    impl<T: Digital + Default> Counter<T> {
+        #[kernel]
+        pub fn next_state(self, enable: bool) -> Counter<T> {
+            let mut next = self;
+            if enable {
+                next.count = self.count + 1;
+            }
+            next
+        }
+    }
+
+    #[kernel]
+    fn do_stuff(state: Counter<bool>, enable: bool) -> b4 {
+        let state: Counter<bool> = state.next_state(enable);
+        state.next_state(true).count
+    }
+    let args = exhaustive::<W4>().into_iter().flat_map(|count| {
+        [false, true]
+            .into_iter()
+            .map(move |enable| (Counter { count, tag: enable }, enable))
+    });
+    test_kernel_vm_and_verilog_synchronous::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_methods_can_be_called_from_rust() {
+    let packet = Packet {
+        header: b4(1),
+        payload: [b4(2), b4(4)],
+    };
+    assert_eq!(packet.checksum(), b4(7));
+    assert!(packet.is_valid(b4(7)));
+    assert_eq!(packet.with_header(b4(0)).checksum(), b4(6));
+}
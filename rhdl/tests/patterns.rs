@@ -0,0 +1,160 @@
+#![allow(dead_code)]
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl_core::sim::testbench::kernel::test_kernel_vm_and_verilog;
+
+#[test]
+fn test_match_range_patterns() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<b4, Red> {
+        let a = a.val();
+        signal(match a.raw() {
+            0..=7 => b4(1),
+            8..16 => b4(2),
+            16..=127 => b4(3),
+            200.. => b4(4),
+            _ => b4(5),
+        })
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+#[test]
+fn test_match_or_patterns() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        signal(match a.raw() {
+            0 | 3 | 7 => b8(1),
+            8 | 12 | 200 => a + 1,
+            _ => a,
+        })
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+#[test]
+fn test_match_or_patterns_of_consts() -> miette::Result<()> {
+    const LO: b8 = b8(3);
+    const MID: b8 = b8(64);
+    const HI: b8 = b8(250);
+
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<b2, Red> {
+        signal(match a.val() {
+            LO | HI => b2(1),
+            MID => b2(2),
+            _ => b2(0),
+        })
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+#[test]
+fn test_match_signed_ranges() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>) -> Signal<b2, Red> {
+        signal(match a.val().raw() {
+            -10..=10 => b2(0),
+            11.. => b2(1),
+            ..-100 => b2(2),
+            _ => b2(3),
+        })
+    }
+    let args = exhaustive_signed::<W8>().into_iter().map(|x| (red(x),));
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_match_guards() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        let b = b.val();
+        signal(match a.raw() {
+            0..=15 if b > a => b,
+            x if x > 200 => b8(200),
+            16 | 42 if b == 0 => b8(17),
+            _ => a,
+        })
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_pair_b8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_match_guards_on_enums() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital, Default)]
+    pub enum Op {
+        #[default]
+        Nop,
+        Add(b4),
+        Sub(b4),
+        Clear,
+    }
+
+    #[kernel]
+    fn do_stuff(op: Signal<Op, Red>, a: Signal<b4, Red>) -> Signal<b4, Red> {
+        let a = a.val();
+        let mut count = b4(0);
+        let result = match op.val() {
+            Op::Add(x) if x == 0 => a,
+            Op::Add(x) => {
+                count = b4(1);
+                a + x
+            }
+            Op::Sub(x) if x > a => {
+                count = b4(2);
+                b4(0)
+            }
+            Op::Sub(x) => a - x,
+            Op::Nop | Op::Clear => b4(0),
+        };
+        signal(result ^ count)
+    }
+    let ops = exhaustive::<W4>()
+        .into_iter()
+        .flat_map(|x| [Op::Add(x), Op::Sub(x)])
+        .chain([Op::Nop, Op::Clear])
+        .collect::<Vec<_>>();
+    let args = ops.into_iter().flat_map(|op| {
+        exhaustive::<W4>()
+            .into_iter()
+            .map(move |a| (red(op), red(a)))
+    });
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+// Checks the kernel against the RHIF VM only, so that the patterns
+// are covered even when iverilog is not installed.
+#[test]
+fn test_match_patterns_in_vm() -> miette::Result<()> {
+    #[kernel]
+    fn classify(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<b2, Red> {
+        let a = a.val();
+        let b = b.val();
+        signal(match a.raw() {
+            0..=7 => b2(0),
+            8 | 12 | 200 => b2(1),
+            x if x > 200 && b != 0 => b2(2),
+            _ => b2(3),
+        })
+    }
+    let design = compile_design_stage1::<classify>(CompilationMode::Asynchronous)?;
+    for (a, b) in tuple_pair_b8_red() {
+        let expected = classify(a, b).typed_bits();
+        let actual = rhdl_core::rhif::vm::execute(&design, vec![a.typed_bits(), b.typed_bits()])?;
+        assert_eq!(expected.bits, actual.bits);
+    }
+    Ok(())
+}
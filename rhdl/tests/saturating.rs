@@ -0,0 +1,138 @@
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl_core::sim::testbench::kernel::{test_kernel_compiled, test_kernel_vm_and_verilog};
+
+#[test]
+fn test_saturating_unsigned() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<(b8, b8), Red> {
+        let a = a.val();
+        let b = b.val();
+        signal((a.saturating_add(b), a.saturating_sub(b)))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_pair_b8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_saturating_signed() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>, b: Signal<s8, Red>) -> Signal<(s8, s8), Red> {
+        let a = a.val();
+        let b = b.val();
+        signal((a.saturating_add(b), a.saturating_sub(b)))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_pair_s8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_saturating_with_literals() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<(b8, b8), Red> {
+        let a = a.val();
+        signal((a.saturating_add(b8(100)), a.saturating_sub(b8(3))))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_b8())?;
+    Ok(())
+}
+
+#[test]
+fn test_overflowing_unsigned() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<(b8, bool, b8, bool), Red> {
+        let a = a.val();
+        let b = b.val();
+        let (sum, carry) = a.overflowing_add(b);
+        let (diff, borrow) = a.overflowing_sub(b);
+        signal((sum, carry, diff, borrow))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_pair_b8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_overflowing_signed() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>, b: Signal<s8, Red>) -> Signal<(s8, bool, s8, bool), Red> {
+        let a = a.val();
+        let b = b.val();
+        let (sum, carry) = a.overflowing_add(b);
+        let (diff, borrow) = a.overflowing_sub(b);
+        signal((sum, carry, diff, borrow))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_pair_s8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_checked() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>, b: Signal<s8, Red>) -> Signal<(Option<s8>, Option<b8>), Red> {
+        let a = a.val();
+        let b = b.val();
+        signal((
+            a.checked_add(b),
+            a.as_unsigned().checked_sub(b.as_unsigned()),
+        ))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_pair_s8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_checked_with_try() -> miette::Result<()> {
+    #[kernel]
+    fn accumulate(a: b8, b: b8, c: b8) -> Option<b8> {
+        let x = a.checked_add(b)?;
+        x.checked_add(c)
+    }
+
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<Option<b8>, Red> {
+        signal(accumulate(a.val(), b.val(), b8(17)))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, tuple_pair_b8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_saturate_narrowing() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>) -> Signal<(s4, b4, s12, b6), Red> {
+        let a = a.val();
+        let u = a.as_unsigned();
+        signal((
+            a.saturate::<W4>(),
+            u.saturate::<W4>(),
+            a.saturate::<W12>(),
+            (u >> 1).saturate::<W6>(),
+        ))
+    }
+    let args = exhaustive_signed::<W8>().into_iter().map(|x| (red(x),));
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_compiled_saturating() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>, b: Signal<s8, Red>) -> Signal<(s8, b8, s4, bool), Red> {
+        let a = a.val();
+        let b = b.val();
+        let (_, overflow) = a.overflowing_sub(b);
+        signal((
+            a.saturating_sub(b),
+            a.as_unsigned().saturating_sub(b.as_unsigned()),
+            a.saturate::<W4>(),
+            overflow,
+        ))
+    }
+    test_kernel_compiled::<do_stuff, _, _, _>(do_stuff, tuple_pair_s8_red())?;
+    Ok(())
+}
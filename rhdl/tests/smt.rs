@@ -0,0 +1,121 @@
+use common::smt::{word, Model, Step};
+use rhdl::prelude::*;
+
+pub mod common;
+
+#[derive(PartialEq, Debug, Digital)]
+pub struct Pair {
+    a: b4,
+    b: s4,
+}
+
+#[derive(PartialEq, Debug, Digital, Default)]
+pub enum Op {
+    #[default]
+    Add,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[kernel]
+fn alu(p: Pair, op: Op) -> (b4, s4, bool) {
+    let a = p.a;
+    let b = p.b;
+    match op {
+        Op::Add => (a + a.reverse_bits(), b + b, b < signed(0)),
+        Op::Mul => (a * a.count_ones(), b * b, a > bits(7)),
+        Op::Div => (a / a.leading_zeros(), b / (b >> 1), a == bits(3)),
+        Op::Rem => (a % a.trailing_zeros(), b % (b >> 2), a.any()),
+    }
+}
+
+#[kernel]
+fn shuffle(a: [b4; 4], n: b2, s: b4) -> ([b4; 4], b4, b4) {
+    let mut x = a;
+    x[n] = s;
+    (
+        x,
+        a[n],
+        s.one_hot_to_binary::<W2>().resize() ^ n.binary_to_one_hot::<W4>(),
+    )
+}
+
+fn pairs() -> impl Iterator<Item = Pair> {
+    (0..16).flat_map(|a| {
+        (-8..8).map(move |b| Pair {
+            a: bits(a),
+            b: signed(b),
+        })
+    })
+}
+
+fn arguments(name: &str, args: &[Vec<BitX>]) -> Step {
+    args.iter()
+        .enumerate()
+        .map(|(ndx, arg)| (format!("{name}{ndx}"), word(arg)))
+        .collect()
+}
+
+#[test]
+fn test_kernel_smt_matches_kernel() -> miette::Result<()> {
+    let model = Model::parse(&kernel_smt::<alu>()?);
+    for p in pairs() {
+        for op in [Op::Add, Op::Mul, Op::Div, Op::Rem] {
+            let step = arguments("alu.arg", &[p.bin(), op.bin()]);
+            assert_eq!(model.eval("alu.out", &[&step]), word(&alu(p, op).bin()));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_kernel_smt_names_type_leaves() -> miette::Result<()> {
+    let model = Model::parse(&kernel_smt::<alu>()?);
+    let p = Pair {
+        a: bits(5),
+        b: signed(-3),
+    };
+    let step = arguments("alu.arg", &[p.bin(), Op::Mul.bin()]);
+    assert_eq!(model.eval("alu.arg0.a", &[&step]), 5);
+    assert_eq!(model.eval("alu.arg0.b", &[&step]), 0b1101);
+    assert_eq!(model.eval("alu.out.0", &[&step]), 10);
+    assert_eq!(model.eval("alu.out.1", &[&step]), 9);
+    assert!(model.has("alu.arg1#"));
+    Ok(())
+}
+
+#[test]
+fn test_kernel_smt_dynamic_indexing() -> miette::Result<()> {
+    let model = Model::parse(&kernel_smt::<shuffle>()?);
+    let mut seed = 1_u32;
+    for _ in 0..1000 {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        let a = [0, 4, 8, 12].map(|shift| bits(((seed >> (shift + 8)) & 0xF) as u128));
+        let n = bits((seed & 3) as u128);
+        let s = bits(((seed >> 2) & 0xF) as u128);
+        let step = arguments("shuffle.arg", &[a.bin(), n.bin(), s.bin()]);
+        assert_eq!(
+            model.eval("shuffle.out", &[&step]),
+            word(&shuffle(a, n, s).bin())
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_flow_graph_smt_matches_kernel() -> miette::Result<()> {
+    let rtl = compile_design::<alu>(CompilationMode::Synchronous)?;
+    let model = Model::parse(&rtl_smt(&rtl)?);
+    let flow_graph = Model::parse(&build_rtl_flow_graph(&rtl).smt("fg")?);
+    for p in pairs() {
+        for op in [Op::Add, Op::Mul, Op::Div, Op::Rem] {
+            let expected = word(&alu(p, op).bin());
+            let step = arguments("alu.arg", &[p.bin(), op.bin()]);
+            assert_eq!(model.eval("alu.out", &[&step]), expected);
+            let step = arguments("fg.in", &[p.bin(), op.bin()]);
+            assert_eq!(flow_graph.eval("fg.out", &[&step]), expected);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,79 @@
+#![allow(dead_code)]
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl_core::sim::testbench::kernel::test_kernel_vm_and_verilog_synchronous;
+
+#[kernel]
+pub trait Reducer {
+    fn combine(a: b4, b: b4) -> b4;
+}
+
+pub struct XorReducer;
+
+#[kernel]
+impl Reducer for XorReducer {
+    fn combine(a: b4, b: b4) -> b4 {
+        a ^ b
+    }
+}
+
+pub struct MaxReducer;
+
+#[kernel]
+impl Reducer for MaxReducer {
+    fn combine(a: b4, b: b4) -> b4 {
+        if a > b {
+            a
+        } else {
+            b
+        }
+    }
+}
+
+#[kernel]
+#[allow(clippy::needless_range_loop)]
+fn fold<T: Reducer>(x: [b4; 4]) -> b4 {
+    let mut acc = x[0];
+    for i in 1..4 {
+        acc = T::combine(acc, x[i]);
+    }
+    acc
+}
+
+fn arrays() -> impl Iterator<Item = ([b4; 4],)> + Clone {
+    exhaustive::<W8>().into_iter().flat_map(|x| {
+        exhaustive::<W8>()
+            .into_iter()
+            .step_by(7)
+            .map(move |y| ([x.resize(), (x >> 4).resize(), y.resize(), (y >> 4).resize()],))
+    })
+}
+
+#[test]
+fn test_trait_call_through_generic_bound() -> miette::Result<()> {
+    test_kernel_vm_and_verilog_synchronous::<fold<XorReducer>, _, _, _>(
+        fold::<XorReducer>,
+        arrays(),
+    )?;
+    test_kernel_vm_and_verilog_synchronous::<fold<MaxReducer>, _, _, _>(
+        fold::<MaxReducer>,
+        arrays(),
+    )?;
+    Ok(())
+}
+
+#[test]
+fn test_trait_implementations_are_distinct_kernels() -> miette::Result<()> {
+    let xor = compile_design::<fold<XorReducer>>(CompilationMode::Synchronous)?;
+    let max = compile_design::<fold<MaxReducer>>(CompilationMode::Synchronous)?;
+    let x = [b4(1), b4(2), b4(4), b4(8)];
+    assert_eq!(fold::<XorReducer>(x), b4(15));
+    assert_eq!(fold::<MaxReducer>(x), b4(8));
+    assert_ne!(xor.hash_value(), max.hash_value());
+    Ok(())
+}
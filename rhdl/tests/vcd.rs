@@ -43,6 +43,46 @@ fn test_vcd_enum() {
     guard.take().dump_vcd(&mut vcd_file, None).unwrap();
 }
 
+#[test]
+fn test_fst_counter() {
+    let guard = trace_init_db();
+    for i in 0..100_000 {
+        trace_time(i * 1_000);
+        trace("clock", &(i % 2 == 0));
+        trace("count", &b16((i / 2) as u128 % 65_536));
+    }
+    let db = guard.take();
+    let mut vcd = vec![];
+    db.dump_vcd(&mut vcd, None).unwrap();
+    let mut fst = std::io::Cursor::new(vec![]);
+    db.dump_fst(&mut fst, None).unwrap();
+    let fst = fst.into_inner();
+    assert!(fst.len() * 4 < vcd.len());
+}
+
+#[test]
+fn test_streaming_dump_to_file() {
+    fn samples() -> impl Iterator<Item = TimedSample<b8>> {
+        (0..10_000).map(|i| {
+            let value = b8(i as u128 % 256);
+            trace_time(i * 1_000);
+            trace("count", &value);
+            timed_sample(i * 1_000, value)
+        })
+    }
+    for file in ["test_streaming.vcd", "test_streaming.fst"] {
+        let path = std::path::Path::new(file);
+        let hash = samples().collect::<Vcd>().dump_to_file(path).unwrap();
+        let collected = std::fs::read(path).unwrap();
+        let streamed_hash = Vcd::streaming(samples())
+            .unwrap()
+            .dump_to_file(path)
+            .unwrap();
+        assert_eq!(hash, streamed_hash);
+        assert_eq!(std::fs::read(path).unwrap(), collected);
+    }
+}
+
 #[test]
 fn test_vcd_basic() {
     #[derive(PartialEq, Digital)]
@@ -71,3 +111,127 @@ fn test_vcd_basic() {
     let mut vcd_file = std::fs::File::create("test.vcd").unwrap();
     guard.take().dump_vcd(&mut vcd_file, None).unwrap();
 }
+
+#[test]
+fn test_read_back_typed_values() {
+    #[derive(PartialEq, Debug, Digital, Default)]
+    enum Enum {
+        #[default]
+        None,
+        A(b8, b16),
+        B {
+            name: b8,
+        },
+        C(bool),
+    }
+
+    #[derive(PartialEq, Debug, Digital, Default)]
+    struct Packet {
+        tag: b4,
+        payload: Enum,
+        valid: bool,
+    }
+
+    let values = [
+        Packet::default(),
+        Packet {
+            tag: bits(3),
+            payload: Enum::A(bits(42), bits(1024)),
+            valid: true,
+        },
+        Packet {
+            tag: bits(5),
+            payload: Enum::B { name: bits(67) },
+            valid: false,
+        },
+        Packet {
+            tag: bits(9),
+            payload: Enum::C(true),
+            valid: true,
+        },
+    ];
+    let guard = trace_init_db();
+    for (ndx, value) in values.iter().enumerate() {
+        trace_time(ndx as u64 * 1_000);
+        trace_push_path("dut");
+        trace("packet", value);
+        trace_pop_path();
+    }
+    let db = guard.take();
+    let mut vcd = vec![];
+    db.dump_vcd(&mut vcd, None).unwrap();
+    let mut fst = std::io::Cursor::new(vec![]);
+    db.dump_fst(&mut fst, None).unwrap();
+    fst.set_position(0);
+    for waveform in [
+        Waveform::read_vcd(vcd.as_slice()).unwrap(),
+        Waveform::read_fst(fst.clone()).unwrap(),
+        Waveform::from_trace_db(&db).unwrap(),
+    ] {
+        let read = waveform.values::<Packet>("top.dut.packet").unwrap();
+        let expected = values
+            .iter()
+            .enumerate()
+            .map(|(ndx, value)| (ndx as u64 * 1_000, Some(*value)))
+            .collect::<Vec<_>>();
+        assert_eq!(read, expected);
+        let typed = waveform.typed_bits("top.dut.packet").unwrap();
+        assert_eq!(typed[2].1, values[2].typed_bits());
+    }
+}
+
+mod adder {
+    use super::*;
+
+    #[derive(Clone, Debug, Synchronous, Default)]
+    pub struct U {}
+
+    impl SynchronousIO for U {
+        type I = b4;
+        type O = b4;
+        type Kernel = adder;
+    }
+
+    impl SynchronousDQ for U {
+        type D = ();
+        type Q = ();
+    }
+
+    #[kernel]
+    pub fn adder(_cr: ClockReset, i: b4, _q: ()) -> (b4, ()) {
+        (i + 1, ())
+    }
+}
+
+#[test]
+fn test_compare_verilog_waveform_with_trace() -> miette::Result<()> {
+    let uut = adder::U::default();
+    let inputs = (0..16).map(b4).stream_after_reset(1).clock_pos_edge(100);
+    let guard = trace_init_db();
+    let test_bench = uut.run(inputs)?.collect::<SynchronousTestBench<_, _>>();
+    let db = guard.take();
+    let tm = test_bench.rtl(&uut, &TestBenchOptions::default())?;
+    assert!(tm.to_string().contains("$dumpfile(\"testbench.vcd\");"));
+    // Stand in for the waveform dumped by iverilog, in which the output
+    // follows the Rust simulation one time unit later, except for one
+    // sample, which is one that is checked by the test bench.
+    let samples = &test_bench.samples;
+    let bad = (samples.len() / 2..)
+        .find(|ndx| samples[ndx + 1].time - samples[*ndx].time > 1)
+        .unwrap();
+    let mut vcd = "$timescale 1ps $end\n$scope module testbench $end\n$var wire 4 ! o [3:0] $end\n$upscope $end\n$enddefinitions $end\n".to_string();
+    for (ndx, sample) in samples.iter().enumerate() {
+        let o = sample.value.2.raw() ^ if ndx == bad { 0b1000 } else { 0 };
+        vcd += &format!("#{}\nb{o:04b} !\n", sample.time + 1);
+    }
+    let waveform = Waveform::read_vcd(vcd.as_bytes()).unwrap();
+    let divergence = tm
+        .compare_trace(&waveform, &db, "top.outputs")?
+        .expect("The waveforms should diverge");
+    let expected = samples[bad].value.2;
+    assert_eq!(divergence.time, samples[bad].time + 1);
+    assert_eq!(divergence.path, "o");
+    assert_eq!(divergence.expected, expected.typed_bits());
+    assert_eq!(divergence.actual, (expected ^ b4(0b1000)).typed_bits());
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use rhdl::prelude::*;
+
+pub mod common;
+use common::*;
+use rhdl_core::sim::testbench::kernel::test_kernel_vhdl;
+
+pub mod alu {
+    use super::*;
+
+    #[derive(PartialEq, Debug, Digital, Default)]
+    pub enum Op {
+        #[default]
+        Add,
+        Sub,
+        Shl,
+        Less,
+    }
+
+    #[derive(Clone, Debug, Synchronous, Default)]
+    pub struct U {}
+
+    impl SynchronousIO for U {
+        type I = (Op, b4, b4);
+        type O = b4;
+        type Kernel = alu;
+    }
+
+    impl SynchronousDQ for U {
+        type D = ();
+        type Q = ();
+    }
+
+    #[kernel]
+    pub fn alu(_cr: ClockReset, i: (Op, b4, b4), _q: ()) -> (b4, ()) {
+        let (op, a, b) = i;
+        let o = match op {
+            Op::Add => a + b,
+            Op::Sub => a - b,
+            Op::Shl => a << b,
+            Op::Less => {
+                if a < b {
+                    bits(1)
+                } else {
+                    bits(0)
+                }
+            }
+        };
+        (o, ())
+    }
+}
+
+pub mod parent {
+    use super::*;
+
+    #[derive(Clone, Debug, Synchronous, SynchronousDQ, Default)]
+    pub struct U {
+        alu: alu::U,
+    }
+
+    impl SynchronousIO for U {
+        type I = (b4, b4);
+        type O = b4;
+        type Kernel = parent;
+    }
+
+    #[kernel]
+    pub fn parent(_cr: ClockReset, i: (b4, b4), q: Q) -> (b4, D) {
+        let mut d = D::dont_care();
+        d.alu = (alu::Op::Sub, i.0, i.1);
+        (q.alu, d)
+    }
+}
+
+fn alu_seq() -> impl Iterator<Item = TimedSample<(ClockReset, (alu::Op, b4, b4))>> {
+    let ops = [alu::Op::Add, alu::Op::Sub, alu::Op::Shl, alu::Op::Less];
+    ops.into_iter()
+        .flat_map(|op| {
+            common::exhaustive::<W4>().into_iter().flat_map(move |a| {
+                common::exhaustive::<W4>()
+                    .into_iter()
+                    .map(move |b| (op, a, b))
+            })
+        })
+        .stream_after_reset(1)
+        .clock_pos_edge(100)
+}
+
+#[test]
+fn test_vhdl_entity_per_module() -> miette::Result<()> {
+    let uut = parent::U::default();
+    let vhdl = uut.hdl("top")?.as_vhdl();
+    assert!(vhdl.contains("package rhdl_support is"));
+    assert!(vhdl.contains("entity top is"));
+    assert!(vhdl.contains("entity top_alu is"));
+    assert!(vhdl.contains("c0: entity work.top_alu"));
+    // The child must be defined before it is instantiated
+    assert!(vhdl.find("entity top_alu is") < vhdl.find("entity top is"));
+    Ok(())
+}
+
+#[test]
+fn test_vhdl_flow_graph() -> miette::Result<()> {
+    let uut = parent::U::default();
+    let vhdl = uut.flow_graph("top")?.hdl("dut")?.as_vhdl();
+    assert!(vhdl.contains("entity dut is"));
+    assert!(vhdl.contains("process (all) is"));
+    // `out` is a reserved word in VHDL
+    assert!(vhdl.contains("\\out\\ : out std_logic_vector(3 downto 0)"));
+    Ok(())
+}
+
+#[test]
+fn test_vhdl_testbench() -> miette::Result<()> {
+    let uut = alu::U::default();
+    let test_bench = uut.run(alu_seq())?.collect::<SynchronousTestBench<_, _>>();
+    let tm = test_bench.rtl(&uut, &TestBenchOptions::default())?;
+    let vhdl = tm.as_vhdl();
+    assert!(vhdl.contains("entity testbench is"));
+    assert!(vhdl.contains("wait for 49 ns;"));
+    assert!(vhdl.contains("rhdl_display(\"TESTBENCH OK\");"));
+    Ok(())
+}
+
+#[test]
+fn test_vhdl_matches_rust_model() -> miette::Result<()> {
+    let uut = alu::U::default();
+    let test_bench = uut.run(alu_seq())?.collect::<SynchronousTestBench<_, _>>();
+    let tm = test_bench.rtl(&uut, &TestBenchOptions::default())?;
+    tm.run_ghdl()?;
+    let tm = test_bench.flow_graph(&uut, &TestBenchOptions::default())?;
+    tm.run_ghdl()?;
+    Ok(())
+}
+
+#[test]
+fn test_vhdl_signed_kernel() -> miette::Result<()> {
+    // Signed shifts and resizes, and a select between signed values
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>, b: Signal<s8, Red>) -> Signal<(s8, s16, s8, bool), Red> {
+        let a = a.val();
+        let b = b.val();
+        let c = if a < b { a } else { b };
+        signal((a >> 3, a.resize(), c, a >= b))
+    }
+    test_kernel_vhdl::<do_stuff, _, _, _>(do_stuff, tuple_pair_s8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_vhdl_case_kernel() -> miette::Result<()> {
+    const ONE: b8 = b8(1);
+    const TWO: b8 = b8(2);
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>) -> Signal<b8, Red> {
+        let a = a.val();
+        signal(match a {
+            ONE => bits(3),
+            TWO => a + 1,
+            _ => a,
+        })
+    }
+    test_kernel_vhdl::<do_stuff, _, _, _>(do_stuff, tuple_exhaustive_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_vhdl_unsigned_division_kernel() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>, b: Signal<b8, Red>) -> Signal<(b8, b8), Red> {
+        let a = a.val();
+        let b = b.val();
+        signal((a / b, a % b))
+    }
+    test_kernel_vhdl::<do_stuff, _, _, _>(do_stuff, tuple_pair_b8_red())?;
+    Ok(())
+}
+
+#[test]
+fn test_vhdl_signed_division_kernel() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s8, Red>, b: Signal<s8, Red>) -> Signal<(s8, s8), Red> {
+        let a = a.val();
+        let b = b.val();
+        signal((a / b, a % b))
+    }
+    test_kernel_vhdl::<do_stuff, _, _, _>(do_stuff, tuple_pair_s8_red())?;
+    Ok(())
+}
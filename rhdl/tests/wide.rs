@@ -0,0 +1,167 @@
+#![allow(dead_code)]
+use rhdl::prelude::*;
+
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+use common::*;
+use rhdl_core::sim::testbench::kernel::test_kernel_vm_and_verilog;
+
+type UnsignedOps = ((b256, b256, b256), (b256, bool, bool));
+type SignedOps = ((s256, s256, s256), (bool, bool));
+
+// A deterministic set of wide test values: the corner cases, plus
+// some pseudo-random values that exercise carries across limbs.
+fn wide_values() -> Vec<b256> {
+    let mut state = 0x1234_5678_9ABC_DEF0_u64;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        state
+    };
+    let mut values = vec![
+        b256(0),
+        b256(1),
+        b256::MAX,
+        b256(u128::MAX),
+        b256(1) << 255,
+        b256(1) << 128,
+    ];
+    for _ in 0..10 {
+        values.push(WideBits::from_limbs(&[next(), next(), next(), next()]));
+    }
+    values
+}
+
+fn wide_pairs() -> impl Iterator<Item = (Signal<b256, Red>, Signal<b256, Red>)> + Clone {
+    let values = wide_values();
+    values
+        .clone()
+        .into_iter()
+        .flat_map(move |x| values.clone().into_iter().map(move |y| (red(x), red(y))))
+}
+
+fn wide_signed_pairs() -> impl Iterator<Item = (Signal<s256, Red>, Signal<s256, Red>)> + Clone {
+    wide_pairs().map(|(x, y)| (red(x.val().as_signed()), red(y.val().as_signed())))
+}
+
+#[test]
+fn test_wide_unsigned_arithmetic() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b256, Red>, b: Signal<b256, Red>) -> Signal<UnsignedOps, Red> {
+        let a = a.val();
+        let b = b.val();
+        signal(((a + b, a - b, a * b), ((a & b) ^ !a, a > b, a == b)))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, wide_pairs())?;
+    Ok(())
+}
+
+#[test]
+fn test_wide_signed_arithmetic() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s256, Red>, b: Signal<s256, Red>) -> Signal<SignedOps, Red> {
+        let a = a.val();
+        let b = b.val();
+        signal(((a + b, a * b, -a), (a < b, a >= b)))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, wide_signed_pairs())?;
+    Ok(())
+}
+
+#[test]
+fn test_wide_shifts() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<s256, Red>, b: Signal<s256, Red>) -> Signal<(s256, s256, b256), Red> {
+        let a = a.val();
+        let shift = b.val().as_unsigned().narrow::<W8>();
+        signal((a << shift, a >> shift, a.as_unsigned() >> shift))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, wide_signed_pairs())?;
+    Ok(())
+}
+
+#[test]
+fn test_wide_xops() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b256, Red>, b: Signal<b256, Red>) -> Signal<(b257, b512, s257), Red> {
+        let a = a.val();
+        let b = b.val();
+        signal((a.xadd(b), a.xmul(b), a.xsub(b)))
+    }
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, wide_pairs())?;
+    Ok(())
+}
+
+#[test]
+fn test_widen_and_narrow() -> miette::Result<()> {
+    #[kernel]
+    fn do_stuff(a: Signal<b8, Red>, b: Signal<s8, Red>) -> Signal<(b256, s192, b8, s4), Red> {
+        let a = a.val();
+        let b = b.val();
+        let wide = a.widen::<W256>() << 200 | b256(1);
+        let signed_wide = b.widen::<W192>() + s192(-1);
+        signal((
+            wide,
+            signed_wide,
+            (wide >> 200).narrow(),
+            signed_wide.narrow(),
+        ))
+    }
+    let args = exhaustive::<W8>().into_iter().flat_map(|a| {
+        exhaustive_signed::<W4>()
+            .into_iter()
+            .map(move |b| (red(a), red(b.resize())))
+    });
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_wide_struct_fields() -> miette::Result<()> {
+    #[derive(PartialEq, Debug, Digital, Default)]
+    pub struct Beat {
+        data: b256,
+        strobe: b32,
+        last: bool,
+    }
+
+    #[kernel]
+    fn do_stuff(beat: Signal<Beat, Red>, mask: Signal<b256, Red>) -> Signal<Beat, Red> {
+        let mut beat = beat.val();
+        if beat.last {
+            beat.data &= mask.val();
+        } else {
+            beat.data += 1;
+        }
+        signal(beat)
+    }
+    let args = wide_pairs().map(|(x, y)| {
+        let beat = Beat {
+            data: x.val(),
+            strobe: x.val().narrow(),
+            last: y.val().xor(),
+        };
+        (red(beat), y)
+    });
+    test_kernel_vm_and_verilog::<do_stuff, _, _, _>(do_stuff, args)?;
+    Ok(())
+}
+
+#[test]
+fn test_wide_digital_layout() {
+    let x = (b256(1) << 200) | b256(0b101);
+    let tb = x.typed_bits();
+    assert_eq!(tb.kind, Kind::make_bits(256));
+    assert_eq!(tb.bits.len(), 256);
+    assert_eq!(tb.bits[0], BitX::One);
+    assert_eq!(tb.bits[1], BitX::Zero);
+    assert_eq!(tb.bits[200], BitX::One);
+    let y = s256(-1).typed_bits();
+    assert!(y.bits.iter().all(|b| *b == BitX::One));
+    assert_eq!(format!("{:?}", y), "-1_s256");
+    assert!(b256(1).typed_bits() < (b256(1) << 200).typed_bits());
+    assert!(s256(-1).typed_bits() < s256(0).typed_bits());
+}